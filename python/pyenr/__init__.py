@@ -1,3 +1,275 @@
-from pyenr._core import Enr, EnrBuilder, SigningKey
+import logging
 
-__all__ = ["Enr", "SigningKey", "EnrBuilder"]
+from pyenr._core import (
+    EIP778_ENR,
+    EIP778_NODE_ID,
+    EIP778_PRIVATE_KEY,
+    ENR_PROTO,
+    INVALID_VECTORS,
+    KEY_ATTNETS,
+    KEY_BSC,
+    KEY_C,
+    KEY_CGC,
+    KEY_DOMAINTYPE,
+    KEY_ED25519,
+    KEY_ETH,
+    KEY_ETH2,
+    KEY_ID,
+    KEY_IP,
+    KEY_IP6,
+    KEY_LES,
+    KEY_MULTIADDRS,
+    KEY_NEXT_DOMAINTYPE,
+    KEY_NFD,
+    KEY_OPSTACK,
+    KEY_QUIC,
+    KEY_QUIC6,
+    KEY_SECP256K1,
+    KEY_SNAP,
+    KEY_SUBNETS,
+    KEY_SYNCNETS,
+    KEY_TCP,
+    KEY_TCP6,
+    KEY_UDP,
+    KEY_UDP6,
+    KEY_WAKU2,
+    VALID_VECTORS,
+    CancelledError,
+    CancelToken,
+    ChurnStats,
+    ChurnWindow,
+    DecodeError,
+    DedupeReport,
+    EndpointConflict,
+    Endpoints,
+    Enode,
+    Enr,
+    EnrBuilder,
+    EnrCache,
+    EnrDiff,
+    EnrError,
+    EnrFields,
+    EnrHistory,
+    EnrReader,
+    EnrStore,
+    EnrTree,
+    EnrTreeBranch,
+    EnrTreeLink,
+    EnrTreeRoot,
+    EnrWarning,
+    EnrWatcher,
+    Eth2ForkId,
+    EthForkId,
+    FeedResult,
+    FieldError,
+    IdentityTracker,
+    KeyType,
+    LivenessResult,
+    NodeChurn,
+    NodeIdFilter,
+    OpStackInfo,
+    Profile,
+    SalvagedEnr,
+    SequenceOverflowError,
+    SignatureError,
+    SigningKey,
+    SizeBreakdown,
+    SizeError,
+    StreamingDeduper,
+    ValidationReport,
+    Violation,
+    WatchEvent,
+    churn_stats,
+    compute_fork_digest,
+    compute_fork_id,
+    decode,
+    decode_many,
+    decode_many_async,
+    decode_many_bytes,
+    decode_many_bytes_async,
+    decode_rlp_stream,
+    dedupe_report,
+    dedupe_report_async,
+    encode,
+    enrich_geo,
+    features,
+    fetch_enr_list,
+    filter_many,
+    filter_many_async,
+    filter_routable,
+    from_eth_enr,
+    fuzz_decode,
+    generate_random_enr,
+    get_default_profile,
+    get_num_threads,
+    get_placeholder_policy,
+    get_repr_style,
+    group_by_fork_digest,
+    known_bootnodes,
+    known_keys,
+    load_bootnodes,
+    load_node_enr,
+    node_ids,
+    parse_tree_entry,
+    probe_liveness,
+    probe_liveness_async,
+    process_enr_files,
+    process_enr_files_async,
+    profiles,
+    read_enr_file,
+    records_digest,
+    register_codec,
+    registered_codecs,
+    resolve_tree,
+    resolve_tree_async,
+    reverse_dns,
+    run_self_test,
+    save_node_enr,
+    set_default_profile,
+    set_num_threads,
+    set_placeholder_policy,
+    set_repr_style,
+    to_eth_enr,
+    unregister_codec,
+    verify_content,
+    write_enr_file,
+)
+
+# Diagnostics are logged on the "pyenr" logger; stay silent unless the
+# application configures logging.
+logging.getLogger(__name__).addHandler(logging.NullHandler())
+
+__all__ = [
+    "Enr",
+    "SigningKey",
+    "KeyType",
+    "EnrBuilder",
+    "EnrDiff",
+    "EnrFields",
+    "EnrHistory",
+    "ChurnStats",
+    "NodeChurn",
+    "ChurnWindow",
+    "LivenessResult",
+    "CancelToken",
+    "EnrStore",
+    "SizeBreakdown",
+    "Enode",
+    "EnrTree",
+    "EnrTreeRoot",
+    "EnrTreeBranch",
+    "EnrTreeLink",
+    "Eth2ForkId",
+    "EthForkId",
+    "OpStackInfo",
+    "Endpoints",
+    "DedupeReport",
+    "NodeIdFilter",
+    "Profile",
+    "EnrReader",
+    "SalvagedEnr",
+    "EnrError",
+    "DecodeError",
+    "SignatureError",
+    "SizeError",
+    "EnrWarning",
+    "FieldError",
+    "SequenceOverflowError",
+    "CancelledError",
+    "StreamingDeduper",
+    "EnrCache",
+    "IdentityTracker",
+    "EndpointConflict",
+    "EnrWatcher",
+    "WatchEvent",
+    "ValidationReport",
+    "Violation",
+    "FeedResult",
+    "compute_fork_digest",
+    "compute_fork_id",
+    "decode",
+    "decode_many",
+    "decode_many_bytes",
+    "encode",
+    "from_eth_enr",
+    "to_eth_enr",
+    "dedupe_report",
+    "filter_many",
+    "filter_routable",
+    "group_by_fork_digest",
+    "node_ids",
+    "records_digest",
+    "churn_stats",
+    "enrich_geo",
+    "reverse_dns",
+    "probe_liveness",
+    "read_enr_file",
+    "decode_rlp_stream",
+    "write_enr_file",
+    "process_enr_files",
+    "load_node_enr",
+    "save_node_enr",
+    "load_bootnodes",
+    "fetch_enr_list",
+    "known_bootnodes",
+    "parse_tree_entry",
+    "resolve_tree",
+    "filter_many_async",
+    "dedupe_report_async",
+    "process_enr_files_async",
+    "decode_many_async",
+    "decode_many_bytes_async",
+    "probe_liveness_async",
+    "resolve_tree_async",
+    "set_num_threads",
+    "get_num_threads",
+    "set_repr_style",
+    "get_repr_style",
+    "set_placeholder_policy",
+    "get_placeholder_policy",
+    "known_keys",
+    "verify_content",
+    "features",
+    "register_codec",
+    "unregister_codec",
+    "registered_codecs",
+    "profiles",
+    "set_default_profile",
+    "get_default_profile",
+    "fuzz_decode",
+    "generate_random_enr",
+    "run_self_test",
+    "EIP778_ENR",
+    "EIP778_NODE_ID",
+    "EIP778_PRIVATE_KEY",
+    "ENR_PROTO",
+    "VALID_VECTORS",
+    "INVALID_VECTORS",
+    "KEY_ATTNETS",
+    "KEY_BSC",
+    "KEY_C",
+    "KEY_CGC",
+    "KEY_DOMAINTYPE",
+    "KEY_ED25519",
+    "KEY_ETH",
+    "KEY_ETH2",
+    "KEY_ID",
+    "KEY_IP",
+    "KEY_IP6",
+    "KEY_LES",
+    "KEY_MULTIADDRS",
+    "KEY_NEXT_DOMAINTYPE",
+    "KEY_NFD",
+    "KEY_OPSTACK",
+    "KEY_QUIC",
+    "KEY_QUIC6",
+    "KEY_SECP256K1",
+    "KEY_SNAP",
+    "KEY_SUBNETS",
+    "KEY_SYNCNETS",
+    "KEY_TCP",
+    "KEY_TCP6",
+    "KEY_UDP",
+    "KEY_UDP6",
+    "KEY_WAKU2",
+]
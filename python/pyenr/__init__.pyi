@@ -1,13 +1,102 @@
-from typing import Optional
+import datetime
+import os
+from typing import (
+    Any,
+    Awaitable,
+    Callable,
+    ClassVar,
+    Iterable,
+    Iterator,
+    Literal,
+    Optional,
+    Sequence,
+    Union,
+    overload,
+)
+
+from typing_extensions import deprecated
+
+# bytes, a non-negative int, or a (nested) list of them; lists are RLP lists.
+_RlpValue = Union[bytes, bytearray, int, Sequence[Any]]
+# See set_placeholder_policy().
+_PlaceholderPolicy = Literal["allow", "flag", "reject", "none"]
+_Progress = Optional[Callable[[int, int], object]]
+
+KEY_ATTNETS: str
+KEY_BSC: str
+KEY_C: str
+KEY_CGC: str
+KEY_DOMAINTYPE: str
+KEY_ED25519: str
+KEY_ETH: str
+KEY_ETH2: str
+KEY_ID: str
+KEY_IP: str
+KEY_IP6: str
+KEY_LES: str
+KEY_MULTIADDRS: str
+KEY_NEXT_DOMAINTYPE: str
+KEY_NFD: str
+KEY_OPSTACK: str
+KEY_QUIC: str
+KEY_QUIC6: str
+KEY_SECP256K1: str
+KEY_SNAP: str
+KEY_SUBNETS: str
+KEY_SYNCNETS: str
+KEY_TCP: str
+KEY_TCP6: str
+KEY_UDP: str
+KEY_UDP6: str
+KEY_WAKU2: str
+
+EIP778_ENR: str
+EIP778_NODE_ID: bytes
+EIP778_PRIVATE_KEY: bytes
+ENR_PROTO: str
+VALID_VECTORS: tuple[tuple[str, bytes], ...]
+INVALID_VECTORS: tuple[tuple[str, bytes, Optional[str]], ...]
+
+class KeyType:
+    SECP256K1: ClassVar["KeyType"]
+    ED25519: ClassVar["KeyType"]
+    @property
+    def enr_key(self) -> str: ...
+
+class EnrError(ValueError): ...
+class DecodeError(EnrError):
+    code: Optional[str]
+    offset: Optional[int]
+    key: Optional[str]
+    expected: Optional[int]
+    actual: Optional[int]
+    format: Optional[str]
+
+class SignatureError(EnrError): ...
+
+class SizeError(EnrError):
+    key: str
+    size: int
+    overflow: int
+
+class FieldError(EnrError): ...
+class SequenceOverflowError(EnrError): ...
+class CancelledError(EnrError): ...
+class EnrWarning(UserWarning): ...
 
 class Enr:
     @staticmethod
-    def from_base64(text: str) -> "Enr": ...
+    def from_base64(text: str, *, warnings: bool = False) -> "Enr": ...
+    @staticmethod
+    def parse(text: str, *, strict: bool = False) -> "Enr": ...
     @staticmethod
-    def from_bytes(data: bytes) -> "Enr": ...
+    def from_bytes(data: bytes, *, strict: bool = False, warnings: bool = False) -> "Enr": ...
 
+    @property
+    def is_verified(self) -> bool: ...
     @property
     def seq(self) -> int: ...
+    def seq_remaining(self) -> int: ...
     @property
     def node_id(self) -> bytes: ...
     @property
@@ -26,6 +115,41 @@ class Enr:
     def public_key(self) -> bytes: ...
     @property
     def identity_scheme(self) -> Optional[str]: ...
+    @property
+    def key_type(self) -> KeyType: ...
+    @property
+    def peer_id(self) -> str: ...
+    @property
+    def eth_fork_id(self) -> Optional["EthForkId"]: ...
+    @property
+    def eth2(self) -> Optional["Eth2ForkId"]: ...
+    @property
+    def attnets(self) -> Optional[list[int]]: ...
+    @property
+    def attnets_raw(self) -> Optional[bytes]: ...
+    @property
+    def syncnets(self) -> Optional[list[int]]: ...
+    @property
+    def syncnets_raw(self) -> Optional[bytes]: ...
+    @property
+    def opstack(self) -> Optional["OpStackInfo"]: ...
+    @property
+    def endpoints(self) -> "Endpoints": ...
+    @property
+    def portal_client(self) -> Optional[str]: ...
+    @property
+    def ssv_subnets(self) -> Optional[list[int]]: ...
+    @property
+    def ssv_domain_type(self) -> Optional[bytes]: ...
+    @property
+    def ssv_next_domain_type(self) -> Optional[bytes]: ...
+    @property
+    def waku_capabilities(self) -> Optional[list[str]]: ...
+    @property
+    @deprecated("Enr.waku_multiaddrs is deprecated, use Enr.multiaddrs")
+    def waku_multiaddrs(self) -> Optional[list[str]]: ...
+    @property
+    def multiaddrs(self) -> Optional[list[str]]: ...
 
     def set_ip4(self, addr: str, key: "SigningKey") -> None: ...
     def set_ip6(self, addr: str, key: "SigningKey") -> None: ...
@@ -33,20 +157,285 @@ class Enr:
     def set_tcp6(self, port: int, key: "SigningKey") -> None: ...
     def set_udp4(self, port: int, key: "SigningKey") -> None: ...
     def set_udp6(self, port: int, key: "SigningKey") -> None: ...
+    def set_eth2(
+        self,
+        fork_digest: bytes,
+        next_fork_version: bytes,
+        next_fork_epoch: int,
+        key: "SigningKey",
+    ) -> None: ...
+    def set_attnets(self, subnets: Sequence[int], key: "SigningKey") -> None: ...
+    def set_syncnets(self, subnets: Sequence[int], key: "SigningKey") -> None: ...
+    def set_portal_client(self, client: str, key: "SigningKey") -> None: ...
+    def set_waku_capabilities(self, capabilities: Sequence[str], key: "SigningKey") -> None: ...
+    @deprecated("Enr.set_waku_multiaddrs() is deprecated, use Enr.set_multiaddrs()")
+    def set_waku_multiaddrs(self, multiaddrs: Sequence[str], key: "SigningKey") -> None: ...
+    def set_multiaddrs(
+        self,
+        multiaddrs: Sequence[str],
+        key: "SigningKey",
+        *,
+        encoding: Literal["length-prefixed", "rlp"] = "length-prefixed",
+    ) -> None: ...
     def set_seq(self, seq: int, key: "SigningKey") -> None: ...
-    def set(self, key: str, value: bytes, signing_key: "SigningKey") -> None: ...
-    def get(self, key: str) -> Optional[bytes]: ...
+    def bump_seq(self, key: "SigningKey") -> int: ...
+    def set(self, key: Union[str, bytes], value: _RlpValue, signing_key: "SigningKey") -> None: ...
+    def minimal(self, keys: list[str], signing_key: "SigningKey") -> "Enr": ...
+    def redacted(
+        self,
+        drop: Optional[list[str]] = None,
+        signing_key: Optional["SigningKey"] = None,
+        *,
+        zero: bool = False,
+    ) -> Union["Enr", dict[str, Any]]: ...
+    def get(
+        self, key: Union[str, bytes], default: Any = None, type: Optional[type] = None
+    ) -> Any: ...
+    def get_raw(self, key: Union[str, bytes]) -> Optional[bytes]: ...
+    def get_raw_rlp(self, key: Union[str, bytes]) -> Optional[bytes]: ...
+    def get_decoded(self, key: Union[str, bytes]) -> Union[bytes, list[Any], None]: ...
+    @property
+    def signature(self) -> bytes: ...
 
     def to_base64(self) -> str: ...
     def to_bytes(self) -> bytes: ...
+    def to_dict(self) -> dict[str, Any]: ...
+    @staticmethod
+    def from_dict(data: dict[str, Any]) -> "Enr": ...
+    def to_cbor(self) -> bytes: ...
+    @staticmethod
+    def from_cbor(data: bytes) -> "Enr": ...
+    def to_msgpack(self) -> bytes: ...
+    @staticmethod
+    def from_msgpack(data: bytes) -> "Enr": ...
+    def to_protobuf_bytes(self) -> bytes: ...
+    @staticmethod
+    def from_protobuf_bytes(data: bytes) -> "Enr": ...
 
-    def keys(self) -> list[str]: ...
-    def items(self) -> list[tuple[str, bytes]]: ...
+    @overload
+    def keys(self, *, raw: Literal[False] = False) -> list[str]: ...
+    @overload
+    def keys(self, *, raw: Literal[True]) -> list[bytes]: ...
+    @overload
+    def items(self, *, raw: Literal[False] = False) -> list[tuple[str, bytes]]: ...
+    @overload
+    def items(self, *, raw: Literal[True]) -> list[tuple[bytes, bytes]]: ...
+    def raw_entries(self) -> list[tuple[bytes, bytes]]: ...
+    @staticmethod
+    def raw_entries_bytes(data: bytes) -> list[tuple[bytes, bytes]]: ...
+    def memory_usage(self) -> int: ...
+    def size_breakdown(self) -> "SizeBreakdown": ...
+    def has_routable_address(self) -> bool: ...
+    def effective_endpoints(
+        self, *, fallback: bool = True
+    ) -> list[tuple[Literal["udp", "tcp"], str, int]]: ...
+    def to_multiaddrs(self, *, fallback: bool = True, p2p: bool = True) -> list[str]: ...
+    def signable_content(self) -> bytes: ...
+    def verify(self) -> bool: ...
+    def verify_against(self, public_key: bytes) -> bool: ...
+    def verify_with(self, public_key: bytes) -> bool: ...
+    @staticmethod
+    def is_canonical(data: bytes) -> bool: ...
+    @staticmethod
+    def from_bytes_lenient(data: bytes) -> SalvagedEnr: ...
+    @staticmethod
+    def from_base64_lenient(text: str) -> SalvagedEnr: ...
+    def validate(
+        self,
+        *,
+        known_keys_only: bool = False,
+        profile: Union["Profile", str, None] = None,
+        placeholders: Optional[_PlaceholderPolicy] = None,
+    ) -> ValidationReport: ...
+    @staticmethod
+    def validate_bytes(
+        data: bytes,
+        *,
+        known_keys_only: bool = False,
+        profile: Union["Profile", str, None] = None,
+        placeholders: Optional[_PlaceholderPolicy] = None,
+    ) -> ValidationReport: ...
+    def unknown_keys(self) -> list[str]: ...
 
+    def fields(self) -> "EnrFields": ...
+    def profile_fields(self, profile: Union["Profile", str, None] = None) -> dict[str, Any]: ...
+    def pretty(self) -> str: ...
     def __str__(self) -> str: ...
     def __repr__(self) -> str: ...
+    def __format__(self, spec: str) -> str: ...
+    def diff(self, other: "Enr") -> "EnrDiff": ...
     def __eq__(self, other: object) -> bool: ...
     def __hash__(self) -> int: ...
+    def __reduce__(self) -> tuple[Any, tuple[bytes]]: ...
+    def __buffer__(self, flags: int) -> memoryview: ...
+    def stable_hash(self, mode: Optional[str] = None) -> int: ...
+    def fingerprint(self) -> str: ...
+    def to_canonical_json(self) -> str: ...
+    def content_hash(self) -> bytes: ...
+    @staticmethod
+    def set_hash_mode(mode: str) -> None: ...
+    @staticmethod
+    def get_hash_mode() -> str: ...
+
+class Eth2ForkId:
+    def __init__(
+        self, fork_digest: bytes, next_fork_version: bytes, next_fork_epoch: int = 2**64 - 1
+    ) -> None: ...
+    @staticmethod
+    def from_ssz_bytes(data: bytes) -> Eth2ForkId: ...
+    def to_ssz_bytes(self) -> bytes: ...
+    @property
+    def fork_digest(self) -> bytes: ...
+    @property
+    def next_fork_version(self) -> bytes: ...
+    @property
+    def next_fork_epoch(self) -> int: ...
+    def next_fork_timestamp(
+        self, genesis_time: int, seconds_per_slot: int = 12, slots_per_epoch: int = 32
+    ) -> Optional[int]: ...
+    def next_fork_time(
+        self, genesis_time: int, seconds_per_slot: int = 12, slots_per_epoch: int = 32
+    ) -> Optional[datetime.datetime]: ...
+    def to_dict(self) -> dict[str, Union[bytes, int]]: ...
+
+def compute_fork_digest(current_fork_version: bytes, genesis_validators_root: bytes) -> bytes: ...
+
+class EthForkId:
+    @property
+    def fork_hash(self) -> bytes: ...
+    @property
+    def fork_next(self) -> int: ...
+    def to_dict(self) -> dict[str, Union[bytes, int]]: ...
+
+class OpStackInfo:
+    @property
+    def chain_id(self) -> int: ...
+    @property
+    def version(self) -> int: ...
+    def to_dict(self) -> dict[str, int]: ...
+
+class Endpoints:
+    @property
+    def ip4(self) -> Optional[str]: ...
+    @property
+    def tcp4(self) -> Optional[int]: ...
+    @property
+    def udp4(self) -> Optional[int]: ...
+    @property
+    def quic4(self) -> Optional[int]: ...
+    @property
+    def ip6(self) -> Optional[str]: ...
+    @property
+    def tcp6(self) -> Optional[int]: ...
+    @property
+    def udp6(self) -> Optional[int]: ...
+    @property
+    def quic6(self) -> Optional[int]: ...
+    def to_dict(self) -> dict[str, Union[str, int, None]]: ...
+    def __bool__(self) -> bool: ...
+
+def compute_fork_id(
+    genesis_hash: bytes,
+    block_forks: Sequence[int],
+    time_forks: Sequence[int] = ...,
+    *,
+    head_block: Optional[int] = None,
+    head_time: Optional[int] = None,
+) -> EthForkId: ...
+
+class EnrFields:
+    @property
+    def seq(self) -> int: ...
+    @property
+    def node_id(self) -> bytes: ...
+    @property
+    def public_key(self) -> bytes: ...
+    @property
+    def identity_scheme(self) -> Optional[str]: ...
+    @property
+    def ip4(self) -> Optional[str]: ...
+    @property
+    def ip6(self) -> Optional[str]: ...
+    @property
+    def tcp4(self) -> Optional[int]: ...
+    @property
+    def udp4(self) -> Optional[int]: ...
+    @property
+    def tcp6(self) -> Optional[int]: ...
+    @property
+    def udp6(self) -> Optional[int]: ...
+    @property
+    def quic4(self) -> Optional[int]: ...
+    @property
+    def quic6(self) -> Optional[int]: ...
+    @property
+    def eth2(self) -> Optional[Eth2ForkId]: ...
+    @property
+    def attnets(self) -> Optional[bytes]: ...
+    @property
+    def syncnets(self) -> Optional[bytes]: ...
+    @property
+    def cgc(self) -> Optional[int]: ...
+
+class EnrDiff:
+    @property
+    def added(self) -> dict[str, Any]: ...
+    @property
+    def removed(self) -> dict[str, Any]: ...
+    @property
+    def changed(self) -> dict[str, tuple[Any, Any]]: ...
+    @property
+    def seq_delta(self) -> int: ...
+    def __bool__(self) -> bool: ...
+
+class SizeBreakdown:
+    @property
+    def total(self) -> int: ...
+    @property
+    def remaining(self) -> int: ...
+    @property
+    def header(self) -> int: ...
+    @property
+    def signature(self) -> int: ...
+    @property
+    def seq(self) -> int: ...
+    @property
+    def fields(self) -> dict[str, int]: ...
+
+class EnrHistory:
+    def __init__(self, enrs: Sequence[Enr] = ...) -> None: ...
+    def add(self, enr: Enr) -> bool: ...
+    @property
+    def node_id(self) -> Optional[bytes]: ...
+    @property
+    def latest(self) -> Optional[Enr]: ...
+    def seqs(self) -> list[int]: ...
+    def get(self, seq: int) -> Optional[Enr]: ...
+    def records(self) -> list[Enr]: ...
+    def changes_between(self, seq_a: int, seq_b: int) -> EnrDiff: ...
+    def changes(self) -> list[EnrDiff]: ...
+    def __len__(self) -> int: ...
+
+class EnrStore:
+    def __init__(self, path: Union[str, os.PathLike[str]]) -> None: ...
+    def upsert(self, enr: Enr) -> bool: ...
+    def upsert_many(self, enrs: Sequence[Enr]) -> int: ...
+    def get(self, node_id: Union[bytes, Enr]) -> Optional[Enr]: ...
+    def remove(self, node_id: Union[bytes, Enr]) -> bool: ...
+    def find(
+        self,
+        *,
+        ip: Optional[str] = None,
+        fork_digest: Optional[bytes] = None,
+        min_seq: Optional[int] = None,
+        limit: Optional[int] = None,
+    ) -> list[Enr]: ...
+    def digest(self) -> str: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> "EnrStore": ...
+    def __exit__(self, *args: object) -> bool: ...
+    def __contains__(self, node_id: Union[bytes, Enr]) -> bool: ...
+    def __len__(self) -> int: ...
 
 class SigningKey:
     @staticmethod
@@ -54,12 +443,22 @@ class SigningKey:
     @staticmethod
     def from_ed25519(secret: bytes) -> "SigningKey": ...
     @staticmethod
+    def generate(key_type: KeyType) -> "SigningKey": ...
+    @staticmethod
     def generate_secp256k1() -> "SigningKey": ...
     @staticmethod
     def generate_ed25519() -> "SigningKey": ...
 
     def public_key(self) -> bytes: ...
+    def peer_id(self) -> str: ...
+    @property
+    def key_type(self) -> KeyType: ...
     def builder(self) -> "EnrBuilder": ...
+    def allow_pickle(self) -> "SigningKey": ...
+    @property
+    def picklable(self) -> bool: ...
+    def __reduce__(self) -> tuple[Any, tuple[tuple[str, bytes]]]: ...
+    def __getstate__(self) -> tuple[str, bytes]: ...
 
 class EnrBuilder:
     def ip4(self, addr: str) -> None: ...
@@ -68,5 +467,602 @@ class EnrBuilder:
     def tcp6(self, port: int) -> None: ...
     def udp4(self, port: int) -> None: ...
     def udp6(self, port: int) -> None: ...
-    def add(self, key: str, value: bytes) -> None: ...
+    def eth2(
+        self, fork_digest: bytes, next_fork_version: bytes, next_fork_epoch: int = 2**64 - 1
+    ) -> None: ...
+    def attnets(self, subnets: Sequence[int]) -> None: ...
+    def syncnets(self, subnets: Sequence[int]) -> None: ...
+    def portal_client(self, client: str) -> None: ...
+    def waku_capabilities(self, capabilities: Sequence[str]) -> None: ...
+    @deprecated("EnrBuilder.waku_multiaddrs() is deprecated, use EnrBuilder.multiaddrs()")
+    def waku_multiaddrs(self, multiaddrs: Sequence[str]) -> None: ...
+    def multiaddrs(
+        self,
+        multiaddrs: Sequence[str],
+        *,
+        encoding: Literal["length-prefixed", "rlp"] = "length-prefixed",
+    ) -> None: ...
+    def add(self, key: str, value: _RlpValue) -> None: ...
+    def clone_template(self) -> EnrBuilder: ...
     def build(self, key: "SigningKey") -> Enr: ...
+
+def decode(value: Union[str, bytes, bytearray, Enr, Any], *, strict: bool = False) -> Enr: ...
+def encode(enr: Enr, format: str = "base64") -> Union[str, bytes]: ...
+def from_eth_enr(record: Any, *, strict: bool = False) -> Enr: ...
+def to_eth_enr(enr: Enr, *, cls: Optional[Any] = None) -> Any: ...
+def fuzz_decode(data: bytes) -> bool: ...
+def generate_random_enr(
+    rng_seed: Optional[int] = None,
+    fields: Optional[Sequence[str]] = None,
+    *,
+    key: Optional[SigningKey] = None,
+) -> Enr: ...
+def set_num_threads(n: Optional[int]) -> None: ...
+def get_num_threads() -> int: ...
+def set_repr_style(style: str) -> None: ...
+def get_repr_style() -> str: ...
+def set_placeholder_policy(policy: _PlaceholderPolicy) -> None: ...
+def get_placeholder_policy() -> _PlaceholderPolicy: ...
+def known_keys() -> dict[str, str]: ...
+def verify_content(
+    content: bytes, signature: bytes, public_key: bytes, scheme: str = "v4"
+) -> bool: ...
+def features() -> dict[str, Any]: ...
+def run_self_test() -> int: ...
+def register_codec(
+    key: str,
+    encode_fn: Optional[Callable[[Any], _RlpValue]] = None,
+    decode_fn: Optional[Callable[[Any], Any]] = None,
+) -> None: ...
+def unregister_codec(key: str) -> bool: ...
+def registered_codecs() -> list[str]: ...
+
+class Profile:
+    @staticmethod
+    def get(name: str) -> Profile: ...
+    @property
+    def name(self) -> str: ...
+    @property
+    def description(self) -> str: ...
+    @property
+    def keys(self) -> list[str]: ...
+    @property
+    def required(self) -> list[str]: ...
+    def fields(self, enr: Enr) -> dict[str, Any]: ...
+    def validate(self, enr: Enr) -> ValidationReport: ...
+    def add(self, builder: EnrBuilder, key: str, value: Union[_RlpValue, str]) -> None: ...
+    def set(self, enr: Enr, key: str, value: Union[_RlpValue, str], signing_key: SigningKey) -> None: ...
+
+def profiles() -> list[str]: ...
+def set_default_profile(profile: Union[Profile, str, None]) -> None: ...
+def get_default_profile() -> Optional[Profile]: ...
+
+class Violation:
+    @property
+    def code(self) -> str: ...
+    @property
+    def key(self) -> Optional[str]: ...
+    @property
+    def offset(self) -> int: ...
+    @property
+    def message(self) -> str: ...
+    @property
+    def expected(self) -> Optional[int]: ...
+    @property
+    def actual(self) -> Optional[int]: ...
+
+class ValidationReport:
+    @property
+    def violations(self) -> list[Violation]: ...
+    @property
+    def is_valid(self) -> bool: ...
+    def codes(self) -> list[str]: ...
+    def __bool__(self) -> bool: ...
+
+class SalvagedEnr:
+    @property
+    def record(self) -> Optional[Enr]: ...
+    @property
+    def problems(self) -> list[Violation]: ...
+    @property
+    def signature_valid(self) -> bool: ...
+    @property
+    def is_verified(self) -> bool: ...
+    @property
+    def seq(self) -> Optional[int]: ...
+    @property
+    def signature(self) -> Optional[bytes]: ...
+    @property
+    def public_key(self) -> Optional[bytes]: ...
+    @property
+    def node_id(self) -> Optional[bytes]: ...
+    @property
+    def identity_scheme(self) -> Optional[str]: ...
+    @property
+    def ip4(self) -> Optional[str]: ...
+    @property
+    def ip6(self) -> Optional[str]: ...
+    @property
+    def tcp4(self) -> Optional[int]: ...
+    @property
+    def tcp6(self) -> Optional[int]: ...
+    @property
+    def udp4(self) -> Optional[int]: ...
+    @property
+    def udp6(self) -> Optional[int]: ...
+    def keys(self) -> list[str]: ...
+    def get_raw_rlp(self, key: str) -> Optional[bytes]: ...
+
+class DedupeReport:
+    @property
+    def records(self) -> list[Enr]: ...
+    @property
+    def duplicates_removed(self) -> int: ...
+    @property
+    def seq_conflicts(self) -> int: ...
+    @property
+    def endpoint_collisions(self) -> int: ...
+    def __len__(self) -> int: ...
+
+class NodeChurn:
+    @property
+    def node_id(self) -> bytes: ...
+    @property
+    def first_seen(self) -> float: ...
+    @property
+    def last_seen(self) -> float: ...
+    @property
+    def observations(self) -> int: ...
+    @property
+    def updates(self) -> int: ...
+    @property
+    def updates_per_day(self) -> Optional[float]: ...
+
+class ChurnWindow:
+    @property
+    def start(self) -> float: ...
+    @property
+    def seen(self) -> int: ...
+    @property
+    def joined(self) -> int: ...
+    @property
+    def left(self) -> int: ...
+
+class ChurnStats:
+    @property
+    def observations(self) -> int: ...
+    @property
+    def interval(self) -> float: ...
+    @property
+    def nodes(self) -> list[NodeChurn]: ...
+    @property
+    def field_changes(self) -> dict[str, int]: ...
+    @property
+    def windows(self) -> list[ChurnWindow]: ...
+
+class CancelToken:
+    def __init__(self) -> None: ...
+    def cancel(self) -> None: ...
+    @property
+    def cancelled(self) -> bool: ...
+
+class LivenessResult:
+    @property
+    def node_id(self) -> bytes: ...
+    @property
+    def method(self) -> Literal["tcp", "discv5_ping"]: ...
+    @property
+    def reachable(self) -> bool: ...
+    @property
+    def endpoint(self) -> Optional[str]: ...
+    @property
+    def rtt(self) -> Optional[float]: ...
+    @property
+    def error(self) -> Optional[str]: ...
+
+class NodeIdFilter:
+    def __init__(self, capacity: int, false_positive_rate: float = 0.01) -> None: ...
+    def add(self, node_id: Union[bytes, Enr]) -> bool: ...
+    def contains(self, node_id: Union[bytes, Enr]) -> bool: ...
+    def add_many(
+        self, node_ids: Sequence[Union[bytes, Enr]], *, workers: Optional[int] = None
+    ) -> int: ...
+    def contains_many(
+        self, node_ids: Sequence[Union[bytes, Enr]], *, workers: Optional[int] = None
+    ) -> list[bool]: ...
+    def __contains__(self, node_id: Union[bytes, Enr]) -> bool: ...
+    def __len__(self) -> int: ...
+    @property
+    def capacity(self) -> int: ...
+    @property
+    def false_positive_rate(self) -> float: ...
+    @property
+    def num_bits(self) -> int: ...
+    @property
+    def num_hashes(self) -> int: ...
+
+class FeedResult:
+    @property
+    def updated(self) -> list[Enr]: ...
+    @property
+    def evicted(self) -> list[Enr]: ...
+
+class StreamingDeduper:
+    def __init__(self, max_entries: Optional[int] = None) -> None: ...
+    def feed(self, enrs: Sequence[Enr]) -> FeedResult: ...
+    def get(self, node_id: Union[bytes, Enr]) -> Optional[Enr]: ...
+    def records(self) -> list[Enr]: ...
+    def clear(self) -> None: ...
+    def __contains__(self, node_id: Union[bytes, Enr]) -> bool: ...
+    def __len__(self) -> int: ...
+    @property
+    def max_entries(self) -> Optional[int]: ...
+    @property
+    def evictions(self) -> int: ...
+
+class EndpointConflict:
+    @property
+    def protocol(self) -> Literal["udp", "tcp"]: ...
+    @property
+    def endpoint(self) -> str: ...
+    @property
+    def records(self) -> list[Enr]: ...
+
+class IdentityTracker:
+    def __init__(self) -> None: ...
+    def add(self, enr: Enr, source: str) -> bool: ...
+    def add_many(self, enrs: Sequence[Enr], source: str) -> int: ...
+    def get(self, node_id: Union[bytes, Enr]) -> Optional[Enr]: ...
+    def sources(self, node_id: Union[bytes, Enr]) -> list[str]: ...
+    def records(self) -> list[Enr]: ...
+    def conflicts(self) -> list[EndpointConflict]: ...
+    def digest(self) -> str: ...
+    def __contains__(self, node_id: Union[bytes, Enr]) -> bool: ...
+    def __len__(self) -> int: ...
+
+class WatchEvent:
+    @property
+    def kind(self) -> Literal["added", "removed", "ip_changed", "port_changed"]: ...
+    @property
+    def node_id(self) -> bytes: ...
+    @property
+    def old(self) -> Optional[Enr]: ...
+    @property
+    def new(self) -> Optional[Enr]: ...
+    @property
+    def keys(self) -> list[str]: ...
+
+class EnrWatcher:
+    def __init__(
+        self,
+        *,
+        on_added: Optional[Callable[[WatchEvent], object]] = None,
+        on_removed: Optional[Callable[[WatchEvent], object]] = None,
+        on_ip_changed: Optional[Callable[[WatchEvent], object]] = None,
+        on_port_changed: Optional[Callable[[WatchEvent], object]] = None,
+    ) -> None: ...
+    def update(self, enrs: Sequence[Enr]) -> list[WatchEvent]: ...
+    def records(self) -> list[Enr]: ...
+    def __len__(self) -> int: ...
+
+class EnrCache:
+    def __init__(self, max_entries: int) -> None: ...
+    def put(self, enr: Enr) -> bool: ...
+    def get(self, node_id: Union[bytes, Enr]) -> Optional[Enr]: ...
+    def pop(self, node_id: Union[bytes, Enr]) -> Optional[Enr]: ...
+    def records(self) -> list[Enr]: ...
+    def clear(self) -> None: ...
+    def __contains__(self, node_id: Union[bytes, Enr]) -> bool: ...
+    def __len__(self) -> int: ...
+    @property
+    def max_entries(self) -> int: ...
+    @property
+    def evictions(self) -> int: ...
+
+class EnrReader:
+    def __iter__(self) -> Iterator[Enr]: ...
+    def __next__(self) -> Enr: ...
+
+class Enode:
+    @staticmethod
+    def from_url(url: str) -> Enode: ...
+    @property
+    def public_key(self) -> bytes: ...
+    @property
+    def node_id(self) -> bytes: ...
+    @property
+    def ip(self) -> str: ...
+    @property
+    def tcp(self) -> int: ...
+    @property
+    def udp(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class EnrTreeRoot:
+    @property
+    def enr_root(self) -> str: ...
+    @property
+    def link_root(self) -> str: ...
+    @property
+    def seq(self) -> int: ...
+    @property
+    def signature(self) -> bytes: ...
+    def verify(self, public_key: bytes) -> bool: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class EnrTreeBranch:
+    @property
+    def children(self) -> list[str]: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class EnrTreeLink:
+    def __init__(self, public_key: bytes, domain: str) -> None: ...
+    @staticmethod
+    def from_url(url: str) -> EnrTreeLink: ...
+    @property
+    def public_key(self) -> bytes: ...
+    @property
+    def domain(self) -> str: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class EnrTree:
+    @staticmethod
+    def build(
+        enrs: Sequence[Enr],
+        key: SigningKey,
+        *,
+        seq: int,
+        links: Sequence[Union[EnrTreeLink, str]] = (),
+    ) -> EnrTree: ...
+    @property
+    def root(self) -> EnrTreeRoot: ...
+    @property
+    def seq(self) -> int: ...
+    @property
+    def records(self) -> list[Enr]: ...
+    @property
+    def links(self) -> list[EnrTreeLink]: ...
+    def link(self, domain: str) -> EnrTreeLink: ...
+    def to_txt(self, domain: str) -> dict[str, str]: ...
+    def __len__(self) -> int: ...
+
+def parse_tree_entry(text: str) -> Union[EnrTreeRoot, EnrTreeBranch, EnrTreeLink, Enr]: ...
+def resolve_tree(
+    url: str,
+    *,
+    nameserver: Optional[str] = None,
+    timeout: float = 5.0,
+    concurrency: int = 16,
+    follow_links: bool = True,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> list[Enr]: ...
+
+def filter_many(
+    enrs: Sequence[Enr],
+    *,
+    has_ip4: Optional[bool] = None,
+    has_ip6: Optional[bool] = None,
+    tcp4: Optional[int] = None,
+    udp4: Optional[int] = None,
+    fork_digest: Optional[bytes] = None,
+    key_present: Optional[Sequence[str]] = None,
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> list[Enr]: ...
+def filter_routable(
+    enrs: Sequence[Enr],
+    *,
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> list[Enr]: ...
+def group_by_fork_digest(
+    enrs: Sequence[Enr],
+    *,
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> dict[Optional[bytes], list[Enr]]: ...
+def node_ids(enrs: Sequence[Enr]) -> bytes: ...
+def dedupe_report(
+    enrs: Sequence[Enr],
+    *,
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> DedupeReport: ...
+def records_digest(enrs: Sequence[Enr]) -> str: ...
+@overload
+def decode_many(
+    texts: Sequence[str],
+    *,
+    strict: bool = False,
+    return_errors: Literal[False] = False,
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> list[Enr]: ...
+@overload
+def decode_many(
+    texts: Sequence[str],
+    *,
+    strict: bool = False,
+    return_errors: Literal[True],
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> tuple[list[Enr], list[tuple[int, EnrError]]]: ...
+@overload
+def decode_many_bytes(
+    data: Sequence[Union[bytes, bytearray]],
+    *,
+    strict: bool = False,
+    return_errors: Literal[False] = False,
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> list[Enr]: ...
+@overload
+def decode_many_bytes(
+    data: Sequence[Union[bytes, bytearray]],
+    *,
+    strict: bool = False,
+    return_errors: Literal[True],
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> tuple[list[Enr], list[tuple[int, EnrError]]]: ...
+def enrich_geo(
+    enrs: Sequence[Enr],
+    mmdb_path: Union[str, os.PathLike[str], Sequence[Union[str, os.PathLike[str]]]],
+) -> list[dict[str, Any]]: ...
+def reverse_dns(
+    enrs: Sequence[Enr],
+    *,
+    concurrency: int = 32,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> dict[str, Optional[str]]: ...
+def probe_liveness(
+    enrs: Sequence[Enr],
+    *,
+    method: Literal["tcp", "discv5_ping"] = "tcp",
+    concurrency: int = 32,
+    timeout: float = 2.0,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> list[LivenessResult]: ...
+def churn_stats(
+    observations: Iterable[tuple[float, Enr]], *, interval: float = 86400.0
+) -> ChurnStats: ...
+def read_enr_file(path: Union[str, os.PathLike[str]]) -> EnrReader: ...
+def decode_rlp_stream(path: Union[str, os.PathLike[str]]) -> EnrReader: ...
+def write_enr_file(
+    path: Union[str, os.PathLike[str]],
+    enrs: Sequence[Enr],
+    *,
+    compression: Optional[str] = None,
+) -> int: ...
+def process_enr_files(
+    paths: Sequence[Union[str, os.PathLike[str]]],
+    *,
+    filter: Optional[dict[str, Any]] = None,
+    workers: Optional[int] = None,
+    format: str = "lines",
+    raise_on_error: bool = False,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> list[Enr]: ...
+def filter_many_async(
+    enrs: Sequence[Enr],
+    *,
+    has_ip4: Optional[bool] = None,
+    has_ip6: Optional[bool] = None,
+    tcp4: Optional[int] = None,
+    udp4: Optional[int] = None,
+    fork_digest: Optional[bytes] = None,
+    key_present: Optional[Sequence[str]] = None,
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> Awaitable[list[Enr]]: ...
+def dedupe_report_async(
+    enrs: Sequence[Enr],
+    *,
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> Awaitable[DedupeReport]: ...
+def process_enr_files_async(
+    paths: Sequence[Union[str, os.PathLike[str]]],
+    *,
+    filter: Optional[dict[str, Any]] = None,
+    workers: Optional[int] = None,
+    format: str = "lines",
+    raise_on_error: bool = False,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> Awaitable[list[Enr]]: ...
+@overload
+def decode_many_async(
+    texts: Sequence[str],
+    *,
+    strict: bool = False,
+    return_errors: Literal[False] = False,
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> Awaitable[list[Enr]]: ...
+@overload
+def decode_many_async(
+    texts: Sequence[str],
+    *,
+    strict: bool = False,
+    return_errors: Literal[True],
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> Awaitable[tuple[list[Enr], list[tuple[int, EnrError]]]]: ...
+@overload
+def decode_many_bytes_async(
+    data: Sequence[Union[bytes, bytearray]],
+    *,
+    strict: bool = False,
+    return_errors: Literal[False] = False,
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> Awaitable[list[Enr]]: ...
+@overload
+def decode_many_bytes_async(
+    data: Sequence[Union[bytes, bytearray]],
+    *,
+    strict: bool = False,
+    return_errors: Literal[True],
+    workers: Optional[int] = None,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> Awaitable[tuple[list[Enr], list[tuple[int, EnrError]]]]: ...
+def probe_liveness_async(
+    enrs: Sequence[Enr],
+    *,
+    method: Literal["tcp", "discv5_ping"] = "tcp",
+    concurrency: int = 32,
+    timeout: float = 2.0,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> Awaitable[list[LivenessResult]]: ...
+def resolve_tree_async(
+    url: str,
+    *,
+    nameserver: Optional[str] = None,
+    timeout: float = 5.0,
+    concurrency: int = 16,
+    follow_links: bool = True,
+    progress: _Progress = None,
+    cancel: Optional[CancelToken] = None,
+) -> Awaitable[list[Enr]]: ...
+def load_node_enr(
+    network_dir: Union[str, os.PathLike[str]],
+) -> tuple[Enr, Optional[SigningKey]]: ...
+def save_node_enr(
+    network_dir: Union[str, os.PathLike[str]],
+    enr: Enr,
+    *,
+    key: Optional[SigningKey] = None,
+) -> None: ...
+def load_bootnodes(
+    path: Union[str, os.PathLike[str]], *, format: Optional[str] = None
+) -> list[Union[Enr, Enode]]: ...
+def fetch_enr_list(
+    url: str, *, format: Optional[str] = None, timeout: float = 30.0
+) -> tuple[list[Union[Enr, Enode]], list[tuple[str, str]]]: ...
+def known_bootnodes(
+    network: str, *, layer: Optional[str] = None
+) -> list[Union[Enr, Enode]]: ...
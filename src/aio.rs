@@ -0,0 +1,348 @@
+//! Awaitable variants of the batch APIs, for asyncio code.
+//!
+//! Each call returns an asyncio future bound to the running event loop and
+//! queues the work for a small pool of threads shared by all calls, handing
+//! the outcome back with `loop.call_soon_threadsafe`. The work releases the
+//! GIL just as the synchronous API does, so the event loop keeps running in
+//! the meantime.
+
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread;
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::pybacked::{PyBackedBytes, PyBackedStr};
+use pyo3::types::PyDict;
+use pyo3::IntoPyObjectExt;
+
+use crate::progress::CancelToken;
+use crate::{batch, files, liveness, Enr};
+
+/// Fewest threads the pool runs. Calls that wait on the network, such as
+/// `probe_liveness_async`, hold a thread for their whole duration, so the
+/// pool does not shrink to the CPU count on small machines.
+const MIN_THREADS: usize = 4;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Queue of the threads running async calls, started on first use.
+static QUEUE: OnceLock<Sender<Job>> = OnceLock::new();
+
+/// Jobs under way, and whether the interpreter is exiting so that no more
+/// may start.
+struct Running {
+    jobs: usize,
+    closed: bool,
+}
+
+static RUNNING: Mutex<Running> = Mutex::new(Running {
+    jobs: 0,
+    closed: false,
+});
+
+/// Signalled whenever a job finishes.
+static FINISHED: Condvar = Condvar::new();
+
+/// Waits for the jobs under way to finish and keeps queued ones from
+/// starting. Registered with `atexit`: a worker can lose the GIL inside
+/// `call_soon_threadsafe` after its result is already on the loop, and one
+/// still attached when the interpreter finalizes aborts the process.
+#[pyfunction]
+fn wait_for_workers(py: Python<'_>) {
+    py.detach(|| {
+        let mut running = RUNNING.lock().expect("job count lock poisoned");
+        running.closed = true;
+        while running.jobs > 0 {
+            running = FINISHED.wait(running).expect("job count lock poisoned");
+        }
+    });
+}
+
+/// Starts the pool: one thread per CPU, at least [`MIN_THREADS`]. Calls
+/// beyond that wait in the queue for a thread to free up.
+fn start_pool(py: Python<'_>) -> PyResult<Sender<Job>> {
+    py.import("atexit")?
+        .call_method1("register", (wrap_pyfunction!(wait_for_workers, py)?,))?;
+    let threads = thread::available_parallelism().map_or(MIN_THREADS, |n| n.get().max(MIN_THREADS));
+    let (sender, receiver) = mpsc::channel::<Job>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..threads {
+        let receiver = Arc::clone(&receiver);
+        thread::Builder::new()
+            .name("pyenr-async".to_string())
+            .spawn(move || run_jobs(&receiver))
+            .map_err(|e| PyRuntimeError::new_err(format!("cannot start worker thread: {e}")))?;
+    }
+    Ok(sender)
+}
+
+fn run_jobs(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        // The lock is held only while taking a job, not while running it.
+        let job = receiver.lock().expect("job queue lock poisoned").recv();
+        let Ok(job) = job else {
+            return;
+        };
+        {
+            let mut running = RUNNING.lock().expect("job count lock poisoned");
+            if running.closed {
+                // Nothing is left to await the result.
+                continue;
+            }
+            running.jobs += 1;
+        }
+        let _finished = Finished;
+        job();
+    }
+}
+
+/// Counts a job as finished when dropped, even if it panicked.
+struct Finished;
+
+impl Drop for Finished {
+    fn drop(&mut self) {
+        // Poisoning is ignored so that a panicking job still counts down.
+        let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        running.jobs -= 1;
+        FINISHED.notify_all();
+    }
+}
+
+/// Sets the outcome of a finished call on its future, on the loop's thread.
+#[pyclass(frozen)]
+struct Completion {
+    future: Py<PyAny>,
+    outcome: Mutex<Option<PyResult<Py<PyAny>>>>,
+}
+
+#[pymethods]
+impl Completion {
+    fn __call__(&self, py: Python<'_>) -> PyResult<()> {
+        let outcome = self
+            .outcome
+            .lock()
+            .expect("completion lock poisoned")
+            .take();
+        let future = self.future.bind(py);
+        // A cancelled future already has its outcome.
+        if future.call_method0("done")?.is_truthy()? {
+            return Ok(());
+        }
+        match outcome {
+            Some(Ok(value)) => future.call_method1("set_result", (value,))?,
+            Some(Err(err)) => future.call_method1("set_exception", (err.into_value(py),))?,
+            None => return Ok(()),
+        };
+        Ok(())
+    }
+}
+
+/// Queues `work` for the pool and returns a future for its result.
+///
+/// Must be called with an event loop running in the current thread, as
+/// `asyncio.get_running_loop()` requires.
+fn spawn<'py, T, F>(py: Python<'py>, work: F) -> PyResult<Bound<'py, PyAny>>
+where
+    T: for<'a> IntoPyObject<'a>,
+    F: FnOnce(Python<'_>) -> PyResult<T> + Send + 'static,
+{
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    let future = event_loop.call_method0("create_future")?;
+    let (event_loop, pending) = (event_loop.unbind(), future.clone().unbind());
+    let job: Job = Box::new(move || {
+        Python::attach(|py| {
+            let outcome = work(py).and_then(|value| value.into_py_any(py));
+            let completion = Completion {
+                future: pending,
+                outcome: Mutex::new(Some(outcome)),
+            };
+            // Fails only if the loop was closed before the work finished,
+            // in which case nothing is left to await the result.
+            let _ = Py::new(py, completion).and_then(|callback| {
+                event_loop
+                    .bind(py)
+                    .call_method1("call_soon_threadsafe", (callback,))
+            });
+        })
+    });
+    let queue = match QUEUE.get() {
+        Some(queue) => queue,
+        None => {
+            let sender = start_pool(py)?;
+            QUEUE.get_or_init(|| sender)
+        }
+    };
+    queue
+        .send(job)
+        .map_err(|_| PyRuntimeError::new_err("async worker threads have stopped"))?;
+    Ok(future)
+}
+
+/// Awaitable variant of `filter_many`.
+#[pyfunction]
+#[pyo3(signature = (enrs, *, has_ip4=None, has_ip6=None, tcp4=None, udp4=None, fork_digest=None, key_present=None, workers=None, progress=None, cancel=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn filter_many_async<'py>(
+    py: Python<'py>,
+    enrs: Vec<Py<Enr>>,
+    has_ip4: Option<bool>,
+    has_ip6: Option<bool>,
+    tcp4: Option<u16>,
+    udp4: Option<u16>,
+    fork_digest: Option<Vec<u8>>,
+    key_present: Option<Vec<String>>,
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    spawn(py, move |py| {
+        let enrs = enrs.into_iter().map(|e| e.into_bound(py)).collect();
+        let kept = batch::filter_many(
+            py,
+            enrs,
+            has_ip4,
+            has_ip6,
+            tcp4,
+            udp4,
+            fork_digest.as_deref(),
+            key_present,
+            workers,
+            progress,
+            cancel,
+        )?;
+        Ok(kept.into_iter().map(Bound::unbind).collect::<Vec<_>>())
+    })
+}
+
+/// Awaitable variant of `dedupe_report`.
+#[pyfunction]
+#[pyo3(signature = (enrs, *, workers=None, progress=None, cancel=None))]
+pub(crate) fn dedupe_report_async<'py>(
+    py: Python<'py>,
+    enrs: Vec<Py<Enr>>,
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    spawn(py, move |py| {
+        let enrs = enrs.into_iter().map(|e| e.into_bound(py)).collect();
+        batch::dedupe_report(py, enrs, workers, progress, cancel)
+    })
+}
+
+/// Awaitable variant of `process_enr_files`.
+#[pyfunction]
+#[pyo3(signature = (paths, *, filter=None, workers=None, format="lines".to_string(), raise_on_error=false, progress=None, cancel=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn process_enr_files_async<'py>(
+    py: Python<'py>,
+    paths: Vec<PathBuf>,
+    filter: Option<Py<PyDict>>,
+    workers: Option<usize>,
+    format: String,
+    raise_on_error: bool,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    spawn(py, move |py| {
+        let filter = filter.map(|f| f.into_bound(py));
+        files::process_enr_files(
+            py,
+            paths,
+            filter.as_ref(),
+            workers,
+            &format,
+            raise_on_error,
+            progress,
+            cancel,
+        )
+    })
+}
+
+/// Awaitable variant of `decode_many`.
+#[pyfunction]
+#[pyo3(signature = (texts, *, strict=false, return_errors=false, workers=None, progress=None, cancel=None))]
+pub(crate) fn decode_many_async<'py>(
+    py: Python<'py>,
+    texts: Vec<PyBackedStr>,
+    strict: bool,
+    return_errors: bool,
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    spawn(py, move |py| {
+        batch::decode_many(py, texts, strict, return_errors, workers, progress, cancel)
+            .map(Bound::unbind)
+    })
+}
+
+/// Awaitable variant of `decode_many_bytes`.
+#[pyfunction]
+#[pyo3(signature = (data, *, strict=false, return_errors=false, workers=None, progress=None, cancel=None))]
+pub(crate) fn decode_many_bytes_async<'py>(
+    py: Python<'py>,
+    data: Vec<PyBackedBytes>,
+    strict: bool,
+    return_errors: bool,
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    spawn(py, move |py| {
+        batch::decode_many_bytes(py, data, strict, return_errors, workers, progress, cancel)
+            .map(Bound::unbind)
+    })
+}
+
+/// Awaitable variant of `probe_liveness`.
+#[pyfunction]
+#[pyo3(signature = (enrs, *, method="tcp".to_string(), concurrency=32, timeout=2.0, progress=None, cancel=None))]
+pub(crate) fn probe_liveness_async<'py>(
+    py: Python<'py>,
+    enrs: Vec<Py<Enr>>,
+    method: String,
+    concurrency: usize,
+    timeout: f64,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    spawn(py, move |py| {
+        let enrs = enrs
+            .iter()
+            .map(|e| e.bind(py).try_borrow())
+            .collect::<Result<_, _>>()?;
+        liveness::probe_liveness(py, enrs, &method, concurrency, timeout, progress, cancel)
+    })
+}
+
+/// Awaitable variant of `resolve_tree`.
+#[cfg(feature = "dns")]
+#[pyfunction]
+#[pyo3(signature = (url, *, nameserver=None, timeout=5.0, concurrency=16, follow_links=true, progress=None, cancel=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn resolve_tree_async<'py>(
+    py: Python<'py>,
+    url: String,
+    nameserver: Option<String>,
+    timeout: f64,
+    concurrency: usize,
+    follow_links: bool,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    spawn(py, move |py| {
+        crate::dnsdisc::resolve_tree(
+            py,
+            &url,
+            nameserver.as_deref(),
+            timeout,
+            concurrency,
+            follow_links,
+            progress,
+            cancel,
+        )
+    })
+}
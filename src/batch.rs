@@ -0,0 +1,627 @@
+//! Operations over many records at once, run in parallel with the GIL released.
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, RwLock};
+
+use enr::CombinedKey;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::pybacked::{PyBackedBytes, PyBackedStr};
+use pyo3::types::{PyBytes, PyDict, PyList};
+use rayon::prelude::*;
+use rayon::ThreadPool;
+
+use crate::logging::{self, Level};
+use crate::progress::{CancelToken, Progress};
+use crate::{decode_record_detached, decode_text, decode_text_detached, errors, eth2, net, Enr};
+
+/// Common predicates applied to each record by the batch APIs.
+#[derive(Default)]
+pub(crate) struct Predicate {
+    has_ip4: Option<bool>,
+    has_ip6: Option<bool>,
+    tcp4: Option<u16>,
+    udp4: Option<u16>,
+    fork_digest: Option<[u8; 4]>,
+    key_present: Vec<String>,
+}
+
+impl Predicate {
+    /// Builds a predicate from a dict using the keyword names of `filter_many`.
+    pub(crate) fn from_dict(filter: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let mut predicate = Predicate::default();
+        for (name, value) in filter.iter() {
+            match name.extract::<String>()?.as_str() {
+                "has_ip4" => predicate.has_ip4 = value.extract()?,
+                "has_ip6" => predicate.has_ip6 = value.extract()?,
+                "tcp4" => predicate.tcp4 = value.extract()?,
+                "udp4" => predicate.udp4 = value.extract()?,
+                "fork_digest" => {
+                    let digest: Option<Vec<u8>> = value.extract()?;
+                    predicate.fork_digest = parse_fork_digest(digest.as_deref())?;
+                }
+                "key_present" => {
+                    predicate.key_present =
+                        value.extract::<Option<Vec<String>>>()?.unwrap_or_default()
+                }
+                other => {
+                    return Err(PyValueError::new_err(format!("unknown filter {other:?}")));
+                }
+            }
+        }
+        Ok(predicate)
+    }
+
+    pub(crate) fn matches(&self, enr: &enr::Enr<CombinedKey>) -> bool {
+        if let Some(want) = self.has_ip4 {
+            if enr.ip4().is_some() != want {
+                return false;
+            }
+        }
+        if let Some(want) = self.has_ip6 {
+            if enr.ip6().is_some() != want {
+                return false;
+            }
+        }
+        if self.tcp4.is_some() && enr.tcp4() != self.tcp4 {
+            return false;
+        }
+        if self.udp4.is_some() && enr.udp4() != self.udp4 {
+            return false;
+        }
+        if self.fork_digest.is_some() && eth2::fork_digest(enr) != self.fork_digest {
+            return false;
+        }
+        self.key_present
+            .iter()
+            .all(|k| enr.get_raw_rlp(k).is_some())
+    }
+}
+
+/// Applies `f` to every record in parallel with the GIL released, preserving order.
+///
+/// The records stay borrowed for the duration of the call, so they are read in
+/// place rather than cloned. Each record counts as one unit of `progress`, and
+/// the job stops early if `cancel` is cancelled or on Ctrl-C.
+fn map_records<T, F>(
+    py: Python<'_>,
+    enrs: &[Bound<'_, Enr>],
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+    f: F,
+) -> PyResult<Vec<T>>
+where
+    T: Send,
+    F: Fn(&enr::Enr<CombinedKey>) -> T + Sync,
+{
+    // A record another thread is mutating raises instead of panicking.
+    let borrowed = enrs
+        .iter()
+        .map(|e| e.try_borrow())
+        .collect::<Result<Vec<PyRef<'_, Enr>>, _>>()?;
+    let records: Vec<&enr::Enr<CombinedKey>> = borrowed.iter().map(|e| &e.inner).collect();
+    let progress = Progress::new(progress, cancel, records.len());
+    let outcome = progress.run(py, || {
+        with_pool(workers, || {
+            records
+                .par_iter()
+                .map(|r| {
+                    let value = f(r);
+                    progress.advance(1).map(|()| value)
+                })
+                .collect()
+        })
+    })?;
+    progress.finish(py, outcome)
+}
+
+/// Pool installed by `set_num_threads`; `None` means rayon's global pool.
+static DEFAULT_POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+
+/// Pools built for per-call `workers` arguments, by thread count.
+///
+/// Kept for the life of the process, so repeated calls with the same
+/// `workers` reuse threads instead of spawning a pool each time. Only counts
+/// up to the number of CPUs are cached, which bounds the map; larger counts
+/// get a pool for the one call.
+static SIZED_POOLS: Mutex<BTreeMap<usize, Arc<ThreadPool>>> = Mutex::new(BTreeMap::new());
+
+fn build_pool(threads: usize) -> PyResult<ThreadPool> {
+    if threads == 0 {
+        return Err(PyValueError::new_err("workers must be at least 1"));
+    }
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+/// Runs `f` on the pool selected for a batch call.
+///
+/// `workers` picks a pool of that many threads, shared by later calls asking
+/// for the same count if it is no more than the number of CPUs; otherwise the
+/// pool configured by `set_num_threads` is used, falling back to rayon's
+/// global pool.
+pub(crate) fn with_pool<R, F>(workers: Option<usize>, f: F) -> PyResult<R>
+where
+    R: Send,
+    F: FnOnce() -> R + Send,
+{
+    if let Some(n) = workers {
+        return Ok(sized_pool(n)?.install(f));
+    }
+    let pool = DEFAULT_POOL
+        .read()
+        .expect("thread pool lock poisoned")
+        .clone();
+    Ok(match pool {
+        Some(pool) => pool.install(f),
+        None => f(),
+    })
+}
+
+/// Runs `f` on a pool of `threads` threads built for this call alone.
+///
+/// For network fan-out, whose threads spend their time blocked on I/O: they
+/// would otherwise hold up the batch pools, and a `concurrency` well past the
+/// CPU count is normal there. The threads exit once `f` returns.
+pub(crate) fn with_io_pool<R, F>(threads: usize, f: F) -> PyResult<R>
+where
+    R: Send,
+    F: FnOnce() -> R + Send,
+{
+    Ok(build_pool(threads)?.install(f))
+}
+
+/// Returns the pool of `threads` threads, shared if `threads` is no more
+/// than the number of CPUs and built for the caller alone otherwise.
+fn sized_pool(threads: usize) -> PyResult<Arc<ThreadPool>> {
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if threads > cpus {
+        return build_pool(threads).map(Arc::new);
+    }
+    let mut pools = SIZED_POOLS.lock().expect("thread pool lock poisoned");
+    if let Some(pool) = pools.get(&threads) {
+        return Ok(Arc::clone(pool));
+    }
+    let pool = Arc::new(build_pool(threads)?);
+    pools.insert(threads, Arc::clone(&pool));
+    Ok(pool)
+}
+
+/// Set the number of threads used by all batch operations.
+///
+/// Pass `None` to go back to rayon's default (one thread per CPU, or the
+/// `RAYON_NUM_THREADS` environment variable). A per-call `workers` argument
+/// still takes precedence.
+#[pyfunction]
+#[pyo3(signature = (n))]
+pub(crate) fn set_num_threads(n: Option<usize>) -> PyResult<()> {
+    let pool = n.map(build_pool).transpose()?.map(Arc::new);
+    *DEFAULT_POOL.write().expect("thread pool lock poisoned") = pool;
+    Ok(())
+}
+
+/// Number of threads batch operations currently run on by default.
+#[pyfunction]
+pub(crate) fn get_num_threads() -> usize {
+    match DEFAULT_POOL
+        .read()
+        .expect("thread pool lock poisoned")
+        .as_ref()
+    {
+        Some(pool) => pool.current_num_threads(),
+        None => rayon::current_num_threads(),
+    }
+}
+
+/// Keeps the highest-seq record per node id, in first-seen order of the node id.
+pub(crate) fn keep_highest_seq(
+    records: impl IntoIterator<Item = enr::Enr<CombinedKey>>,
+) -> Vec<enr::Enr<CombinedKey>> {
+    let mut by_node: HashMap<[u8; 32], usize> = HashMap::new();
+    let mut kept: Vec<enr::Enr<CombinedKey>> = Vec::new();
+    for record in records {
+        match by_node.entry(record.node_id().raw()) {
+            Entry::Vacant(slot) => {
+                slot.insert(kept.len());
+                kept.push(record);
+            }
+            Entry::Occupied(slot) => {
+                let best = &mut kept[*slot.get()];
+                if record.seq() > best.seq() {
+                    *best = record;
+                }
+            }
+        }
+    }
+    kept
+}
+
+fn parse_fork_digest(digest: Option<&[u8]>) -> PyResult<Option<[u8; 4]>> {
+    digest
+        .map(|d| {
+            <[u8; 4]>::try_from(d).map_err(|_| {
+                PyValueError::new_err(format!("fork digest must be 4 bytes, got {}", d.len()))
+            })
+        })
+        .transpose()
+}
+
+/// Returns the records matching all given predicates, preserving input order.
+///
+/// Predicates left as `None` are not applied. The matching runs in parallel
+/// with the GIL released, on `workers` threads if given. `progress`, if
+/// given, is called as `progress(done, total)` while records are matched;
+/// cancelling the `CancelToken` given as `cancel` stops the call, as does
+/// Ctrl-C, with every batch API.
+#[pyfunction]
+#[pyo3(signature = (enrs, *, has_ip4=None, has_ip6=None, tcp4=None, udp4=None, fork_digest=None, key_present=None, workers=None, progress=None, cancel=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn filter_many<'py>(
+    py: Python<'py>,
+    enrs: Vec<Bound<'py, Enr>>,
+    has_ip4: Option<bool>,
+    has_ip6: Option<bool>,
+    tcp4: Option<u16>,
+    udp4: Option<u16>,
+    fork_digest: Option<&[u8]>,
+    key_present: Option<Vec<String>>,
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Vec<Bound<'py, Enr>>> {
+    let predicate = Predicate {
+        has_ip4,
+        has_ip6,
+        tcp4,
+        udp4,
+        fork_digest: parse_fork_digest(fork_digest)?,
+        key_present: key_present.unwrap_or_default(),
+    };
+
+    let keep = map_records(py, &enrs, workers, progress, cancel, |r| {
+        predicate.matches(r)
+    })?;
+
+    Ok(enrs
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(enr, keep)| keep.then_some(enr))
+        .collect())
+}
+
+/// Returns the records advertising at least one publicly routable address,
+/// preserving input order.
+#[pyfunction]
+#[pyo3(signature = (enrs, *, workers=None, progress=None, cancel=None))]
+pub(crate) fn filter_routable<'py>(
+    py: Python<'py>,
+    enrs: Vec<Bound<'py, Enr>>,
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Vec<Bound<'py, Enr>>> {
+    let keep = map_records(
+        py,
+        &enrs,
+        workers,
+        progress,
+        cancel,
+        net::has_routable_address,
+    )?;
+    Ok(enrs
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(enr, keep)| keep.then_some(enr))
+        .collect())
+}
+
+/// Groups records by the fork digest of their `eth2` field.
+///
+/// Returns a dict mapping each 4-byte digest to its records, in first-seen
+/// order. Records without a well-formed `eth2` field are grouped under `None`.
+#[pyfunction]
+#[pyo3(signature = (enrs, *, workers=None, progress=None, cancel=None))]
+pub(crate) fn group_by_fork_digest<'py>(
+    py: Python<'py>,
+    enrs: Vec<Bound<'py, Enr>>,
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let digests = map_records(py, &enrs, workers, progress, cancel, eth2::fork_digest)?;
+
+    let groups = PyDict::new(py);
+    for (enr, digest) in enrs.into_iter().zip(digests) {
+        let group_key = digest.map(|d| PyBytes::new(py, &d));
+        match groups.get_item(&group_key)? {
+            Some(group) => group.cast_into::<PyList>()?.append(enr)?,
+            None => groups.set_item(group_key, PyList::new(py, [enr])?)?,
+        }
+    }
+    Ok(groups)
+}
+
+/// The node ids of `enrs` packed back to back into one `bytes` object, 32
+/// bytes each, for bulk exports such as `numpy.frombuffer(ids, "S32")`.
+///
+/// Each id is written straight into the result from the record, where the
+/// `node_id` getter would create one `bytes` object per record.
+#[pyfunction]
+pub(crate) fn node_ids<'py>(
+    py: Python<'py>,
+    enrs: Vec<PyRef<'py, Enr>>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    PyBytes::new_with(py, enrs.len() * 32, |buf| {
+        for (slot, enr) in buf.chunks_exact_mut(32).zip(&enrs) {
+            slot.copy_from_slice(&enr.inner.node_id().raw());
+        }
+        Ok(())
+    })
+}
+
+/// Result of [`dedupe_report`]: the surviving records plus data-quality counters.
+#[pyclass(name = "DedupeReport", frozen, get_all)]
+pub(crate) struct DedupeReport {
+    /// One record per node id (the highest seq seen), in first-seen order.
+    records: Vec<Py<Enr>>,
+    /// Number of input records dropped because a record for the same node id was kept.
+    duplicates_removed: usize,
+    /// Records sharing node id and seq with a kept record but carrying different content.
+    seq_conflicts: usize,
+    /// Kept records advertising the IP address and UDP port of an earlier
+    /// kept record, that is the same endpoint under another node id.
+    endpoint_collisions: usize,
+}
+
+#[pymethods]
+impl DedupeReport {
+    fn __len__(&self) -> usize {
+        self.records.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "DedupeReport(records={}, duplicates_removed={}, seq_conflicts={}, endpoint_collisions={})",
+            self.records.len(),
+            self.duplicates_removed,
+            self.seq_conflicts,
+            self.endpoint_collisions
+        )
+    }
+}
+
+/// Identity of a record as far as deduplication is concerned.
+struct RecordIdentity {
+    node_id: [u8; 32],
+    seq: u64,
+    signature: Vec<u8>,
+    endpoints: Vec<SocketAddr>,
+}
+
+impl RecordIdentity {
+    fn of(enr: &enr::Enr<CombinedKey>) -> Self {
+        RecordIdentity {
+            node_id: enr.node_id().raw(),
+            seq: enr.seq(),
+            signature: enr.signature().to_vec(),
+            endpoints: [
+                enr.udp4_socket().map(SocketAddr::V4),
+                enr.udp6_socket().map(SocketAddr::V6),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+        }
+    }
+}
+
+/// Deduplicates records by node id, keeping the highest seq for each node.
+///
+/// Alongside the surviving records, reports how many duplicates were removed,
+/// how many records conflicted with a kept record at the same seq, and how
+/// many kept records advertise the UDP endpoint of another kept node. Non-zero
+/// conflict and collision counts are also logged at `WARNING` on the `pyenr`
+/// logger.
+#[pyfunction]
+#[pyo3(signature = (enrs, *, workers=None, progress=None, cancel=None))]
+pub(crate) fn dedupe_report(
+    py: Python<'_>,
+    enrs: Vec<Bound<'_, Enr>>,
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<DedupeReport> {
+    let identities = map_records(py, &enrs, workers, progress, cancel, RecordIdentity::of)?;
+
+    // Index into `kept` by node id.
+    let mut by_node: HashMap<[u8; 32], usize> = HashMap::with_capacity(enrs.len());
+    let mut kept: Vec<(usize, &RecordIdentity)> = Vec::new();
+    let mut seq_conflicts = 0;
+
+    for (idx, ident) in identities.iter().enumerate() {
+        match by_node.entry(ident.node_id) {
+            Entry::Vacant(slot) => {
+                slot.insert(kept.len());
+                kept.push((idx, ident));
+            }
+            Entry::Occupied(slot) => {
+                let best = &mut kept[*slot.get()];
+                if ident.seq > best.1.seq {
+                    *best = (idx, ident);
+                } else if ident.seq == best.1.seq && ident.signature != best.1.signature {
+                    seq_conflicts += 1;
+                }
+            }
+        }
+    }
+
+    if seq_conflicts > 0 {
+        logging::log(py, Level::Warning, || {
+            format!("{seq_conflicts} record(s) share node id and seq with a kept record but differ in content")
+        })?;
+    }
+    // Counted over the survivors only: a stale record losing to a newer one
+    // from the same node is not a collision.
+    let mut endpoints = HashSet::new();
+    let endpoint_collisions = kept
+        .iter()
+        .filter(|(_, ident)| {
+            let mut seen = false;
+            for endpoint in &ident.endpoints {
+                seen |= !endpoints.insert(*endpoint);
+            }
+            seen
+        })
+        .count();
+    if endpoint_collisions > 0 {
+        logging::log(py, Level::Warning, || {
+            format!("{endpoint_collisions} record(s) advertise the UDP endpoint of another node")
+        })?;
+    }
+
+    Ok(DedupeReport {
+        duplicates_removed: enrs.len() - kept.len(),
+        records: kept
+            .iter()
+            .map(|(idx, _)| enrs[*idx].clone().unbind())
+            .collect(),
+        seq_conflicts,
+        endpoint_collisions,
+    })
+}
+
+/// Decodes `items` in parallel with the GIL released, for `decode_many`
+/// and `decode_many_bytes`.
+///
+/// `detached` decodes one item on a worker thread, giving `None` if it
+/// cannot. Those items are decoded again by `attached` with the GIL held,
+/// which raises the error a single-record call would. Returns the records
+/// in input order, raising the error of the first undecodable item with
+/// its position as `index`, or with `return_errors`, a tuple of the
+/// records and the `(index, error)` pairs of the items left out.
+#[allow(clippy::too_many_arguments)]
+fn decode_all<'py, T, D, A>(
+    py: Python<'py>,
+    items: &[T],
+    return_errors: bool,
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+    detached: D,
+    attached: A,
+) -> PyResult<Bound<'py, PyAny>>
+where
+    T: Sync,
+    D: Fn(&T) -> Option<enr::Enr<CombinedKey>> + Sync,
+    A: Fn(&T) -> PyResult<Enr>,
+{
+    let progress = Progress::new(progress, cancel, items.len());
+    let outcome = progress.run(py, || {
+        with_pool(workers, || {
+            items
+                .par_iter()
+                .map(|item| {
+                    let decoded = errors::catch_panic(|| detached(item)).ok().flatten();
+                    progress.advance(1).map(|()| decoded)
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+    })?;
+    let decoded = progress.finish(py, outcome)?;
+
+    let mut records = Vec::with_capacity(decoded.len());
+    let mut failures = Vec::new();
+    for (index, (item, decoded)) in items.iter().zip(decoded).enumerate() {
+        let decoded = match decoded {
+            Some(inner) => Ok(Enr { inner }),
+            None => attached(item),
+        };
+        match decoded {
+            Ok(enr) => records.push(enr),
+            Err(err) => {
+                // Setting attributes on a fresh exception only fails on memory exhaustion.
+                let _ = err.value(py).setattr("index", index);
+                if !return_errors {
+                    return Err(err);
+                }
+                failures.push((index, err.into_value(py)));
+            }
+        }
+    }
+    if return_errors {
+        Ok((records, failures).into_pyobject(py)?.into_any())
+    } else {
+        Ok(records.into_pyobject(py)?.into_any())
+    }
+}
+
+/// Decode many records from their text form at once.
+///
+/// Takes each of `texts` as `Enr.from_base64` does, or as
+/// `Enr.from_bytes(..., strict=True)` checks them with `strict`, decoding in
+/// parallel with the GIL released. Returns the records in input order.
+/// An undecodable text raises the same error `Enr.from_base64` would, with
+/// the text's position in `texts` as its `index` attribute; the first one
+/// in input order is raised. With `return_errors=True`, returns
+/// `(records, errors)` instead, where `errors` lists the `(index, error)`
+/// of each text left out of `records`.
+#[pyfunction]
+#[pyo3(signature = (texts, *, strict=false, return_errors=false, workers=None, progress=None, cancel=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn decode_many<'py>(
+    py: Python<'py>,
+    texts: Vec<PyBackedStr>,
+    strict: bool,
+    return_errors: bool,
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    decode_all(
+        py,
+        &texts,
+        return_errors,
+        workers,
+        progress,
+        cancel,
+        |text| {
+            let data = decode_text_detached(text).ok()?;
+            decode_record_detached(&data, strict).ok()
+        },
+        |text| Enr::from_bytes(py, &decode_text(py, text)?, strict, false),
+    )
+}
+
+/// Decode many records from their RLP encodings at once.
+///
+/// The bytes counterpart of `decode_many`, taking each item as
+/// `Enr.from_bytes` does, with the same `strict`, `return_errors`,
+/// `workers`, `progress` and `cancel` options.
+#[pyfunction]
+#[pyo3(signature = (data, *, strict=false, return_errors=false, workers=None, progress=None, cancel=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn decode_many_bytes<'py>(
+    py: Python<'py>,
+    data: Vec<PyBackedBytes>,
+    strict: bool,
+    return_errors: bool,
+    workers: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    decode_all(
+        py,
+        &data,
+        return_errors,
+        workers,
+        progress,
+        cancel,
+        |item| decode_record_detached(item, strict).ok(),
+        |item| Enr::from_bytes(py, item, strict, false),
+    )
+}
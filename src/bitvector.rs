@@ -0,0 +1,22 @@
+//! SSZ bitvectors, as used for subnet subscriptions: bit `i` is bit `i % 8`
+//! (least significant first) of byte `i / 8`.
+
+/// Indices of the set bits, in increasing order.
+pub(crate) fn indices(bits: &[u8]) -> Vec<usize> {
+    (0..bits.len() * 8)
+        .filter(|i| bits[i / 8] & (1 << (i % 8)) != 0)
+        .collect()
+}
+
+/// A bitvector of `len` bits with `indices` set, or the first index that does
+/// not fit.
+pub(crate) fn from_indices(indices: &[usize], len: usize) -> Result<Vec<u8>, usize> {
+    let mut bits = vec![0u8; len.div_ceil(8)];
+    for &i in indices {
+        if i >= len {
+            return Err(i);
+        }
+        bits[i / 8] |= 1 << (i % 8);
+    }
+    Ok(bits)
+}
@@ -0,0 +1,191 @@
+//! Probabilistic membership set for node ids.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::batch::with_pool;
+use crate::Enr;
+
+/// Either a record or a raw 32-byte node id.
+#[derive(FromPyObject)]
+pub(crate) enum NodeIdArg<'py> {
+    Enr(PyRef<'py, Enr>),
+    Bytes(Vec<u8>),
+}
+
+impl NodeIdArg<'_> {
+    pub(crate) fn node_id(&self) -> PyResult<[u8; 32]> {
+        match self {
+            NodeIdArg::Enr(enr) => Ok(enr.inner.node_id().raw()),
+            NodeIdArg::Bytes(bytes) => <[u8; 32]>::try_from(bytes.as_slice()).map_err(|_| {
+                PyValueError::new_err(format!("node id must be 32 bytes, got {}", bytes.len()))
+            }),
+        }
+    }
+}
+
+/// A Bloom filter over node ids.
+///
+/// Node ids are keccak256 digests, so their bits are already uniformly
+/// distributed and are used directly as the two base hashes for double
+/// hashing. Bits are stored in atomics, so one filter can be shared and
+/// updated from several threads.
+#[pyclass(name = "NodeIdFilter", frozen)]
+pub(crate) struct NodeIdFilter {
+    bits: Vec<AtomicU64>,
+    num_bits: u64,
+    num_hashes: u32,
+    capacity: usize,
+    false_positive_rate: f64,
+    inserted: AtomicUsize,
+}
+
+impl NodeIdFilter {
+    fn bit_indices(&self, node_id: &[u8; 32]) -> impl Iterator<Item = u64> + '_ {
+        let mut h1 = [0u8; 8];
+        let mut h2 = [0u8; 8];
+        h1.copy_from_slice(&node_id[..8]);
+        h2.copy_from_slice(&node_id[8..16]);
+        let h1 = u64::from_le_bytes(h1);
+        let h2 = u64::from_le_bytes(h2) | 1;
+        (0..u64::from(self.num_hashes))
+            .map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+
+    fn insert(&self, node_id: &[u8; 32]) -> bool {
+        let mut added = false;
+        for bit in self.bit_indices(node_id) {
+            let mask = 1u64 << (bit % 64);
+            let prev = self.bits[(bit / 64) as usize].fetch_or(mask, Ordering::Relaxed);
+            added |= prev & mask == 0;
+        }
+        if added {
+            self.inserted.fetch_add(1, Ordering::Relaxed);
+        }
+        added
+    }
+
+    fn check(&self, node_id: &[u8; 32]) -> bool {
+        self.bit_indices(node_id).all(|bit| {
+            self.bits[(bit / 64) as usize].load(Ordering::Relaxed) & (1u64 << (bit % 64)) != 0
+        })
+    }
+}
+
+fn collect_node_ids(items: &[NodeIdArg<'_>]) -> PyResult<Vec<[u8; 32]>> {
+    items.iter().map(NodeIdArg::node_id).collect()
+}
+
+#[pymethods]
+impl NodeIdFilter {
+    /// Create a filter sized for `capacity` node ids at the given false-positive rate.
+    #[new]
+    #[pyo3(signature = (capacity, false_positive_rate=0.01))]
+    fn new(capacity: usize, false_positive_rate: f64) -> PyResult<Self> {
+        if capacity == 0 {
+            return Err(PyValueError::new_err("capacity must be positive"));
+        }
+        if !(false_positive_rate > 0.0 && false_positive_rate < 1.0) {
+            return Err(PyValueError::new_err(
+                "false_positive_rate must be between 0 and 1",
+            ));
+        }
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-(capacity as f64) * false_positive_rate.ln() / (ln2 * ln2))
+            .ceil()
+            .max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / capacity as f64) * ln2).round().max(1.0) as u32;
+        let words = num_bits.div_ceil(64) as usize;
+        Ok(NodeIdFilter {
+            bits: (0..words).map(|_| AtomicU64::new(0)).collect(),
+            num_bits,
+            num_hashes,
+            capacity,
+            false_positive_rate,
+            inserted: AtomicUsize::new(0),
+        })
+    }
+
+    /// Add a node id (or a record's node id). Returns `False` if it was probably already present.
+    fn add(&self, node_id: NodeIdArg<'_>) -> PyResult<bool> {
+        Ok(self.insert(&node_id.node_id()?))
+    }
+
+    /// Returns `True` if the node id was probably added, `False` if it definitely was not.
+    fn contains(&self, node_id: NodeIdArg<'_>) -> PyResult<bool> {
+        Ok(self.check(&node_id.node_id()?))
+    }
+
+    fn __contains__(&self, node_id: NodeIdArg<'_>) -> PyResult<bool> {
+        self.contains(node_id)
+    }
+
+    /// Add many node ids in parallel. Returns how many were not already present.
+    #[pyo3(signature = (node_ids, *, workers=None))]
+    fn add_many(
+        &self,
+        py: Python<'_>,
+        node_ids: Vec<NodeIdArg<'_>>,
+        workers: Option<usize>,
+    ) -> PyResult<usize> {
+        let ids = collect_node_ids(&node_ids)?;
+        py.detach(|| {
+            with_pool(workers, || {
+                ids.par_iter().filter(|id| self.insert(id)).count()
+            })
+        })
+    }
+
+    /// Check many node ids in parallel, returning one flag per input.
+    #[pyo3(signature = (node_ids, *, workers=None))]
+    fn contains_many(
+        &self,
+        py: Python<'_>,
+        node_ids: Vec<NodeIdArg<'_>>,
+        workers: Option<usize>,
+    ) -> PyResult<Vec<bool>> {
+        let ids = collect_node_ids(&node_ids)?;
+        py.detach(|| {
+            with_pool(workers, || {
+                ids.par_iter().map(|id| self.check(id)).collect()
+            })
+        })
+    }
+
+    /// Approximate number of distinct node ids added so far.
+    fn __len__(&self) -> usize {
+        self.inserted.load(Ordering::Relaxed)
+    }
+
+    #[getter]
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[getter]
+    fn false_positive_rate(&self) -> f64 {
+        self.false_positive_rate
+    }
+
+    #[getter]
+    fn num_bits(&self) -> u64 {
+        self.num_bits
+    }
+
+    #[getter]
+    fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "NodeIdFilter(capacity={}, false_positive_rate={}, len={})",
+            self.capacity,
+            self.false_positive_rate,
+            self.__len__()
+        )
+    }
+}
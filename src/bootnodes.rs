@@ -0,0 +1,394 @@
+//! Bootnode lists as clients ship them: YAML or TOML config files, and
+//! plain lists of one record per line; plus a few well-known sets built in.
+
+use std::path::{Path, PathBuf};
+#[cfg(feature = "http")]
+use std::time::Duration;
+
+#[cfg(feature = "http")]
+use pyo3::exceptions::PyConnectionError;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+#[cfg(feature = "yaml")]
+use yaml_rust2::{Yaml, YamlLoader};
+
+use crate::enode::Enode;
+use crate::errors::DecodeError;
+use crate::Enr;
+
+/// Well-known bootnodes: network, layer, and the entries in list order.
+///
+/// Only entries that can be checked are included. Consensus records are
+/// signed, and their `eth2` field names the network's fork version;
+/// execution entries are `enode://` URLs whose keys are validated on
+/// decode. The tests re-check both.
+const KNOWN: &[(&str, &str, &[&str])] = &[
+    (
+        "mainnet",
+        "consensus",
+        &[
+            // Teku team (Consensys)
+            "enr:-KG4QNTx85fjxABbSq_Rta9wy56nQ1fHK0PewJbGjLm1M4bMGx5-3Qq4ZX2-iFJ0pys_O90sVXNNOxp2E7afBsGsBrgDhGV0aDKQu6TalgMAAAD__________4JpZIJ2NIJpcIQEnfA2iXNlY3AyNTZrMaECGXWQ-rQ2KZKRH1aOW4IlPDBkY4XDphxg9pxKytFCkayDdGNwgiMog3VkcIIjKA",
+            // Lighthouse team (Sigma Prime)
+            "enr:-Le4QPUXJS2BTORXxyx2Ia-9ae4YqA_JWX3ssj4E_J-3z1A-HmFGrU8BpvpqhNabayXeOZ2Nq_sbeDgtzMJpLLnXFgAChGV0aDKQtTA_KgEAAAAAIgEAAAAAAIJpZIJ2NIJpcISsaa0Zg2lwNpAkAIkHAAAAAPA8kv_-awoTiXNlY3AyNTZrMaEDHAD2JKYevx89W0CcFJFiskdcEzkH_Wdv9iW42qLK79ODdWRwgiMohHVkcDaCI4I",
+            // EF
+            "enr:-Ku4QHqVeJ8PPICcWk1vSn_XcSkjOkNiTg6Fmii5j6vUQgvzMc9L1goFnLKgXqBJspJjIsB91LTOleFmyWWrFVATGngBh2F0dG5ldHOIAAAAAAAAAACEZXRoMpC1MD8qAAAAAP__________gmlkgnY0gmlwhAMRHkWJc2VjcDI1NmsxoQKLVXFOhp2uX6jeT0DvvDpPcU8FWMjQdR4wMuORMhpX24N1ZHCCIyg",
+            // Nimbus team (Status)
+            "enr:-LK4QA8FfhaAjlb_BXsXxSfiysR7R52Nhi9JBt4F8SPssu8hdE1BXQQEtVDC3qStCW60LSO7hEsVHv5zm8_6Vnjhcn0Bh2F0dG5ldHOIAAAAAAAAAACEZXRoMpC1MD8qAAAAAP__________gmlkgnY0gmlwhAN4aBKJc2VjcDI1NmsxoQJerDhsJ-KxZ8sHySMOCmTO6sHM3iCFQ6VMvLTe948MyYN0Y3CCI4yDdWRwgiOM",
+        ],
+    ),
+    (
+        "mainnet",
+        "execution",
+        &[
+            "enode://d860a01f9722d78051619d1e2351aba3f43f943f6f00718d1b9baa4101932a1f5011f16bb2b1bb35db20d6fe28fa0bf09636d26a87d31de9ec6203eeedb1f666@18.138.108.67:30303",
+            "enode://22a8232c3abc76a16ae9d6c3b164f98775fe226f0917b0ca871128a74a8e9630b458460865bab457221f1d448dd9791d24c4e5d88786180ac185df813a68d4de@3.209.45.79:30303",
+            "enode://2b252ab6a1d0f971d9722cb839a42cb81db019ba44c08754628ab4a823487071b5695317c8ccd085219c3a03af063495b2f1da8d18218da2d6a82981b45e6ffc@65.108.70.101:30303",
+            "enode://4aeb4ab6c14b23e2c4cfdce879c04b0748a20d8e9b59e25ded2a08143e265c6c25936e74cbc8e641e3312ca288673d91f2f93f8e277de3cfa444ecdaaf982052@157.90.35.166:30303",
+        ],
+    ),
+    (
+        "sepolia",
+        "execution",
+        &[
+            "enode://4e5e92199ee224a01932a377160aa432f31d0b351f84ab413a8e0a42f4f36476f8fb1cbe914af0d9aef0d51665c214cf653c651c4bbd9d5550a934f241f1682b@138.197.51.181:30303",
+            "enode://143e11fb766781d22d92a2e33f8f104cddae4411a122295ed1fdb6638de96a6ce65f5b7c964ba3763bba27961738fef7d3ecc739268f3e5e771fb4c87b6234ba@146.190.1.103:30303",
+            "enode://8b61dc2d06c3f96fddcbebb0efb29d60d3598650275dc469c22229d3e5620369b0d3dedafd929835fe7f489618f19f456fe7c0df572bf2d914a9f4e006f783a9@170.64.250.88:30303",
+            "enode://10d62eff032205fcef19497f35ca8477bea0eadfff6d769a147e895d8b2b8f8ae6341630c645c30f5df6e67547c03494ced3d9c5764e8622a26587b083b028e8@139.59.49.206:30303",
+            "enode://9e9492e2e8836114cc75f5b929784f4f46c324ad01daf87d956f98b3b6c5fcba95524d6e5cf9861dc96a2c8a171ea7105bb554a197455058de185fa870970c7c@138.68.123.152:30303",
+        ],
+    ),
+    (
+        "holesky",
+        "consensus",
+        &[
+            // EF
+            "enr:-Ku4QFo-9q73SspYI8cac_4kTX7yF800VXqJW4Lj3HkIkb5CMqFLxciNHePmMt4XdJzHvhrCC5ADI4D_GkAsxGJRLnQBh2F0dG5ldHOIAAAAAAAAAACEZXRoMpAhnTT-AQFwAP__________gmlkgnY0gmlwhLKAiOmJc2VjcDI1NmsxoQORcM6e19T1T9gi7jxEZjk_sjVLGFscUNqAY9obgZaxbIN1ZHCCIyk",
+            "enr:-Ku4QPG7F72mbKx3gEQEx07wpYYusGDh-ni6SNkLvOS-hhN-BxIggN7tKlmalb0L5JPoAfqD-akTZ-gX06hFeBEz4WoBh2F0dG5ldHOIAAAAAAAAAACEZXRoMpAhnTT-AQFwAP__________gmlkgnY0gmlwhJK-DYCJc2VjcDI1NmsxoQKLVXFOhp2uX6jeT0DvvDpPcU8FWMjQdR4wMuORMhpX24N1ZHCCIyk",
+        ],
+    ),
+    (
+        "holesky",
+        "execution",
+        &[
+            "enode://ac906289e4b7f12df423d654c5a962b6ebe5b3a74cc9e06292a85221f9a64a6f1cfdd6b714ed6dacef51578f92b34c60ee91e9ede9c7f8fadc4d347326d95e2b@146.190.13.128:30303",
+            "enode://a3435a0155a3e837c02f5e7f5662a2f1fbc25b48e4dc232016e1c51b544cb5b4510ef633ea3278c0e970fa8ad8141e2d4d0f9f95456c537ff05fdf9b31c15072@178.128.136.233:30303",
+        ],
+    ),
+];
+
+/// Syntax of a bootnode list.
+#[derive(Clone, Copy)]
+pub(crate) enum Format {
+    /// One record per line; blank lines and `#` comments are skipped. Only
+    /// downloaded lists use it.
+    #[cfg_attr(not(feature = "http"), allow(dead_code))]
+    Lines,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+impl Format {
+    fn from_name(name: Option<&str>, path: &Path) -> PyResult<Self> {
+        let name = match name {
+            Some(name) => name,
+            None => match path.extension().and_then(|e| e.to_str()) {
+                Some("yaml" | "yml") => "yaml",
+                Some("toml") => "toml",
+                _ => {
+                    return Err(PyValueError::new_err(format!(
+                        "cannot tell the format of {} from its extension, pass format=",
+                        path.display()
+                    )))
+                }
+            },
+        };
+        match name {
+            #[cfg(feature = "yaml")]
+            "yaml" => Ok(Format::Yaml),
+            #[cfg(not(feature = "yaml"))]
+            "yaml" => Err(crate::features::missing("YAML bootnode files", "yaml")),
+            #[cfg(feature = "toml")]
+            "toml" => Ok(Format::Toml),
+            #[cfg(not(feature = "toml"))]
+            "toml" => Err(crate::features::missing("TOML bootnode files", "toml")),
+            other => Err(PyValueError::new_err(format!(
+                "unknown format {other:?}, expected 'yaml' or 'toml'"
+            ))),
+        }
+    }
+
+    /// The format of a downloaded list named by `format=`, if any.
+    #[cfg(feature = "http")]
+    fn for_download(name: Option<&str>) -> PyResult<Option<Self>> {
+        match name {
+            None => Ok(None),
+            Some("lines") => Ok(Some(Format::Lines)),
+            #[cfg(feature = "yaml")]
+            Some("yaml") => Ok(Some(Format::Yaml)),
+            #[cfg(not(feature = "yaml"))]
+            Some("yaml") => Err(crate::features::missing("YAML bootnode lists", "yaml")),
+            Some(other) => Err(PyValueError::new_err(format!(
+                "unknown format {other:?}, expected 'lines' or 'yaml'"
+            ))),
+        }
+    }
+
+    /// Guesses the format of a downloaded list: YAML for a `.yaml`/`.yml`
+    /// URL or a body made of `- ` items, otherwise lines.
+    #[cfg(feature = "http")]
+    fn sniff(url: &str, body: &str) -> PyResult<Self> {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let yaml_items = body
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .any(|l| l.starts_with("- "));
+        if path.ends_with(".yaml") || path.ends_with(".yml") || yaml_items {
+            #[cfg(feature = "yaml")]
+            return Ok(Format::Yaml);
+            #[cfg(not(feature = "yaml"))]
+            return Err(crate::features::missing("YAML bootnode lists", "yaml"));
+        }
+        Ok(Format::Lines)
+    }
+}
+
+/// Every string in the document that looks like a node address, with its
+/// location (such as `bootnodes[2]`). In the lines format every line that
+/// is not blank or a comment is taken, located as `line 3`.
+pub(crate) fn entries(text: &str, format: Format) -> Result<Vec<(String, String)>, String> {
+    let mut out = Vec::new();
+    match format {
+        Format::Lines => {
+            for (i, line) in text.lines().enumerate() {
+                let line = line.trim();
+                if !line.is_empty() && !line.starts_with('#') {
+                    out.push((format!("line {}", i + 1), line.to_string()));
+                }
+            }
+        }
+        #[cfg(feature = "yaml")]
+        Format::Yaml => {
+            let docs = YamlLoader::load_from_str(text).map_err(|e| e.to_string())?;
+            for doc in &docs {
+                walk_yaml(doc, String::new(), &mut out);
+            }
+        }
+        #[cfg(feature = "toml")]
+        Format::Toml => {
+            let doc: toml::Table = text
+                .parse()
+                .map_err(|e: toml::de::Error| e.message().to_string())?;
+            for (key, value) in &doc {
+                walk_toml(value, key.clone(), &mut out);
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(any(feature = "yaml", feature = "toml"))]
+fn push_entry(location: String, text: &str, out: &mut Vec<(String, String)>) {
+    let text = text.trim();
+    if text.starts_with("enr:") || text.starts_with("enode://") {
+        out.push((location, text.to_string()));
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn walk_yaml(value: &Yaml, location: String, out: &mut Vec<(String, String)>) {
+    match value {
+        Yaml::String(text) => push_entry(location, text, out),
+        Yaml::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                walk_yaml(item, format!("{location}[{i}]"), out);
+            }
+        }
+        Yaml::Hash(map) => {
+            for (key, item) in map {
+                let key = match key {
+                    Yaml::String(key) => key.clone(),
+                    other => format!("{other:?}"),
+                };
+                walk_yaml(item, join(&location, &key), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(feature = "toml")]
+fn walk_toml(value: &toml::Value, location: String, out: &mut Vec<(String, String)>) {
+    match value {
+        toml::Value::String(text) => push_entry(location, text, out),
+        toml::Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                walk_toml(item, format!("{location}[{i}]"), out);
+            }
+        }
+        toml::Value::Table(table) => {
+            for (key, item) in table {
+                walk_toml(item, join(&location, key), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(any(feature = "yaml", feature = "toml"))]
+fn join(location: &str, key: &str) -> String {
+    if location.is_empty() {
+        key.to_string()
+    } else {
+        format!("{location}.{key}")
+    }
+}
+
+/// Decodes one entry to an `Enode` if it is an `enode://` URL, otherwise to
+/// an `Enr`.
+fn decode(py: Python<'_>, text: &str) -> PyResult<Py<PyAny>> {
+    if text.starts_with("enode://") {
+        let enode = Enode::parse(text).map_err(DecodeError::new_err)?;
+        Ok(Py::new(py, enode)?.into_any())
+    } else {
+        Ok(Py::new(py, Enr::from_base64(py, text, false)?)?.into_any())
+    }
+}
+
+/// Decodes one entry, naming `source` and `location` in the error if it is
+/// invalid.
+pub(crate) fn decode_entry(
+    py: Python<'_>,
+    source: &str,
+    location: &str,
+    text: &str,
+) -> PyResult<Py<PyAny>> {
+    decode(py, text)
+        .map_err(|err| DecodeError::new_err(format!("{source}: {location}: {}", err.value(py))))
+}
+
+/// Read the bootnodes listed in a YAML or TOML config file.
+///
+/// Every string in the file starting with `enr:` or `enode://` is taken,
+/// wherever it appears (a top-level list, or a list under any key), and
+/// returned in file order as an `Enr` or `Enode`. The format follows the
+/// extension (`.yaml`, `.yml`, `.toml`) unless `format` is given. Raises
+/// `DecodeError` naming the location of the first invalid entry.
+#[pyfunction]
+#[pyo3(signature = (path, *, format=None))]
+pub(crate) fn load_bootnodes(
+    py: Python<'_>,
+    path: PathBuf,
+    format: Option<&str>,
+) -> PyResult<Vec<Py<PyAny>>> {
+    let format = Format::from_name(format, &path)?;
+    let text = std::fs::read_to_string(&path)?;
+    let source = path.display().to_string();
+    let entries =
+        entries(&text, format).map_err(|e| DecodeError::new_err(format!("{source}: {e}")))?;
+    entries
+        .iter()
+        .map(|(location, text)| decode_entry(py, &source, location, text))
+        .collect()
+}
+
+/// Decoded records and `(location, message)` for each entry that failed.
+#[cfg(feature = "http")]
+type Fetched = (Vec<Py<PyAny>>, Vec<(String, String)>);
+
+/// Download a bootnode list over HTTP(S) and decode every entry.
+///
+/// The list is either one record per line (`enr:` text, with or without the
+/// prefix, or `enode://` URLs; blank lines and `#` comments are skipped) or
+/// YAML, picked by `format` (`"lines"` or `"yaml"`) or else guessed from
+/// the URL and the body. The download runs with the GIL released and fails
+/// with `ConnectionError` after `timeout` seconds or on an HTTP error.
+///
+/// Returns `(records, errors)`: the `Enr` and `Enode` objects decoded, in
+/// list order, and a `(location, message)` pair for each entry that failed,
+/// where the location is like `"line 3"`.
+#[cfg(feature = "http")]
+#[pyfunction]
+#[pyo3(signature = (url, *, format=None, timeout=30.0))]
+pub(crate) fn fetch_enr_list(
+    py: Python<'_>,
+    url: &str,
+    format: Option<&str>,
+    timeout: f64,
+) -> PyResult<Fetched> {
+    let format = Format::for_download(format)?;
+    let timeout = Duration::try_from_secs_f64(timeout)
+        .map_err(|_| PyValueError::new_err("timeout must be a non-negative number of seconds"))?;
+    let body = py
+        .detach(|| {
+            let agent = ureq::AgentBuilder::new().timeout(timeout).build();
+            agent
+                .get(url)
+                .call()
+                .map_err(|e| e.to_string())?
+                .into_string()
+                .map_err(|e| e.to_string())
+        })
+        .map_err(PyConnectionError::new_err)?;
+
+    let format = match format {
+        Some(format) => format,
+        None => Format::sniff(url, &body)?,
+    };
+    let entries =
+        entries(&body, format).map_err(|e| DecodeError::new_err(format!("{url}: {e}")))?;
+    let mut records = Vec::with_capacity(entries.len());
+    let mut errors = Vec::new();
+    for (location, text) in &entries {
+        match decode(py, text) {
+            Ok(record) => records.push(record),
+            Err(err) => errors.push((location.clone(), err.value(py).to_string())),
+        }
+    }
+    Ok((records, errors))
+}
+
+/// The built-in bootnodes of a well-known network.
+///
+/// `network` is `"mainnet"`, `"sepolia"` or `"holesky"`. `layer` selects
+/// the `"consensus"` layer (discv5 `Enr` records) or the `"execution"`
+/// layer (`Enode` URLs); by default both are returned, consensus first.
+/// Raises `ValueError` for an unknown network or layer, or if no set for
+/// that layer is built in.
+#[pyfunction]
+#[pyo3(signature = (network, *, layer=None))]
+pub(crate) fn known_bootnodes(
+    py: Python<'_>,
+    network: &str,
+    layer: Option<&str>,
+) -> PyResult<Vec<Py<PyAny>>> {
+    if !KNOWN.iter().any(|(n, _, _)| *n == network) {
+        let mut networks: Vec<&str> = KNOWN.iter().map(|(n, _, _)| *n).collect();
+        networks.dedup();
+        return Err(PyValueError::new_err(format!(
+            "unknown network {network:?}, expected one of {networks:?}"
+        )));
+    }
+    if let Some(layer) = layer {
+        if !matches!(layer, "consensus" | "execution") {
+            return Err(PyValueError::new_err(format!(
+                "unknown layer {layer:?}, expected 'consensus' or 'execution'"
+            )));
+        }
+        if !KNOWN.iter().any(|(n, l, _)| *n == network && *l == layer) {
+            return Err(PyValueError::new_err(format!(
+                "no {layer} bootnodes are built in for {network}; \
+                 fetch a published list with fetch_enr_list()"
+            )));
+        }
+    }
+    KNOWN
+        .iter()
+        .filter(|(n, l, _)| *n == network && layer.is_none_or(|layer| *l == layer))
+        .flat_map(|(_, _, entries)| entries.iter())
+        .map(|text| decode(py, text))
+        .collect()
+}
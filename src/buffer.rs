@@ -0,0 +1,82 @@
+//! Read-only buffer protocol exports of bytes computed on demand.
+//!
+//! A record does not keep its encoding around, so each export encodes it
+//! once into memory owned by the `Py_buffer` itself. The view stays valid
+//! however the record changes afterwards, and nothing is copied again on
+//! the way to `memoryview`, `bytes()` or `numpy.frombuffer`.
+
+use std::ffi::{c_int, c_void, CStr};
+use std::ptr;
+
+use pyo3::exceptions::PyBufferError;
+use pyo3::ffi;
+use pyo3::prelude::*;
+
+/// Item format of the exported bytes: unsigned char.
+const FORMAT: &CStr = c"B";
+
+/// Fills `view` with a read-only, one-dimensional export of `data`, which
+/// the view owns until [`release`] frees it.
+///
+/// # Safety
+///
+/// `view` must be the pointer CPython passed to `__getbuffer__`.
+pub(crate) unsafe fn export(
+    view: *mut ffi::Py_buffer,
+    flags: c_int,
+    data: Vec<u8>,
+    owner: Bound<'_, PyAny>,
+) -> PyResult<()> {
+    if view.is_null() {
+        return Err(PyBufferError::new_err("view is null"));
+    }
+    if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+        return Err(PyBufferError::new_err("the buffer is read-only"));
+    }
+    let data: Box<Box<[u8]>> = Box::new(data.into_boxed_slice());
+    // SAFETY: `view` is non-null and points to a `Py_buffer` CPython owns for
+    // the duration of the export.
+    unsafe {
+        (*view).buf = data.as_ptr() as *mut c_void;
+        (*view).len = data.len() as ffi::Py_ssize_t;
+        (*view).readonly = 1;
+        (*view).itemsize = 1;
+        (*view).format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+            FORMAT.as_ptr() as *mut _
+        } else {
+            ptr::null_mut()
+        };
+        (*view).ndim = 1;
+        (*view).shape = if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+            &mut (*view).len
+        } else {
+            ptr::null_mut()
+        };
+        (*view).strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+            &mut (*view).itemsize
+        } else {
+            ptr::null_mut()
+        };
+        (*view).suboffsets = ptr::null_mut();
+        (*view).internal = Box::into_raw(data) as *mut c_void;
+        (*view).obj = owner.into_ptr();
+    }
+    Ok(())
+}
+
+/// Frees the bytes an [`export`] handed to `view`.
+///
+/// # Safety
+///
+/// `view` must be the pointer CPython passed to `__releasebuffer__` for a
+/// view filled by [`export`].
+pub(crate) unsafe fn release(view: *mut ffi::Py_buffer) {
+    // SAFETY: `internal` holds the box `export` leaked, released only once.
+    unsafe {
+        let data = (*view).internal as *mut Box<[u8]>;
+        if !data.is_null() {
+            drop(Box::from_raw(data));
+            (*view).internal = ptr::null_mut();
+        }
+    }
+}
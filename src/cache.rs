@@ -0,0 +1,138 @@
+//! Bounded cache of the newest record per node.
+
+use std::sync::{Mutex, MutexGuard};
+
+use enr::CombinedKey;
+use hashlink::LinkedHashMap;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::bloom::NodeIdArg;
+use crate::Enr;
+
+#[derive(Default)]
+struct State {
+    /// Records from least to most recently used.
+    records: LinkedHashMap<[u8; 32], enr::Enr<CombinedKey>>,
+    evictions: u64,
+}
+
+/// The newest record of up to `max_entries` nodes, evicting the least
+/// recently used node once full.
+///
+/// Storing a record or looking one up marks its node as used. Every
+/// operation is O(1), and one cache can be shared between threads.
+#[pyclass(name = "EnrCache", frozen)]
+pub(crate) struct EnrCache {
+    max_entries: usize,
+    state: Mutex<State>,
+}
+
+impl EnrCache {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("cache lock poisoned")
+    }
+}
+
+#[pymethods]
+impl EnrCache {
+    #[new]
+    fn new(max_entries: usize) -> PyResult<Self> {
+        if max_entries == 0 {
+            return Err(PyValueError::new_err("max_entries must be positive"));
+        }
+        Ok(EnrCache {
+            max_entries,
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    /// Store `enr` unless the cache holds a record for the node with the
+    /// same or a higher seq; either way the node is marked as used.
+    ///
+    /// Returns `True` if the record was stored.
+    fn put(&self, enr: &Enr) -> bool {
+        let node_id = enr.inner.node_id().raw();
+        let mut state = self.lock();
+        if let Some(held) = state.records.to_back(&node_id) {
+            if enr.inner.seq() <= held.seq() {
+                return false;
+            }
+            *held = enr.inner.clone();
+            return true;
+        }
+        state.records.insert(node_id, enr.inner.clone());
+        if state.records.len() > self.max_entries {
+            state.records.pop_front();
+            state.evictions += 1;
+        }
+        true
+    }
+
+    /// The record held for a node id (or a record's node id), marking the
+    /// node as used.
+    fn get(&self, node_id: NodeIdArg<'_>) -> PyResult<Option<Enr>> {
+        let node_id = node_id.node_id()?;
+        let mut state = self.lock();
+        Ok(state.records.to_back(&node_id).map(|record| Enr {
+            inner: record.clone(),
+        }))
+    }
+
+    /// Remove and return the record held for a node id, if any.
+    fn pop(&self, node_id: NodeIdArg<'_>) -> PyResult<Option<Enr>> {
+        let node_id = node_id.node_id()?;
+        Ok(self
+            .lock()
+            .records
+            .remove(&node_id)
+            .map(|inner| Enr { inner }))
+    }
+
+    /// Checks for a node without marking it as used.
+    fn __contains__(&self, node_id: NodeIdArg<'_>) -> PyResult<bool> {
+        let node_id = node_id.node_id()?;
+        Ok(self.lock().records.contains_key(&node_id))
+    }
+
+    /// All records held, from least to most recently used.
+    fn records(&self) -> Vec<Enr> {
+        self.lock()
+            .records
+            .values()
+            .map(|record| Enr {
+                inner: record.clone(),
+            })
+            .collect()
+    }
+
+    /// Drop all records and reset the eviction counter.
+    fn clear(&self) {
+        *self.lock() = State::default();
+    }
+
+    fn __len__(&self) -> usize {
+        self.lock().records.len()
+    }
+
+    #[getter]
+    fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    /// Total number of records evicted so far.
+    #[getter]
+    fn evictions(&self) -> u64 {
+        self.lock().evictions
+    }
+
+    fn __repr__(&self) -> String {
+        let state = self.lock();
+        format!(
+            "EnrCache(max_entries={}, len={}, evictions={})",
+            self.max_entries,
+            state.records.len(),
+            state.evictions
+        )
+    }
+}
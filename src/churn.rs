@@ -0,0 +1,270 @@
+//! Update and population churn over timestamped observations of records.
+
+use std::collections::{BTreeMap, HashMap};
+
+use enr::CombinedKey;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::Enr;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Everything seen of one node.
+struct NodeAcc {
+    first_seen: f64,
+    last_seen: f64,
+    observations: u64,
+    /// The first record seen with each seq.
+    versions: BTreeMap<u64, enr::Enr<CombinedKey>>,
+    /// Indexes of the windows the node was observed in.
+    windows: Vec<i64>,
+}
+
+/// How often one node was seen and updated its record.
+#[pyclass(name = "NodeChurn", frozen, get_all)]
+pub(crate) struct NodeChurn {
+    node_id: Py<PyBytes>,
+    /// Earliest and latest observation timestamps.
+    first_seen: f64,
+    last_seen: f64,
+    observations: u64,
+    /// Number of distinct seqs seen, less one.
+    updates: u64,
+    /// `updates` per day between the first and last observation; `None`
+    /// if those are the same instant.
+    updates_per_day: Option<f64>,
+}
+
+#[pymethods]
+impl NodeChurn {
+    fn __repr__(&self, py: Python<'_>) -> String {
+        let node_id = self.node_id.bind(py).as_bytes();
+        format!(
+            "NodeChurn(node_id=0x{}\u{2026}, observations={}, updates={})",
+            crate::convert::to_hex(&node_id[..4]),
+            self.observations,
+            self.updates
+        )
+    }
+}
+
+/// The population in one time window.
+#[pyclass(name = "ChurnWindow", frozen, get_all)]
+pub(crate) struct ChurnWindow {
+    /// Start of the window, a multiple of the interval.
+    start: f64,
+    /// Nodes observed in the window.
+    seen: u64,
+    /// Nodes observed in the window but not the one before it.
+    joined: u64,
+    /// Nodes observed in the window before but not this one.
+    left: u64,
+}
+
+#[pymethods]
+impl ChurnWindow {
+    fn __repr__(&self) -> String {
+        format!(
+            "ChurnWindow(start={}, seen={}, joined={}, left={})",
+            self.start, self.seen, self.joined, self.left
+        )
+    }
+}
+
+/// Result of [`churn_stats`].
+#[pyclass(name = "ChurnStats", frozen, get_all)]
+pub(crate) struct ChurnStats {
+    /// Number of observations processed.
+    observations: u64,
+    /// Window length in seconds.
+    interval: f64,
+    /// Per-node statistics, in node id order.
+    nodes: Vec<Py<NodeChurn>>,
+    /// For each key, how many updates added, removed or changed it.
+    field_changes: BTreeMap<String, u64>,
+    /// Every window from the first observation to the last, in time order.
+    windows: Vec<Py<ChurnWindow>>,
+}
+
+#[pymethods]
+impl ChurnStats {
+    fn __repr__(&self) -> String {
+        format!(
+            "ChurnStats(observations={}, nodes={}, windows={})",
+            self.observations,
+            self.nodes.len(),
+            self.windows.len()
+        )
+    }
+}
+
+/// The keys whose values differ between two versions of a record.
+fn changed_keys<'a>(
+    old: &'a enr::Enr<CombinedKey>,
+    new: &'a enr::Enr<CombinedKey>,
+) -> impl Iterator<Item = &'a [u8]> {
+    let removed_or_changed = old
+        .iter()
+        .filter(|(key, value)| new.get_raw_rlp(key) != Some(*value))
+        .map(|(key, _)| key.as_slice());
+    let added = new
+        .iter()
+        .filter(|(key, _)| old.get_raw_rlp(key).is_none())
+        .map(|(key, _)| key.as_slice());
+    removed_or_changed.chain(added)
+}
+
+/// Window counts before conversion to Python objects.
+#[derive(Default)]
+struct WindowCounts {
+    seen: u64,
+    joined: u64,
+    left: u64,
+}
+
+struct Summary {
+    nodes: Vec<([u8; 32], NodeAcc)>,
+    field_changes: BTreeMap<String, u64>,
+    windows: BTreeMap<i64, WindowCounts>,
+}
+
+fn summarize(nodes: HashMap<[u8; 32], NodeAcc>) -> Summary {
+    let mut nodes: Vec<_> = nodes.into_iter().collect();
+    nodes.sort_unstable_by_key(|(node_id, _)| *node_id);
+
+    let mut field_changes = BTreeMap::new();
+    let mut windows: BTreeMap<i64, WindowCounts> = BTreeMap::new();
+    for (_, node) in &mut nodes {
+        let versions: Vec<_> = node.versions.values().collect();
+        for pair in versions.windows(2) {
+            for key in changed_keys(pair[0], pair[1]) {
+                *field_changes
+                    .entry(String::from_utf8_lossy(key).into_owned())
+                    .or_default() += 1;
+            }
+        }
+
+        node.windows.sort_unstable();
+        node.windows.dedup();
+        for (i, &w) in node.windows.iter().enumerate() {
+            windows.entry(w).or_default().seen += 1;
+            if i == 0 || node.windows[i - 1] != w - 1 {
+                windows.entry(w).or_default().joined += 1;
+            }
+            if node.windows.get(i + 1) != Some(&(w + 1)) {
+                windows.entry(w + 1).or_default().left += 1;
+            }
+        }
+    }
+
+    // Fill the gaps, and drop the window after the last observation, which
+    // only counts everyone as having left.
+    if let (Some(&first), Some(&last)) = (windows.keys().next(), windows.keys().next_back()) {
+        windows.remove(&last);
+        for w in first..last {
+            windows.entry(w).or_default();
+        }
+    }
+    Summary {
+        nodes,
+        field_changes,
+        windows,
+    }
+}
+
+/// Statistics over `(timestamp, enr)` observations, such as the output of
+/// repeated crawls: per-node update rates, how often each key changes, and
+/// how many nodes join and leave per time window.
+///
+/// Timestamps are Unix seconds, in any order. Windows are `interval` seconds
+/// long (a day by default) and aligned to multiples of it, so daily windows
+/// are UTC days; all nodes in the first window count as joined. Updates are
+/// the distinct seqs seen for a node, compared in seq order.
+#[pyfunction]
+#[pyo3(signature = (observations, *, interval=SECONDS_PER_DAY))]
+pub(crate) fn churn_stats(
+    py: Python<'_>,
+    observations: &Bound<'_, PyAny>,
+    interval: f64,
+) -> PyResult<ChurnStats> {
+    if !(interval.is_finite() && interval > 0.0) {
+        return Err(PyValueError::new_err(
+            "interval must be a positive number of seconds",
+        ));
+    }
+    let mut count = 0u64;
+    let mut nodes: HashMap<[u8; 32], NodeAcc> = HashMap::new();
+    for item in observations.try_iter()? {
+        let (timestamp, enr): (f64, PyRef<'_, Enr>) = item?.extract()?;
+        if !timestamp.is_finite() {
+            return Err(PyValueError::new_err(format!(
+                "observation {count}: timestamp must be finite, got {timestamp}"
+            )));
+        }
+        count += 1;
+        let window = (timestamp / interval).floor() as i64;
+        let node = nodes
+            .entry(enr.inner.node_id().raw())
+            .or_insert_with(|| NodeAcc {
+                first_seen: timestamp,
+                last_seen: timestamp,
+                observations: 0,
+                versions: BTreeMap::new(),
+                windows: Vec::new(),
+            });
+        node.first_seen = node.first_seen.min(timestamp);
+        node.last_seen = node.last_seen.max(timestamp);
+        node.observations += 1;
+        node.versions
+            .entry(enr.inner.seq())
+            .or_insert_with(|| enr.inner.clone());
+        if node.windows.last() != Some(&window) {
+            node.windows.push(window);
+        }
+    }
+
+    let summary = py.detach(|| summarize(nodes));
+    let nodes = summary
+        .nodes
+        .into_iter()
+        .map(|(node_id, node)| {
+            let updates = node.versions.len() as u64 - 1;
+            let days = (node.last_seen - node.first_seen) / SECONDS_PER_DAY;
+            Py::new(
+                py,
+                NodeChurn {
+                    node_id: PyBytes::new(py, &node_id).unbind(),
+                    first_seen: node.first_seen,
+                    last_seen: node.last_seen,
+                    observations: node.observations,
+                    updates,
+                    updates_per_day: (days > 0.0).then(|| updates as f64 / days),
+                },
+            )
+        })
+        .collect::<PyResult<_>>()?;
+    let windows = summary
+        .windows
+        .into_iter()
+        .map(|(w, counts)| {
+            Py::new(
+                py,
+                ChurnWindow {
+                    start: w as f64 * interval,
+                    seen: counts.seen,
+                    joined: counts.joined,
+                    left: counts.left,
+                },
+            )
+        })
+        .collect::<PyResult<_>>()?;
+    Ok(ChurnStats {
+        observations: count,
+        interval,
+        nodes,
+        field_changes: summary.field_changes,
+        windows,
+    })
+}
@@ -0,0 +1,100 @@
+//! User-registered codecs for custom fields.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Converters between a field's Python value and what `set()` accepts and
+/// `get_decoded()` returns.
+struct Codec {
+    encode: Option<Py<PyAny>>,
+    decode: Option<Py<PyAny>>,
+}
+
+fn registry() -> MutexGuard<'static, HashMap<String, Codec>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, Codec>>> = OnceLock::new();
+    REGISTRY
+        .get_or_init(Default::default)
+        .lock()
+        .expect("codec registry lock poisoned")
+}
+
+/// The encoder registered for `key`, if any.
+///
+/// Callables are cloned out so they run without the registry locked.
+pub(crate) fn encoder(py: Python<'_>, key: &str) -> Option<Py<PyAny>> {
+    registry()
+        .get(key)
+        .and_then(|c| c.encode.as_ref().map(|f| f.clone_ref(py)))
+}
+
+/// The decoder registered for `key`, if any.
+pub(crate) fn decoder(py: Python<'_>, key: &str) -> Option<Py<PyAny>> {
+    registry()
+        .get(key)
+        .and_then(|c| c.decode.as_ref().map(|f| f.clone_ref(py)))
+}
+
+/// Register how `get()` and `set()` convert the value of a custom field.
+///
+/// `encode_fn(value)` runs on every value passed to `Enr.set()` or
+/// `EnrBuilder.add()` for `key` and must return what those accept: bytes,
+/// an int or a nested list of them. `decode_fn(raw)` runs in `Enr.get()`
+/// (unless a `type` is given) on the value as `Enr.get_decoded()` returns
+/// it. Either may be `None`. Registering a key again replaces its codec.
+#[pyfunction]
+#[pyo3(signature = (key, encode_fn=None, decode_fn=None))]
+pub(crate) fn register_codec(
+    key: &str,
+    encode_fn: Option<Bound<'_, PyAny>>,
+    decode_fn: Option<Bound<'_, PyAny>>,
+) -> PyResult<()> {
+    if matches!(key, "id" | "secp256k1" | "ed25519") {
+        return Err(PyValueError::new_err(format!(
+            "key {key:?} is reserved and cannot have a codec"
+        )));
+    }
+    for f in [&encode_fn, &decode_fn].into_iter().flatten() {
+        if !f.is_callable() {
+            return Err(PyValueError::new_err(format!(
+                "codec functions must be callable, got {}",
+                f.get_type().name()?
+            )));
+        }
+    }
+    let codec = Codec {
+        encode: encode_fn.map(Bound::unbind),
+        decode: decode_fn.map(Bound::unbind),
+    };
+    // Dropped after the lock is released, since dropping a callable can run
+    // arbitrary Python code.
+    let previous = registry().insert(key.to_string(), codec);
+    drop(previous);
+    Ok(())
+}
+
+/// Remove the codec registered for `key`, returning whether there was one.
+#[pyfunction]
+pub(crate) fn unregister_codec(key: &str) -> bool {
+    let removed = registry().remove(key);
+    removed.is_some()
+}
+
+/// Keys that currently have a codec, sorted.
+#[pyfunction]
+pub(crate) fn registered_codecs() -> Vec<String> {
+    let mut keys: Vec<String> = registry().keys().cloned().collect();
+    keys.sort();
+    keys
+}
+
+/// Applies the encoder registered for `key` to `value`, if there is one.
+pub(crate) fn encode<'py>(key: &str, value: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let py = value.py();
+    match encoder(py, key) {
+        Some(f) => f.bind(py).call1((value,)),
+        None => Ok(value.clone()),
+    }
+}
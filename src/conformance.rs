@@ -0,0 +1,801 @@
+//! EIP-778 conformance checks on the raw encoding of a record.
+//!
+//! The `enr` crate normalises what it decodes, so some spec violations in the
+//! original bytes (trailing data, uncompressed public keys, missing `id`) are
+//! invisible once a record is decoded. These checks walk the bytes directly.
+
+use std::fmt;
+
+use alloy_rlp::{Encodable, Header};
+use pyo3::prelude::*;
+
+use crate::rlp::MAX_ENR_SIZE;
+use crate::{eth2, keys};
+
+/// Kind of EIP-778 violation found in an encoded record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ViolationKind {
+    Oversize,
+    Malformed,
+    TrailingData,
+    UnsortedKeys,
+    BadIpLength,
+    BadFieldLength,
+    NonMinimalInteger,
+    IntegerOverflow,
+    UnknownIdScheme,
+    BadPublicKey,
+    KeyTypeMismatch,
+    /// Only reported by salvage decoding; the strict checks never verify signatures.
+    InvalidSignature,
+    /// Only reported when validating with `known_keys_only`.
+    UnknownKey,
+    /// Only reported when validating against a network profile.
+    MissingKey,
+    /// Only reported when placeholder values are flagged.
+    Placeholder,
+}
+
+impl ViolationKind {
+    /// Stable identifier exposed to Python.
+    pub(crate) fn code(self) -> &'static str {
+        match self {
+            ViolationKind::Oversize => "oversize",
+            ViolationKind::Malformed => "malformed",
+            ViolationKind::TrailingData => "trailing_data",
+            ViolationKind::UnsortedKeys => "unsorted_keys",
+            ViolationKind::BadIpLength => "bad_ip_length",
+            ViolationKind::BadFieldLength => "bad_field_length",
+            ViolationKind::NonMinimalInteger => "non_minimal_integer",
+            ViolationKind::IntegerOverflow => "integer_overflow",
+            ViolationKind::UnknownIdScheme => "unknown_id_scheme",
+            ViolationKind::BadPublicKey => "bad_public_key",
+            ViolationKind::KeyTypeMismatch => "key_type_mismatch",
+            ViolationKind::InvalidSignature => "invalid_signature",
+            ViolationKind::UnknownKey => "unknown_key",
+            ViolationKind::MissingKey => "missing_key",
+            ViolationKind::Placeholder => "placeholder",
+        }
+    }
+}
+
+/// One violation, located by byte offset and (for content entries) key.
+#[derive(Clone, Debug)]
+pub(crate) struct Violation {
+    pub(crate) kind: ViolationKind,
+    pub(crate) offset: usize,
+    pub(crate) key: Option<String>,
+    pub(crate) message: String,
+    /// Length the spec requires, for violations about a wrong length.
+    pub(crate) expected: Option<usize>,
+    /// Length actually found.
+    pub(crate) actual: Option<usize>,
+}
+
+impl Violation {
+    pub(crate) fn new(kind: ViolationKind, offset: usize, message: impl Into<String>) -> Self {
+        Violation {
+            kind,
+            offset,
+            key: None,
+            message: message.into(),
+            expected: None,
+            actual: None,
+        }
+    }
+
+    pub(crate) fn lengths(mut self, expected: usize, actual: usize) -> Self {
+        self.expected = Some(expected);
+        self.actual = Some(actual);
+        self
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(key) = &self.key {
+            write!(f, "key {key:?}: ")?;
+        }
+        write!(f, "{} (offset {})", self.message, self.offset)
+    }
+}
+
+/// Parses an RLP header without the canonical-form checks `Header::decode`
+/// makes, and without requiring the payload to be present.
+///
+/// Returns whether the item is a list, the header length and the payload length.
+fn parse_header_lenient(buf: &[u8]) -> Option<(bool, usize, usize)> {
+    let prefix = *buf.first()?;
+    Some(match prefix {
+        0x00..=0x7f => (false, 0, 1),
+        0x80..=0xb7 => (false, 1, usize::from(prefix - 0x80)),
+        0xc0..=0xf7 => (true, 1, usize::from(prefix - 0xc0)),
+        _ => {
+            let (list, len_of_len) = if prefix >= 0xf8 {
+                (true, usize::from(prefix - 0xf7))
+            } else {
+                (false, usize::from(prefix - 0xb7))
+            };
+            let len_bytes = buf.get(1..1 + len_of_len)?;
+            let payload_length = len_bytes.iter().fold(0usize, |len, b| {
+                len.saturating_mul(256).saturating_add(usize::from(*b))
+            });
+            (list, 1 + len_of_len, payload_length)
+        }
+    })
+}
+
+/// A single RLP item: where it starts and its payload.
+pub(crate) struct Item<'a> {
+    pub(crate) offset: usize,
+    pub(crate) payload_offset: usize,
+    pub(crate) list: bool,
+    pub(crate) payload: &'a [u8],
+}
+
+impl<'a> Item<'a> {
+    /// The whole item, header included.
+    pub(crate) fn raw(&self, data: &'a [u8]) -> &'a [u8] {
+        &data[self.offset..self.payload_offset + self.payload.len()]
+    }
+}
+
+/// Cursor over the items of an RLP list payload.
+pub(crate) struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+    end: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Cursor over the top-level items of `data`.
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Cursor {
+            data,
+            pos: 0,
+            end: data.len(),
+        }
+    }
+
+    /// Cursor over the items inside the list `item`.
+    pub(crate) fn inside(data: &'a [u8], item: &Item<'_>) -> Self {
+        Cursor {
+            data,
+            pos: item.payload_offset,
+            end: item.payload_offset + item.payload.len(),
+        }
+    }
+
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pos >= self.end
+    }
+
+    /// Like `next_item`, but a payload running past the end is cut short
+    /// instead of rejected. Returns `None` if no header can be read at all.
+    pub(crate) fn next_item_truncated(&mut self) -> Option<Item<'a>> {
+        let offset = self.pos;
+        let (list, header_len, payload_length) =
+            parse_header_lenient(&self.data[offset..self.end])?;
+        let start = offset + header_len;
+        self.pos = start.saturating_add(payload_length).min(self.end);
+        Some(Item {
+            offset,
+            payload_offset: start,
+            list,
+            payload: &self.data[start..self.pos],
+        })
+    }
+
+    /// Like `next_item`, but accepting non-canonical headers (long forms for
+    /// short lengths, single bytes wrapped in a string header).
+    fn next_item_lenient(&mut self) -> Option<Item<'a>> {
+        let offset = self.pos;
+        let (list, header_len, payload_length) =
+            parse_header_lenient(&self.data[offset..self.end])?;
+        let start = offset + header_len;
+        let end = start
+            .checked_add(payload_length)
+            .filter(|end| *end <= self.end)?;
+        self.pos = end;
+        Some(Item {
+            offset,
+            payload_offset: start,
+            list,
+            payload: &self.data[start..end],
+        })
+    }
+
+    pub(crate) fn next_item(&mut self) -> Result<Item<'a>, Violation> {
+        let offset = self.pos;
+        let mut buf = &self.data[self.pos..self.end];
+        let header = Header::decode(&mut buf).map_err(|e| Violation {
+            kind: ViolationKind::Malformed,
+            offset,
+            key: None,
+            expected: None,
+            actual: None,
+            message: format!("invalid RLP item: {e}"),
+        })?;
+        if header.payload_length > buf.len() {
+            return Err(Violation {
+                kind: ViolationKind::Malformed,
+                offset,
+                key: None,
+                expected: None,
+                actual: None,
+                message: "RLP item is truncated".into(),
+            }
+            .lengths(header.payload_length, buf.len()));
+        }
+        let start = self.end - buf.len();
+        self.pos = start + header.payload_length;
+        Ok(Item {
+            offset,
+            payload_offset: start,
+            list: header.list,
+            payload: &self.data[start..self.pos],
+        })
+    }
+}
+
+pub(crate) fn violation(
+    kind: ViolationKind,
+    item: &Item<'_>,
+    key: Option<&str>,
+    message: String,
+) -> Violation {
+    Violation {
+        kind,
+        offset: item.offset,
+        key: key.map(str::to_string),
+        expected: None,
+        actual: None,
+        message,
+    }
+}
+
+/// Checks that `item` is a minimally encoded unsigned integer of at most `max_len` bytes.
+fn check_integer(
+    item: &Item<'_>,
+    key: Option<&str>,
+    what: &str,
+    max_len: usize,
+    out: &mut Vec<Violation>,
+) {
+    if item.list {
+        out.push(violation(
+            ViolationKind::Malformed,
+            item,
+            key,
+            format!("{what} must be an integer, got a list"),
+        ));
+    } else if item.payload.first() == Some(&0) {
+        out.push(violation(
+            ViolationKind::NonMinimalInteger,
+            item,
+            key,
+            format!("{what} has leading zero bytes"),
+        ));
+    } else if item.payload.len() > max_len {
+        out.push(
+            violation(
+                ViolationKind::IntegerOverflow,
+                item,
+                key,
+                format!(
+                    "{what} is {} bytes, at most {max_len} allowed",
+                    item.payload.len()
+                ),
+            )
+            .lengths(max_len, item.payload.len()),
+        );
+    }
+}
+
+fn check_value(key: &str, item: &Item<'_>, out: &mut Vec<Violation>) {
+    let wants_string = matches!(
+        key,
+        "id" | "ip" | "ip6" | "secp256k1" | "ed25519" | eth2::ETH2_ENR_KEY
+    );
+    if wants_string && item.list {
+        out.push(violation(
+            ViolationKind::Malformed,
+            item,
+            Some(key),
+            "expected a byte string, got a list".into(),
+        ));
+        return;
+    }
+    match key {
+        "id" if item.payload != b"v4" => {
+            out.push(violation(
+                ViolationKind::UnknownIdScheme,
+                item,
+                Some(key),
+                format!(
+                    "unknown identity scheme {:?}",
+                    String::from_utf8_lossy(item.payload)
+                ),
+            ));
+        }
+        "tcp" | "udp" | "tcp6" | "udp6" => check_integer(item, Some(key), "port", 2, out),
+        "ip" | "ip6" => {
+            let want = if key == "ip" { 4 } else { 16 };
+            if item.payload.len() != want {
+                out.push(
+                    violation(
+                        ViolationKind::BadIpLength,
+                        item,
+                        Some(key),
+                        format!("address must be {want} bytes, got {}", item.payload.len()),
+                    )
+                    .lengths(want, item.payload.len()),
+                );
+            }
+        }
+        "secp256k1" if !(item.payload.len() == 33 && matches!(item.payload[0], 0x02 | 0x03)) => {
+            out.push(
+                violation(
+                    ViolationKind::BadPublicKey,
+                    item,
+                    Some(key),
+                    format!(
+                        "expected a 33-byte compressed public key, got {} bytes",
+                        item.payload.len()
+                    ),
+                )
+                .lengths(33, item.payload.len()),
+            );
+        }
+        "ed25519" if item.payload.len() != 32 => {
+            out.push(
+                violation(
+                    ViolationKind::BadPublicKey,
+                    item,
+                    Some(key),
+                    format!(
+                        "expected a 32-byte public key, got {} bytes",
+                        item.payload.len()
+                    ),
+                )
+                .lengths(32, item.payload.len()),
+            );
+        }
+        eth2::ETH2_ENR_KEY if item.payload.len() != eth2::ENR_FORK_ID_LEN => {
+            out.push(
+                violation(
+                    ViolationKind::BadFieldLength,
+                    item,
+                    Some(key),
+                    format!(
+                        "expected a {}-byte ENRForkID, got {} bytes",
+                        eth2::ENR_FORK_ID_LEN,
+                        item.payload.len()
+                    ),
+                )
+                .lengths(eth2::ENR_FORK_ID_LEN, item.payload.len()),
+            );
+        }
+        _ => {}
+    }
+}
+
+/// Checks that exactly one public key is stored and that the signature has
+/// the length its algorithm produces.
+///
+/// Both `secp256k1` and `ed25519` keys are accepted under the `v4` scheme, as
+/// the `enr` crate does; `v4` signatures are 64 bytes either way.
+fn check_key_type(signature: &Item<'_>, key_entries: &[(String, usize)], out: &mut Vec<Violation>) {
+    match key_entries {
+        [] => out.push(Violation::new(
+            ViolationKind::BadPublicKey,
+            signature.offset,
+            "record has no secp256k1 or ed25519 public key",
+        )),
+        [(key, _)] => {
+            if signature.list || signature.payload.len() != 64 {
+                out.push(
+                    Violation::new(
+                        ViolationKind::KeyTypeMismatch,
+                        signature.offset,
+                        format!(
+                            "{key} signature must be 64 bytes, got {}",
+                            signature.payload.len()
+                        ),
+                    )
+                    .lengths(64, signature.payload.len()),
+                );
+            }
+        }
+        [_, rest @ ..] => {
+            for (key, offset) in rest {
+                let mut v = Violation::new(
+                    ViolationKind::KeyTypeMismatch,
+                    *offset,
+                    "record stores more than one public key",
+                );
+                v.key = Some(key.clone());
+                out.push(v);
+            }
+        }
+    }
+}
+
+/// Returns every EIP-778 violation found in an encoded record, in byte order.
+///
+/// Framing errors stop the walk, since nothing after them can be located
+/// reliably; all other violations are collected.
+pub(crate) fn check(data: &[u8]) -> Vec<Violation> {
+    check_with(data, false)
+}
+
+/// Like [`check`], additionally reporting every key missing from the
+/// known-key registry if `known_keys_only` is set.
+pub(crate) fn check_with(data: &[u8], known_keys_only: bool) -> Vec<Violation> {
+    let mut out = Vec::new();
+    if data.len() > MAX_ENR_SIZE {
+        out.push(
+            Violation {
+                kind: ViolationKind::Oversize,
+                offset: 0,
+                key: None,
+                expected: None,
+                actual: None,
+                message: format!("record is {} bytes, max size is {MAX_ENR_SIZE}", data.len()),
+            }
+            .lengths(MAX_ENR_SIZE, data.len()),
+        );
+    }
+
+    let mut top = Cursor::new(data);
+    let record = match top.next_item() {
+        Ok(item) if item.list => item,
+        Ok(item) => {
+            out.push(Violation {
+                kind: ViolationKind::Malformed,
+                offset: item.offset,
+                key: None,
+                expected: None,
+                actual: None,
+                message: "record is not an RLP list".into(),
+            });
+            return out;
+        }
+        Err(v) => {
+            out.push(v);
+            return out;
+        }
+    };
+    if !top.is_empty() {
+        out.push(Violation {
+            kind: ViolationKind::TrailingData,
+            offset: top.pos,
+            key: None,
+            expected: None,
+            actual: None,
+            message: format!("{} bytes after the end of the record", top.end - top.pos),
+        });
+    }
+
+    let mut items = Cursor::inside(data, &record);
+    if let Err(v) = walk_content(&mut items, known_keys_only, &mut out) {
+        out.push(v);
+    }
+    out.sort_by_key(|v| v.offset);
+    out
+}
+
+fn walk_content(
+    items: &mut Cursor<'_>,
+    known_keys_only: bool,
+    out: &mut Vec<Violation>,
+) -> Result<(), Violation> {
+    let missing = |what: &str, offset: usize| Violation {
+        kind: ViolationKind::Malformed,
+        offset,
+        key: None,
+        expected: None,
+        actual: None,
+        message: format!("record has no {what}"),
+    };
+
+    if items.is_empty() {
+        return Err(missing("signature", items.pos));
+    }
+    let signature = items.next_item()?;
+    if items.is_empty() {
+        return Err(missing("sequence number", items.pos));
+    }
+    let seq = items.next_item()?;
+    check_integer(&seq, None, "sequence number", 8, out);
+
+    let mut prev: Option<&[u8]> = None;
+    let mut has_id = false;
+    let mut key_entries: Vec<(String, usize)> = Vec::new();
+    while !items.is_empty() {
+        let key_item = items.next_item()?;
+        let key = String::from_utf8_lossy(key_item.payload).into_owned();
+        if key_item.list {
+            return Err(violation(
+                ViolationKind::Malformed,
+                &key_item,
+                Some(&key),
+                "key must be a byte string".into(),
+            ));
+        }
+        if let Some(prev) = prev {
+            if prev >= key_item.payload {
+                let message = if prev == key_item.payload {
+                    "duplicate key"
+                } else {
+                    "keys are not sorted"
+                };
+                out.push(violation(
+                    ViolationKind::UnsortedKeys,
+                    &key_item,
+                    Some(&key),
+                    message.into(),
+                ));
+            }
+        }
+        if known_keys_only && keys::lookup(&key).is_none() {
+            out.push(violation(
+                ViolationKind::UnknownKey,
+                &key_item,
+                Some(&key),
+                "key is not in the known-key registry".into(),
+            ));
+        }
+        prev = Some(key_item.payload);
+        has_id |= key_item.payload == b"id";
+        if matches!(key.as_str(), "secp256k1" | "ed25519") {
+            key_entries.push((key.clone(), key_item.offset));
+        }
+
+        if items.is_empty() {
+            return Err(violation(
+                ViolationKind::Malformed,
+                &key_item,
+                Some(&key),
+                "key has no value".into(),
+            ));
+        }
+        let value = items.next_item()?;
+        check_value(&key, &value, out);
+    }
+
+    check_key_type(&signature, &key_entries, out);
+    if !has_id {
+        out.push(Violation {
+            kind: ViolationKind::UnknownIdScheme,
+            offset: 0,
+            key: None,
+            expected: None,
+            actual: None,
+            message: "record has no id key".into(),
+        });
+    }
+    Ok(())
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    &bytes[bytes.iter().take_while(|b| **b == 0).count()..]
+}
+
+fn encode_canonical(data: &[u8], item: &Item<'_>, out: &mut Vec<u8>) -> Option<()> {
+    if !item.list {
+        item.payload.encode(out);
+        return Some(());
+    }
+    let mut children = Cursor::inside(data, item);
+    let mut payload = Vec::new();
+    while !children.is_empty() {
+        let child = children.next_item_lenient()?;
+        encode_canonical(data, &child, &mut payload)?;
+    }
+    Header {
+        list: true,
+        payload_length: payload.len(),
+    }
+    .encode(out);
+    out.extend_from_slice(&payload);
+    Some(())
+}
+
+/// Re-encodes a record in canonical RLP: minimal headers, no leading zeros in
+/// the sequence number or ports, and nothing after the record.
+///
+/// Non-canonical framing that other implementations accept is tolerated on
+/// input. Returns `None` if the bytes cannot be framed as a record at all.
+pub(crate) fn canonical_encoding(data: &[u8]) -> Option<Vec<u8>> {
+    // Bounds the recursion into nested list values.
+    if data.len() > MAX_ENR_SIZE {
+        return None;
+    }
+    let record = Cursor::new(data).next_item_lenient().filter(|i| i.list)?;
+    let mut items = Cursor::inside(data, &record);
+    let mut payload = Vec::new();
+
+    let signature = items.next_item_lenient().filter(|i| !i.list)?;
+    signature.payload.encode(&mut payload);
+    let seq = items.next_item_lenient().filter(|i| !i.list)?;
+    strip_leading_zeros(seq.payload).encode(&mut payload);
+    while !items.is_empty() {
+        let key = items.next_item_lenient().filter(|i| !i.list)?;
+        key.payload.encode(&mut payload);
+        let value = items.next_item_lenient()?;
+        let is_port = matches!(key.payload, b"tcp" | b"udp" | b"tcp6" | b"udp6");
+        if is_port && !value.list {
+            strip_leading_zeros(value.payload).encode(&mut payload);
+        } else {
+            encode_canonical(data, &value, &mut payload)?;
+        }
+    }
+
+    let mut out = Vec::with_capacity(payload.len() + 3);
+    Header {
+        list: true,
+        payload_length: payload.len(),
+    }
+    .encode(&mut out);
+    out.extend_from_slice(&payload);
+    Some(out)
+}
+
+/// Returns `true` if `data` is exactly the canonical encoding of its record.
+pub(crate) fn is_canonical(data: &[u8]) -> bool {
+    canonical_encoding(data).is_some_and(|canonical| canonical == data)
+}
+
+/// A content entry: the key's payload and the whole value item.
+pub(crate) type RawEntry<'a> = (&'a [u8], &'a [u8]);
+
+/// The content entries of an encoded record exactly as they appear in
+/// `data`: each key's payload and its value with the RLP header.
+///
+/// Non-canonical framing is accepted and nothing beyond the framing is
+/// checked, so the entries can be compared byte for byte with what another
+/// implementation produced.
+pub(crate) fn raw_entries(data: &[u8]) -> Result<Vec<RawEntry<'_>>, Violation> {
+    let malformed =
+        |offset, message: &str| Violation::new(ViolationKind::Malformed, offset, message);
+    let mut top = Cursor::new(data);
+    let record = top
+        .next_item_lenient()
+        .filter(|i| i.list)
+        .ok_or_else(|| malformed(0, "record is not an RLP list"))?;
+    if !top.is_empty() {
+        return Err(Violation::new(
+            ViolationKind::TrailingData,
+            top.pos,
+            format!("{} bytes after the end of the record", top.end - top.pos),
+        ));
+    }
+
+    let mut items = Cursor::inside(data, &record);
+    for name in ["signature", "sequence number"] {
+        let offset = items.pos();
+        items
+            .next_item_lenient()
+            .filter(|i| !i.list)
+            .ok_or_else(|| malformed(offset, &format!("missing or invalid {name}")))?;
+    }
+    let mut entries = Vec::new();
+    while !items.is_empty() {
+        let offset = items.pos();
+        let key = items
+            .next_item_lenient()
+            .filter(|i| !i.list)
+            .ok_or_else(|| malformed(offset, "key is not a byte string"))?;
+        let offset = items.pos();
+        let value = items.next_item_lenient().ok_or_else(|| Violation {
+            key: Some(String::from_utf8_lossy(key.payload).into_owned()),
+            ..malformed(offset, "missing or invalid value")
+        })?;
+        entries.push((key.payload, value.raw(data)));
+    }
+    Ok(entries)
+}
+
+/// A single violation as seen from Python.
+#[pyclass(name = "Violation", frozen, get_all)]
+pub(crate) struct PyViolation {
+    /// Stable code such as `"oversize"` or `"bad_ip_length"`.
+    code: &'static str,
+    /// Content key the violation was found in, if any.
+    key: Option<String>,
+    /// Byte offset of the offending item in the encoded record.
+    offset: usize,
+    message: String,
+    /// Required length, for violations about a wrong length.
+    expected: Option<usize>,
+    /// Length actually found.
+    actual: Option<usize>,
+}
+
+impl PyViolation {
+    pub(crate) fn code(&self) -> &'static str {
+        self.code
+    }
+}
+
+impl From<Violation> for PyViolation {
+    fn from(v: Violation) -> Self {
+        PyViolation {
+            code: v.kind.code(),
+            key: v.key,
+            offset: v.offset,
+            message: v.message,
+            expected: v.expected,
+            actual: v.actual,
+        }
+    }
+}
+
+#[pymethods]
+impl PyViolation {
+    fn __str__(&self) -> String {
+        format!("{}: {}", self.code, self.message)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Violation(code={:?}, key={}, offset={})",
+            self.code,
+            self.key
+                .as_ref()
+                .map_or_else(|| "None".to_string(), |k| format!("{k:?}")),
+            self.offset
+        )
+    }
+}
+
+/// Result of validating a record against EIP-778.
+#[pyclass(name = "ValidationReport", frozen, get_all)]
+pub(crate) struct ValidationReport {
+    /// Every violation found, in byte order. Empty for a conforming record.
+    violations: Vec<Py<PyViolation>>,
+}
+
+impl ValidationReport {
+    pub(crate) fn new(py: Python<'_>, violations: Vec<Violation>) -> PyResult<Self> {
+        let violations = violations
+            .into_iter()
+            .map(|v| Py::new(py, PyViolation::from(v)))
+            .collect::<PyResult<_>>()?;
+        Ok(ValidationReport { violations })
+    }
+}
+
+#[pymethods]
+impl ValidationReport {
+    /// `True` if no violations were found.
+    #[getter]
+    fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Distinct violation codes, in order of first occurrence.
+    fn codes(&self, py: Python<'_>) -> Vec<&'static str> {
+        let mut codes: Vec<&'static str> = Vec::new();
+        for v in &self.violations {
+            let code = v.bind(py).get().code;
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+        codes
+    }
+
+    fn __bool__(&self) -> bool {
+        self.is_valid()
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> String {
+        format!("ValidationReport(codes={:?})", self.codes(py))
+    }
+}
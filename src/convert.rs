@@ -0,0 +1,121 @@
+//! Module-level `decode` and `encode`, accepting any supported representation,
+//! and conversion to and from the record objects of other Python libraries.
+
+use pyo3::exceptions::{PyImportError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyString};
+
+use crate::{decode_text, Enr};
+
+/// Lowercase hex without a prefix.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Parses hex digits (without a prefix), or `None` if they are not valid hex.
+pub(crate) fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// The text form of a record object from another library, taken from its
+/// `repr()`, which eth-enr and ddht give as `enr:` followed by base64.
+fn foreign_text(value: &Bound<'_, PyAny>) -> PyResult<Option<String>> {
+    let text = value.repr()?.to_string();
+    Ok(text.starts_with("enr:").then_some(text))
+}
+
+/// Decode a record from whatever form it comes in.
+///
+/// Accepts an `Enr` (returned as is), a base64 string with or without the
+/// `enr:` prefix, a `0x`-prefixed hex string of the RLP encoding, the RLP
+/// encoding as `bytes` or `bytearray`, or a record object from eth-enr or
+/// ddht.
+#[pyfunction]
+#[pyo3(signature = (value, *, strict=false))]
+pub(crate) fn decode(value: &Bound<'_, PyAny>, strict: bool) -> PyResult<Py<Enr>> {
+    let py = value.py();
+    if let Ok(enr) = value.cast::<Enr>() {
+        return Ok(enr.clone().unbind());
+    }
+    let data = if let Ok(text) = value.cast::<PyString>() {
+        decode_text(py, text.to_str()?)?
+    } else if let Ok(data) = value.extract::<Vec<u8>>() {
+        data
+    } else if let Some(text) = foreign_text(value)? {
+        decode_text(py, &text)?
+    } else {
+        return Err(PyTypeError::new_err(format!(
+            "cannot decode a record from {}",
+            value.get_type().name()?
+        )));
+    };
+    Py::new(py, Enr::from_bytes(py, &data, strict, false)?)
+}
+
+/// Encode a record as `"base64"` text (with the `enr:` prefix), `"hex"`
+/// text (`0x`-prefixed) or raw `"bytes"`.
+#[pyfunction]
+#[pyo3(signature = (enr, format="base64"))]
+pub(crate) fn encode<'py>(py: Python<'py>, enr: &Enr, format: &str) -> PyResult<Bound<'py, PyAny>> {
+    let data = || alloy_rlp::encode(&enr.inner);
+    match format {
+        "base64" => Ok(PyString::new(py, &enr.inner.to_base64()).into_any()),
+        "hex" => Ok(PyString::new(py, &format!("0x{}", to_hex(&data()))).into_any()),
+        "bytes" => Ok(PyBytes::new(py, &data()).into_any()),
+        _ => Err(PyValueError::new_err(format!(
+            "unknown format {format:?}, expected \"base64\", \"hex\" or \"bytes\""
+        ))),
+    }
+}
+
+/// Convert a record object from eth-enr (`eth_enr.ENR`) or ddht to an
+/// `Enr`, through the `enr:` text form its `repr()` gives.
+///
+/// Unsigned records have no such form and raise `TypeError`. The signature
+/// is verified again, and `strict` applies as in `Enr.from_bytes`.
+#[pyfunction]
+#[pyo3(signature = (record, *, strict=false))]
+pub(crate) fn from_eth_enr(record: &Bound<'_, PyAny>, strict: bool) -> PyResult<Enr> {
+    let py = record.py();
+    if let Ok(enr) = record.cast::<Enr>() {
+        return Ok(enr.try_borrow()?.clone());
+    }
+    let Some(text) = foreign_text(record)? else {
+        return Err(PyTypeError::new_err(format!(
+            "expected a signed eth-enr or ddht record, whose repr() is its enr: text, got {}",
+            record.get_type().name()?
+        )));
+    };
+    Enr::from_bytes(py, &decode_text(py, &text)?, strict, false)
+}
+
+/// Convert an `Enr` to an `eth_enr.ENR`, or to `cls` if given, through the
+/// `enr:` text form: `cls.from_repr(text)`, which ddht's and other
+/// eth-enr-compatible classes provide.
+///
+/// eth-enr must be installed unless `cls` is given; its default identity
+/// scheme registry only verifies secp256k1 records.
+#[pyfunction]
+#[pyo3(signature = (enr, *, cls=None))]
+pub(crate) fn to_eth_enr<'py>(
+    py: Python<'py>,
+    enr: &Enr,
+    cls: Option<Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let cls = match cls {
+        Some(cls) => cls,
+        None => py
+            .import("eth_enr")
+            .map_err(|_| {
+                PyImportError::new_err("to_eth_enr() needs the eth-enr package, or pass cls=")
+            })?
+            .getattr("ENR")?,
+    };
+    cls.call_method1("from_repr", (enr.inner.to_base64(),))
+}
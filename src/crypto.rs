@@ -0,0 +1,101 @@
+//! Public-key helpers shared by the modules that check signatures themselves.
+
+use enr::{ed25519_dalek, k256, CombinedPublicKey, EnrPublicKey};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Parses the value stored under a record's `secp256k1` or `ed25519` key.
+pub(crate) fn decode_public_key(key: &str, bytes: &[u8]) -> Option<CombinedPublicKey> {
+    match key {
+        "secp256k1" => k256::ecdsa::VerifyingKey::from_sec1_bytes(bytes)
+            .ok()
+            .map(CombinedPublicKey::Secp256k1),
+        "ed25519" => {
+            let bytes = <&[u8; 32]>::try_from(bytes).ok()?;
+            ed25519_dalek::VerifyingKey::from_bytes(bytes)
+                .ok()
+                .map(CombinedPublicKey::Ed25519)
+        }
+        _ => None,
+    }
+}
+
+/// Parses a caller-supplied public key: 33 or 65 bytes for secp256k1
+/// (compressed or uncompressed SEC1), 32 bytes for ed25519.
+pub(crate) fn parse_public_key(bytes: &[u8]) -> Option<CombinedPublicKey> {
+    match bytes.len() {
+        32 => decode_public_key("ed25519", bytes),
+        33 | 65 => decode_public_key("secp256k1", bytes),
+        _ => None,
+    }
+}
+
+/// Like [`parse_public_key`], for a key passed as an argument: raises
+/// `ValueError` if it cannot be parsed.
+pub(crate) fn public_key_arg(bytes: &[u8]) -> PyResult<CombinedPublicKey> {
+    parse_public_key(bytes).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "invalid public key: expected a 33- or 65-byte secp256k1 key \
+             or a 32-byte ed25519 key, got {} bytes",
+            bytes.len()
+        ))
+    })
+}
+
+/// Check a signature over content assembled outside an `Enr`, such as the
+/// output of `Enr.signable_content()` or another implementation's.
+///
+/// `content` is the RLP list of seq and key/value pairs that identity
+/// scheme `scheme` signs; only `"v4"` is defined. `public_key` is a
+/// secp256k1 key (33 or 65 bytes), whose signature is the 64-byte `r || s`
+/// over the keccak256 of `content`, or a 32-byte ed25519 key. Returns
+/// `False` if the signature does not verify.
+#[pyfunction]
+#[pyo3(signature = (content, signature, public_key, scheme="v4"))]
+pub(crate) fn verify_content(
+    py: Python<'_>,
+    content: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+    scheme: &str,
+) -> PyResult<bool> {
+    if scheme != "v4" {
+        return Err(PyValueError::new_err(format!(
+            "unsupported identity scheme {scheme:?}, expected \"v4\""
+        )));
+    }
+    let key = public_key_arg(public_key)?;
+    Ok(py.detach(|| key.verify_v4(content, signature)))
+}
+
+/// The signature algorithm of a key, named after the ENR key its public key
+/// is stored under.
+#[pyclass(name = "KeyType", eq, eq_int, frozen, hash, from_py_object)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum KeyType {
+    #[pyo3(name = "SECP256K1")]
+    Secp256k1,
+    #[pyo3(name = "ED25519")]
+    Ed25519,
+}
+
+impl KeyType {
+    pub(crate) fn of(public_key: &CombinedPublicKey) -> Self {
+        match public_key {
+            CombinedPublicKey::Secp256k1(_) => KeyType::Secp256k1,
+            CombinedPublicKey::Ed25519(_) => KeyType::Ed25519,
+        }
+    }
+}
+
+#[pymethods]
+impl KeyType {
+    /// The ENR key the public key is stored under: `"secp256k1"` or `"ed25519"`.
+    #[getter]
+    fn enr_key(&self) -> &'static str {
+        match self {
+            KeyType::Secp256k1 => "secp256k1",
+            KeyType::Ed25519 => "ed25519",
+        }
+    }
+}
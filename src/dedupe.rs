@@ -0,0 +1,223 @@
+//! Incremental deduplication of records arriving in batches.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+
+use enr::CombinedKey;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::bloom::NodeIdArg;
+use crate::Enr;
+
+struct Slot {
+    record: enr::Enr<CombinedKey>,
+    /// Position of this node id in `State::order`.
+    tick: u64,
+}
+
+#[derive(Default)]
+struct State {
+    by_node: HashMap<[u8; 32], Slot>,
+    /// Node ids ordered from least to most recently seen.
+    order: BTreeMap<u64, [u8; 32]>,
+    next_tick: u64,
+    evictions: u64,
+}
+
+impl State {
+    /// Marks `node_id` as seen now, returning its new tick.
+    fn touch(&mut self, node_id: [u8; 32], old_tick: Option<u64>) -> u64 {
+        if let Some(tick) = old_tick {
+            self.order.remove(&tick);
+        }
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        self.order.insert(tick, node_id);
+        tick
+    }
+
+    /// Drops the least recently seen record.
+    fn evict_oldest(&mut self) -> Option<enr::Enr<CombinedKey>> {
+        let (_, node_id) = self.order.pop_first()?;
+        self.evictions += 1;
+        self.by_node.remove(&node_id).map(|slot| slot.record)
+    }
+}
+
+/// What changed while feeding one batch to a [`StreamingDeduper`].
+#[pyclass(name = "FeedResult", frozen, get_all)]
+pub(crate) struct FeedResult {
+    /// Records that were new or replaced a lower seq, in first-updated order.
+    updated: Vec<Py<Enr>>,
+    /// Records dropped to stay within `max_entries`, oldest first.
+    evicted: Vec<Py<Enr>>,
+}
+
+#[pymethods]
+impl FeedResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "FeedResult(updated={}, evicted={})",
+            self.updated.len(),
+            self.evicted.len()
+        )
+    }
+}
+
+/// Keeps the highest-seq record per node id across successive batches.
+///
+/// With `max_entries` set, the least recently seen node is evicted once the
+/// limit is reached, so memory stays bounded however many batches are fed.
+#[pyclass(name = "StreamingDeduper", frozen)]
+pub(crate) struct StreamingDeduper {
+    max_entries: Option<usize>,
+    state: Mutex<State>,
+}
+
+impl StreamingDeduper {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("deduper lock poisoned")
+    }
+}
+
+fn into_py_records(
+    py: Python<'_>,
+    records: impl IntoIterator<Item = enr::Enr<CombinedKey>>,
+) -> PyResult<Vec<Py<Enr>>> {
+    records
+        .into_iter()
+        .map(|inner| Py::new(py, Enr { inner }))
+        .collect()
+}
+
+#[pymethods]
+impl StreamingDeduper {
+    #[new]
+    #[pyo3(signature = (max_entries=None))]
+    fn new(max_entries: Option<usize>) -> PyResult<Self> {
+        if max_entries == Some(0) {
+            return Err(PyValueError::new_err("max_entries must be positive"));
+        }
+        Ok(StreamingDeduper {
+            max_entries,
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    /// Feed a batch of records.
+    ///
+    /// Every record counts as a sighting of its node, even if its seq is not
+    /// newer than the one already held.
+    fn feed(&self, py: Python<'_>, enrs: Vec<PyRef<'_, Enr>>) -> PyResult<FeedResult> {
+        let records: Vec<enr::Enr<CombinedKey>> = enrs.iter().map(|e| e.inner.clone()).collect();
+        drop(enrs);
+
+        let (updated, evicted) = py.detach(|| {
+            let mut state = self.lock();
+            let mut updated_ids: Vec<[u8; 32]> = Vec::new();
+            let mut seen_updated: HashSet<[u8; 32]> = HashSet::new();
+            let mut evicted = Vec::new();
+
+            for record in records {
+                let node_id = record.node_id().raw();
+                let old_tick = state.by_node.get(&node_id).map(|slot| slot.tick);
+                let tick = state.touch(node_id, old_tick);
+                let is_update = match state.by_node.get_mut(&node_id) {
+                    Some(slot) => {
+                        slot.tick = tick;
+                        if record.seq() > slot.record.seq() {
+                            slot.record = record;
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                    None => {
+                        state.by_node.insert(node_id, Slot { record, tick });
+                        true
+                    }
+                };
+                if is_update && seen_updated.insert(node_id) {
+                    updated_ids.push(node_id);
+                }
+                if let Some(max) = self.max_entries {
+                    while state.by_node.len() > max {
+                        evicted.extend(state.evict_oldest());
+                    }
+                }
+            }
+
+            // A node updated and then evicted in the same batch is only reported as evicted.
+            let updated: Vec<_> = updated_ids
+                .iter()
+                .filter_map(|id| state.by_node.get(id).map(|slot| slot.record.clone()))
+                .collect();
+            (updated, evicted)
+        });
+
+        Ok(FeedResult {
+            updated: into_py_records(py, updated)?,
+            evicted: into_py_records(py, evicted)?,
+        })
+    }
+
+    /// The record currently held for a node id (or a record's node id).
+    fn get(&self, node_id: NodeIdArg<'_>) -> PyResult<Option<Enr>> {
+        let node_id = node_id.node_id()?;
+        Ok(self.lock().by_node.get(&node_id).map(|slot| Enr {
+            inner: slot.record.clone(),
+        }))
+    }
+
+    fn __contains__(&self, node_id: NodeIdArg<'_>) -> PyResult<bool> {
+        let node_id = node_id.node_id()?;
+        Ok(self.lock().by_node.contains_key(&node_id))
+    }
+
+    /// All records currently held, from least to most recently seen.
+    fn records(&self, py: Python<'_>) -> PyResult<Vec<Py<Enr>>> {
+        let records: Vec<_> = {
+            let state = self.lock();
+            state
+                .order
+                .values()
+                .map(|id| state.by_node[id].record.clone())
+                .collect()
+        };
+        into_py_records(py, records)
+    }
+
+    /// Drop all records and reset the eviction counter.
+    fn clear(&self) {
+        *self.lock() = State::default();
+    }
+
+    fn __len__(&self) -> usize {
+        self.lock().by_node.len()
+    }
+
+    #[getter]
+    fn max_entries(&self) -> Option<usize> {
+        self.max_entries
+    }
+
+    /// Total number of records evicted so far.
+    #[getter]
+    fn evictions(&self) -> u64 {
+        self.lock().evictions
+    }
+
+    fn __repr__(&self) -> String {
+        let state = self.lock();
+        let max_entries = match self.max_entries {
+            Some(n) => n.to_string(),
+            None => "None".to_string(),
+        };
+        format!(
+            "StreamingDeduper(max_entries={max_entries}, len={}, evictions={})",
+            state.by_node.len(),
+            state.evictions
+        )
+    }
+}
@@ -0,0 +1,87 @@
+//! Field-level comparison of two records.
+
+use enr::CombinedKey;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+
+use crate::keys;
+
+/// What changed between two records, as returned by `Enr.diff`.
+///
+/// Values are decoded by key: addresses as strings, ports as ints, `id` as
+/// a string and everything else as bytes.
+#[pyclass(name = "EnrDiff", frozen, get_all)]
+pub(crate) struct EnrDiff {
+    /// Keys only in the newer record, with their values.
+    added: Py<PyDict>,
+    /// Keys only in the older record, with their values.
+    removed: Py<PyDict>,
+    /// Keys in both records with different values, as `(old, new)` pairs.
+    changed: Py<PyDict>,
+    /// Newer seq minus older seq; negative if the "newer" record is older.
+    seq_delta: i128,
+}
+
+impl EnrDiff {
+    pub(crate) fn between(
+        py: Python<'_>,
+        old: &enr::Enr<CombinedKey>,
+        new: &enr::Enr<CombinedKey>,
+    ) -> PyResult<Self> {
+        let added = PyDict::new(py);
+        let removed = PyDict::new(py);
+        let changed = PyDict::new(py);
+        for (key, old_value) in old.iter() {
+            let name = String::from_utf8_lossy(key);
+            match new.get_raw_rlp(key) {
+                None => removed.set_item(&name, keys::decode_value(py, &name, old_value)?)?,
+                Some(new_value) if new_value != old_value => {
+                    let pair = PyTuple::new(
+                        py,
+                        [
+                            keys::decode_value(py, &name, old_value)?,
+                            keys::decode_value(py, &name, new_value)?,
+                        ],
+                    )?;
+                    changed.set_item(&name, pair)?;
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, new_value) in new.iter() {
+            if old.get_raw_rlp(key).is_none() {
+                let name = String::from_utf8_lossy(key);
+                added.set_item(&name, keys::decode_value(py, &name, new_value)?)?;
+            }
+        }
+        Ok(EnrDiff {
+            added: added.unbind(),
+            removed: removed.unbind(),
+            changed: changed.unbind(),
+            seq_delta: i128::from(new.seq()) - i128::from(old.seq()),
+        })
+    }
+}
+
+#[pymethods]
+impl EnrDiff {
+    /// `True` if any key was added, removed or changed.
+    fn __bool__(&self, py: Python<'_>) -> bool {
+        !(self.added.bind(py).is_empty()
+            && self.removed.bind(py).is_empty()
+            && self.changed.bind(py).is_empty())
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let keys = |d: &Py<PyDict>| -> PyResult<Vec<String>> {
+            d.bind(py).keys().iter().map(|k| k.extract()).collect()
+        };
+        Ok(format!(
+            "EnrDiff(added={:?}, removed={:?}, changed={:?}, seq_delta={})",
+            keys(&self.added)?,
+            keys(&self.removed)?,
+            keys(&self.changed)?,
+            self.seq_delta
+        ))
+    }
+}
@@ -0,0 +1,184 @@
+//! The parts of the discv5 wire protocol needed to check that a node is
+//! listening: an ordinary message packet it cannot decrypt, and the
+//! WHOAREYOU packet it answers with.
+//!
+//! Packet headers are masked with AES-128-CTR keyed by the recipient's node
+//! id.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use rand::RngCore;
+
+const PROTOCOL_ID: &[u8; 6] = b"discv5";
+const VERSION: [u8; 2] = [0x00, 0x01];
+const FLAG_MESSAGE: u8 = 0;
+const FLAG_WHOAREYOU: u8 = 1;
+/// Protocol id, version, flag, nonce and authdata size.
+const STATIC_HEADER_LEN: usize = 6 + 2 + 1 + 12 + 2;
+const MASKING_IV_LEN: usize = 16;
+
+/// AES-128-CTR with a 128-bit big-endian counter, as discv5 masks headers.
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+/// Masks or unmasks `data` with AES-128-CTR starting at `iv`; the same
+/// operation does both.
+fn aes_ctr(key: &[u8; 16], iv: &[u8; 16], data: &mut [u8]) {
+    Aes128Ctr::new(key.into(), iv.into()).apply_keystream(data);
+}
+
+fn masking_key(node_id: &[u8; 32]) -> [u8; 16] {
+    let mut key = [0u8; 16];
+    key.copy_from_slice(&node_id[..16]);
+    key
+}
+
+/// A packet that `dest_id` cannot decrypt, so that it answers with a
+/// WHOAREYOU carrying `nonce`: an ordinary message from `src_id` with a
+/// random message body.
+pub(crate) fn random_message(dest_id: &[u8; 32], src_id: &[u8; 32], nonce: &[u8; 12]) -> Vec<u8> {
+    let mut rng = rand::thread_rng();
+    let mut iv = [0u8; MASKING_IV_LEN];
+    rng.fill_bytes(&mut iv);
+
+    let mut header = Vec::with_capacity(STATIC_HEADER_LEN + 32);
+    header.extend_from_slice(PROTOCOL_ID);
+    header.extend_from_slice(&VERSION);
+    header.push(FLAG_MESSAGE);
+    header.extend_from_slice(nonce);
+    header.extend_from_slice(&32u16.to_be_bytes());
+    header.extend_from_slice(src_id);
+    aes_ctr(&masking_key(dest_id), &iv, &mut header);
+
+    let mut message = [0u8; 20];
+    rng.fill_bytes(&mut message);
+    [&iv[..], &header, &message].concat()
+}
+
+/// Returns `true` if `packet`, sent to `local_id`, is a WHOAREYOU answering
+/// the message with `nonce`.
+pub(crate) fn is_whoareyou(packet: &[u8], local_id: &[u8; 32], nonce: &[u8; 12]) -> bool {
+    if packet.len() < MASKING_IV_LEN + STATIC_HEADER_LEN {
+        return false;
+    }
+    let mut iv = [0u8; MASKING_IV_LEN];
+    iv.copy_from_slice(&packet[..MASKING_IV_LEN]);
+    let mut header = [0u8; STATIC_HEADER_LEN];
+    header.copy_from_slice(&packet[MASKING_IV_LEN..MASKING_IV_LEN + STATIC_HEADER_LEN]);
+    aes_ctr(&masking_key(local_id), &iv, &mut header);
+    header[..6] == PROTOCOL_ID[..]
+        && header[6..8] == VERSION
+        && header[8] == FLAG_WHOAREYOU
+        && header[9..21] == nonce[..]
+}
+
+/// Sends a message the node cannot decrypt and waits for its WHOAREYOU.
+pub(crate) fn ping(dest_id: &[u8; 32], addr: SocketAddr, timeout: Duration) -> io::Result<()> {
+    let local: IpAddr = match addr {
+        SocketAddr::V4(_) => Ipv4Addr::UNSPECIFIED.into(),
+        SocketAddr::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
+    };
+    let socket = UdpSocket::bind((local, 0))?;
+    // Connecting filters out other senders and surfaces ICMP errors, such as
+    // a closed port, as a failed receive.
+    socket.connect(addr)?;
+    let mut rng = rand::thread_rng();
+    let mut src_id = [0u8; 32];
+    let mut nonce = [0u8; 12];
+    rng.fill_bytes(&mut src_id);
+    rng.fill_bytes(&mut nonce);
+    socket.send(&random_message(dest_id, &src_id, &nonce))?;
+
+    let deadline = Instant::now() + timeout;
+    let mut buf = [0u8; 1280];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        socket.set_read_timeout(Some(remaining))?;
+        let n = socket.recv(&mut buf).map_err(|err| match err.kind() {
+            io::ErrorKind::WouldBlock => io::ErrorKind::TimedOut.into(),
+            _ => err,
+        })?;
+        if is_whoareyou(&buf[..n], &src_id, &nonce) {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    // Packets from the discv5 wire test vectors, both sent to `DEST_ID` from
+    // `SRC_ID` with a zero masking IV.
+    const SRC_ID: &str = "aaaa8419e9f49d0083561b48287df592939a8d19947d8c0ef88f2a4856a69fbb";
+    const DEST_ID: &str = "bbbb9d047f0488c0b5a93c1c3f2d8bafc7c8ff337024a55434a0d0555de64db9";
+    const PING_PACKET: &str = concat!(
+        "00000000000000000000000000000000088b3d4342774649325f313964a39e55",
+        "ea96c005ad52be8c7560413a7008f16c9e6d2f43bbea8814a546b7409ce783d3",
+        "4c4f53245d08dab84102ed931f66d1492acb308fa1c6715b9d139b81acbdcc",
+    );
+    const WHOAREYOU_PACKET: &str = concat!(
+        "00000000000000000000000000000000088b3d434277464933a1ccc59f5967ad",
+        "1d6035f15e528627dde75cd68292f9e6c27d6b66c8100a873fcbaed4e16b8d",
+    );
+
+    fn dest_key() -> [u8; 16] {
+        masking_key(&hex(DEST_ID).try_into().unwrap())
+    }
+
+    #[test]
+    fn masks_ping_header_as_the_spec_vector() {
+        let packet = hex(PING_PACKET);
+        let mut header = [PROTOCOL_ID.as_slice(), &VERSION, &[FLAG_MESSAGE]].concat();
+        header.extend_from_slice(&[0xff; 12]);
+        header.extend_from_slice(&32u16.to_be_bytes());
+        header.extend_from_slice(&hex(SRC_ID));
+        let plain = header.clone();
+
+        aes_ctr(&dest_key(), &[0; MASKING_IV_LEN], &mut header);
+        assert_eq!(header, packet[MASKING_IV_LEN..MASKING_IV_LEN + plain.len()]);
+        aes_ctr(&dest_key(), &[0; MASKING_IV_LEN], &mut header);
+        assert_eq!(header, plain);
+    }
+
+    #[test]
+    fn recognizes_whoareyou_spec_vector() {
+        let packet = hex(WHOAREYOU_PACKET);
+        let dest_id: [u8; 32] = hex(DEST_ID).try_into().unwrap();
+        let nonce: [u8; 12] = std::array::from_fn(|i| i as u8 + 1);
+        assert!(is_whoareyou(&packet, &dest_id, &nonce));
+        assert!(!is_whoareyou(&packet, &dest_id, &[0; 12]));
+        assert!(!is_whoareyou(
+            &packet[..MASKING_IV_LEN + 1],
+            &dest_id,
+            &nonce
+        ));
+    }
+
+    #[test]
+    fn random_message_unmasks_to_a_message_header() {
+        let dest_id: [u8; 32] = hex(DEST_ID).try_into().unwrap();
+        let src_id: [u8; 32] = hex(SRC_ID).try_into().unwrap();
+        let nonce = [7; 12];
+        let packet = random_message(&dest_id, &src_id, &nonce);
+        let iv: [u8; MASKING_IV_LEN] = packet[..MASKING_IV_LEN].try_into().unwrap();
+        let mut header = packet[MASKING_IV_LEN..MASKING_IV_LEN + STATIC_HEADER_LEN + 32].to_vec();
+        aes_ctr(&dest_key(), &iv, &mut header);
+        assert_eq!(header[..6], PROTOCOL_ID[..]);
+        assert_eq!(header[8], FLAG_MESSAGE);
+        assert_eq!(header[9..21], nonce);
+        assert_eq!(header[STATIC_HEADER_LEN..], src_id);
+    }
+}
@@ -0,0 +1,80 @@
+//! How records are shown in `repr()`.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use enr::CombinedKey;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::convert;
+
+/// Whether `repr()` shows decoded fields instead of the base64 text.
+static FIELDS_REPR: AtomicBool = AtomicBool::new(false);
+
+/// `repr()` of a record in the current style.
+pub(crate) fn repr(enr: &enr::Enr<CombinedKey>) -> String {
+    if FIELDS_REPR.load(Ordering::Relaxed) {
+        fields_repr(enr)
+    } else {
+        format!("Enr({})", enr.to_base64())
+    }
+}
+
+/// The `"fields"` repr style.
+pub(crate) fn fields_repr(enr: &enr::Enr<CombinedKey>) -> String {
+    let node_id = enr.node_id().raw();
+    let mut out = format!(
+        "Enr(node_id=0x{}\u{2026}, seq={}",
+        convert::to_hex(&node_id[..4]),
+        enr.seq()
+    );
+    // Writing to a `String` cannot fail.
+    if let Some(ip) = enr.ip4() {
+        let _ = write!(out, ", ip4={ip}");
+    }
+    if let Some(ip) = enr.ip6() {
+        let _ = write!(out, ", ip6={ip}");
+    }
+    let ports = [
+        ("tcp4", enr.tcp4()),
+        ("udp4", enr.udp4()),
+        ("tcp6", enr.tcp6()),
+        ("udp6", enr.udp6()),
+    ];
+    for (name, port) in ports {
+        if let Some(port) = port {
+            let _ = write!(out, ", {name}={port}");
+        }
+    }
+    out.push(')');
+    out
+}
+
+/// Select how `repr()` shows records: `"base64"` (the default) or
+/// `"fields"`, which shows a node id prefix, the seq, and any addresses and
+/// ports, e.g. `Enr(node_id=0x9d3f0010…, seq=42, ip4=1.2.3.4, tcp4=9000)`.
+#[pyfunction]
+pub(crate) fn set_repr_style(style: &str) -> PyResult<()> {
+    let fields = match style {
+        "base64" => false,
+        "fields" => true,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "unknown repr style {style:?}, expected \"base64\" or \"fields\""
+            )))
+        }
+    };
+    FIELDS_REPR.store(fields, Ordering::Relaxed);
+    Ok(())
+}
+
+/// The style `repr()` currently uses.
+#[pyfunction]
+pub(crate) fn get_repr_style() -> &'static str {
+    if FIELDS_REPR.load(Ordering::Relaxed) {
+        "fields"
+    } else {
+        "base64"
+    }
+}
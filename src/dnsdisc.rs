@@ -0,0 +1,485 @@
+//! Resolving EIP-1459 trees: TXT lookups over the DNS wire protocol, and the
+//! walk from a tree's root through its branches and links.
+//!
+//! Queries go straight to the nameservers of `/etc/resolv.conf`, or to one
+//! given by the caller, over UDP with EDNS(0), falling back to TCP for
+//! truncated answers. The C library's resolver has no portable TXT lookup,
+//! and a stub resolver needs little more than building one query and
+//! reading the answers.
+
+use std::collections::{HashSet, VecDeque};
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket};
+use std::time::{Duration, Instant};
+
+use enr::CombinedKey;
+use pyo3::exceptions::{PyConnectionError, PyValueError};
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::errors::{DecodeError, SignatureError};
+use crate::progress::{CancelToken, Progress, Stopped};
+use crate::tree::{self, Entry, Link};
+use crate::{batch, Enr};
+
+const DNS_PORT: u16 = 53;
+const TYPE_TXT: u16 = 16;
+const TYPE_OPT: u16 = 41;
+const CLASS_IN: u16 = 1;
+/// Largest UDP answer we ask servers for.
+const UDP_PAYLOAD: u16 = 4096;
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAG_TRUNCATED: u16 = 0x0200;
+const FLAG_RECURSION_DESIRED: u16 = 0x0100;
+const RCODE_NXDOMAIN: u16 = 3;
+const HEADER_LEN: usize = 12;
+
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+
+/// The nameservers listed in `/etc/resolv.conf`.
+///
+/// Unlike the C library, which falls back to a server on localhost, a
+/// missing file or one without nameservers is an error: on systems without
+/// a local resolver the lookups would otherwise only ever time out.
+fn system_servers() -> PyResult<Vec<SocketAddr>> {
+    let conf = std::fs::read_to_string(RESOLV_CONF).map_err(|err| {
+        PyConnectionError::new_err(format!(
+            "cannot read {RESOLV_CONF} to find a nameserver ({err}); pass nameserver="
+        ))
+    })?;
+    let servers: Vec<SocketAddr> = conf
+        .lines()
+        .filter_map(|line| line.strip_prefix("nameserver"))
+        .filter_map(|rest| rest.trim().parse::<IpAddr>().ok())
+        .map(|ip| SocketAddr::new(ip, DNS_PORT))
+        .collect();
+    if servers.is_empty() {
+        return Err(PyConnectionError::new_err(format!(
+            "{RESOLV_CONF} lists no nameserver; pass nameserver="
+        )));
+    }
+    Ok(servers)
+}
+
+/// A `nameserver=` argument: an IP address, with an optional port.
+fn parse_server(text: &str) -> PyResult<SocketAddr> {
+    text.parse::<IpAddr>()
+        .map(|ip| SocketAddr::new(ip, DNS_PORT))
+        .or_else(|_| text.parse::<SocketAddr>())
+        .map_err(|_| {
+            PyValueError::new_err(format!(
+                "invalid nameserver {text:?}: expected an IP address, optionally with a port"
+            ))
+        })
+}
+
+/// A TXT query for `name` with EDNS(0).
+fn query(id: u16, name: &str) -> io::Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(HEADER_LEN + name.len() + 2 + 4 + 11);
+    out.extend_from_slice(&id.to_be_bytes());
+    out.extend_from_slice(&FLAG_RECURSION_DESIRED.to_be_bytes());
+    // One question and one additional record, the OPT pseudo-record.
+    for count in [1u16, 0, 0, 1] {
+        out.extend_from_slice(&count.to_be_bytes());
+    }
+    let name = name.trim_end_matches('.');
+    if name.len() > 253 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("name too long: {name}"),
+        ));
+    }
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid name: {name}"),
+            ));
+        }
+        out.push(label.len() as u8);
+        out.extend_from_slice(label.as_bytes());
+    }
+    out.push(0);
+    out.extend_from_slice(&TYPE_TXT.to_be_bytes());
+    out.extend_from_slice(&CLASS_IN.to_be_bytes());
+    // OPT: root name, type, payload size as class, zero TTL and no data.
+    out.push(0);
+    out.extend_from_slice(&TYPE_OPT.to_be_bytes());
+    out.extend_from_slice(&UDP_PAYLOAD.to_be_bytes());
+    out.extend_from_slice(&[0; 6]);
+    Ok(out)
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed DNS answer")
+}
+
+/// Reads a big-endian `u16` at `*pos`, moving past it.
+fn read_u16(message: &[u8], pos: &mut usize) -> io::Result<u16> {
+    let bytes = message.get(*pos..*pos + 2).ok_or_else(malformed)?;
+    *pos += 2;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Moves past a possibly compressed name at `*pos`.
+fn skip_name(message: &[u8], pos: &mut usize) -> io::Result<()> {
+    loop {
+        let len = *message.get(*pos).ok_or_else(malformed)?;
+        match len {
+            0 => {
+                *pos += 1;
+                return Ok(());
+            }
+            // A pointer ends the name.
+            _ if len & 0xc0 == 0xc0 => {
+                *pos += 2;
+                return Ok(());
+            }
+            _ => *pos += 1 + usize::from(len),
+        }
+    }
+}
+
+/// What a server answered.
+enum Answer {
+    /// The text of each TXT record, its strings joined.
+    Records(Vec<String>),
+    /// Too long for UDP; ask again over TCP.
+    Truncated,
+}
+
+fn parse_answer(message: &[u8], id: u16) -> io::Result<Answer> {
+    let mut pos = 0;
+    if read_u16(message, &mut pos)? != id {
+        return Err(malformed());
+    }
+    let flags = read_u16(message, &mut pos)?;
+    if flags & FLAG_RESPONSE == 0 {
+        return Err(malformed());
+    }
+    if flags & FLAG_TRUNCATED != 0 {
+        return Ok(Answer::Truncated);
+    }
+    match flags & 0x000f {
+        0 => {}
+        RCODE_NXDOMAIN => return Ok(Answer::Records(Vec::new())),
+        rcode => {
+            return Err(io::Error::other(format!(
+                "server failed with rcode {rcode}"
+            )))
+        }
+    }
+    let questions = read_u16(message, &mut pos)?;
+    let answers = read_u16(message, &mut pos)?;
+    pos = HEADER_LEN;
+    for _ in 0..questions {
+        skip_name(message, &mut pos)?;
+        pos += 4;
+    }
+    let mut records = Vec::new();
+    for _ in 0..answers {
+        skip_name(message, &mut pos)?;
+        let kind = read_u16(message, &mut pos)?;
+        let class = read_u16(message, &mut pos)?;
+        pos += 4;
+        let len = usize::from(read_u16(message, &mut pos)?);
+        let data = message.get(pos..pos + len).ok_or_else(malformed)?;
+        pos += len;
+        // Answers may also hold the CNAMEs that led to the TXT records.
+        if kind != TYPE_TXT || class != CLASS_IN {
+            continue;
+        }
+        let mut text = Vec::with_capacity(len);
+        let mut rest = data;
+        while let Some((&n, tail)) = rest.split_first() {
+            let chunk = tail.get(..usize::from(n)).ok_or_else(malformed)?;
+            text.extend_from_slice(chunk);
+            rest = &tail[usize::from(n)..];
+        }
+        records.push(String::from_utf8_lossy(&text).into_owned());
+    }
+    Ok(Answer::Records(records))
+}
+
+fn timed_out() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "no answer in time")
+}
+
+fn ask_udp(server: SocketAddr, message: &[u8], id: u16, timeout: Duration) -> io::Result<Answer> {
+    let local: IpAddr = match server {
+        SocketAddr::V4(_) => Ipv4Addr::UNSPECIFIED.into(),
+        SocketAddr::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
+    };
+    let socket = UdpSocket::bind((local, 0))?;
+    socket.connect(server)?;
+    socket.send(message)?;
+    let deadline = Instant::now() + timeout;
+    let mut buf = vec![0u8; usize::from(UDP_PAYLOAD)];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(timed_out());
+        }
+        socket.set_read_timeout(Some(remaining))?;
+        let n = socket.recv(&mut buf).map_err(|err| match err.kind() {
+            io::ErrorKind::WouldBlock => timed_out(),
+            _ => err,
+        })?;
+        // Stray datagrams, such as late answers to an earlier query, are
+        // dropped.
+        if n >= 3 && buf[..2] == id.to_be_bytes() && buf[2] & 0x80 != 0 {
+            return parse_answer(&buf[..n], id);
+        }
+    }
+}
+
+fn ask_tcp(server: SocketAddr, message: &[u8], id: u16, timeout: Duration) -> io::Result<Answer> {
+    let mut stream = TcpStream::connect_timeout(&server, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    let len = u16::try_from(message.len()).map_err(|_| malformed())?;
+    stream.write_all(&[&len.to_be_bytes()[..], message].concat())?;
+    let mut len = [0u8; 2];
+    stream.read_exact(&mut len)?;
+    let mut answer = vec![0u8; usize::from(u16::from_be_bytes(len))];
+    stream.read_exact(&mut answer)?;
+    match parse_answer(&answer, id)? {
+        Answer::Truncated => Err(malformed()),
+        records => Ok(records),
+    }
+}
+
+/// Looks up TXT records, trying each nameserver in turn.
+struct Client {
+    servers: Vec<SocketAddr>,
+    timeout: Duration,
+}
+
+impl Client {
+    fn txt(&self, name: &str) -> io::Result<Vec<String>> {
+        let id = rand::random::<u16>();
+        let message = query(id, name)?;
+        let mut last_error = None;
+        for &server in &self.servers {
+            let answer =
+                ask_udp(server, &message, id, self.timeout).and_then(|answer| match answer {
+                    Answer::Truncated => ask_tcp(server, &message, id, self.timeout),
+                    records => Ok(records),
+                });
+            match answer {
+                Ok(Answer::Records(records)) => return Ok(records),
+                Ok(Answer::Truncated) => unreachable!("TCP answers are never truncated"),
+                Err(err) => {
+                    last_error = Some(io::Error::new(err.kind(), format!("{server}: {err}")))
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| io::Error::other("no nameservers")))
+    }
+}
+
+/// Why a walk ended early.
+enum Failure {
+    Error(PyErr),
+    Stopped(Stopped),
+}
+
+impl From<Stopped> for Failure {
+    fn from(stopped: Stopped) -> Self {
+        Failure::Stopped(stopped)
+    }
+}
+
+/// Which subtree of a tree an entry was reached from.
+#[derive(Clone, Copy, PartialEq)]
+enum Subtree {
+    Records,
+    Links,
+}
+
+/// Resolves trees one after the other, fetching the entries of each level
+/// of a tree in parallel.
+struct Walk<'a> {
+    client: &'a Client,
+    progress: &'a Progress,
+    follow_links: bool,
+}
+
+impl Walk<'_> {
+    /// The verified root of the tree `link` points to.
+    fn root(&self, link: &Link) -> Result<tree::Root, Failure> {
+        let domain = &link.domain;
+        let texts = self
+            .client
+            .txt(domain)
+            .map_err(|err| Failure::Error(lookup_error(domain, err)))?;
+        // The domain may hold other TXT records too, such as SPF policies.
+        let Some(text) = texts.iter().find(|text| text.starts_with("enrtree-root:")) else {
+            return Err(Failure::Error(DecodeError::new_err(format!(
+                "{domain}: no EIP-1459 root entry"
+            ))));
+        };
+        let root = match tree::parse_entry_detached(text) {
+            Ok(Entry::Root(root)) => root,
+            Ok(_) => unreachable!("entries starting with enrtree-root: are roots"),
+            Err(message) => {
+                return Err(Failure::Error(DecodeError::new_err(format!(
+                    "{domain}: {message}"
+                ))))
+            }
+        };
+        if !root.verify(&link.verifying_key()) {
+            return Err(Failure::Error(SignatureError::new_err(format!(
+                "{domain}: root entry is not signed by the key in the link"
+            ))));
+        }
+        Ok(root)
+    }
+
+    /// The entry named `hash` under `domain`, checked against its name.
+    fn entry(&self, domain: &str, hash: &str) -> Result<Entry, Failure> {
+        self.progress.check()?;
+        let name = format!("{hash}.{domain}");
+        let texts = self
+            .client
+            .txt(&name)
+            .map_err(|err| Failure::Error(lookup_error(&name, err)))?;
+        let Some(text) = texts.iter().find(|text| tree::matches_hash(hash, text)) else {
+            let message = if texts.is_empty() {
+                format!("{name}: no such entry")
+            } else {
+                format!("{name}: entry does not match its hash")
+            };
+            return Err(Failure::Error(DecodeError::new_err(message)));
+        };
+        let entry = tree::parse_entry_detached(text).map_err(|message| {
+            Failure::Error(DecodeError::new_err(format!("{name}: {message}")))
+        })?;
+        self.progress.advance(1)?;
+        Ok(entry)
+    }
+
+    /// The records of the tree `start` points to and, if following links,
+    /// of every tree reachable from it, each tree resolved once.
+    fn run(&self, start: Link) -> Result<Vec<enr::Enr<CombinedKey>>, Failure> {
+        let mut records = Vec::new();
+        let mut seen_trees = HashSet::from([start.clone()]);
+        let mut trees = VecDeque::from([start]);
+        while let Some(link) = trees.pop_front() {
+            self.progress.add_total(1);
+            let root = self.root(&link)?;
+            self.progress.advance(1)?;
+            let mut level = vec![(root.enr_root.clone(), Subtree::Records)];
+            if self.follow_links {
+                level.push((root.link_root.clone(), Subtree::Links));
+            }
+            let mut seen: HashSet<String> = level.iter().map(|(hash, _)| hash.clone()).collect();
+            while !level.is_empty() {
+                self.progress.add_total(level.len());
+                let entries = level
+                    .par_iter()
+                    .map(|(hash, _)| self.entry(&link.domain, hash))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut next = Vec::new();
+                for ((hash, subtree), entry) in level.into_iter().zip(entries) {
+                    match (entry, subtree) {
+                        (Entry::Branch(branch), _) => next.extend(
+                            branch
+                                .children
+                                .into_iter()
+                                .filter(|child| seen.insert(child.clone()))
+                                .map(|child| (child, subtree)),
+                        ),
+                        (Entry::Record(enr), Subtree::Records) => records.push(enr.inner),
+                        (Entry::Link(next_link), Subtree::Links) => {
+                            if seen_trees.insert(next_link.clone()) {
+                                trees.push_back(next_link);
+                            }
+                        }
+                        (entry, _) => {
+                            let kind = match entry {
+                                Entry::Root(_) => "root",
+                                Entry::Link(_) => "link",
+                                _ => "record",
+                            };
+                            return Err(Failure::Error(DecodeError::new_err(format!(
+                                "{hash}.{}: unexpected {kind} entry in the {} subtree",
+                                link.domain,
+                                if subtree == Subtree::Records {
+                                    "record"
+                                } else {
+                                    "link"
+                                }
+                            ))));
+                        }
+                    }
+                }
+                level = next;
+            }
+        }
+        Ok(batch::keep_highest_seq(records))
+    }
+}
+
+fn lookup_error(name: &str, err: io::Error) -> PyErr {
+    PyConnectionError::new_err(format!("TXT lookup of {name} failed: {err}"))
+}
+
+/// Resolve the EIP-1459 tree at `url`, an `enrtree://<key>@<domain>` link,
+/// and return its records.
+///
+/// The TXT lookups go to `nameserver` (`"ip"` or `"ip:port"`) or else to
+/// those of `/etc/resolv.conf`, `concurrency` at a time, with the GIL
+/// released; each waits `timeout` seconds per server. The root must be
+/// signed by the link's key. Links to other trees are followed, each tree
+/// resolved once however many link to it, unless `follow_links` is false.
+/// Records are deduplicated by node id, keeping the highest seq.
+///
+/// Raises `ConnectionError` if a lookup fails or, without `nameserver`, if
+/// `/etc/resolv.conf` names no nameserver; `DecodeError` for a missing
+/// or malformed entry, and `SignatureError` for a root not signed by its
+/// link's key. `progress(done, total)`, if given, counts entries fetched of
+/// those found so far; `cancel` and Ctrl-C stop the call once the lookups
+/// under way are done.
+#[pyfunction]
+#[pyo3(signature = (url, *, nameserver=None, timeout=5.0, concurrency=16, follow_links=true, progress=None, cancel=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn resolve_tree(
+    py: Python<'_>,
+    url: &str,
+    nameserver: Option<&str>,
+    timeout: f64,
+    concurrency: usize,
+    follow_links: bool,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Vec<Enr>> {
+    let start = Link::parse(url.trim()).map_err(DecodeError::new_err)?;
+    let timeout = Duration::try_from_secs_f64(timeout)
+        .ok()
+        .filter(|timeout| !timeout.is_zero())
+        .ok_or_else(|| PyValueError::new_err("timeout must be a positive number of seconds"))?;
+    if concurrency == 0 {
+        return Err(PyValueError::new_err("concurrency must be at least 1"));
+    }
+    let client = Client {
+        servers: match nameserver {
+            Some(server) => vec![parse_server(server)?],
+            None => system_servers()?,
+        },
+        timeout,
+    };
+    let progress = Progress::new(progress, cancel, 0);
+    let walk = Walk {
+        client: &client,
+        progress: &progress,
+        follow_links,
+    };
+    let outcome = progress.run(py, || batch::with_io_pool(concurrency, || walk.run(start)))?;
+    let records = match outcome {
+        Ok(records) => progress.finish(py, Ok(records))?,
+        Err(Failure::Error(err)) => return Err(err),
+        Err(Failure::Stopped(stopped)) => progress.finish(py, Err(stopped))?,
+    };
+    Ok(records.into_iter().map(|inner| Enr { inner }).collect())
+}
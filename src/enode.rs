@@ -0,0 +1,118 @@
+//! `enode://` URLs, the node addresses devp2p used before ENRs.
+
+use std::net::{IpAddr, SocketAddr};
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha3::{Digest, Keccak256};
+
+use crate::convert;
+use crate::errors::DecodeError;
+
+const SCHEME: &str = "enode://";
+
+/// A node address in `enode://<public key>@<ip>:<tcp port>?discport=<udp port>` form.
+#[pyclass(name = "Enode", frozen, eq, hash, skip_from_py_object)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Enode {
+    /// Uncompressed secp256k1 public key without the `0x04` prefix.
+    public_key: [u8; 64],
+    ip: IpAddr,
+    tcp: u16,
+    udp: u16,
+}
+
+impl Enode {
+    /// Parses an `enode://` URL, checking that the public key is a curve point.
+    pub(crate) fn parse(text: &str) -> Result<Self, String> {
+        let rest = text
+            .strip_prefix(SCHEME)
+            .ok_or_else(|| format!("expected an {SCHEME} URL"))?;
+        let (key, address) = rest
+            .split_once('@')
+            .ok_or("missing '@' between public key and address")?;
+        let public_key: [u8; 64] = convert::from_hex(key)
+            .and_then(|k| k.try_into().ok())
+            .ok_or("public key must be 128 hex digits")?;
+        let mut sec1 = [0x04; 65];
+        sec1[1..].copy_from_slice(&public_key);
+        k256::ecdsa::VerifyingKey::from_sec1_bytes(&sec1)
+            .map_err(|_| "public key is not a valid secp256k1 key")?;
+
+        let (address, query) = match address.split_once('?') {
+            Some((address, query)) => (address, Some(query)),
+            None => (address, None),
+        };
+        let socket: SocketAddr = address
+            .parse()
+            .map_err(|_| format!("invalid address {address:?}, expected ip:port"))?;
+        let mut udp = socket.port();
+        for param in query.into_iter().flat_map(|q| q.split('&')) {
+            if let Some(port) = param.strip_prefix("discport=") {
+                udp = port
+                    .parse()
+                    .map_err(|_| format!("invalid discport {port:?}"))?;
+            }
+        }
+        Ok(Enode {
+            public_key,
+            ip: socket.ip(),
+            tcp: socket.port(),
+            udp,
+        })
+    }
+
+    fn url(&self) -> String {
+        let address = SocketAddr::new(self.ip, self.tcp);
+        let mut url = format!("{SCHEME}{}@{address}", convert::to_hex(&self.public_key));
+        if self.udp != self.tcp {
+            url.push_str(&format!("?discport={}", self.udp));
+        }
+        url
+    }
+}
+
+#[pymethods]
+impl Enode {
+    /// Parse an `enode://` URL.
+    #[staticmethod]
+    fn from_url(url: &str) -> PyResult<Self> {
+        Enode::parse(url.trim()).map_err(DecodeError::new_err)
+    }
+
+    /// The 64-byte uncompressed secp256k1 public key, without the `0x04` prefix.
+    #[getter]
+    fn public_key<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.public_key)
+    }
+
+    /// keccak256 of the public key, the same node id an ENR for this key has.
+    #[getter]
+    fn node_id<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &Keccak256::digest(self.public_key))
+    }
+
+    #[getter]
+    fn ip(&self) -> String {
+        self.ip.to_string()
+    }
+
+    #[getter]
+    fn tcp(&self) -> u16 {
+        self.tcp
+    }
+
+    /// The discovery port: `discport` if given, otherwise the TCP port.
+    #[getter]
+    fn udp(&self) -> u16 {
+        self.udp
+    }
+
+    fn __str__(&self) -> String {
+        self.url()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Enode({:?})", self.url())
+    }
+}
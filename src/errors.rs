@@ -0,0 +1,230 @@
+//! Exception hierarchy raised by the module.
+//!
+//! Every exception derives from `EnrError`, which is itself a `ValueError` so
+//! code written against earlier releases keeps working.
+
+use std::panic::AssertUnwindSafe;
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyUserWarning, PyValueError};
+use pyo3::prelude::*;
+
+use crate::conformance::{self, Violation};
+use crate::rlp::MAX_ENR_SIZE;
+
+create_exception!(
+    pyenr,
+    EnrError,
+    PyValueError,
+    "Base class for errors raised by pyenr."
+);
+create_exception!(
+    pyenr,
+    DecodeError,
+    EnrError,
+    "Input could not be decoded as a record."
+);
+create_exception!(
+    pyenr,
+    SignatureError,
+    EnrError,
+    "A record's signature is invalid or could not be produced."
+);
+create_exception!(
+    pyenr,
+    SizeError,
+    EnrError,
+    "A record would exceed the EIP-778 max size of 300 bytes."
+);
+create_exception!(
+    pyenr,
+    SequenceOverflowError,
+    EnrError,
+    "A record's seq is already u64::MAX, so it cannot be updated again."
+);
+create_exception!(
+    pyenr,
+    FieldError,
+    EnrError,
+    "A field value is invalid for its key."
+);
+
+create_exception!(
+    pyenr,
+    CancelledError,
+    EnrError,
+    "A batch call was stopped through its CancelToken."
+);
+
+create_exception!(
+    pyenr,
+    EnrWarning,
+    PyUserWarning,
+    "A decoded record is usable but looks wrong."
+);
+
+/// Message of the `DecoderError::Custom` the `enr` crate returns when a decoded
+/// record's signature does not verify.
+const INVALID_SIGNATURE: &str = "Invalid Signature";
+
+/// Maps a failure to decode a record to `DecodeError`, or `SignatureError`
+/// if only the signature check failed.
+pub(crate) fn decode_error(err: alloy_rlp::Error) -> PyErr {
+    match err {
+        alloy_rlp::Error::Custom(INVALID_SIGNATURE) => SignatureError::new_err(err.to_string()),
+        _ => DecodeError::new_err(err.to_string()),
+    }
+}
+
+/// Runs `f`, returning the panic message instead of unwinding into Python if it panics.
+///
+/// Decoding runs on untrusted input, so a bug there must surface as an
+/// ordinary decode failure rather than PyO3's `PanicException`.
+pub(crate) fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    std::panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        format!("internal error while decoding: {message}")
+    })
+}
+
+/// Runs a decode step, reporting a panic inside it as `DecodeError`.
+pub(crate) fn guard_decode<T>(py: Python<'_>, f: impl FnOnce() -> PyResult<T>) -> PyResult<T> {
+    catch_panic(f).unwrap_or_else(|message| {
+        let err = DecodeError::new_err(message);
+        attach_context(py, &err, None);
+        Err(err)
+    })
+}
+
+/// Sets the triage attributes every decode error carries: `code`, `offset`,
+/// `key`, `expected`, `actual` and `format`, each `None` when not known.
+fn attach_context(py: Python<'_>, err: &PyErr, violation: Option<&Violation>) {
+    let value = err.value(py);
+    // Setting attributes on a fresh exception instance only fails on memory exhaustion.
+    let _ = value.setattr("code", violation.map(|v| v.kind.code()));
+    let _ = value.setattr("offset", violation.map(|v| v.offset));
+    let _ = value.setattr("key", violation.and_then(|v| v.key.as_deref()));
+    let _ = value.setattr("expected", violation.and_then(|v| v.expected));
+    let _ = value.setattr("actual", violation.and_then(|v| v.actual));
+    let _ = value.setattr("format", None::<&str>);
+}
+
+/// Builds a `DecodeError` for a strict-mode violation.
+pub(crate) fn violation_error(py: Python<'_>, violation: &Violation) -> PyErr {
+    let err = DecodeError::new_err(format!(
+        "record violates EIP-778 ({}): {violation}",
+        violation.kind.code()
+    ));
+    attach_context(py, &err, Some(violation));
+    err
+}
+
+/// Builds a `DecodeError` for a record refused under the `"reject"`
+/// placeholder policy.
+pub(crate) fn placeholder_error(py: Python<'_>, violation: &Violation) -> PyErr {
+    let err = DecodeError::new_err(format!("record has a placeholder value: {violation}"));
+    attach_context(py, &err, Some(violation));
+    err
+}
+
+/// Builds a `DecodeError` for `0x`-prefixed text that is not valid hex.
+pub(crate) fn hex_error(py: Python<'_>) -> PyErr {
+    let err = DecodeError::new_err("invalid hex encoding");
+    attach_context(py, &err, None);
+    err
+}
+
+/// Builds a `DecodeError` for text recognised as `format` (such as
+/// `"enode"`) that is not a record, carrying the name as `format`.
+pub(crate) fn unsupported_format_error(py: Python<'_>, format: &str, reason: &str) -> PyErr {
+    let err = DecodeError::new_err(format!("not a record: {reason}"));
+    attach_context(py, &err, None);
+    let _ = err.value(py).setattr("format", format);
+    err
+}
+
+/// Builds a `DecodeError` for a `format` (such as `"CBOR"`) map that does
+/// not hold a record in the `to_dict()` schema.
+pub(crate) fn interchange_error(py: Python<'_>, format: &str, message: &str) -> PyErr {
+    let err = DecodeError::new_err(format!("invalid {format} record: {message}"));
+    attach_context(py, &err, None);
+    err
+}
+
+/// Builds a `DecodeError` for text that is not valid base64.
+pub(crate) fn base64_error(py: Python<'_>, err: base64::DecodeError) -> PyErr {
+    let err = DecodeError::new_err(format!("invalid base64 encoding: {err}"));
+    attach_context(py, &err, None);
+    err
+}
+
+/// Like [`decode_error`], but locates the problem in `data` so the exception
+/// carries the offset and key of the first malformed item.
+pub(crate) fn decode_error_in(py: Python<'_>, err: alloy_rlp::Error, data: &[u8]) -> PyErr {
+    let err = decode_error(err);
+    let violations = conformance::check(data);
+    attach_context(py, &err, violations.first());
+    err
+}
+
+/// Maps a failure to update or build a record to the matching exception.
+pub(crate) fn record_error(err: enr::Error) -> PyErr {
+    match err {
+        enr::Error::ExceedsMaxSize => SizeError::new_err(err.to_string()),
+        enr::Error::SigningError => SignatureError::new_err(err.to_string()),
+        enr::Error::SequenceNumberTooHigh => SequenceOverflowError::new_err(err.to_string()),
+        enr::Error::UnsupportedIdentityScheme | enr::Error::InvalidRlpData(_) => {
+            FieldError::new_err(err.to_string())
+        }
+    }
+}
+
+/// Maps an unparsable field value (such as an IP address string) to `FieldError`.
+pub(crate) fn field_error<E: std::fmt::Display>(err: E) -> PyErr {
+    FieldError::new_err(err.to_string())
+}
+
+/// Builds a `SizeError` for setting `key`, carrying `key`, `size` and `overflow` attributes.
+pub(crate) fn size_error(py: Python<'_>, key: &str, size: usize) -> PyErr {
+    let overflow = size - MAX_ENR_SIZE;
+    let err = SizeError::new_err(format!(
+        "setting {key:?} would make the record {size} bytes, \
+         {overflow} over the max size of {MAX_ENR_SIZE}"
+    ));
+    let value = err.value(py);
+    // Setting attributes on a fresh exception instance only fails on memory exhaustion.
+    let _ = value.setattr("key", key);
+    let _ = value.setattr("size", size);
+    let _ = value.setattr("overflow", overflow);
+    err
+}
+
+/// Raised before touching a record whose seq cannot be incremented.
+pub(crate) fn seq_overflow_error() -> PyErr {
+    SequenceOverflowError::new_err(format!(
+        "record seq is already {}; it cannot be updated without wrapping, \
+         which peers would treat as stale",
+        u64::MAX
+    ))
+}
+
+/// Registers the exception types on the extension module.
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("EnrError", py.get_type::<EnrError>())?;
+    m.add("DecodeError", py.get_type::<DecodeError>())?;
+    m.add("SignatureError", py.get_type::<SignatureError>())?;
+    m.add("SizeError", py.get_type::<SizeError>())?;
+    m.add("FieldError", py.get_type::<FieldError>())?;
+    m.add(
+        "SequenceOverflowError",
+        py.get_type::<SequenceOverflowError>(),
+    )?;
+    m.add("CancelledError", py.get_type::<CancelledError>())?;
+    m.add("EnrWarning", py.get_type::<EnrWarning>())?;
+    Ok(())
+}
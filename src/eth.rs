@@ -0,0 +1,137 @@
+//! Decoding of the execution-layer `eth` field (an EIP-2124 fork id).
+//!
+//! Geth-derived clients, including those of BSC and Polygon, all publish the
+//! fork id in the same shape: `[[fork_hash, fork_next], ...]`, where clients
+//! may append items after the fork id. Chains are told apart by the fork hash,
+//! which [`compute_fork_id`] derives from a chain's genesis and fork schedule.
+
+use alloy_rlp::{Decodable, Header};
+use enr::CombinedKey;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+use crate::convert;
+use crate::errors::FieldError;
+
+/// ENR key holding the execution-layer fork id.
+pub(crate) const ETH_ENR_KEY: &str = "eth";
+
+/// ENR key BNB Smart Chain nodes add to mark support for their `bsc` protocol.
+pub(crate) const BSC_ENR_KEY: &str = "bsc";
+
+/// An EIP-2124 fork id: a checksum of the genesis hash and passed forks, and
+/// the next scheduled fork (a block number or timestamp, 0 if none).
+#[pyclass(name = "EthForkId", frozen, eq, hash)]
+#[derive(PartialEq, Eq, Hash)]
+pub(crate) struct EthForkId {
+    fork_hash: [u8; 4],
+    fork_next: u64,
+}
+
+impl EthForkId {
+    /// Parses the raw RLP of an `eth` value.
+    fn from_rlp(mut raw: &[u8]) -> Result<Self, alloy_rlp::Error> {
+        let outer = Header::decode(&mut raw)?;
+        if !outer.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+        let mut items = &raw[..outer.payload_length];
+        let inner = Header::decode(&mut items)?;
+        if !inner.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+        let mut fork_id = &items[..inner.payload_length];
+        let fork_hash = <[u8; 4]>::decode(&mut fork_id)?;
+        let fork_next = u64::decode(&mut fork_id)?;
+        Ok(EthForkId {
+            fork_hash,
+            fork_next,
+        })
+    }
+
+    /// The record's `eth` field, or a `FieldError` if it is malformed.
+    pub(crate) fn from_enr(enr: &enr::Enr<CombinedKey>) -> PyResult<Option<Self>> {
+        enr.get_raw_rlp(ETH_ENR_KEY)
+            .map(|raw| {
+                Self::from_rlp(raw).map_err(|e| {
+                    FieldError::new_err(format!("key {ETH_ENR_KEY:?} is not a fork id: {e}"))
+                })
+            })
+            .transpose()
+    }
+}
+
+#[pymethods]
+impl EthForkId {
+    #[getter]
+    fn fork_hash<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.fork_hash)
+    }
+
+    /// Block number or timestamp of the next fork, 0 when none is scheduled.
+    #[getter]
+    fn fork_next(&self) -> u64 {
+        self.fork_next
+    }
+
+    /// The fields as a dict.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let out = PyDict::new(py);
+        out.set_item("fork_hash", self.fork_hash(py))?;
+        out.set_item("fork_next", self.fork_next)?;
+        Ok(out)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "EthForkId(fork_hash=0x{}, fork_next={})",
+            convert::to_hex(&self.fork_hash),
+            self.fork_next
+        )
+    }
+}
+
+/// Compute the EIP-2124 fork id of a chain at a given head.
+///
+/// `block_forks` and `time_forks` are the chain's fork activation block
+/// numbers and timestamps; zeros (forks active at genesis), duplicates and
+/// ordering are ignored. Forks are counted as passed up to `head_block` and
+/// `head_time`, or all of them if these are `None`.
+#[pyfunction]
+#[pyo3(signature = (genesis_hash, block_forks, time_forks=Vec::new(), *, head_block=None, head_time=None))]
+pub(crate) fn compute_fork_id(
+    genesis_hash: &[u8],
+    block_forks: Vec<u64>,
+    time_forks: Vec<u64>,
+    head_block: Option<u64>,
+    head_time: Option<u64>,
+) -> PyResult<EthForkId> {
+    if genesis_hash.len() != 32 {
+        return Err(PyValueError::new_err(format!(
+            "genesis hash must be 32 bytes, got {}",
+            genesis_hash.len()
+        )));
+    }
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(genesis_hash);
+    let schedules = [(block_forks, head_block), (time_forks, head_time)];
+    for (mut forks, head) in schedules {
+        forks.retain(|fork| *fork != 0);
+        forks.sort_unstable();
+        forks.dedup();
+        for fork in forks {
+            if head.is_some_and(|head| fork > head) {
+                return Ok(EthForkId {
+                    fork_hash: hasher.finalize().to_be_bytes(),
+                    fork_next: fork,
+                });
+            }
+            hasher.update(&fork.to_be_bytes());
+        }
+    }
+    Ok(EthForkId {
+        fork_hash: hasher.finalize().to_be_bytes(),
+        fork_next: 0,
+    })
+}
@@ -0,0 +1,286 @@
+//! Decoding of the consensus-layer `eth2` field (an SSZ-encoded `ENRForkID`),
+//! and of the `attnets` and `syncnets` subnet bitvectors.
+
+use enr::CombinedKey;
+use pyo3::exceptions::{PyOverflowError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use sha2::{Digest, Sha256};
+
+use crate::errors::FieldError;
+use crate::{bitvector, convert, rlp};
+
+/// ENR key holding the SSZ-encoded `ENRForkID`.
+pub(crate) const ETH2_ENR_KEY: &str = "eth2";
+
+/// Encoded length of an `ENRForkID`: fork digest, next fork version and next fork epoch.
+pub(crate) const ENR_FORK_ID_LEN: usize = 16;
+
+/// ENR key holding the subscribed attestation subnets.
+pub(crate) const ATTNETS_KEY: &str = "attnets";
+
+/// ENR key holding the subscribed sync committee subnets.
+pub(crate) const SYNCNETS_KEY: &str = "syncnets";
+
+/// Attestation subnets, and so bits in `attnets`.
+pub(crate) const ATTESTATION_SUBNET_COUNT: usize = 64;
+
+/// Sync committee subnets, and so bits in `syncnets`.
+pub(crate) const SYNC_COMMITTEE_SUBNET_COUNT: usize = 4;
+
+/// `next_fork_epoch` of a node with no fork scheduled.
+pub(crate) const FAR_FUTURE_EPOCH: u64 = u64::MAX;
+
+/// Returns the 4-byte fork digest of the record's `eth2` field, if present and well-formed.
+pub(crate) fn fork_digest(enr: &enr::Enr<CombinedKey>) -> Option<[u8; 4]> {
+    Eth2ForkId::from_enr(enr).map(|fork_id| fork_id.fork_digest)
+}
+
+/// Indices of the subnets set in the `count`-bit bitvector under `key`.
+pub(crate) fn subnets(
+    enr: &enr::Enr<CombinedKey>,
+    key: &str,
+    count: usize,
+) -> PyResult<Option<Vec<usize>>> {
+    let Some(bits) = rlp::string_value(enr, key)? else {
+        return Ok(None);
+    };
+    if bits.len() != count.div_ceil(8) {
+        return Err(FieldError::new_err(format!(
+            "key {key:?} must be {} bytes, got {}",
+            count.div_ceil(8),
+            bits.len()
+        )));
+    }
+    // Bits past `count` are padding; SSZ requires them to be zero.
+    if bitvector::indices(bits).last().is_some_and(|&i| i >= count) {
+        return Err(FieldError::new_err(format!(
+            "key {key:?} has bits set past its {count} subnets"
+        )));
+    }
+    Ok(Some(bitvector::indices(bits)))
+}
+
+/// The `count`-bit bitvector under `key` with `indices` set.
+pub(crate) fn subnet_bits(key: &str, indices: &[usize], count: usize) -> PyResult<Vec<u8>> {
+    bitvector::from_indices(indices, count).map_err(|i| {
+        FieldError::new_err(format!(
+            "subnet {i} is out of range for {key:?}, which has {count} subnets"
+        ))
+    })
+}
+
+/// The decoded `eth2` field: the node's current fork digest and the next
+/// scheduled fork.
+#[pyclass(name = "Eth2ForkId", frozen, eq, hash)]
+#[derive(PartialEq, Eq, Hash)]
+pub(crate) struct Eth2ForkId {
+    fork_digest: [u8; 4],
+    next_fork_version: [u8; 4],
+    next_fork_epoch: u64,
+}
+
+impl Eth2ForkId {
+    /// Parses the SSZ encoding of an `ENRForkID`.
+    pub(crate) fn from_ssz(value: &[u8]) -> Option<Self> {
+        let value = <&[u8; ENR_FORK_ID_LEN]>::try_from(value).ok()?;
+        let mut fork_digest = [0u8; 4];
+        let mut next_fork_version = [0u8; 4];
+        let mut next_fork_epoch = [0u8; 8];
+        fork_digest.copy_from_slice(&value[..4]);
+        next_fork_version.copy_from_slice(&value[4..8]);
+        next_fork_epoch.copy_from_slice(&value[8..]);
+        Some(Eth2ForkId {
+            fork_digest,
+            next_fork_version,
+            next_fork_epoch: u64::from_le_bytes(next_fork_epoch),
+        })
+    }
+
+    /// The record's `eth2` field, if present and well-formed.
+    pub(crate) fn from_enr(enr: &enr::Enr<CombinedKey>) -> Option<Self> {
+        enr.get_raw_rlp(ETH2_ENR_KEY)
+            .and_then(rlp::string_payload)
+            .and_then(Self::from_ssz)
+    }
+
+    /// The record's `eth2` field, or a `FieldError` if it is malformed.
+    pub(crate) fn from_enr_checked(enr: &enr::Enr<CombinedKey>) -> PyResult<Option<Self>> {
+        let Some(value) = rlp::string_value(enr, ETH2_ENR_KEY)? else {
+            return Ok(None);
+        };
+        Self::from_ssz(value).map(Some).ok_or_else(|| {
+            FieldError::new_err(format!(
+                "key {ETH2_ENR_KEY:?} must hold a {ENR_FORK_ID_LEN}-byte ENRForkID, got {} bytes",
+                value.len()
+            ))
+        })
+    }
+
+    /// A fork id from its fields, checking the lengths of the versions.
+    pub(crate) fn from_fields(
+        fork_digest: &[u8],
+        next_fork_version: &[u8],
+        next_fork_epoch: u64,
+    ) -> PyResult<Self> {
+        let four_bytes = |name: &str, value: &[u8]| {
+            <[u8; 4]>::try_from(value).map_err(|_| {
+                FieldError::new_err(format!("{name} must be 4 bytes, got {}", value.len()))
+            })
+        };
+        Ok(Eth2ForkId {
+            fork_digest: four_bytes("fork_digest", fork_digest)?,
+            next_fork_version: four_bytes("next_fork_version", next_fork_version)?,
+            next_fork_epoch,
+        })
+    }
+
+    /// The SSZ encoding, as the `eth2` field holds it.
+    pub(crate) fn ssz(&self) -> [u8; ENR_FORK_ID_LEN] {
+        let mut out = [0u8; ENR_FORK_ID_LEN];
+        out[..4].copy_from_slice(&self.fork_digest);
+        out[4..8].copy_from_slice(&self.next_fork_version);
+        out[8..].copy_from_slice(&self.next_fork_epoch.to_le_bytes());
+        out
+    }
+}
+
+#[pymethods]
+impl Eth2ForkId {
+    /// `next_fork_epoch` defaults to `2**64 - 1`, no fork scheduled.
+    #[new]
+    #[pyo3(signature = (fork_digest, next_fork_version, next_fork_epoch=FAR_FUTURE_EPOCH))]
+    fn new(fork_digest: &[u8], next_fork_version: &[u8], next_fork_epoch: u64) -> PyResult<Self> {
+        Self::from_fields(fork_digest, next_fork_version, next_fork_epoch)
+    }
+
+    /// Parse the 16-byte SSZ encoding of an `ENRForkID`.
+    #[staticmethod]
+    fn from_ssz_bytes(data: &[u8]) -> PyResult<Self> {
+        Self::from_ssz(data).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "an ENRForkID is {ENR_FORK_ID_LEN} bytes, got {}",
+                data.len()
+            ))
+        })
+    }
+
+    /// The 16-byte SSZ encoding, as stored under `eth2`.
+    fn to_ssz_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.ssz())
+    }
+
+    #[getter]
+    fn fork_digest<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.fork_digest)
+    }
+
+    #[getter]
+    fn next_fork_version<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.next_fork_version)
+    }
+
+    /// `2**64 - 1` when no fork is scheduled.
+    #[getter]
+    fn next_fork_epoch(&self) -> u64 {
+        self.next_fork_epoch
+    }
+
+    /// Unix time at which `next_fork_epoch` starts, for a chain with the given
+    /// genesis time (in Unix seconds) and slot timing. `None` when no fork is
+    /// scheduled.
+    #[pyo3(signature = (genesis_time, seconds_per_slot=12, slots_per_epoch=32))]
+    fn next_fork_timestamp(
+        &self,
+        genesis_time: u64,
+        seconds_per_slot: u64,
+        slots_per_epoch: u64,
+    ) -> PyResult<Option<u64>> {
+        if self.next_fork_epoch == FAR_FUTURE_EPOCH {
+            return Ok(None);
+        }
+        self.next_fork_epoch
+            .checked_mul(slots_per_epoch)
+            .and_then(|slots| slots.checked_mul(seconds_per_slot))
+            .and_then(|seconds| seconds.checked_add(genesis_time))
+            .map(Some)
+            .ok_or_else(|| {
+                PyOverflowError::new_err(format!(
+                    "epoch {} is too far in the future for a timestamp",
+                    self.next_fork_epoch
+                ))
+            })
+    }
+
+    /// Like `next_fork_timestamp`, as a timezone-aware UTC `datetime`.
+    #[pyo3(signature = (genesis_time, seconds_per_slot=12, slots_per_epoch=32))]
+    fn next_fork_time<'py>(
+        &self,
+        py: Python<'py>,
+        genesis_time: u64,
+        seconds_per_slot: u64,
+        slots_per_epoch: u64,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let Some(timestamp) =
+            self.next_fork_timestamp(genesis_time, seconds_per_slot, slots_per_epoch)?
+        else {
+            return Ok(None);
+        };
+        let datetime = py.import("datetime")?;
+        let utc = datetime.getattr("timezone")?.getattr("utc")?;
+        datetime
+            .getattr("datetime")?
+            .call_method1("fromtimestamp", (timestamp, utc))
+            .map(Some)
+    }
+
+    /// The fields as a dict.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let out = PyDict::new(py);
+        out.set_item("fork_digest", self.fork_digest(py))?;
+        out.set_item("next_fork_version", self.next_fork_version(py))?;
+        out.set_item("next_fork_epoch", self.next_fork_epoch)?;
+        Ok(out)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Eth2ForkId(fork_digest=0x{}, next_fork_version=0x{}, next_fork_epoch={})",
+            convert::to_hex(&self.fork_digest),
+            convert::to_hex(&self.next_fork_version),
+            self.next_fork_epoch
+        )
+    }
+}
+
+/// Compute the 4-byte fork digest the `eth2` field starts with: the first
+/// four bytes of the SSZ hash tree root of `ForkData(current_fork_version,
+/// genesis_validators_root)`.
+#[pyfunction]
+pub(crate) fn compute_fork_digest<'py>(
+    py: Python<'py>,
+    current_fork_version: &[u8],
+    genesis_validators_root: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+    let version = <[u8; 4]>::try_from(current_fork_version).map_err(|_| {
+        PyValueError::new_err(format!(
+            "fork version must be 4 bytes, got {}",
+            current_fork_version.len()
+        ))
+    })?;
+    if genesis_validators_root.len() != 32 {
+        return Err(PyValueError::new_err(format!(
+            "genesis validators root must be 32 bytes, got {}",
+            genesis_validators_root.len()
+        )));
+    }
+    // Both fields are one 32-byte chunk: the version is zero-padded and the
+    // root is its own hash tree root, so the container root is one hash.
+    let mut chunk = [0u8; 32];
+    chunk[..4].copy_from_slice(&version);
+    let root = Sha256::new()
+        .chain_update(chunk)
+        .chain_update(genesis_validators_root)
+        .finalize();
+    Ok(PyBytes::new(py, &root[..4]))
+}
@@ -0,0 +1,145 @@
+//! Runtime introspection of what this build supports, and stand-ins for the
+//! functions of optional features left out of it.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::rlp::MAX_ENR_SIZE;
+
+/// Optional features compiled into this build.
+const COMPILED_FEATURES: &[&str] = &[
+    #[cfg(feature = "dns")]
+    "dns",
+    #[cfg(feature = "discv5")]
+    "discv5",
+    #[cfg(feature = "geo")]
+    "geo",
+    #[cfg(feature = "http")]
+    "http",
+    #[cfg(feature = "interchange")]
+    "interchange",
+    #[cfg(feature = "sqlite")]
+    "sqlite",
+    #[cfg(feature = "toml")]
+    "toml",
+    #[cfg(feature = "yaml")]
+    "yaml",
+    #[cfg(feature = "zstd")]
+    "zstd",
+];
+
+/// Compressed file formats this build reads and writes.
+const COMPRESSION: &[&str] = &[
+    "gzip",
+    #[cfg(feature = "zstd")]
+    "zstd",
+];
+
+/// The error raised by anything that needs `feature` when it was left out.
+// Unused when every feature is compiled in.
+#[allow(dead_code)]
+pub(crate) fn missing(what: &str, feature: &str) -> PyErr {
+    pyo3::exceptions::PyNotImplementedError::new_err(missing_message(what, feature))
+}
+
+/// The message of [`missing`], for errors raised through other types.
+#[allow(dead_code)]
+pub(crate) fn missing_message(what: &str, feature: &str) -> String {
+    format!("{what} needs the {feature:?} feature, which this build of pyenr was compiled without")
+}
+
+/// What this build of pyenr supports, so deployments can check at startup.
+///
+/// Keys: `version` (pyenr), `enr_version` (the Rust `enr` crate it wraps),
+/// `identity_schemes`, `key_types`, `features` (optional features compiled
+/// in, a subset of `"dns"`, `"discv5"`, `"geo"`, `"http"`, `"interchange"`,
+/// `"sqlite"`, `"toml"`, `"yaml"` and `"zstd"`), `max_record_size` and
+/// `compression` (file formats `write_enr_file` and the readers handle).
+#[pyfunction]
+pub(crate) fn features(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let out = PyDict::new(py);
+    out.set_item("version", env!("CARGO_PKG_VERSION"))?;
+    out.set_item("enr_version", env!("PYENR_ENR_VERSION"))?;
+    out.set_item("identity_schemes", vec!["v4"])?;
+    out.set_item("key_types", vec!["secp256k1", "ed25519"])?;
+    out.set_item("features", COMPILED_FEATURES.to_vec())?;
+    out.set_item("max_record_size", MAX_ENR_SIZE)?;
+    out.set_item("compression", COMPRESSION.to_vec())?;
+    Ok(out)
+}
+
+/// Stand-in for `geo::enrich_geo` in builds without the `geo` feature, so
+/// that the name still imports.
+#[cfg(not(feature = "geo"))]
+#[pyfunction]
+#[pyo3(signature = (*_args, **_kwargs))]
+pub(crate) fn enrich_geo(
+    _args: &Bound<'_, pyo3::types::PyTuple>,
+    _kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<()> {
+    Err(missing("enrich_geo()", "geo"))
+}
+
+/// Stand-in for `rdns::reverse_dns` in builds without the `dns` feature.
+#[cfg(not(feature = "dns"))]
+#[pyfunction]
+#[pyo3(signature = (*_args, **_kwargs))]
+pub(crate) fn reverse_dns(
+    _args: &Bound<'_, pyo3::types::PyTuple>,
+    _kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<()> {
+    Err(missing("reverse_dns()", "dns"))
+}
+
+/// Stand-in for `dnsdisc::resolve_tree` in builds without the `dns` feature.
+#[cfg(not(feature = "dns"))]
+#[pyfunction]
+#[pyo3(signature = (*_args, **_kwargs))]
+pub(crate) fn resolve_tree(
+    _args: &Bound<'_, pyo3::types::PyTuple>,
+    _kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<()> {
+    Err(missing("resolve_tree()", "dns"))
+}
+
+/// Stand-in for `aio::resolve_tree_async` in builds without the `dns` feature.
+#[cfg(not(feature = "dns"))]
+#[pyfunction]
+#[pyo3(signature = (*_args, **_kwargs))]
+pub(crate) fn resolve_tree_async(
+    _args: &Bound<'_, pyo3::types::PyTuple>,
+    _kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<()> {
+    Err(missing("resolve_tree_async()", "dns"))
+}
+
+/// Stand-in for `bootnodes::fetch_enr_list` in builds without the `http`
+/// feature.
+#[cfg(not(feature = "http"))]
+#[pyfunction]
+#[pyo3(signature = (*_args, **_kwargs))]
+pub(crate) fn fetch_enr_list(
+    _args: &Bound<'_, pyo3::types::PyTuple>,
+    _kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<()> {
+    Err(missing("fetch_enr_list()", "http"))
+}
+
+/// Stand-in for `store::EnrStore` in builds without the `sqlite` feature:
+/// the class imports, but cannot be opened.
+#[cfg(not(feature = "sqlite"))]
+#[pyclass(name = "EnrStore", frozen)]
+pub(crate) struct EnrStore;
+
+#[cfg(not(feature = "sqlite"))]
+#[pymethods]
+impl EnrStore {
+    #[new]
+    #[pyo3(signature = (*_args, **_kwargs))]
+    fn new(
+        _args: &Bound<'_, pyo3::types::PyTuple>,
+        _kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        Err(missing("EnrStore", "sqlite"))
+    }
+}
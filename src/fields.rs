@@ -0,0 +1,212 @@
+//! Typed snapshots of a record's well-known fields: all of them, and the
+//! endpoint group on its own.
+
+use enr::CombinedKey;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+use crate::eth2::Eth2ForkId;
+use crate::{keys, placeholder, rlp};
+
+/// Every well-known field of a record, decoded, as returned by
+/// `Enr.fields()`.
+///
+/// A field is `None` when the record does not carry it or its value does
+/// not have the expected shape.
+#[pyclass(name = "EnrFields", frozen, get_all)]
+pub(crate) struct EnrFields {
+    seq: u64,
+    node_id: Py<PyBytes>,
+    public_key: Py<PyBytes>,
+    identity_scheme: Option<String>,
+    ip4: Option<String>,
+    ip6: Option<String>,
+    tcp4: Option<u16>,
+    udp4: Option<u16>,
+    tcp6: Option<u16>,
+    udp6: Option<u16>,
+    quic4: Option<u16>,
+    quic6: Option<u16>,
+    eth2: Option<Py<Eth2ForkId>>,
+    attnets: Option<Py<PyBytes>>,
+    syncnets: Option<Py<PyBytes>>,
+    /// Custody group count (PeerDAS).
+    cgc: Option<u64>,
+}
+
+/// Value of a port key, if it has a port's shape.
+fn port(enr: &enr::Enr<CombinedKey>, key: &str) -> Option<u16> {
+    match keys::decode(key, enr.get_raw_rlp(key)?) {
+        keys::Decoded::Int(n) => u16::try_from(n).ok(),
+        _ => None,
+    }
+}
+
+/// Payload of a fixed-length byte string key.
+fn fixed_bytes(py: Python<'_>, enr: &enr::Enr<CombinedKey>, key: &str) -> Option<Py<PyBytes>> {
+    let payload = enr.get_raw_rlp(key).and_then(rlp::string_payload)?;
+    match keys::lookup(key) {
+        Some(keys::ValueType::Bytes(Some(len))) if payload.len() != len => None,
+        _ => Some(PyBytes::new(py, payload).unbind()),
+    }
+}
+
+impl EnrFields {
+    pub(crate) fn new(
+        py: Python<'_>,
+        enr: &enr::Enr<CombinedKey>,
+        public_key: Bound<'_, PyBytes>,
+    ) -> PyResult<Self> {
+        Ok(EnrFields {
+            seq: enr.seq(),
+            node_id: PyBytes::new(py, &enr.node_id().raw()).unbind(),
+            public_key: public_key.unbind(),
+            identity_scheme: enr.id(),
+            ip4: placeholder::ip4(enr.ip4()).map(|ip| ip.to_string()),
+            ip6: placeholder::ip6(enr.ip6()).map(|ip| ip.to_string()),
+            tcp4: placeholder::port(enr.tcp4()),
+            udp4: placeholder::port(enr.udp4()),
+            tcp6: placeholder::port(enr.tcp6()),
+            udp6: placeholder::port(enr.udp6()),
+            quic4: placeholder::port(port(enr, "quic")),
+            quic6: placeholder::port(port(enr, "quic6")),
+            eth2: Eth2ForkId::from_enr(enr)
+                .map(|fork_id| Py::new(py, fork_id))
+                .transpose()?,
+            attnets: fixed_bytes(py, enr, "attnets"),
+            syncnets: fixed_bytes(py, enr, "syncnets"),
+            cgc: match enr.get_raw_rlp("cgc").map(|raw| keys::decode("cgc", raw)) {
+                Some(keys::Decoded::Int(n)) => Some(n),
+                _ => None,
+            },
+        })
+    }
+}
+
+#[pymethods]
+impl EnrFields {
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let mut parts = vec![format!("seq={}", self.seq)];
+        let optional: [(&str, Option<String>); 13] = [
+            (
+                "identity_scheme",
+                self.identity_scheme.as_ref().map(|s| format!("{s:?}")),
+            ),
+            ("ip4", self.ip4.as_ref().map(|s| format!("{s:?}"))),
+            ("ip6", self.ip6.as_ref().map(|s| format!("{s:?}"))),
+            ("tcp4", self.tcp4.map(|p| p.to_string())),
+            ("udp4", self.udp4.map(|p| p.to_string())),
+            ("tcp6", self.tcp6.map(|p| p.to_string())),
+            ("udp6", self.udp6.map(|p| p.to_string())),
+            ("quic4", self.quic4.map(|p| p.to_string())),
+            ("quic6", self.quic6.map(|p| p.to_string())),
+            (
+                "eth2",
+                self.eth2
+                    .as_ref()
+                    .map(|e| e.bind(py).repr().map(|r| r.to_string()))
+                    .transpose()?,
+            ),
+            (
+                "attnets",
+                self.attnets
+                    .as_ref()
+                    .map(|b| b.bind(py).repr().map(|r| r.to_string()))
+                    .transpose()?,
+            ),
+            (
+                "syncnets",
+                self.syncnets
+                    .as_ref()
+                    .map(|b| b.bind(py).repr().map(|r| r.to_string()))
+                    .transpose()?,
+            ),
+            ("cgc", self.cgc.map(|n| n.to_string())),
+        ];
+        parts.extend(
+            optional
+                .into_iter()
+                .filter_map(|(name, value)| value.map(|v| format!("{name}={v}"))),
+        );
+        Ok(format!("EnrFields({})", parts.join(", ")))
+    }
+}
+
+/// The addresses and ports a record advertises, as returned by
+/// `Enr.endpoints`.
+///
+/// Unlike `Enr.effective_endpoints()`, these are the values as stored, with
+/// no EIP-778 fallback from the IPv6 to the IPv4 ports; placeholders are
+/// handled as in `EnrFields`.
+#[pyclass(name = "Endpoints", frozen, eq, hash, get_all)]
+#[derive(PartialEq, Eq, Hash)]
+pub(crate) struct Endpoints {
+    ip4: Option<String>,
+    tcp4: Option<u16>,
+    udp4: Option<u16>,
+    quic4: Option<u16>,
+    ip6: Option<String>,
+    tcp6: Option<u16>,
+    udp6: Option<u16>,
+    quic6: Option<u16>,
+}
+
+impl Endpoints {
+    pub(crate) fn new(enr: &enr::Enr<CombinedKey>) -> Self {
+        Endpoints {
+            ip4: placeholder::ip4(enr.ip4()).map(|ip| ip.to_string()),
+            tcp4: placeholder::port(enr.tcp4()),
+            udp4: placeholder::port(enr.udp4()),
+            quic4: placeholder::port(port(enr, "quic")),
+            ip6: placeholder::ip6(enr.ip6()).map(|ip| ip.to_string()),
+            tcp6: placeholder::port(enr.tcp6()),
+            udp6: placeholder::port(enr.udp6()),
+            quic6: placeholder::port(port(enr, "quic6")),
+        }
+    }
+
+    fn items(&self) -> [(&'static str, Option<String>); 8] {
+        let port = |p: Option<u16>| p.map(|p| p.to_string());
+        [
+            ("ip4", self.ip4.as_ref().map(|s| format!("{s:?}"))),
+            ("tcp4", port(self.tcp4)),
+            ("udp4", port(self.udp4)),
+            ("quic4", port(self.quic4)),
+            ("ip6", self.ip6.as_ref().map(|s| format!("{s:?}"))),
+            ("tcp6", port(self.tcp6)),
+            ("udp6", port(self.udp6)),
+            ("quic6", port(self.quic6)),
+        ]
+    }
+}
+
+#[pymethods]
+impl Endpoints {
+    /// The fields as a dict, `None` included.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let out = PyDict::new(py);
+        out.set_item("ip4", &self.ip4)?;
+        out.set_item("tcp4", self.tcp4)?;
+        out.set_item("udp4", self.udp4)?;
+        out.set_item("quic4", self.quic4)?;
+        out.set_item("ip6", &self.ip6)?;
+        out.set_item("tcp6", self.tcp6)?;
+        out.set_item("udp6", self.udp6)?;
+        out.set_item("quic6", self.quic6)?;
+        Ok(out)
+    }
+
+    /// Whether the record advertises any address or port.
+    fn __bool__(&self) -> bool {
+        self.items().iter().any(|(_, value)| value.is_some())
+    }
+
+    fn __repr__(&self) -> String {
+        let parts: Vec<String> = self
+            .items()
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|v| format!("{name}={v}")))
+            .collect();
+        format!("Endpoints({})", parts.join(", "))
+    }
+}
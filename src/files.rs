@@ -0,0 +1,569 @@
+//! Reading and writing records in dump files.
+//!
+//! Plain files are memory-mapped and decoded lazily, one record per iteration
+//! step, so dumps larger than RAM can be scanned with flat memory usage.
+//! gzip and zstd files are detected by their magic bytes and decompressed as
+//! a stream; zstd needs the `zstd` feature.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use alloy_rlp::{Decodable, Header};
+use enr::CombinedKey;
+use memmap2::Mmap;
+use pyo3::exceptions::{PyNotImplementedError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+
+use crate::batch::{self, Predicate};
+use crate::errors::{catch_panic, DecodeError, SizeError};
+use crate::logging::{self, Level};
+use crate::progress::{CancelToken, Progress, Stopped};
+use crate::rlp::MAX_ENR_SIZE;
+use crate::Enr;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Layout of the records within a file.
+#[derive(Clone, Copy)]
+enum Format {
+    /// One base64 ENR per line; blank lines and `#` comments are skipped.
+    Lines,
+    /// RLP-encoded records concatenated back to back.
+    Rlp,
+}
+
+impl Format {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "lines" => Ok(Format::Lines),
+            "rlp" => Ok(Format::Rlp),
+            other => Err(PyValueError::new_err(format!(
+                "unknown format {other:?}, expected 'lines' or 'rlp'"
+            ))),
+        }
+    }
+}
+
+/// Compression applied to a file.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    None,
+    Gzip,
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    fn detect(path: &Path) -> io::Result<Self> {
+        let mut head = [0u8; 4];
+        let mut file = File::open(path)?;
+        let mut filled = 0;
+        while filled < head.len() {
+            match file.read(&mut head[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        let head = &head[..filled];
+        if head.starts_with(ZSTD_MAGIC) {
+            #[cfg(feature = "zstd")]
+            return Ok(Compression::Zstd);
+            #[cfg(not(feature = "zstd"))]
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                crate::features::missing_message("reading zstd files", "zstd"),
+            ));
+        }
+        Ok(if head.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else {
+            Compression::None
+        })
+    }
+
+    fn from_name(name: Option<&str>, path: &Path) -> PyResult<Self> {
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => match path.extension().and_then(|e| e.to_str()) {
+                Some("gz") => "gzip".to_string(),
+                Some("zst" | "zstd") => "zstd".to_string(),
+                _ => "none".to_string(),
+            },
+        };
+        match name.as_str() {
+            "none" => Ok(Compression::None),
+            "gzip" => Ok(Compression::Gzip),
+            #[cfg(feature = "zstd")]
+            "zstd" => Ok(Compression::Zstd),
+            #[cfg(not(feature = "zstd"))]
+            "zstd" => Err(crate::features::missing("zstd compression", "zstd")),
+            other => Err(PyValueError::new_err(format!(
+                "unknown compression {other:?}, expected 'none', 'gzip' or 'zstd'"
+            ))),
+        }
+    }
+}
+
+/// Where the bytes of a file come from.
+enum Source {
+    /// A memory-mapped plain file (`None` for an empty file, which cannot be mapped).
+    Mapped { data: Option<Mmap>, pos: usize },
+    /// A decompressing stream.
+    Stream(Mutex<Box<dyn BufRead + Send>>),
+}
+
+impl Source {
+    fn open(path: &Path) -> io::Result<Self> {
+        let compression = Compression::detect(path)?;
+        let file = File::open(path)?;
+        let stream: Box<dyn BufRead + Send> = match compression {
+            Compression::None => {
+                let data = if file.metadata()?.len() == 0 {
+                    None
+                } else {
+                    // SAFETY: the mapping is read-only. If another process truncates
+                    // the file while it is being read, the OS may raise SIGBUS; this
+                    // is the usual caveat of memory-mapped I/O.
+                    Some(unsafe { Mmap::map(&file)? })
+                };
+                return Ok(Source::Mapped { data, pos: 0 });
+            }
+            Compression::Gzip => Box::new(BufReader::new(flate2::read::MultiGzDecoder::new(
+                BufReader::new(file),
+            ))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Box::new(BufReader::new(zstd::stream::read::Decoder::new(file)?)),
+        };
+        Ok(Source::Stream(Mutex::new(stream)))
+    }
+
+    /// Returns the next line without its terminator, or `None` at end of file.
+    fn next_line(&mut self) -> io::Result<Option<Cow<'_, [u8]>>> {
+        match self {
+            Source::Mapped { data, pos } => {
+                let rest = data.as_deref().map_or(&[][..], |d| &d[*pos..]);
+                if rest.is_empty() {
+                    return Ok(None);
+                }
+                let end = rest.iter().position(|&b| b == b'\n');
+                *pos += end.map_or(rest.len(), |e| e + 1);
+                Ok(Some(Cow::Borrowed(&rest[..end.unwrap_or(rest.len())])))
+            }
+            Source::Stream(stream) => {
+                let mut line = Vec::new();
+                let stream = stream.get_mut().expect("reader lock poisoned");
+                if stream.read_until(b'\n', &mut line)? == 0 {
+                    return Ok(None);
+                }
+                if line.last() == Some(&b'\n') {
+                    line.pop();
+                }
+                Ok(Some(Cow::Owned(line)))
+            }
+        }
+    }
+
+    /// Returns the next complete RLP item, or `None` at end of file.
+    ///
+    /// An `Err` means the framing is broken and the rest of the input is unusable.
+    fn next_rlp(&mut self) -> Result<Option<Cow<'_, [u8]>>, Framing> {
+        match self {
+            Source::Mapped { data, pos } => {
+                let rest = data.as_deref().map_or(&[][..], |d| &d[*pos..]);
+                if rest.is_empty() {
+                    return Ok(None);
+                }
+                let header = Header::decode(&mut &rest[..]).ok().filter(|h| h.list);
+                let failure = match header.map(|h| h.length_with_payload()) {
+                    Some(len) if len > MAX_ENR_SIZE => Framing::Oversized,
+                    Some(len) => {
+                        *pos += len;
+                        return Ok(Some(Cow::Borrowed(&rest[..len])));
+                    }
+                    None => Framing::Invalid,
+                };
+                *pos += rest.len();
+                Err(failure)
+            }
+            Source::Stream(stream) => {
+                let stream = stream.get_mut().expect("reader lock poisoned");
+                read_rlp_item(stream).map(|item| item.map(Cow::Owned))
+            }
+        }
+    }
+}
+
+/// Why an RLP file could not be split into records.
+enum Framing {
+    /// A header is malformed or promises more bytes than remain.
+    Invalid,
+    /// A header announces a record larger than [`MAX_ENR_SIZE`].
+    Oversized,
+    /// The underlying stream failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for Framing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Framing::Invalid => f.write_str("invalid or truncated record header"),
+            Framing::Oversized => write!(f, "record exceeds max size of {MAX_ENR_SIZE} bytes"),
+            Framing::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+/// Reads one RLP list item from a stream.
+fn read_rlp_item(stream: &mut dyn BufRead) -> Result<Option<Vec<u8>>, Framing> {
+    let truncated = |_| Framing::Invalid;
+    let mut item = vec![0u8; 1];
+    if stream.read(&mut item).map_err(Framing::Io)? == 0 {
+        return Ok(None);
+    }
+    let payload_length = match item[0] {
+        prefix @ 0xc0..=0xf7 => usize::from(prefix - 0xc0),
+        prefix @ 0xf8..=0xff => {
+            // Long-form list headers carry the payload length in up to 8 bytes.
+            let length_bytes = usize::from(prefix - 0xf7);
+            item.resize(1 + length_bytes, 0);
+            stream.read_exact(&mut item[1..]).map_err(truncated)?;
+            item[1..].iter().fold(0usize, |len, b| {
+                len.saturating_mul(256).saturating_add(usize::from(*b))
+            })
+        }
+        _ => return Err(Framing::Invalid),
+    };
+    if item.len().saturating_add(payload_length) > MAX_ENR_SIZE {
+        // Skip the payload without buffering it, to tell a record that is too
+        // large from a header promising more bytes than remain.
+        let length = payload_length as u64;
+        let skipped =
+            io::copy(&mut Read::take(stream, length), &mut io::sink()).map_err(Framing::Io)?;
+        return Err(if skipped < length {
+            Framing::Invalid
+        } else {
+            Framing::Oversized
+        });
+    }
+    let start = item.len();
+    item.resize(start + payload_length, 0);
+    stream.read_exact(&mut item[start..]).map_err(truncated)?;
+    Ok(Some(item))
+}
+
+/// Failure while reading records from a file.
+pub(crate) enum ReadError {
+    /// The file could not be opened or read.
+    Io(io::Error),
+    /// A record could not be decoded; the message names its position.
+    Record(String),
+    /// A record header announced more than [`MAX_ENR_SIZE`] bytes.
+    Size(String),
+}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+impl From<ReadError> for PyErr {
+    fn from(err: ReadError) -> PyErr {
+        match err {
+            // A compression this build was compiled without.
+            ReadError::Io(e) if e.kind() == io::ErrorKind::Unsupported => {
+                PyNotImplementedError::new_err(e.to_string())
+            }
+            ReadError::Io(e) => e.into(),
+            ReadError::Record(msg) => DecodeError::new_err(msg),
+            ReadError::Size(msg) => SizeError::new_err(msg),
+        }
+    }
+}
+
+impl ReadError {
+    /// Prefixes the error with the path it came from.
+    fn in_file(self, path: &Path) -> Self {
+        match self {
+            ReadError::Io(e) => {
+                ReadError::Io(io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+            }
+            ReadError::Record(msg) => ReadError::Record(format!("{}: {msg}", path.display())),
+            ReadError::Size(msg) => ReadError::Size(format!("{}: {msg}", path.display())),
+        }
+    }
+}
+
+/// Decodes records one at a time from a [`Source`].
+struct Records {
+    source: Source,
+    /// Line number for line-based files, byte offset for RLP files.
+    position: usize,
+    format: Format,
+    exhausted: bool,
+}
+
+impl Records {
+    fn open(path: &Path, format: Format) -> io::Result<Self> {
+        Ok(Records {
+            source: Source::open(path)?,
+            position: 0,
+            format,
+            exhausted: false,
+        })
+    }
+
+    fn next_line(&mut self) -> Result<Option<enr::Enr<CombinedKey>>, ReadError> {
+        loop {
+            let Some(raw) = self.source.next_line()? else {
+                return Ok(None);
+            };
+            let text = String::from_utf8_lossy(&raw).trim().to_string();
+            self.position += 1;
+
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+            return catch_panic(|| enr::Enr::<CombinedKey>::from_str(&text))
+                .and_then(|r| r)
+                .map(Some)
+                .map_err(|e| ReadError::Record(format!("line {}: {e}", self.position)));
+        }
+    }
+
+    fn next_rlp(&mut self) -> Result<Option<enr::Enr<CombinedKey>>, ReadError> {
+        let offset = self.position;
+        let raw = match self.source.next_rlp() {
+            Ok(Some(raw)) => raw,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                // Framing is lost, nothing after this point can be trusted.
+                self.exhausted = true;
+                let msg = format!("offset {offset}: {e}");
+                return Err(match e {
+                    Framing::Oversized => ReadError::Size(msg),
+                    _ => ReadError::Record(msg),
+                });
+            }
+        };
+        self.position += raw.len();
+        catch_panic(|| enr::Enr::<CombinedKey>::decode(&mut &raw[..]).map_err(|e| e.to_string()))
+            .and_then(|r| r)
+            .map(Some)
+            .map_err(|e| ReadError::Record(format!("offset {offset}: {e}")))
+    }
+}
+
+impl Iterator for Records {
+    type Item = Result<enr::Enr<CombinedKey>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exhausted {
+            return None;
+        }
+        let next = match self.format {
+            Format::Lines => self.next_line(),
+            Format::Rlp => self.next_rlp(),
+        };
+        next.transpose()
+    }
+}
+
+/// Iterator over the records stored in a file.
+#[pyclass(name = "EnrReader")]
+pub(crate) struct EnrReader {
+    records: Records,
+}
+
+impl EnrReader {
+    fn open(path: &Path, format: Format) -> PyResult<Self> {
+        Ok(EnrReader {
+            records: Records::open(path, format).map_err(ReadError::from)?,
+        })
+    }
+}
+
+#[pymethods]
+impl EnrReader {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<Enr>> {
+        Ok(self.records.next().transpose()?.map(|inner| Enr { inner }))
+    }
+}
+
+/// Lazily read base64 ENRs from a text file, one per line.
+///
+/// Blank lines and lines starting with `#` are skipped. gzip and zstd files
+/// are decompressed transparently. A malformed line raises `DecodeError`
+/// naming the line number; iteration can be resumed afterwards.
+#[pyfunction]
+pub(crate) fn read_enr_file(path: PathBuf) -> PyResult<EnrReader> {
+    EnrReader::open(&path, Format::Lines)
+}
+
+/// Lazily decode RLP-encoded records concatenated back to back in a file.
+///
+/// gzip and zstd files are decompressed transparently. A malformed record
+/// raises `DecodeError`, and one larger than 300 bytes `SizeError`, naming
+/// its byte offset in the decompressed stream. If the record framing itself
+/// is broken, iteration stops after the error.
+#[pyfunction]
+pub(crate) fn decode_rlp_stream(path: PathBuf) -> PyResult<EnrReader> {
+    EnrReader::open(&path, Format::Rlp)
+}
+
+/// Write records to a text file as base64 ENRs, one per line.
+///
+/// Compression defaults to the file extension (`.gz` for gzip, `.zst`/`.zstd`
+/// for zstd, otherwise none) and can be forced with `compression`.
+#[pyfunction]
+#[pyo3(signature = (path, enrs, *, compression=None))]
+pub(crate) fn write_enr_file(
+    path: PathBuf,
+    enrs: Vec<PyRef<'_, Enr>>,
+    compression: Option<&str>,
+) -> PyResult<usize> {
+    let compression = Compression::from_name(compression, &path)?;
+    let file = BufWriter::new(File::create(&path)?);
+    let mut out: Box<dyn Write> = match compression {
+        Compression::None => Box::new(file),
+        Compression::Gzip => Box::new(flate2::write::GzEncoder::new(
+            file,
+            flate2::Compression::default(),
+        )),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Box::new(zstd::stream::write::Encoder::new(file, 0)?.auto_finish()),
+    };
+    for enr in &enrs {
+        writeln!(out, "{}", enr.inner.to_base64())?;
+    }
+    out.flush()?;
+    drop(out);
+    Ok(enrs.len())
+}
+
+/// Records read from one file, and why any others were skipped.
+struct FileRecords {
+    records: Vec<enr::Enr<CombinedKey>>,
+    skipped: Vec<String>,
+}
+
+/// Why reading a set of files ended early.
+enum Interrupted {
+    Read(ReadError),
+    Stopped(Stopped),
+}
+
+/// Reads every record of one file, skipping undecodable records unless
+/// `raise_on_error`.
+fn read_all(
+    path: &Path,
+    format: Format,
+    raise_on_error: bool,
+    progress: &Progress,
+) -> Result<FileRecords, Interrupted> {
+    let mut out = FileRecords {
+        records: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let records = Records::open(path, format)
+        .map_err(|e| Interrupted::Read(ReadError::from(e).in_file(path)))?;
+    for record in records {
+        progress.check().map_err(Interrupted::Stopped)?;
+        match record {
+            Ok(enr) => out.records.push(enr),
+            Err(ReadError::Record(msg) | ReadError::Size(msg)) if !raise_on_error => {
+                out.skipped.push(msg)
+            }
+            Err(e) => return Err(Interrupted::Read(e.in_file(path))),
+        }
+    }
+    Ok(out)
+}
+
+/// Read a set of dump files in parallel and return their combined records.
+///
+/// Each file is read on its own worker thread (`workers` sets the thread
+/// count). Records are deduplicated by node id, keeping the highest seq, and
+/// then narrowed by `filter`, a dict of the same predicates accepted by
+/// `filter_many`. Undecodable records are skipped unless `raise_on_error`
+/// is set; each is logged at `DEBUG` on the `pyenr` logger, with a `WARNING`
+/// per file that had any. `progress(done, total)`, if given, counts files read;
+/// `cancel` and Ctrl-C stop the call between two records.
+#[pyfunction]
+#[pyo3(signature = (paths, *, filter=None, workers=None, format="lines", raise_on_error=false, progress=None, cancel=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn process_enr_files(
+    py: Python<'_>,
+    paths: Vec<PathBuf>,
+    filter: Option<&Bound<'_, PyDict>>,
+    workers: Option<usize>,
+    format: &str,
+    raise_on_error: bool,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Vec<Enr>> {
+    let format = Format::from_name(format)?;
+    let predicate = filter.map(Predicate::from_dict).transpose()?;
+    let progress = Progress::new(progress, cancel, paths.len());
+
+    let outcome = progress.run(py, || {
+        batch::with_pool(workers, || {
+            let per_file = paths
+                .par_iter()
+                .map(|path| {
+                    let file = read_all(path, format, raise_on_error, &progress)?;
+                    progress.advance(1).map_err(Interrupted::Stopped)?;
+                    Ok(file)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut skipped = Vec::new();
+            let mut all = Vec::new();
+            for (path, file) in paths.iter().zip(per_file) {
+                all.extend(file.records);
+                if !file.skipped.is_empty() {
+                    skipped.push((path, file.skipped));
+                }
+            }
+            let mut merged = batch::keep_highest_seq(all);
+            if let Some(predicate) = &predicate {
+                let keep: Vec<bool> = merged.par_iter().map(|r| predicate.matches(r)).collect();
+                let mut keep = keep.into_iter();
+                merged.retain(|_| keep.next().unwrap_or(false));
+            }
+            Ok((merged, skipped))
+        })
+    })?;
+    let (records, skipped) = match outcome {
+        Ok(done) => progress.finish(py, Ok(done))?,
+        Err(Interrupted::Read(err)) => return Err(err.into()),
+        Err(Interrupted::Stopped(stopped)) => progress.finish(py, Err(stopped))?,
+    };
+
+    for (path, reasons) in skipped {
+        for reason in &reasons {
+            logging::log(py, Level::Debug, || {
+                format!("skipped undecodable record in {}: {reason}", path.display())
+            })?;
+        }
+        logging::log(py, Level::Warning, || {
+            format!(
+                "skipped {} undecodable record(s) in {}",
+                reasons.len(),
+                path.display()
+            )
+        })?;
+    }
+    Ok(records.into_iter().map(|inner| Enr { inner }).collect())
+}
@@ -0,0 +1,75 @@
+//! Entry point for external fuzzing harnesses.
+
+use pyo3::exceptions::PyAssertionError;
+use pyo3::prelude::*;
+
+use crate::{conformance, decode_record, decode_text, decode_without_policy, rlp, salvage};
+
+fn invariant(holds: bool, what: &str) -> PyResult<()> {
+    if holds {
+        Ok(())
+    } else {
+        Err(PyAssertionError::new_err(format!(
+            "fuzz invariant violated: {what}"
+        )))
+    }
+}
+
+/// Run every decode path on `data` and check their results agree.
+///
+/// Unlike the public decoders, panics are not converted to `DecodeError`
+/// here: they propagate as PyO3's `PanicException` so the harness records
+/// them as crashes. Broken cross-decoder invariants raise `AssertionError`.
+/// Ordinary decode failures are expected and swallowed.
+///
+/// Returns `True` if `data` decoded into a valid record.
+#[pyfunction]
+pub(crate) fn fuzz_decode(py: Python<'_>, data: &[u8]) -> PyResult<bool> {
+    let canonical = conformance::is_canonical(data);
+    // Salvaging also runs the conformance checks.
+    let salvaged = salvage::SalvagedEnr::from_bytes(py, data)?;
+    // Salvage does not apply the placeholder policy, so compare it against
+    // decodes that do not either.
+    let strict = decode_without_policy(data, true);
+    let decoded = decode_without_policy(data, false);
+
+    // The same bytes read as base64 text exercise the text path too.
+    if let Ok(text) = std::str::from_utf8(data) {
+        if let Ok(bytes) = decode_text(py, text) {
+            let _ = decode_record(py, &bytes, false);
+        }
+    }
+
+    invariant(
+        strict.is_err() || decoded.is_ok(),
+        "strict decode accepted what plain decode rejected",
+    )?;
+    invariant(
+        decoded.is_ok() == salvaged.is_verified(),
+        "salvage and plain decode disagree on validity",
+    )?;
+
+    let Ok(record) = decoded else {
+        return Ok(false);
+    };
+    let mut encoded = Vec::with_capacity(rlp::encoded_len(&record));
+    rlp::encode_into(&record, &mut encoded);
+    invariant(
+        encoded.len() == rlp::encoded_len(&record),
+        "encoded length mismatch",
+    )?;
+    invariant(
+        conformance::is_canonical(&encoded),
+        "re-encoded record is not canonical",
+    )?;
+    invariant(
+        !canonical || encoded == data,
+        "canonical input changed on re-encoding",
+    )?;
+    let again = decode_without_policy(&encoded, false);
+    invariant(
+        again.is_ok_and(|r| r == record),
+        "re-encoded record does not decode to itself",
+    )?;
+    Ok(true)
+}
@@ -0,0 +1,154 @@
+//! Country and ASN lookups in MaxMind DB (`.mmdb`) files.
+//!
+//! Databases are read with the `maxminddb` crate, taking the
+//! `country`/`registered_country` and `autonomous_system_*` fields of the
+//! GeoIP2 and GeoLite2 Country, City and ASN databases.
+
+use std::fs::File;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use maxminddb::{geoip2, MaxMindDBError};
+use memmap2::Mmap;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+use crate::Enr;
+
+/// An open MaxMind database.
+struct Reader {
+    path: PathBuf,
+    db: maxminddb::Reader<Mmap>,
+}
+
+impl Reader {
+    fn open(path: &Path) -> PyResult<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only. If another process truncates the
+        // file while it is being read, the OS may raise SIGBUS; this is the
+        // usual caveat of memory-mapped I/O.
+        let data = unsafe { Mmap::map(&file)? };
+        let path = path.to_path_buf();
+        match maxminddb::Reader::from_source(data) {
+            Ok(db) => Ok(Reader { path, db }),
+            Err(err) => Err(invalid_database(&path, err)),
+        }
+    }
+
+    /// Fills the fields of `geo` still missing from the network containing
+    /// `ip`, if the database has one.
+    fn merge_into(&self, ip: IpAddr, geo: &mut Geo) -> Result<(), MaxMindDBError> {
+        let ip = match (ip, self.db.metadata.ip_version) {
+            (IpAddr::V6(ip), 4) => match ip.to_ipv4_mapped() {
+                Some(v4) => IpAddr::V4(v4),
+                None => return Ok(()),
+            },
+            _ => ip,
+        };
+        let country = match self.db.lookup::<geoip2::Country>(ip) {
+            Err(MaxMindDBError::AddressNotFoundError(_)) => return Ok(()),
+            result => result?,
+        };
+        let asn: geoip2::Asn = self.db.lookup(ip)?;
+        if geo.country.is_none() {
+            geo.country = [country.country, country.registered_country]
+                .into_iter()
+                .find_map(|c| c?.iso_code)
+                .map(str::to_string);
+        }
+        geo.asn = geo.asn.or(asn.autonomous_system_number);
+        if geo.as_org.is_none() {
+            geo.as_org = asn.autonomous_system_organization.map(str::to_string);
+        }
+        Ok(())
+    }
+}
+
+fn invalid_database(path: &Path, err: MaxMindDBError) -> PyErr {
+    PyValueError::new_err(format!(
+        "invalid MaxMind database {}: {err}",
+        path.display()
+    ))
+}
+
+/// What the databases say about one address.
+#[derive(Default)]
+struct Geo {
+    country: Option<String>,
+    asn: Option<u32>,
+    as_org: Option<String>,
+}
+
+/// Paths of one database or several.
+#[derive(FromPyObject)]
+pub(crate) enum MmdbPaths {
+    One(PathBuf),
+    Many(Vec<PathBuf>),
+}
+
+/// Look up the country and autonomous system of each record's address in
+/// MaxMind databases, returning one row per record in input order.
+///
+/// `mmdb_path` is a database or a list of them, such as GeoLite2-Country
+/// and GeoLite2-ASN; for each field the first database that has it wins.
+/// The IPv4 address is looked up if the record has one, else the IPv6
+/// address. Each row is a dict with `node_id`, `ip`, `country` (ISO 3166
+/// code), `asn` and `as_org`, each `None` when unknown, ready for
+/// `pandas.DataFrame`.
+#[pyfunction]
+pub(crate) fn enrich_geo<'py>(
+    py: Python<'py>,
+    enrs: Vec<PyRef<'py, Enr>>,
+    mmdb_path: MmdbPaths,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let paths = match mmdb_path {
+        MmdbPaths::One(path) => vec![path],
+        MmdbPaths::Many(paths) => paths,
+    };
+    let readers = paths
+        .iter()
+        .map(|path| Reader::open(path))
+        .collect::<PyResult<Vec<_>>>()?;
+    let records: Vec<_> = enrs
+        .iter()
+        .map(|e| {
+            let ip = e
+                .inner
+                .ip4()
+                .map(IpAddr::V4)
+                .or(e.inner.ip6().map(IpAddr::V6));
+            (e.inner.node_id().raw(), ip)
+        })
+        .collect();
+    drop(enrs);
+
+    let rows = py
+        .detach(|| {
+            records
+                .into_iter()
+                .map(|(node_id, ip)| {
+                    let mut geo = Geo::default();
+                    if let Some(ip) = ip {
+                        for reader in &readers {
+                            reader.merge_into(ip, &mut geo).map_err(|e| (reader, e))?;
+                        }
+                    }
+                    Ok((node_id, ip, geo))
+                })
+                .collect::<Result<Vec<_>, (&Reader, MaxMindDBError)>>()
+        })
+        .map_err(|(reader, err)| invalid_database(&reader.path, err))?;
+
+    rows.into_iter()
+        .map(|(node_id, ip, geo)| {
+            let row = PyDict::new(py);
+            row.set_item("node_id", PyBytes::new(py, &node_id))?;
+            row.set_item("ip", ip.map(|ip| ip.to_string()))?;
+            row.set_item("country", geo.country)?;
+            row.set_item("asn", geo.asn)?;
+            row.set_item("as_org", geo.as_org)?;
+            Ok(row)
+        })
+        .collect()
+}
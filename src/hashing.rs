@@ -0,0 +1,149 @@
+//! Process-independent hashes of records.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use enr::CombinedKey;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+use crate::convert;
+
+/// What a record's hash is computed from.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HashMode {
+    /// Sequence number and signature, which commits to the full content.
+    Content,
+    /// Node id and sequence number only.
+    NodeSeq,
+}
+
+impl HashMode {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            HashMode::Content => "content",
+            HashMode::NodeSeq => "node_seq",
+        }
+    }
+
+    pub(crate) fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "content" => Ok(HashMode::Content),
+            "node_seq" => Ok(HashMode::NodeSeq),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown hash mode {name:?}, expected \"content\" or \"node_seq\""
+            ))),
+        }
+    }
+}
+
+/// Mode used by `Enr.__hash__`, shared by all records.
+static DEFAULT_MODE: AtomicU8 = AtomicU8::new(HashMode::Content as u8);
+
+pub(crate) fn default_mode() -> HashMode {
+    if DEFAULT_MODE.load(Ordering::Relaxed) == HashMode::NodeSeq as u8 {
+        HashMode::NodeSeq
+    } else {
+        HashMode::Content
+    }
+}
+
+pub(crate) fn set_default_mode(mode: HashMode) {
+    DEFAULT_MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+/// First eight bytes of a keccak256 digest, so the value is the same in
+/// every process and on every platform.
+pub(crate) fn stable_hash(enr: &enr::Enr<CombinedKey>, mode: HashMode) -> u64 {
+    let mut hasher = Keccak256::new();
+    match mode {
+        HashMode::Content => {
+            hasher.update(enr.seq().to_be_bytes());
+            hasher.update(enr.signature());
+        }
+        HashMode::NodeSeq => {
+            hasher.update(enr.node_id().raw());
+            hasher.update(enr.seq().to_be_bytes());
+        }
+    }
+    let digest = hasher.finalize();
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(prefix)
+}
+
+/// The record's key/value pairs as compact JSON, keys in byte order and
+/// values as `0x`-prefixed hex of their RLP encoding.
+///
+/// Keys that are not valid UTF-8 are written as `0x`-prefixed hex as well.
+pub(crate) fn canonical_json(enr: &enr::Enr<CombinedKey>) -> String {
+    let mut out = String::from("{");
+    // Records keep their keys sorted by bytes.
+    for (i, (key, value)) in enr.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        match std::str::from_utf8(key) {
+            Ok(key) => push_json_string(&mut out, key),
+            Err(_) => push_json_string(&mut out, &format!("0x{}", convert::to_hex(key))),
+        }
+        out.push(':');
+        push_json_string(&mut out, &format!("0x{}", convert::to_hex(value)));
+    }
+    out.push('}');
+    out
+}
+
+/// Appends `text` as a JSON string, escaping as RFC 8785 does.
+fn push_json_string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// SHA-256 of [`canonical_json`].
+pub(crate) fn content_hash(enr: &enr::Enr<CombinedKey>) -> [u8; 32] {
+    Sha256::digest(canonical_json(enr).as_bytes()).into()
+}
+
+/// keccak256 over the distinct `(node id, seq)` pairs in ascending order,
+/// each as the 32-byte node id followed by the seq as 8 big-endian bytes.
+pub(crate) fn set_digest(mut pairs: Vec<([u8; 32], u64)>) -> String {
+    pairs.sort_unstable();
+    pairs.dedup();
+    let mut hasher = Keccak256::new();
+    for (node_id, seq) in pairs {
+        hasher.update(node_id);
+        hasher.update(seq.to_be_bytes());
+    }
+    convert::to_hex(&hasher.finalize())
+}
+
+/// A digest of which version of which nodes `enrs` holds, as 64 lowercase
+/// hex digits, for use as an ETag.
+///
+/// It covers only each record's node id and seq, and ignores order and
+/// repeats, so two crawls or replicas holding the same versions of the same
+/// nodes get the same digest.
+#[pyfunction]
+pub(crate) fn records_digest(py: Python<'_>, enrs: Vec<PyRef<'_, crate::Enr>>) -> String {
+    let pairs: Vec<_> = enrs
+        .iter()
+        .map(|e| (e.inner.node_id().raw(), e.inner.seq()))
+        .collect();
+    drop(enrs);
+    py.detach(|| set_digest(pairs))
+}
@@ -0,0 +1,157 @@
+//! Successive versions of one node's record.
+
+use std::sync::{Mutex, MutexGuard};
+
+use enr::CombinedKey;
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::diff::EnrDiff;
+use crate::{convert, Enr};
+
+/// The lineage of one node's record: every version seen, in increasing seq.
+///
+/// Records must all have the same node id, and each must have a higher seq
+/// than the last. Adding the latest record again is a no-op; any other
+/// record with a seq that is not higher raises `ValueError`.
+#[pyclass(name = "EnrHistory", frozen)]
+pub(crate) struct EnrHistory {
+    records: Mutex<Vec<enr::Enr<CombinedKey>>>,
+}
+
+impl EnrHistory {
+    fn lock(&self) -> MutexGuard<'_, Vec<enr::Enr<CombinedKey>>> {
+        self.records.lock().expect("history lock poisoned")
+    }
+
+    fn find(records: &[enr::Enr<CombinedKey>], seq: u64) -> PyResult<&enr::Enr<CombinedKey>> {
+        records
+            .binary_search_by_key(&seq, |r| r.seq())
+            .map(|i| &records[i])
+            .map_err(|_| PyKeyError::new_err(format!("no record with seq {seq} in the history")))
+    }
+}
+
+#[pymethods]
+impl EnrHistory {
+    #[new]
+    #[pyo3(signature = (enrs=Vec::new()))]
+    fn new(enrs: Vec<PyRef<'_, Enr>>) -> PyResult<Self> {
+        let history = EnrHistory {
+            records: Mutex::new(Vec::new()),
+        };
+        for enr in enrs {
+            history.add(&enr)?;
+        }
+        Ok(history)
+    }
+
+    /// Append the next version of the record.
+    ///
+    /// Returns `False` if `enr` is the latest version already held.
+    fn add(&self, enr: &Enr) -> PyResult<bool> {
+        let mut records = self.lock();
+        if let Some(latest) = records.last() {
+            if enr.inner.node_id() != latest.node_id() {
+                return Err(PyValueError::new_err(format!(
+                    "record is for node 0x{}, not 0x{}",
+                    convert::to_hex(&enr.inner.node_id().raw()),
+                    convert::to_hex(&latest.node_id().raw())
+                )));
+            }
+            if enr.inner == *latest {
+                return Ok(false);
+            }
+            if enr.inner.seq() <= latest.seq() {
+                let problem = if enr.inner.seq() == latest.seq() {
+                    "has the same seq as the latest record but different content"
+                } else {
+                    "is older than the latest record"
+                };
+                return Err(PyValueError::new_err(format!(
+                    "record with seq {} {problem} (seq {})",
+                    enr.inner.seq(),
+                    latest.seq()
+                )));
+            }
+        }
+        records.push(enr.inner.clone());
+        Ok(true)
+    }
+
+    /// Node id shared by every record, or `None` while the history is empty.
+    #[getter]
+    fn node_id<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyBytes>> {
+        let node_id = self.lock().first().map(|r| r.node_id().raw());
+        node_id.map(|id| PyBytes::new(py, &id))
+    }
+
+    /// The most recent record, or `None` while the history is empty.
+    #[getter]
+    fn latest(&self) -> Option<Enr> {
+        self.lock().last().map(|r| Enr { inner: r.clone() })
+    }
+
+    /// Sequence numbers held, in increasing order.
+    fn seqs(&self) -> Vec<u64> {
+        self.lock().iter().map(|r| r.seq()).collect()
+    }
+
+    /// The record with sequence number `seq`, or `None`.
+    fn get(&self, seq: u64) -> Option<Enr> {
+        let records = self.lock();
+        Self::find(&records, seq)
+            .ok()
+            .map(|r| Enr { inner: r.clone() })
+    }
+
+    /// Every record, oldest first.
+    fn records(&self) -> Vec<Enr> {
+        self.lock()
+            .iter()
+            .map(|r| Enr { inner: r.clone() })
+            .collect()
+    }
+
+    /// What changed from the record with `seq_a` to the one with `seq_b`.
+    ///
+    /// Raises `KeyError` if either seq is not in the history.
+    fn changes_between(&self, py: Python<'_>, seq_a: u64, seq_b: u64) -> PyResult<EnrDiff> {
+        let (old, new) = {
+            let records = self.lock();
+            (
+                Self::find(&records, seq_a)?.clone(),
+                Self::find(&records, seq_b)?.clone(),
+            )
+        };
+        EnrDiff::between(py, &old, &new)
+    }
+
+    /// The change made by each update, oldest first.
+    fn changes(&self, py: Python<'_>) -> PyResult<Vec<EnrDiff>> {
+        let records = self.lock().clone();
+        records
+            .windows(2)
+            .map(|pair| EnrDiff::between(py, &pair[0], &pair[1]))
+            .collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn __repr__(&self) -> String {
+        let records = self.lock();
+        match (records.first(), records.last()) {
+            (Some(first), Some(last)) => format!(
+                "EnrHistory(node_id=0x{}\u{2026}, len={}, seqs={}..{})",
+                convert::to_hex(&first.node_id().raw()[..4]),
+                records.len(),
+                first.seq(),
+                last.seq()
+            ),
+            _ => "EnrHistory(len=0)".to_string(),
+        }
+    }
+}
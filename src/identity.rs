@@ -0,0 +1,195 @@
+//! Merging records for the same nodes reported by several sources.
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::{Mutex, MutexGuard};
+
+use enr::CombinedKey;
+use pyo3::prelude::*;
+
+use crate::bloom::NodeIdArg;
+use crate::{hashing, net, Enr};
+
+struct Node {
+    record: enr::Enr<CombinedKey>,
+    /// Every source that reported the node, in first-seen order.
+    sources: Vec<String>,
+}
+
+/// An endpoint claimed by records of more than one node.
+#[pyclass(name = "EndpointConflict", frozen, get_all)]
+pub(crate) struct EndpointConflict {
+    /// `"udp"` or `"tcp"`.
+    protocol: &'static str,
+    /// The address as `ip:port` (`[ip]:port` for IPv6).
+    endpoint: String,
+    /// The freshest record of each node claiming it, in node id order.
+    records: Vec<Py<Enr>>,
+}
+
+#[pymethods]
+impl EndpointConflict {
+    fn __repr__(&self) -> String {
+        format!(
+            "EndpointConflict(protocol={:?}, endpoint={:?}, nodes={})",
+            self.protocol,
+            self.endpoint,
+            self.records.len()
+        )
+    }
+}
+
+/// The freshest record of each node seen across several sources (DNS lists,
+/// discovery, static config), with the sources that reported it.
+///
+/// A record replaces the one held for its node only if its seq is higher.
+/// `conflicts()` reports endpoints that records for different nodes, and so
+/// different keys, both claim.
+#[pyclass(name = "IdentityTracker", frozen)]
+pub(crate) struct IdentityTracker {
+    nodes: Mutex<HashMap<[u8; 32], Node>>,
+}
+
+impl IdentityTracker {
+    fn lock(&self) -> MutexGuard<'_, HashMap<[u8; 32], Node>> {
+        self.nodes.lock().expect("tracker lock poisoned")
+    }
+
+    fn add_one(
+        nodes: &mut HashMap<[u8; 32], Node>,
+        enr: &enr::Enr<CombinedKey>,
+        source: &str,
+    ) -> bool {
+        let node = match nodes.entry(enr.node_id().raw()) {
+            Entry::Vacant(entry) => {
+                entry.insert(Node {
+                    record: enr.clone(),
+                    sources: vec![source.to_string()],
+                });
+                return true;
+            }
+            Entry::Occupied(entry) => entry.into_mut(),
+        };
+        if !node.sources.iter().any(|s| s == source) {
+            node.sources.push(source.to_string());
+        }
+        if enr.seq() > node.record.seq() {
+            node.record = enr.clone();
+            return true;
+        }
+        false
+    }
+}
+
+#[pymethods]
+impl IdentityTracker {
+    #[new]
+    fn new() -> Self {
+        IdentityTracker {
+            nodes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record that `source` reported `enr`.
+    ///
+    /// Returns `True` if the record is new or fresher than the one held.
+    fn add(&self, enr: &Enr, source: &str) -> bool {
+        Self::add_one(&mut self.lock(), &enr.inner, source)
+    }
+
+    /// `add` each record from one source, returning how many were new or
+    /// fresher.
+    fn add_many(&self, enrs: Vec<PyRef<'_, Enr>>, source: &str) -> usize {
+        let mut nodes = self.lock();
+        enrs.iter()
+            .filter(|enr| Self::add_one(&mut nodes, &enr.inner, source))
+            .count()
+    }
+
+    /// The freshest record for a node id (or a record's node id), or `None`.
+    fn get(&self, node_id: NodeIdArg<'_>) -> PyResult<Option<Enr>> {
+        let node_id = node_id.node_id()?;
+        Ok(self.lock().get(&node_id).map(|node| Enr {
+            inner: node.record.clone(),
+        }))
+    }
+
+    /// The sources that reported a node, in the order first seen; empty for
+    /// an unknown node.
+    fn sources(&self, node_id: NodeIdArg<'_>) -> PyResult<Vec<String>> {
+        let node_id = node_id.node_id()?;
+        Ok(self
+            .lock()
+            .get(&node_id)
+            .map(|node| node.sources.clone())
+            .unwrap_or_default())
+    }
+
+    /// The freshest record of every node, in node id order.
+    fn records(&self) -> Vec<Enr> {
+        let nodes = self.lock();
+        let mut records: Vec<_> = nodes.values().map(|node| &node.record).collect();
+        records.sort_by_key(|record| record.node_id().raw());
+        records
+            .into_iter()
+            .map(|record| Enr {
+                inner: record.clone(),
+            })
+            .collect()
+    }
+
+    /// Endpoints that the freshest records of more than one node claim,
+    /// ordered by protocol and address.
+    fn conflicts(&self, py: Python<'_>) -> PyResult<Vec<EndpointConflict>> {
+        let mut claims: BTreeMap<(&'static str, SocketAddr), Vec<enr::Enr<CombinedKey>>> =
+            BTreeMap::new();
+        for node in self.lock().values() {
+            for endpoint in net::endpoints(&node.record, false) {
+                claims
+                    .entry(endpoint)
+                    .or_default()
+                    .push(node.record.clone());
+            }
+        }
+        claims
+            .into_iter()
+            .filter(|(_, records)| records.len() > 1)
+            .map(|((protocol, address), mut records)| {
+                records.sort_by_key(|record| record.node_id().raw());
+                Ok(EndpointConflict {
+                    protocol,
+                    endpoint: address.to_string(),
+                    records: records
+                        .into_iter()
+                        .map(|inner| Py::new(py, Enr { inner }))
+                        .collect::<PyResult<_>>()?,
+                })
+            })
+            .collect()
+    }
+
+    /// `records_digest()` of the freshest records, so that two trackers fed
+    /// from different places can be compared cheaply.
+    fn digest(&self) -> String {
+        let pairs = self
+            .lock()
+            .iter()
+            .map(|(node_id, node)| (*node_id, node.record.seq()))
+            .collect();
+        hashing::set_digest(pairs)
+    }
+
+    fn __contains__(&self, node_id: NodeIdArg<'_>) -> PyResult<bool> {
+        let node_id = node_id.node_id()?;
+        Ok(self.lock().contains_key(&node_id))
+    }
+
+    fn __len__(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("IdentityTracker(len={})", self.lock().len())
+    }
+}
@@ -0,0 +1,500 @@
+//! `to_dict()` and the same map encoded as CBOR or MessagePack, and the
+//! protobuf message in `python/pyenr/enr.proto`, for typed interchange with
+//! systems that do not speak RLP.
+//!
+//! Every form holds `seq`, `node_id`, `signature` and `content`, the
+//! key/value pairs with values RLP-encoded as `items()` gives them. That is
+//! everything the signature covers, so decoding rebuilds the exact record and
+//! verifies it again; `node_id` is informational and checked if present.
+//!
+//! The binary encodings need the `interchange` feature; `to_dict()` is always
+//! available.
+
+use alloy_rlp::{BufMut, Encodable, Header};
+#[cfg(feature = "interchange")]
+use ciborium::value::Value as Cbor;
+use enr::CombinedKey;
+#[cfg(feature = "interchange")]
+use prost::Message as _;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+#[cfg(feature = "interchange")]
+use rmpv::Value as MsgPack;
+
+use crate::{errors, key_name};
+
+/// The fields of the interchange map.
+struct Parts {
+    seq: u64,
+    node_id: Option<Vec<u8>>,
+    signature: Vec<u8>,
+    content: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Parts {
+    fn of(enr: &enr::Enr<CombinedKey>) -> Self {
+        Parts {
+            seq: enr.seq(),
+            node_id: Some(enr.node_id().raw().to_vec()),
+            signature: enr.signature().to_vec(),
+            content: enr.iter().map(|(k, v)| (k.clone(), v.to_vec())).collect(),
+        }
+    }
+
+    /// Rebuilds the RLP encoding and decodes it, verifying the signature.
+    fn into_record(mut self, py: Python<'_>, format: &str) -> PyResult<crate::Enr> {
+        self.content.sort_by(|a, b| a.0.cmp(&b.0));
+        if let Some(pair) = self.content.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            let key = String::from_utf8_lossy(&pair[0].0);
+            return Err(errors::interchange_error(
+                py,
+                format,
+                &format!("duplicate key {key:?}"),
+            ));
+        }
+        let mut payload = Vec::new();
+        self.signature.as_slice().encode(&mut payload);
+        Encodable::encode(&self.seq, &mut payload);
+        for (key, value) in &self.content {
+            key.as_slice().encode(&mut payload);
+            payload.put_slice(value);
+        }
+        let mut data = Vec::with_capacity(payload.len() + 3);
+        Header {
+            list: true,
+            payload_length: payload.len(),
+        }
+        .encode(&mut data);
+        data.extend_from_slice(&payload);
+
+        let inner = errors::guard_decode(py, || crate::decode_record(py, &data, false))?;
+        if self
+            .node_id
+            .is_some_and(|id| id.as_slice() != inner.node_id().raw().as_slice())
+        {
+            return Err(errors::interchange_error(
+                py,
+                format,
+                "node_id does not match the record's public key",
+            ));
+        }
+        Ok(crate::Enr { inner })
+    }
+
+    /// Collects the fields from `(name, value)` pairs of a map, reading each
+    /// value with `field` and each content entry with `entry`.
+    fn collect<V>(
+        pairs: impl IntoIterator<Item = (Option<String>, V)>,
+        field: impl Fn(V) -> Option<Field<V>>,
+        entry: impl Fn((V, V)) -> Option<(Vec<u8>, Vec<u8>)>,
+    ) -> Result<Self, String> {
+        let mut seq = None;
+        let mut node_id = None;
+        let mut signature = None;
+        let mut content = None;
+        for (name, value) in pairs {
+            let name = name.ok_or("field names must be text")?;
+            let invalid = || format!("invalid {name:?}");
+            match name.as_str() {
+                "seq" => match field(value) {
+                    Some(Field::Uint(n)) => seq = Some(n),
+                    _ => return Err(format!("{} (expected an unsigned integer)", invalid())),
+                },
+                "node_id" | "signature" => match field(value) {
+                    Some(Field::Bytes(b)) if name == "node_id" => node_id = Some(b),
+                    Some(Field::Bytes(b)) => signature = Some(b),
+                    _ => return Err(format!("{} (expected bytes)", invalid())),
+                },
+                "content" => match field(value) {
+                    Some(Field::Map(map)) => {
+                        let entries = map
+                            .into_iter()
+                            .map(&entry)
+                            .collect::<Option<Vec<_>>>()
+                            .ok_or("content must map text or bytes keys to bytes values")?;
+                        content = Some(entries);
+                    }
+                    _ => return Err(format!("{} (expected a map)", invalid())),
+                },
+                _ => return Err(format!("unknown field {name:?}")),
+            }
+        }
+        Ok(Parts {
+            seq: seq.ok_or("missing \"seq\"")?,
+            node_id,
+            signature: signature.ok_or("missing \"signature\"")?,
+            content: content.ok_or("missing \"content\"")?,
+        })
+    }
+}
+
+/// A field value in one of the shapes the schema uses.
+enum Field<V> {
+    Uint(u64),
+    Bytes(Vec<u8>),
+    Map(Vec<(V, V)>),
+}
+
+/// A key as text if it is valid UTF-8, otherwise as bytes.
+enum Key<'a> {
+    Text(&'a str),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> Key<'a> {
+    fn of(key: &'a [u8]) -> Self {
+        std::str::from_utf8(key).map_or(Key::Bytes(key), Key::Text)
+    }
+}
+
+/// Implements `Enr.to_dict()`.
+pub(crate) fn to_dict<'py>(
+    py: Python<'py>,
+    enr: &enr::Enr<CombinedKey>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let parts = Parts::of(enr);
+    let content = PyDict::new(py);
+    for (key, value) in &parts.content {
+        let value = PyBytes::new(py, value);
+        match Key::of(key) {
+            Key::Text(key) => content.set_item(key_name(py, key.as_bytes()), value)?,
+            Key::Bytes(key) => content.set_item(PyBytes::new(py, key), value)?,
+        }
+    }
+    let out = PyDict::new(py);
+    out.set_item("seq", parts.seq)?;
+    out.set_item(
+        "node_id",
+        PyBytes::new(py, parts.node_id.as_deref().unwrap_or_default()),
+    )?;
+    out.set_item("signature", PyBytes::new(py, &parts.signature))?;
+    out.set_item("content", content)?;
+    Ok(out)
+}
+
+/// Implements `Enr.from_dict()`.
+pub(crate) fn from_dict<'py>(py: Python<'py>, data: &Bound<'py, PyDict>) -> PyResult<crate::Enr> {
+    let key_bytes = |key: &Bound<'py, PyAny>| match key.extract::<String>() {
+        Ok(text) => Some(text.into_bytes()),
+        Err(_) => key.cast::<PyBytes>().ok().map(|b| b.as_bytes().to_vec()),
+    };
+    let field = |value: Bound<'py, PyAny>| -> Option<Field<Bound<'py, PyAny>>> {
+        if let Ok(map) = value.cast::<PyDict>() {
+            return Some(Field::Map(map.iter().collect()));
+        }
+        if let Ok(bytes) = value.cast::<PyBytes>() {
+            return Some(Field::Bytes(bytes.as_bytes().to_vec()));
+        }
+        value.extract::<u64>().ok().map(Field::Uint)
+    };
+    let entry = |(key, value): (Bound<'py, PyAny>, Bound<'py, PyAny>)| {
+        Some((
+            key_bytes(&key)?,
+            value.cast::<PyBytes>().ok()?.as_bytes().to_vec(),
+        ))
+    };
+    let pairs = data.iter().map(|(k, v)| (k.extract::<String>().ok(), v));
+    Parts::collect(pairs, field, entry)
+        .map_err(|message| errors::interchange_error(py, "dict", &message))?
+        .into_record(py, "dict")
+}
+
+/// Implements `Enr.to_cbor()`.
+#[cfg(feature = "interchange")]
+pub(crate) fn to_cbor(enr: &enr::Enr<CombinedKey>) -> PyResult<Vec<u8>> {
+    let parts = Parts::of(enr);
+    let content = parts
+        .content
+        .into_iter()
+        .map(|(key, value)| {
+            let key = match Key::of(&key) {
+                Key::Text(text) => Cbor::Text(text.to_string()),
+                Key::Bytes(_) => Cbor::Bytes(key),
+            };
+            (key, Cbor::Bytes(value))
+        })
+        .collect();
+    let map = Cbor::Map(vec![
+        (Cbor::Text("seq".into()), Cbor::Integer(parts.seq.into())),
+        (
+            Cbor::Text("node_id".into()),
+            Cbor::Bytes(parts.node_id.unwrap_or_default()),
+        ),
+        (Cbor::Text("signature".into()), Cbor::Bytes(parts.signature)),
+        (Cbor::Text("content".into()), Cbor::Map(content)),
+    ]);
+    let mut out = Vec::new();
+    ciborium::ser::into_writer(&map, &mut out).expect("writing to a Vec cannot fail");
+    Ok(out)
+}
+
+/// Implements `Enr.from_cbor()`.
+#[cfg(feature = "interchange")]
+pub(crate) fn from_cbor(py: Python<'_>, mut data: &[u8]) -> PyResult<crate::Enr> {
+    let fail = |message: &str| errors::interchange_error(py, "CBOR", message);
+    let value: Cbor = ciborium::de::from_reader(&mut data).map_err(|e| {
+        fail(&match e {
+            ciborium::de::Error::Io(_) => "unexpected end of input".to_string(),
+            ciborium::de::Error::Syntax(offset) => format!("malformed item at offset {offset}"),
+            ciborium::de::Error::Semantic(_, message) => message,
+            ciborium::de::Error::RecursionLimitExceeded => "nested too deeply".to_string(),
+        })
+    })?;
+    if !data.is_empty() {
+        return Err(fail("trailing bytes after the map"));
+    }
+    let Cbor::Map(pairs) = value else {
+        return Err(fail("expected a map"));
+    };
+    let field = |value: Cbor| match value {
+        Cbor::Integer(n) => u64::try_from(n).ok().map(Field::Uint),
+        Cbor::Bytes(b) => Some(Field::Bytes(b)),
+        Cbor::Map(map) => Some(Field::Map(map)),
+        _ => None,
+    };
+    let entry = |pair: (Cbor, Cbor)| match pair {
+        (Cbor::Text(key), Cbor::Bytes(value)) => Some((key.into_bytes(), value)),
+        (Cbor::Bytes(key), Cbor::Bytes(value)) => Some((key, value)),
+        _ => None,
+    };
+    let pairs = pairs.into_iter().map(|(k, v)| (k.into_text().ok(), v));
+    Parts::collect(pairs, field, entry)
+        .map_err(|message| fail(&message))?
+        .into_record(py, "CBOR")
+}
+
+/// Implements `Enr.to_msgpack()`.
+#[cfg(feature = "interchange")]
+pub(crate) fn to_msgpack(enr: &enr::Enr<CombinedKey>) -> PyResult<Vec<u8>> {
+    let parts = Parts::of(enr);
+    let content = parts
+        .content
+        .into_iter()
+        .map(|(key, value)| {
+            let key = match Key::of(&key) {
+                Key::Text(text) => MsgPack::from(text),
+                Key::Bytes(_) => MsgPack::Binary(key),
+            };
+            (key, MsgPack::Binary(value))
+        })
+        .collect();
+    let map = MsgPack::Map(vec![
+        (MsgPack::from("seq"), MsgPack::from(parts.seq)),
+        (
+            MsgPack::from("node_id"),
+            MsgPack::Binary(parts.node_id.unwrap_or_default()),
+        ),
+        (MsgPack::from("signature"), MsgPack::Binary(parts.signature)),
+        (MsgPack::from("content"), MsgPack::Map(content)),
+    ]);
+    let mut out = Vec::new();
+    rmpv::encode::write_value(&mut out, &map).expect("writing to a Vec cannot fail");
+    Ok(out)
+}
+
+/// Implements `Enr.from_msgpack()`.
+#[cfg(feature = "interchange")]
+pub(crate) fn from_msgpack(py: Python<'_>, mut data: &[u8]) -> PyResult<crate::Enr> {
+    let fail = |message: &str| errors::interchange_error(py, "MessagePack", message);
+    let value = rmpv::decode::read_value(&mut data).map_err(|e| fail(&e.to_string()))?;
+    if !data.is_empty() {
+        return Err(fail("trailing bytes after the map"));
+    }
+    let MsgPack::Map(pairs) = value else {
+        return Err(fail("expected a map"));
+    };
+    let field = |value: MsgPack| match value {
+        MsgPack::Integer(n) => n.as_u64().map(Field::Uint),
+        MsgPack::Binary(b) => Some(Field::Bytes(b)),
+        MsgPack::Map(map) => Some(Field::Map(map)),
+        _ => None,
+    };
+    let entry = |pair: (MsgPack, MsgPack)| match pair {
+        (MsgPack::String(key), MsgPack::Binary(value)) => {
+            Some((key.into_str()?.into_bytes(), value))
+        }
+        (MsgPack::Binary(key), MsgPack::Binary(value)) => Some((key, value)),
+        _ => None,
+    };
+    let pairs = pairs.into_iter().map(|(k, v)| match k {
+        MsgPack::String(name) => (name.into_str(), v),
+        _ => (None, v),
+    });
+    Parts::collect(pairs, field, entry)
+        .map_err(|message| fail(&message))?
+        .into_record(py, "MessagePack")
+}
+
+/// The protobuf schema, shipped next to the Python package.
+pub(crate) const ENR_PROTO: &str = include_str!("../python/pyenr/enr.proto");
+
+/// The `Enr` message of [`ENR_PROTO`].
+#[cfg(feature = "interchange")]
+#[derive(Clone, PartialEq, prost::Message)]
+struct EnrMessage {
+    #[prost(uint64, tag = "1")]
+    seq: u64,
+    #[prost(bytes = "vec", tag = "2")]
+    signature: Vec<u8>,
+    #[prost(message, repeated, tag = "3")]
+    content: Vec<EntryMessage>,
+    #[prost(bytes = "vec", tag = "4")]
+    node_id: Vec<u8>,
+    #[prost(bytes = "vec", tag = "5")]
+    public_key: Vec<u8>,
+    #[prost(string, optional, tag = "6")]
+    identity_scheme: Option<String>,
+    #[prost(string, optional, tag = "7")]
+    ip4: Option<String>,
+    #[prost(uint32, optional, tag = "8")]
+    tcp4: Option<u32>,
+    #[prost(uint32, optional, tag = "9")]
+    udp4: Option<u32>,
+    #[prost(string, optional, tag = "10")]
+    ip6: Option<String>,
+    #[prost(uint32, optional, tag = "11")]
+    tcp6: Option<u32>,
+    #[prost(uint32, optional, tag = "12")]
+    udp6: Option<u32>,
+}
+
+/// The `Entry` message of [`ENR_PROTO`].
+#[cfg(feature = "interchange")]
+#[derive(Clone, PartialEq, prost::Message)]
+struct EntryMessage {
+    #[prost(bytes = "vec", tag = "1")]
+    key: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+#[cfg(feature = "interchange")]
+impl EnrMessage {
+    fn of(enr: &enr::Enr<CombinedKey>) -> Self {
+        use enr::EnrPublicKey;
+        let parts = Parts::of(enr);
+        EnrMessage {
+            seq: parts.seq,
+            signature: parts.signature,
+            content: parts
+                .content
+                .into_iter()
+                .map(|(key, value)| EntryMessage { key, value })
+                .collect(),
+            node_id: parts.node_id.unwrap_or_default(),
+            public_key: enr.public_key().encode(),
+            identity_scheme: enr.id(),
+            ip4: enr.ip4().map(|ip| ip.to_string()),
+            tcp4: enr.tcp4().map(u32::from),
+            udp4: enr.udp4().map(u32::from),
+            ip6: enr.ip6().map(|ip| ip.to_string()),
+            tcp6: enr.tcp6().map(u32::from),
+            udp6: enr.udp6().map(u32::from),
+        }
+    }
+
+    /// The first decoded field set in `self` that disagrees with `actual`.
+    fn mismatch(&self, actual: &EnrMessage) -> Option<&'static str> {
+        let bytes = [
+            ("node_id", &self.node_id, &actual.node_id),
+            ("public_key", &self.public_key, &actual.public_key),
+        ];
+        let text = [
+            (
+                "identity_scheme",
+                &self.identity_scheme,
+                &actual.identity_scheme,
+            ),
+            ("ip4", &self.ip4, &actual.ip4),
+            ("ip6", &self.ip6, &actual.ip6),
+        ];
+        let ports = [
+            ("tcp4", self.tcp4, actual.tcp4),
+            ("udp4", self.udp4, actual.udp4),
+            ("tcp6", self.tcp6, actual.tcp6),
+            ("udp6", self.udp6, actual.udp6),
+        ];
+        bytes
+            .iter()
+            .find(|(_, given, actual)| !given.is_empty() && given != actual)
+            .map(|(name, ..)| *name)
+            .or_else(|| {
+                text.iter()
+                    .find(|(_, given, actual)| given.is_some() && given != actual)
+                    .map(|(name, ..)| *name)
+            })
+            .or_else(|| {
+                ports
+                    .iter()
+                    .find(|(_, given, actual)| given.is_some() && given != actual)
+                    .map(|(name, ..)| *name)
+            })
+    }
+}
+
+/// Implements `Enr.to_protobuf_bytes()`.
+#[cfg(feature = "interchange")]
+pub(crate) fn to_protobuf(enr: &enr::Enr<CombinedKey>) -> PyResult<Vec<u8>> {
+    Ok(EnrMessage::of(enr).encode_to_vec())
+}
+
+/// Implements `Enr.from_protobuf_bytes()`.
+#[cfg(feature = "interchange")]
+pub(crate) fn from_protobuf(py: Python<'_>, data: &[u8]) -> PyResult<crate::Enr> {
+    let fail = |message: &str| errors::interchange_error(py, "protobuf", message);
+    let message = EnrMessage::decode(data).map_err(|e| fail(&e.to_string()))?;
+    let parts = Parts {
+        seq: message.seq,
+        node_id: None,
+        signature: message.signature.clone(),
+        content: message
+            .content
+            .iter()
+            .map(|entry| (entry.key.clone(), entry.value.clone()))
+            .collect(),
+    };
+    let enr = parts.into_record(py, "protobuf")?;
+    if let Some(field) = message.mismatch(&EnrMessage::of(&enr.inner)) {
+        return Err(fail(&format!(
+            "{field} does not match the record's content"
+        )));
+    }
+    Ok(enr)
+}
+
+/// Stand-ins for the CBOR, MessagePack and protobuf encodings in builds
+/// without the `interchange` feature.
+#[cfg(not(feature = "interchange"))]
+mod unavailable {
+    use enr::CombinedKey;
+    use pyo3::prelude::*;
+
+    use crate::features::missing;
+
+    pub(crate) fn to_cbor(_enr: &enr::Enr<CombinedKey>) -> PyResult<Vec<u8>> {
+        Err(missing("Enr.to_cbor()", "interchange"))
+    }
+
+    pub(crate) fn from_cbor(_py: Python<'_>, _data: &[u8]) -> PyResult<crate::Enr> {
+        Err(missing("Enr.from_cbor()", "interchange"))
+    }
+
+    pub(crate) fn to_msgpack(_enr: &enr::Enr<CombinedKey>) -> PyResult<Vec<u8>> {
+        Err(missing("Enr.to_msgpack()", "interchange"))
+    }
+
+    pub(crate) fn from_msgpack(_py: Python<'_>, _data: &[u8]) -> PyResult<crate::Enr> {
+        Err(missing("Enr.from_msgpack()", "interchange"))
+    }
+
+    pub(crate) fn to_protobuf(_enr: &enr::Enr<CombinedKey>) -> PyResult<Vec<u8>> {
+        Err(missing("Enr.to_protobuf_bytes()", "interchange"))
+    }
+
+    pub(crate) fn from_protobuf(_py: Python<'_>, _data: &[u8]) -> PyResult<crate::Enr> {
+        Err(missing("Enr.from_protobuf_bytes()", "interchange"))
+    }
+}
+
+#[cfg(not(feature = "interchange"))]
+pub(crate) use unavailable::*;
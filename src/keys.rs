@@ -0,0 +1,316 @@
+//! Rules for the keys EIP-778 predefines.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyInt, PyString, PyType};
+
+use crate::errors::FieldError;
+use crate::rlp::RlpValue;
+use crate::{convert, eth, eth2, multiaddr, portal, rlp, ssv, waku};
+
+/// Shape a known key's value is expected to have.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ValueType {
+    /// A UTF-8 byte string, such as the identity scheme name.
+    Text,
+    /// A byte string, of the given length if fixed.
+    Bytes(Option<usize>),
+    Ip4,
+    Ip6,
+    Port,
+    /// A minimally encoded unsigned integer.
+    Uint,
+    /// An RLP list.
+    List,
+}
+
+impl ValueType {
+    /// Name exposed to Python, such as `"port"` or `"bytes[16]"`.
+    pub(crate) fn name(self) -> String {
+        match self {
+            ValueType::Text => "text".into(),
+            ValueType::Bytes(None) => "bytes".into(),
+            ValueType::Bytes(Some(len)) => format!("bytes[{len}]"),
+            ValueType::Ip4 => "ip4".into(),
+            ValueType::Ip6 => "ip6".into(),
+            ValueType::Port => "port".into(),
+            ValueType::Uint => "uint".into(),
+            ValueType::List => "list".into(),
+        }
+    }
+}
+
+/// Keys defined by EIP-778 or in common use on the networks this library
+/// is used to crawl, with the shape of their values.
+pub(crate) const KNOWN_KEYS: &[(&str, ValueType)] = &[
+    // EIP-778.
+    ("id", ValueType::Text),
+    ("secp256k1", ValueType::Bytes(Some(33))),
+    ("ed25519", ValueType::Bytes(Some(32))),
+    ("ip", ValueType::Ip4),
+    ("ip6", ValueType::Ip6),
+    ("tcp", ValueType::Port),
+    ("tcp6", ValueType::Port),
+    ("udp", ValueType::Port),
+    ("udp6", ValueType::Port),
+    // libp2p QUIC transport.
+    ("quic", ValueType::Port),
+    ("quic6", ValueType::Port),
+    // Consensus layer.
+    (
+        eth2::ETH2_ENR_KEY,
+        ValueType::Bytes(Some(eth2::ENR_FORK_ID_LEN)),
+    ),
+    ("attnets", ValueType::Bytes(Some(8))),
+    ("syncnets", ValueType::Bytes(Some(1))),
+    ("cgc", ValueType::Uint),
+    ("nfd", ValueType::Bytes(Some(4))),
+    // Execution layer devp2p capabilities.
+    (eth::ETH_ENR_KEY, ValueType::List),
+    ("snap", ValueType::List),
+    ("les", ValueType::List),
+    (eth::BSC_ENR_KEY, ValueType::List),
+    // Portal network client version string.
+    (portal::PORTAL_CLIENT_KEY, ValueType::Text),
+    // Waku v2 capabilities.
+    (waku::WAKU2_KEY, ValueType::Bytes(Some(1))),
+    // libp2p addresses, length-prefixed or as an RLP list.
+    (multiaddr::MULTIADDRS_KEY, ValueType::Bytes(None)),
+    // SSV network operators.
+    (
+        ssv::SUBNETS_KEY,
+        ValueType::Bytes(Some(ssv::SUBNET_COUNT / 8)),
+    ),
+    (ssv::DOMAIN_TYPE_KEY, ValueType::Uint),
+    (ssv::NEXT_DOMAIN_TYPE_KEY, ValueType::Uint),
+    // OP Stack chain id and version.
+    ("opstack", ValueType::Bytes(None)),
+];
+
+/// The expected value shape for `key`, if it is registered.
+pub(crate) fn lookup(key: &str) -> Option<ValueType> {
+    KNOWN_KEYS
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, value_type)| *value_type)
+}
+
+/// Checks that `value` may be stored under `key` through the generic
+/// key-value API.
+///
+/// The identity scheme and public key are derived from the signing key, so
+/// setting them directly would leave a record that no longer matches its
+/// signature. Addresses and the `eth2` fork id must have their fixed
+/// length, and ports must be minimally encoded and fit in a `u16`; none of
+/// these may be given a list.
+pub(crate) fn check_settable(key: &str, value: &RlpValue) -> PyResult<()> {
+    match key {
+        "id" => {
+            return Err(FieldError::new_err(
+                "key \"id\" is reserved: the identity scheme is always v4",
+            ))
+        }
+        "secp256k1" | "ed25519" => {
+            return Err(FieldError::new_err(format!(
+                "key {key:?} is reserved: the public key is set from the signing key"
+            )))
+        }
+        _ => {}
+    }
+    let shaped =
+        matches!(key, "ip" | "ip6" | "tcp" | "tcp6" | "udp" | "udp6") || key == eth2::ETH2_ENR_KEY;
+    let value = match value.as_bytes() {
+        Some(bytes) => bytes,
+        None if shaped => {
+            return Err(FieldError::new_err(format!(
+                "key {key:?} must be a byte string, not a list"
+            )))
+        }
+        None => return Ok(()),
+    };
+    match key {
+        "ip" => check_length(key, value, 4),
+        "ip6" => check_length(key, value, 16),
+        eth2::ETH2_ENR_KEY => check_length(key, value, eth2::ENR_FORK_ID_LEN),
+        "tcp" | "tcp6" | "udp" | "udp6" => check_port(key, value),
+        _ => Ok(()),
+    }
+}
+
+fn check_port(key: &str, value: &[u8]) -> PyResult<()> {
+    if value.len() > 2 {
+        return Err(FieldError::new_err(format!(
+            "key {key:?} must be a port of at most 2 bytes, got {}",
+            value.len()
+        )));
+    }
+    if value.first() == Some(&0) {
+        return Err(FieldError::new_err(format!(
+            "key {key:?} must be a minimally encoded port, without leading zero bytes"
+        )));
+    }
+    Ok(())
+}
+
+fn check_length(key: &str, value: &[u8], expected: usize) -> PyResult<()> {
+    if value.len() != expected {
+        return Err(FieldError::new_err(format!(
+            "key {key:?} must be {expected} bytes, got {}",
+            value.len()
+        )));
+    }
+    Ok(())
+}
+
+/// A value interpreted according to its key's registered type.
+pub(crate) enum Decoded<'a> {
+    Text(&'a str),
+    Address(IpAddr),
+    Int(u64),
+    /// A byte string of unknown or opaque meaning (payload only).
+    Bytes(&'a [u8]),
+    /// A list, or a value that is not valid RLP, as raw RLP.
+    Raw(&'a [u8]),
+}
+
+/// Interprets the raw RLP value stored under `key`: addresses, ports and
+/// integers by value, `id` as text and other byte strings as bytes. Values
+/// that do not have their key's registered shape are treated as bytes.
+pub(crate) fn decode<'a>(key: &str, raw: &'a [u8]) -> Decoded<'a> {
+    let Some(payload) = rlp::string_payload(raw) else {
+        return Decoded::Raw(raw);
+    };
+    let int = |max_len: usize| {
+        (payload.len() <= max_len)
+            .then(|| payload.iter().fold(0u64, |n, b| (n << 8) | u64::from(*b)))
+    };
+    let decoded = match lookup(key) {
+        Some(ValueType::Text) => std::str::from_utf8(payload).ok().map(Decoded::Text),
+        Some(ValueType::Ip4) => <[u8; 4]>::try_from(payload)
+            .ok()
+            .map(|octets| Decoded::Address(Ipv4Addr::from(octets).into())),
+        Some(ValueType::Ip6) => <[u8; 16]>::try_from(payload)
+            .ok()
+            .map(|octets| Decoded::Address(Ipv6Addr::from(octets).into())),
+        Some(ValueType::Port) => int(2).map(Decoded::Int),
+        Some(ValueType::Uint) => int(8).map(Decoded::Int),
+        _ => None,
+    };
+    decoded.unwrap_or(Decoded::Bytes(payload))
+}
+
+/// Converts the raw RLP value stored under `key` to the Python value it
+/// stands for (see [`decode`]): a `str`, an `int` or `bytes`.
+pub(crate) fn decode_value<'py>(
+    py: Python<'py>,
+    key: &str,
+    raw: &[u8],
+) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match decode(key, raw) {
+        Decoded::Text(text) => PyString::new(py, text).into_any(),
+        Decoded::Address(ip) => PyString::new(py, &ip.to_string()).into_any(),
+        Decoded::Int(n) => n.into_pyobject(py)?.into_any(),
+        Decoded::Bytes(bytes) | Decoded::Raw(bytes) => PyBytes::new(py, bytes).into_any(),
+    })
+}
+
+/// Formats the raw RLP value stored under `key` for people: decoded where
+/// the key is known, `0x` hex otherwise.
+pub(crate) fn display_value(key: &str, raw: &[u8]) -> String {
+    match decode(key, raw) {
+        Decoded::Text(text) => text.to_string(),
+        Decoded::Address(ip) => ip.to_string(),
+        Decoded::Int(n) => n.to_string(),
+        Decoded::Bytes(bytes) => format!("0x{}", convert::to_hex(bytes)),
+        Decoded::Raw(raw) => format!("rlp 0x{}", convert::to_hex(raw)),
+    }
+}
+
+/// A key name as given from Python: `str`, or `bytes` for the keys some
+/// records carry that are not valid UTF-8.
+#[derive(FromPyObject)]
+pub(crate) enum KeyArg {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl KeyArg {
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        match self {
+            KeyArg::Text(text) => text.as_bytes(),
+            KeyArg::Bytes(bytes) => bytes,
+        }
+    }
+
+    /// The name as text if it is valid UTF-8, otherwise its bytes.
+    pub(crate) fn into_text(self) -> Result<String, Vec<u8>> {
+        match self {
+            KeyArg::Text(text) => Ok(text),
+            KeyArg::Bytes(bytes) => String::from_utf8(bytes).map_err(|e| e.into_bytes()),
+        }
+    }
+}
+
+/// Maps the property-style names `ip4`, `tcp4` and `udp4` to the keys
+/// EIP-778 stores them under; other names are returned unchanged.
+pub(crate) fn normalize(key: &str) -> &str {
+    match key {
+        "ip4" => "ip",
+        "tcp4" => "tcp",
+        "udp4" => "udp",
+        _ => key,
+    }
+}
+
+/// Converts the raw RLP value stored under `key` to an instance of `ty`,
+/// which must be `bytes`, `int` (big-endian unsigned) or `str` (the
+/// address text for `ip`/`ip6`, UTF-8 otherwise).
+pub(crate) fn decode_as<'py>(
+    py: Python<'py>,
+    key: &str,
+    raw: &[u8],
+    ty: &Bound<'py, PyType>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let payload = rlp::string_payload(raw).ok_or_else(|| {
+        FieldError::new_err(format!("key {key:?} holds a list, not a byte string"))
+    })?;
+    if ty.is(py.get_type::<PyBytes>()) {
+        Ok(PyBytes::new(py, payload).into_any())
+    } else if ty.is(py.get_type::<PyInt>()) {
+        py.get_type::<PyInt>()
+            .call_method1("from_bytes", (PyBytes::new(py, payload), "big"))
+    } else if ty.is(py.get_type::<PyString>()) {
+        if let Decoded::Address(ip) = decode(key, raw) {
+            return Ok(PyString::new(py, &ip.to_string()).into_any());
+        }
+        let text = std::str::from_utf8(payload)
+            .map_err(|_| FieldError::new_err(format!("key {key:?} does not hold UTF-8 text")))?;
+        Ok(PyString::new(py, text).into_any())
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "cannot decode a value as {}, expected bytes, int or str",
+            ty.name()?
+        )))
+    }
+}
+
+/// The known-key registry, as a dict of key to expected value type.
+#[pyfunction]
+pub(crate) fn known_keys(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let out = PyDict::new(py);
+    for (name, value_type) in KNOWN_KEYS {
+        out.set_item(name, value_type.name())?;
+    }
+    Ok(out)
+}
+
+/// Adds a `KEY_<NAME>` constant for every registered key, e.g.
+/// `KEY_SECP256K1 = "secp256k1"`.
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    for (name, _) in KNOWN_KEYS {
+        m.add(format!("KEY_{}", name.to_uppercase()), *name)?;
+    }
+    Ok(())
+}
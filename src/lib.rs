@@ -1,86 +1,472 @@
 use std::net::{Ipv4Addr, Ipv6Addr};
-use std::str::FromStr;
 
 use alloy_rlp::{Decodable, Encodable};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use enr::{CombinedKey, EnrPublicKey};
-use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::{PyDeprecationWarning, PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
+use pyo3::types::{PyBytes, PyDict, PyString, PyType};
 
-fn to_enr_error<E: std::fmt::Display>(err: E) -> PyErr {
-    PyValueError::new_err(err.to_string())
+mod aio;
+mod batch;
+mod bitvector;
+mod bloom;
+mod bootnodes;
+mod buffer;
+mod cache;
+mod churn;
+mod codecs;
+mod conformance;
+mod convert;
+mod crypto;
+mod dedupe;
+mod diff;
+#[cfg(feature = "discv5")]
+mod discv5;
+mod display;
+#[cfg(feature = "dns")]
+mod dnsdisc;
+mod enode;
+mod errors;
+mod eth;
+mod eth2;
+mod features;
+mod fields;
+mod files;
+mod fuzz;
+#[cfg(feature = "geo")]
+mod geo;
+mod hashing;
+mod history;
+mod identity;
+mod interchange;
+mod keys;
+mod libp2p;
+mod liveness;
+mod logging;
+mod multiaddr;
+mod net;
+mod opstack;
+mod parse;
+mod persist;
+mod placeholder;
+mod portal;
+mod profiles;
+mod progress;
+mod random;
+#[cfg(feature = "dns")]
+mod rdns;
+mod redact;
+mod rlp;
+mod salvage;
+mod size;
+mod ssv;
+#[cfg(feature = "sqlite")]
+mod store;
+mod tree;
+mod vectors;
+mod waku;
+mod warn;
+mod watch;
+
+/// A key name for Python.
+///
+/// Names in the known-key registry are interned, so the records of a crawl
+/// share one string per name. Any other name, which whoever signs a record
+/// can make up, gets a string of its own that is freed with its last
+/// reference, so hostile records cannot grow the interned table.
+fn key_name<'py>(py: Python<'py>, key: &[u8]) -> Bound<'py, PyString> {
+    let name = String::from_utf8_lossy(key);
+    if keys::lookup(&name).is_some() {
+        PyString::intern(py, &name)
+    } else {
+        PyString::new(py, &name)
+    }
+}
+
+/// A key name for Python: its bytes if `raw`, otherwise as `key_name` gives it.
+fn key_object<'py>(py: Python<'py>, key: &[u8], raw: bool) -> Bound<'py, PyAny> {
+    if raw {
+        PyBytes::new(py, key).into_any()
+    } else {
+        key_name(py, key).into_any()
+    }
+}
+
+/// Decodes the text of a record in any of the shapes it is copied around
+/// in: base64url with or without the `enr:` prefix, padded or in the
+/// standard alphabet, or `0x`-prefixed hex; surrounding whitespace is
+/// ignored.
+fn decode_text(py: Python<'_>, text: &str) -> PyResult<Vec<u8>> {
+    decode_text_detached(text).map_err(|failure| match failure {
+        TextFailure::Hex => errors::hex_error(py),
+        TextFailure::Base64(e) => errors::base64_error(py, e),
+    })
+}
+
+/// Why `decode_text_detached` refused a piece of text.
+enum TextFailure {
+    Hex,
+    Base64(base64::DecodeError),
+}
+
+/// `decode_text` without the GIL, for decoding on worker threads.
+fn decode_text_detached(text: &str) -> Result<Vec<u8>, TextFailure> {
+    let text = text.trim();
+    if let Some(digits) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        return convert::from_hex(digits).ok_or(TextFailure::Hex);
+    }
+    let encoded = text
+        .strip_prefix("enr:")
+        .unwrap_or(text)
+        .trim_end_matches('=');
+    // Standard base64 differs from base64url only in these two characters.
+    let encoded = encoded.replace('+', "-").replace('/', "_");
+    URL_SAFE_NO_PAD.decode(encoded).map_err(TextFailure::Base64)
+}
+
+/// Decodes and verifies a record, checking EIP-778 conformance first if
+/// `strict`, and refusing placeholder values under the `"reject"` policy.
+///
+/// Panics are not caught here; the Python entry points wrap this in
+/// `errors::guard_decode`, while `fuzz_decode` lets them through.
+fn decode_record(py: Python<'_>, data: &[u8], strict: bool) -> PyResult<enr::Enr<CombinedKey>> {
+    decode_record_detached(data, strict).map_err(|failure| match failure {
+        RecordFailure::Violation(violation) => errors::violation_error(py, &violation),
+        RecordFailure::Decode(e) => errors::decode_error_in(py, e, data),
+        RecordFailure::Placeholder(violation) => errors::placeholder_error(py, &violation),
+    })
+}
+
+/// Why `decode_record_detached` refused a record.
+enum RecordFailure {
+    Violation(conformance::Violation),
+    Decode(alloy_rlp::Error),
+    Placeholder(conformance::Violation),
+}
+
+/// `decode_record` without the GIL, for decoding on worker threads.
+fn decode_record_detached(
+    data: &[u8],
+    strict: bool,
+) -> Result<enr::Enr<CombinedKey>, RecordFailure> {
+    let enr = decode_without_policy(data, strict)?;
+    if placeholder::policy() == placeholder::Policy::Reject {
+        if let Some(violation) = placeholder::check(data).into_iter().next() {
+            return Err(RecordFailure::Placeholder(violation));
+        }
+    }
+    Ok(enr)
+}
+
+/// `decode_record_detached` ignoring the placeholder policy, so the result
+/// depends on `data` alone.
+fn decode_without_policy(
+    data: &[u8],
+    strict: bool,
+) -> Result<enr::Enr<CombinedKey>, RecordFailure> {
+    if strict {
+        if let Some(violation) = conformance::check(data).into_iter().next() {
+            return Err(RecordFailure::Violation(violation));
+        }
+    }
+    enr::Enr::<CombinedKey>::decode(&mut &data[..]).map_err(RecordFailure::Decode)
+}
+
+/// Whether validation reports placeholder values under `policy`, or under
+/// the global policy if `None`.
+fn placeholder_flags(policy: Option<&str>) -> PyResult<bool> {
+    let policy = match policy {
+        Some(name) => placeholder::Policy::parse(name)?,
+        None => placeholder::policy(),
+    };
+    Ok(policy.flags())
+}
+
+fn multiaddr_error(message: String) -> PyErr {
+    errors::FieldError::new_err(format!(
+        "key {:?} is invalid: {message}",
+        multiaddr::MULTIADDRS_KEY
+    ))
+}
+
+/// Encodes text multiaddrs as a `multiaddrs` value in the layout `encoding`
+/// names.
+fn encode_multiaddrs(addrs: &[String], encoding: &str) -> PyResult<rlp::RlpValue> {
+    let encoding = multiaddr::ListEncoding::from_name(encoding).map_err(PyValueError::new_err)?;
+    multiaddr::encode_list(addrs, encoding).map_err(errors::FieldError::new_err)
 }
 
 /// Python wrapper around `enr::Enr<CombinedKey>`.
-#[pyclass(name = "Enr", skip_from_py_object)]
+#[pyclass(name = "Enr", module = "pyenr", skip_from_py_object)]
 #[derive(Clone)]
 struct Enr {
     inner: enr::Enr<CombinedKey>,
 }
 
+impl Enr {
+    /// Fails before any change is made if setting `key` to a value of
+    /// `value_len` encoded bytes cannot succeed: with `SequenceOverflowError`
+    /// if the seq cannot be incremented, or `SizeError` if the record
+    /// would grow past the size limit.
+    fn check_insert(&self, py: Python<'_>, key: &[u8], value_len: usize) -> PyResult<()> {
+        // The `enr` crate only checks the seq after changing the content,
+        // leaving the record modified but unsigned.
+        if self.inner.seq() == u64::MAX {
+            return Err(errors::seq_overflow_error());
+        }
+        let size = rlp::encoded_len_after_insert(&self.inner, key, value_len);
+        if size > rlp::MAX_ENR_SIZE {
+            return Err(errors::size_error(py, &String::from_utf8_lossy(key), size));
+        }
+        Ok(())
+    }
+
+    /// Stores `value` as a byte string under `key` and re-signs.
+    fn insert_bytes(
+        &mut self,
+        py: Python<'_>,
+        key: &str,
+        value: &[u8],
+        signing_key: &SigningKey,
+    ) -> PyResult<()> {
+        self.check_insert(py, key.as_bytes(), value.length())?;
+        self.inner
+            .insert(key, &value, &signing_key.inner)
+            .map_err(errors::record_error)?;
+        Ok(())
+    }
+}
+
 #[pymethods]
 impl Enr {
-    /// Decode an ENR from a base64url string (with or without `enr:` prefix).
+    /// Decode an ENR from its text form.
+    ///
+    /// Takes base64url with or without the `enr:` prefix, and also padded or
+    /// standard-alphabet base64, `0x`-prefixed hex of the RLP encoding, and
+    /// text with surrounding whitespace, as records copied from logs and
+    /// explorers come.
     #[staticmethod]
-    fn from_base64(text: &str) -> PyResult<Self> {
-        let inner = enr::Enr::<CombinedKey>::from_str(text).map_err(to_enr_error)?;
-        Ok(Enr { inner })
+    #[pyo3(signature = (text, *, warnings=false))]
+    fn from_base64(py: Python<'_>, text: &str, warnings: bool) -> PyResult<Self> {
+        let data = decode_text(py, text)?;
+        Self::from_bytes(py, &data, false, warnings)
+    }
+
+    /// Decode a record from text of any shape, working out which it is.
+    ///
+    /// Takes everything `from_base64` does, plus bare hex of the RLP
+    /// encoding. Text that is recognisably some other node reference (an
+    /// `enode://` URL, an EIP-1459 tree entry, a multiaddr, a libp2p peer id,
+    /// a node id or public key, JSON) raises `DecodeError` saying so, with
+    /// the name of the format in its `format` attribute.
+    #[staticmethod]
+    #[pyo3(signature = (text, *, strict=false))]
+    fn parse(py: Python<'_>, text: &str, strict: bool) -> PyResult<Self> {
+        parse::parse(py, text, strict)
     }
 
     /// Decode an ENR from raw RLP bytes.
+    ///
+    /// With `strict=True` the bytes must also follow EIP-778 exactly: no
+    /// trailing data, minimally encoded integers, correctly sized addresses
+    /// and public keys, and an `id` of `"v4"`.
+    ///
+    /// Decode errors carry `code`, `offset`, `key`, `expected` and `actual`
+    /// attributes locating the problem, where it can be pinned down.
+    ///
+    /// With `warnings=True`, oddities that do not prevent decoding (tolerated
+    /// violations, a non-canonical encoding, port 0 or an unspecified
+    /// address) are each reported as an `EnrWarning`.
     #[staticmethod]
-    fn from_bytes(data: &[u8]) -> PyResult<Self> {
-        let inner =
-            enr::Enr::<CombinedKey>::decode(&mut &data[..]).map_err(to_enr_error)?;
+    #[pyo3(signature = (data, *, strict=false, warnings=false))]
+    fn from_bytes(py: Python<'_>, data: &[u8], strict: bool, warnings: bool) -> PyResult<Self> {
+        let inner = errors::guard_decode(py, || decode_record(py, data, strict))?;
+        if warnings {
+            warn::emit(py, data, &inner)?;
+        }
         Ok(Enr { inner })
     }
 
+    /// Returns `True` if `data` is exactly the canonical RLP encoding of the
+    /// record it frames.
+    ///
+    /// Non-canonical encodings (long-form length headers for short items,
+    /// leading zeros in the sequence number or ports, trailing bytes) that
+    /// some implementations accept silently return `False`, as does input
+    /// that cannot be framed as a record. Records decoded by this library are
+    /// always canonical once re-encoded.
+    #[staticmethod]
+    fn is_canonical(py: Python<'_>, data: &[u8]) -> PyResult<bool> {
+        errors::guard_decode(py, || Ok(conformance::is_canonical(data)))
+    }
+
+    /// Recover as much as possible from a malformed record.
+    ///
+    /// Never raises: returns a `SalvagedEnr` with whatever fields could be
+    /// read, whether the signature verifies, and every problem found. Its
+    /// `record` holds the decoded `Enr` when the input was valid after all.
+    #[staticmethod]
+    fn from_bytes_lenient(py: Python<'_>, data: &[u8]) -> PyResult<salvage::SalvagedEnr> {
+        errors::guard_decode(py, || salvage::SalvagedEnr::from_bytes(py, data))
+    }
+
+    /// Like `from_bytes_lenient`, for text in any form `from_base64` accepts.
+    ///
+    /// Raises `DecodeError` only if the text is not valid base64 or hex.
+    #[staticmethod]
+    fn from_base64_lenient(py: Python<'_>, text: &str) -> PyResult<salvage::SalvagedEnr> {
+        let data = decode_text(py, text)?;
+        Self::from_bytes_lenient(py, &data)
+    }
+
     // -- Read accessors --
 
+    /// Always `True`: an `Enr` only exists once its signature has been
+    /// verified. See `SalvagedEnr.is_verified` for records that were not.
+    #[getter]
+    fn is_verified(&self) -> bool {
+        true
+    }
+
     #[getter]
     fn seq(&self) -> u64 {
         self.inner.seq()
     }
 
+    /// How many more updates the record can take before its seq overflows.
+    fn seq_remaining(&self) -> u64 {
+        u64::MAX - self.inner.seq()
+    }
+
     #[getter]
     fn node_id<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
         PyBytes::new(py, &self.inner.node_id().raw())
     }
 
+    /// The libp2p peer id of the record's public key, as base58 text
+    /// (`16Uiu2HA...` for secp256k1, `12D3KooW...` for ed25519).
+    #[getter]
+    fn peer_id(&self) -> String {
+        libp2p::peer_id(&self.inner.public_key())
+    }
+
     #[getter]
     fn ip4(&self) -> Option<String> {
-        self.inner.ip4().map(|ip| ip.to_string())
+        placeholder::ip4(self.inner.ip4()).map(|ip| ip.to_string())
     }
 
     #[getter]
     fn ip6(&self) -> Option<String> {
-        self.inner.ip6().map(|ip| ip.to_string())
+        placeholder::ip6(self.inner.ip6()).map(|ip| ip.to_string())
     }
 
     #[getter]
     fn tcp4(&self) -> Option<u16> {
-        self.inner.tcp4()
+        placeholder::port(self.inner.tcp4())
     }
 
     #[getter]
     fn tcp6(&self) -> Option<u16> {
-        self.inner.tcp6()
+        placeholder::port(self.inner.tcp6())
     }
 
     #[getter]
     fn udp4(&self) -> Option<u16> {
-        self.inner.udp4()
+        placeholder::port(self.inner.udp4())
     }
 
     #[getter]
     fn udp6(&self) -> Option<u16> {
-        self.inner.udp6()
+        placeholder::port(self.inner.udp6())
     }
 
+    /// Compressed public key, copied straight out of the record's stored entry.
     #[getter]
     fn public_key<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
-        let pk = self.inner.public_key();
-        PyBytes::new(py, &pk.encode())
+        let stored = ["secp256k1", "ed25519"]
+            .iter()
+            .find_map(|k| self.inner.get_raw_rlp(k))
+            .and_then(rlp::string_payload);
+        match stored {
+            Some(pk) => PyBytes::new(py, pk),
+            None => PyBytes::new(py, &self.inner.public_key().encode()),
+        }
+    }
+
+    /// The bytes the record's signature covers: the RLP list of its seq and
+    /// key/value pairs, for checking with `verify_content()` or another
+    /// implementation.
+    fn signable_content<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &rlp::signed_content(&self.inner))
+    }
+
+    /// Check the signature against the public key the record carries.
+    ///
+    /// Decoding already rejects records whose signature does not verify, so
+    /// this is `True` for every `Enr`; it re-checks from the encoded content,
+    /// for pipelines that want the check explicit.
+    fn verify(&self, py: Python<'_>) -> bool {
+        py.detach(|| self.inner.verify())
+    }
+
+    /// Check the signature against `public_key` instead of the key the
+    /// record carries, for when the signer's key is known out of band.
+    ///
+    /// Accepts a secp256k1 key (33-byte compressed or 65-byte uncompressed)
+    /// or a 32-byte ed25519 key. Returns `False` if the record was not signed
+    /// by that key, including when it carries a different one.
+    fn verify_against(&self, py: Python<'_>, public_key: &[u8]) -> PyResult<bool> {
+        let key = crypto::public_key_arg(public_key)?;
+        let content = rlp::signed_content(&self.inner);
+        let signature = self.inner.signature();
+        Ok(py.detach(|| key.verify_v4(&content, signature)))
+    }
+
+    /// Same as `verify_against`.
+    fn verify_with(&self, py: Python<'_>, public_key: &[u8]) -> PyResult<bool> {
+        self.verify_against(py, public_key)
+    }
+
+    /// Returns `True` if the record advertises a publicly routable IPv4 or
+    /// IPv6 address, rather than only private, loopback, link-local, CGNAT or
+    /// reserved ones.
+    fn has_routable_address(&self) -> bool {
+        net::has_routable_address(&self.inner)
+    }
+
+    /// The advertised addresses and ports, as an `Endpoints`.
+    #[getter]
+    fn endpoints(&self) -> fields::Endpoints {
+        fields::Endpoints::new(&self.inner)
+    }
+
+    /// The endpoints a node can be dialed on, as `(protocol, ip, port)`
+    /// tuples: UDP then TCP on the IPv4 address, then on the IPv6 address.
+    ///
+    /// With `fallback` (the default), an IPv6 address without `udp6` or
+    /// `tcp6` uses the `udp` or `tcp` port, as EIP-778 specifies. Port 0 and
+    /// the unspecified addresses are never dialable and are left out.
+    #[pyo3(signature = (*, fallback=true))]
+    fn effective_endpoints(&self, fallback: bool) -> Vec<(&'static str, String, u16)> {
+        net::endpoints(&self.inner, fallback)
+            .into_iter()
+            .filter(|(_, addr)| net::is_dialable(addr))
+            .map(|(protocol, addr)| (protocol, addr.ip().to_string(), addr.port()))
+            .collect()
+    }
+
+    /// The libp2p multiaddrs of the record's dialable endpoints, such as
+    /// `/ip4/1.2.3.4/tcp/9000/p2p/16Uiu2...`: TCP, UDP and QUIC (`quic`,
+    /// `quic6`) on the IPv4 address, then on the IPv6 address.
+    ///
+    /// `fallback` applies the IPv4 ports to an IPv6 address without its own,
+    /// as in `effective_endpoints`. Each ends in `/p2p/<peer id>` unless
+    /// `p2p` is false.
+    #[pyo3(signature = (*, fallback=true, p2p=true))]
+    fn to_multiaddrs(&self, fallback: bool, p2p: bool) -> Vec<String> {
+        libp2p::multiaddrs(&self.inner, fallback, p2p)
     }
 
     #[getter]
@@ -88,85 +474,470 @@ impl Enr {
         self.inner.id()
     }
 
+    /// The type of the record's public key.
+    #[getter]
+    fn key_type(&self) -> crypto::KeyType {
+        crypto::KeyType::of(&self.inner.public_key())
+    }
+
+    /// Execution-layer fork id from the `eth` key, as published by Geth and
+    /// the BSC and Polygon clients derived from it.
+    #[getter]
+    fn eth_fork_id(&self) -> PyResult<Option<eth::EthForkId>> {
+        eth::EthForkId::from_enr(&self.inner)
+    }
+
+    /// Consensus-layer fork id from the `eth2` key.
+    #[getter]
+    fn eth2(&self) -> PyResult<Option<eth2::Eth2ForkId>> {
+        eth2::Eth2ForkId::from_enr_checked(&self.inner)
+    }
+
+    /// Indices of the attestation subnets in the `attnets` bitvector (0 to 63).
+    #[getter]
+    fn attnets(&self) -> PyResult<Option<Vec<usize>>> {
+        eth2::subnets(
+            &self.inner,
+            eth2::ATTNETS_KEY,
+            eth2::ATTESTATION_SUBNET_COUNT,
+        )
+    }
+
+    /// The `attnets` bitvector as stored.
+    #[getter]
+    fn attnets_raw<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyBytes>>> {
+        let bits = rlp::string_value(&self.inner, eth2::ATTNETS_KEY)?;
+        Ok(bits.map(|bits| PyBytes::new(py, bits)))
+    }
+
+    /// Indices of the sync committee subnets in the `syncnets` bitvector
+    /// (0 to 3).
+    #[getter]
+    fn syncnets(&self) -> PyResult<Option<Vec<usize>>> {
+        eth2::subnets(
+            &self.inner,
+            eth2::SYNCNETS_KEY,
+            eth2::SYNC_COMMITTEE_SUBNET_COUNT,
+        )
+    }
+
+    /// The `syncnets` bitvector as stored.
+    #[getter]
+    fn syncnets_raw<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyBytes>>> {
+        let bits = rlp::string_value(&self.inner, eth2::SYNCNETS_KEY)?;
+        Ok(bits.map(|bits| PyBytes::new(py, bits)))
+    }
+
+    /// OP Stack chain id and version from the `opstack` key.
+    #[getter]
+    fn opstack(&self) -> PyResult<Option<opstack::OpStackInfo>> {
+        opstack::OpStackInfo::from_enr(&self.inner)
+    }
+
+    /// Portal network client string (the `c` key), such as `"t 0.1.1"`.
+    #[getter]
+    fn portal_client(&self) -> Option<&str> {
+        portal::client(&self.inner)
+    }
+
+    /// Indices of the SSV subnets in the `subnets` bitvector (0 to 127).
+    #[getter]
+    fn ssv_subnets(&self) -> PyResult<Option<Vec<usize>>> {
+        ssv::subnets(&self.inner)
+    }
+
+    /// SSV network domain type from the `domaintype` key, as 4 bytes.
+    #[getter]
+    fn ssv_domain_type<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyBytes>>> {
+        let domain = ssv::domain_type(&self.inner, ssv::DOMAIN_TYPE_KEY)?;
+        Ok(domain.map(|d| PyBytes::new(py, &d)))
+    }
+
+    /// SSV domain type after the next network fork (`next_domaintype`).
+    #[getter]
+    fn ssv_next_domain_type<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyBytes>>> {
+        let domain = ssv::domain_type(&self.inner, ssv::NEXT_DOMAIN_TYPE_KEY)?;
+        Ok(domain.map(|d| PyBytes::new(py, &d)))
+    }
+
+    /// Waku v2 capabilities from the `waku2` flags, such as `["relay", "store"]`.
+    #[getter]
+    fn waku_capabilities(&self) -> PyResult<Option<Vec<&'static str>>> {
+        rlp::string_value(&self.inner, waku::WAKU2_KEY)?
+            .map(waku::capabilities)
+            .transpose()
+    }
+
+    /// Deprecated alias of `multiaddrs`.
+    #[getter]
+    #[pyo3(warn(
+        message = "Enr.waku_multiaddrs is deprecated, use Enr.multiaddrs",
+        category = PyDeprecationWarning
+    ))]
+    fn waku_multiaddrs(&self) -> PyResult<Option<Vec<String>>> {
+        self.multiaddrs()
+    }
+
+    /// The `multiaddrs` list as text, in either the length-prefixed layout
+    /// or as an RLP list. Each must start with a host (`/ip4`, `/ip6` or a
+    /// `/dns` protocol).
+    #[getter]
+    fn multiaddrs(&self) -> PyResult<Option<Vec<String>>> {
+        self.inner
+            .get_raw_rlp(multiaddr::MULTIADDRS_KEY)
+            .map(|raw| multiaddr::decode_list(raw).map_err(multiaddr_error))
+            .transpose()
+    }
+
+    /// All well-known fields decoded into a typed, read-only snapshot.
+    fn fields(&self, py: Python<'_>) -> PyResult<fields::EnrFields> {
+        fields::EnrFields::new(py, &self.inner, self.public_key(py))
+    }
+
+    /// The keys of a network profile present in the record, decoded by
+    /// their registered type. `profile` is a `Profile` or its name, and
+    /// defaults to the one set with `set_default_profile`.
+    #[pyo3(signature = (profile=None))]
+    fn profile_fields<'py>(
+        &self,
+        py: Python<'py>,
+        profile: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let spec = profiles::resolve(profile)?
+            .ok_or_else(|| PyValueError::new_err("no profile given and no default profile set"))?;
+        profiles::fields(py, spec, &self.inner)
+    }
+
     // -- Mutation methods --
 
-    fn set_ip4(&mut self, addr: &str, key: &SigningKey) -> PyResult<()> {
-        let ip: Ipv4Addr = addr
-            .parse()
-            .map_err(|e: std::net::AddrParseError| PyValueError::new_err(e.to_string()))?;
+    fn set_ip4(&mut self, py: Python<'_>, addr: &str, key: &SigningKey) -> PyResult<()> {
+        let ip: Ipv4Addr = addr.parse().map_err(errors::field_error)?;
+        self.check_insert(py, b"ip", ip.length())?;
         self.inner
             .set_ip(ip.into(), &key.inner)
-            .map_err(to_enr_error)?;
+            .map_err(errors::record_error)?;
         Ok(())
     }
 
-    fn set_ip6(&mut self, addr: &str, key: &SigningKey) -> PyResult<()> {
-        let ip: Ipv6Addr = addr
-            .parse()
-            .map_err(|e: std::net::AddrParseError| PyValueError::new_err(e.to_string()))?;
+    fn set_ip6(&mut self, py: Python<'_>, addr: &str, key: &SigningKey) -> PyResult<()> {
+        let ip = net::parse_ip6(addr).map_err(errors::field_error)?;
+        self.check_insert(py, b"ip6", ip.length())?;
         self.inner
             .set_ip(ip.into(), &key.inner)
-            .map_err(to_enr_error)?;
+            .map_err(errors::record_error)?;
         Ok(())
     }
 
-    fn set_tcp4(&mut self, port: u16, key: &SigningKey) -> PyResult<()> {
+    fn set_tcp4(&mut self, py: Python<'_>, port: u16, key: &SigningKey) -> PyResult<()> {
+        self.check_insert(py, b"tcp", port.length())?;
         self.inner
             .set_tcp4(port, &key.inner)
-            .map_err(to_enr_error)?;
+            .map_err(errors::record_error)?;
         Ok(())
     }
 
-    fn set_tcp6(&mut self, port: u16, key: &SigningKey) -> PyResult<()> {
+    fn set_tcp6(&mut self, py: Python<'_>, port: u16, key: &SigningKey) -> PyResult<()> {
+        self.check_insert(py, b"tcp6", port.length())?;
         self.inner
             .insert("tcp6", &port, &key.inner)
-            .map_err(to_enr_error)?;
+            .map_err(errors::record_error)?;
         Ok(())
     }
 
-    fn set_udp4(&mut self, port: u16, key: &SigningKey) -> PyResult<()> {
+    fn set_udp4(&mut self, py: Python<'_>, port: u16, key: &SigningKey) -> PyResult<()> {
+        self.check_insert(py, b"udp", port.length())?;
         self.inner
             .set_udp4(port, &key.inner)
-            .map_err(to_enr_error)?;
+            .map_err(errors::record_error)?;
         Ok(())
     }
 
-    fn set_udp6(&mut self, port: u16, key: &SigningKey) -> PyResult<()> {
+    fn set_udp6(&mut self, py: Python<'_>, port: u16, key: &SigningKey) -> PyResult<()> {
+        self.check_insert(py, b"udp6", port.length())?;
         self.inner
             .insert("udp6", &port, &key.inner)
-            .map_err(to_enr_error)?;
+            .map_err(errors::record_error)?;
+        Ok(())
+    }
+
+    /// Set the `eth2` key to the SSZ encoding of an `ENRForkID`.
+    fn set_eth2(
+        &mut self,
+        py: Python<'_>,
+        fork_digest: &[u8],
+        next_fork_version: &[u8],
+        next_fork_epoch: u64,
+        key: &SigningKey,
+    ) -> PyResult<()> {
+        let fork_id =
+            eth2::Eth2ForkId::from_fields(fork_digest, next_fork_version, next_fork_epoch)?;
+        self.insert_bytes(py, eth2::ETH2_ENR_KEY, &fork_id.ssz(), key)
+    }
+
+    /// Set `attnets` to the bitvector of the attestation subnets `subnets`.
+    fn set_attnets(
+        &mut self,
+        py: Python<'_>,
+        subnets: Vec<usize>,
+        key: &SigningKey,
+    ) -> PyResult<()> {
+        let bits = eth2::subnet_bits(eth2::ATTNETS_KEY, &subnets, eth2::ATTESTATION_SUBNET_COUNT)?;
+        self.insert_bytes(py, eth2::ATTNETS_KEY, &bits, key)
+    }
+
+    /// Set `syncnets` to the bitvector of the sync committee subnets
+    /// `subnets`.
+    fn set_syncnets(
+        &mut self,
+        py: Python<'_>,
+        subnets: Vec<usize>,
+        key: &SigningKey,
+    ) -> PyResult<()> {
+        let bits = eth2::subnet_bits(
+            eth2::SYNCNETS_KEY,
+            &subnets,
+            eth2::SYNC_COMMITTEE_SUBNET_COUNT,
+        )?;
+        self.insert_bytes(py, eth2::SYNCNETS_KEY, &bits, key)
+    }
+
+    fn set_portal_client(
+        &mut self,
+        py: Python<'_>,
+        client: &str,
+        key: &SigningKey,
+    ) -> PyResult<()> {
+        self.insert_bytes(py, portal::PORTAL_CLIENT_KEY, client.as_bytes(), key)
+    }
+
+    /// Set the Waku v2 capabilities, e.g. `["relay", "store"]`.
+    fn set_waku_capabilities(
+        &mut self,
+        py: Python<'_>,
+        capabilities: Vec<String>,
+        key: &SigningKey,
+    ) -> PyResult<()> {
+        let flags = waku::flags(capabilities)?;
+        self.insert_bytes(py, waku::WAKU2_KEY, &[flags], key)
+    }
+
+    /// Deprecated alias of `set_multiaddrs`.
+    #[pyo3(warn(
+        message = "Enr.set_waku_multiaddrs() is deprecated, use Enr.set_multiaddrs()",
+        category = PyDeprecationWarning
+    ))]
+    fn set_waku_multiaddrs(
+        &mut self,
+        py: Python<'_>,
+        multiaddrs: Vec<String>,
+        key: &SigningKey,
+    ) -> PyResult<()> {
+        self.set_multiaddrs(py, multiaddrs, key, "length-prefixed")
+    }
+
+    /// Set the `multiaddrs` list from text multiaddrs, length-prefixed in
+    /// one byte string or, with `encoding="rlp"`, as an RLP list.
+    #[pyo3(signature = (multiaddrs, key, *, encoding="length-prefixed"))]
+    fn set_multiaddrs(
+        &mut self,
+        py: Python<'_>,
+        multiaddrs: Vec<String>,
+        key: &SigningKey,
+        encoding: &str,
+    ) -> PyResult<()> {
+        let value = encode_multiaddrs(&multiaddrs, encoding)?;
+        self.check_insert(py, multiaddr::MULTIADDRS_KEY.as_bytes(), value.length())?;
+        self.inner
+            .insert(multiaddr::MULTIADDRS_KEY, &value, &key.inner)
+            .map_err(errors::record_error)?;
         Ok(())
     }
 
     fn set_seq(&mut self, seq: u64, key: &SigningKey) -> PyResult<()> {
         self.inner
             .set_seq(seq, &key.inner)
-            .map_err(to_enr_error)?;
+            .map_err(errors::record_error)?;
         Ok(())
     }
 
+    /// Increment the seq by one and re-sign, leaving the content unchanged,
+    /// to republish the record as fresher. Returns the new seq.
+    ///
+    /// Raises `SequenceOverflowError` if the seq is already `2**64 - 1`.
+    fn bump_seq(&mut self, key: &SigningKey) -> PyResult<u64> {
+        let seq = self
+            .inner
+            .seq()
+            .checked_add(1)
+            .ok_or_else(errors::seq_overflow_error)?;
+        self.set_seq(seq, key)?;
+        Ok(seq)
+    }
+
     /// Set an arbitrary key-value pair.
+    ///
+    /// `value` is `bytes`, a non-negative `int` (encoded minimally, big
+    /// endian) or a nested `list` of these, which is encoded as an RLP list.
+    ///
+    /// The reserved keys `id`, `secp256k1` and `ed25519` cannot be set, and
+    /// values for other well-known keys must have the shape EIP-778 (or, for
+    /// `eth2`, the consensus specs) gives them.
+    ///
+    /// `key` may be `bytes` for a name that is not valid UTF-8.
     #[pyo3(name = "set")]
-    fn set_kv(&mut self, key: &str, value: &[u8], signing_key: &SigningKey) -> PyResult<()> {
+    fn set_kv(
+        &mut self,
+        py: Python<'_>,
+        key: keys::KeyArg,
+        value: &Bound<'_, PyAny>,
+        signing_key: &SigningKey,
+    ) -> PyResult<()> {
+        let (key, value) = match key.into_text() {
+            Ok(key) => {
+                let value = rlp::RlpValue::from_py(&codecs::encode(&key, value)?)?;
+                keys::check_settable(&key, &value)?;
+                (key.into_bytes(), value)
+            }
+            // Only text names are reserved or have codecs.
+            Err(key) => (key, rlp::RlpValue::from_py(value)?),
+        };
+        self.check_insert(py, &key, value.length())?;
         self.inner
-            .insert(key, &value.to_vec(), &signing_key.inner)
-            .map_err(to_enr_error)?;
+            .insert(key, &value, &signing_key.inner)
+            .map_err(errors::record_error)?;
         Ok(())
     }
 
-    /// Reads a custom key from the record if it exists, decoded as data.
-    /// Caution! Only use for data that is not an aggregate type.
-    /// Returns RLP-decoded bytes (without the RLP length prefix).
-    fn get<'py>(&self, py: Python<'py>, key: &str) -> Option<Bound<'py, PyBytes>> {
-        #[allow(deprecated)]
-        self.inner.get(key).map(|v| PyBytes::new(py, &v))
+    /// Returns a copy keeping only `keys`, re-signed by `signing_key`.
+    ///
+    /// `id` and the public key of `signing_key` are always present; keys the
+    /// record does not have are ignored. The copy's seq is one higher, so it
+    /// supersedes this record. Signing with a different key gives the copy
+    /// a different node id.
+    fn minimal(&self, keys: Vec<String>, signing_key: &SigningKey) -> PyResult<Enr> {
+        let dropped: Vec<Vec<u8>> = self
+            .inner
+            .iter()
+            .map(|(key, _)| key.clone())
+            .filter(|key| {
+                let public_key = key == b"secp256k1" || key == b"ed25519";
+                key != b"id" && (public_key || !keys.iter().any(|k| k.as_bytes() == key))
+            })
+            .collect();
+        let mut inner = self.inner.clone();
+        inner
+            .remove_insert(
+                dropped.iter(),
+                std::iter::empty::<(&[u8], &[u8])>(),
+                &signing_key.inner,
+            )
+            .map_err(errors::record_error)?;
+        Ok(Enr { inner })
+    }
+
+    /// A copy without network addresses, for publishing datasets.
+    ///
+    /// Removes the keys in `drop` (by default `ip`, `ip6`, `udp` and `tcp`),
+    /// or with `zero=True` keeps them with zeroed values (`0.0.0.0`, port
+    /// 0). With `signing_key` the result is an `Enr` re-signed one seq
+    /// higher; otherwise it is an unsigned dict with `seq`, `node_id` and
+    /// `content`, the remaining pairs as `items()` gives them.
+    #[pyo3(signature = (drop=None, signing_key=None, *, zero=false))]
+    fn redacted(
+        &self,
+        py: Python<'_>,
+        drop: Option<Vec<String>>,
+        signing_key: Option<&SigningKey>,
+        zero: bool,
+    ) -> PyResult<Py<PyAny>> {
+        redact::redacted(py, &self.inner, drop, signing_key, zero)
+    }
+
+    /// Reads a key from the record, returning `default` if it is absent.
+    ///
+    /// Values are RLP-decoded bytes (without the RLP length prefix), an
+    /// instance of `type` (`bytes`, `int` or `str`) if given, or otherwise
+    /// the result of the decoder registered with `register_codec`. `ip4`,
+    /// `tcp4` and `udp4` are accepted for `ip`, `tcp` and `udp` when the
+    /// record has no key by that exact name. Caution! Only use for data that is not an
+    /// aggregate type.
+    ///
+    /// `key` may be `bytes` to read a key whose name is not valid UTF-8.
+    #[pyo3(signature = (key, default=None, r#type=None))]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        key: keys::KeyArg,
+        default: Option<Bound<'py, PyAny>>,
+        r#type: Option<Bound<'py, PyType>>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let key = match key.into_text() {
+            Ok(key) => key,
+            // No alias or codec has a name that is not UTF-8.
+            Err(name) => {
+                let Some(raw) = self.inner.get_raw_rlp(&name) else {
+                    return Ok(default);
+                };
+                return match r#type {
+                    Some(ty) => {
+                        keys::decode_as(py, &String::from_utf8_lossy(&name), raw, &ty).map(Some)
+                    }
+                    None => {
+                        #[allow(deprecated)]
+                        let value = self.inner.get(&name);
+                        Ok(value.map(|v| PyBytes::new(py, &v).into_any()))
+                    }
+                };
+            }
+        };
+        let key = key.as_str();
+        let found = match self.inner.get_raw_rlp(key) {
+            Some(raw) => Some((key, raw)),
+            None => {
+                let key = keys::normalize(key);
+                self.inner.get_raw_rlp(key).map(|raw| (key, raw))
+            }
+        };
+        let Some((key, raw)) = found else {
+            return Ok(default);
+        };
+        if let (None, Some(decode)) = (&r#type, codecs::decoder(py, key)) {
+            return decode.bind(py).call1((rlp::to_python(py, raw)?,)).map(Some);
+        }
+        match r#type {
+            Some(ty) => keys::decode_as(py, key, raw, &ty).map(Some),
+            None => {
+                #[allow(deprecated)]
+                let value = self.inner.get(key);
+                Ok(value.map(|v| PyBytes::new(py, &v).into_any()))
+            }
+        }
+    }
+
+    /// Returns the value for a key with its RLP structure intact: the
+    /// length prefix and, for list values, every nested item header.
+    fn get_raw<'py>(&self, py: Python<'py>, key: keys::KeyArg) -> Option<Bound<'py, PyBytes>> {
+        self.get_raw_rlp(py, key)
+    }
+
+    /// Returns the value for a key decoded from RLP: a byte string as
+    /// `bytes`, a list as a (possibly nested) `list` of such values.
+    fn get_decoded<'py>(
+        &self,
+        py: Python<'py>,
+        key: keys::KeyArg,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.inner
+            .get_raw_rlp(key.as_bytes())
+            .map(|raw| rlp::to_python(py, raw))
+            .transpose()
     }
 
     /// Returns the raw RLP-encoded value for a key, including the RLP length prefix.
-    fn get_raw_rlp<'py>(&self, py: Python<'py>, key: &str) -> Option<Bound<'py, PyBytes>> {
+    fn get_raw_rlp<'py>(&self, py: Python<'py>, key: keys::KeyArg) -> Option<Bound<'py, PyBytes>> {
         self.inner
-            .get_raw_rlp(key)
-            .map(|v| PyBytes::new(py, &v))
+            .get_raw_rlp(key.as_bytes())
+            .map(|v| PyBytes::new(py, v))
     }
 
     /// The signature of the ENR record.
@@ -181,11 +952,153 @@ impl Enr {
         self.inner.to_base64()
     }
 
+    /// Check the record against EIP-778, returning every violation found.
+    ///
+    /// With `known_keys_only=True`, keys missing from the known-key registry
+    /// are reported as `"unknown_key"` violations. With a `profile` (a
+    /// `Profile` or its name, defaulting to the one set with
+    /// `set_default_profile`), its required keys that are missing are
+    /// reported as `"missing_key"` and its fixed-length keys with the wrong
+    /// length as `"bad_field_length"`.
+    ///
+    /// `placeholders` overrides the placeholder policy for this call; under
+    /// `"flag"` or `"reject"`, port 0, `0.0.0.0` and `::` are reported as
+    /// `"placeholder"` violations.
+    #[pyo3(signature = (*, known_keys_only=false, profile=None, placeholders=None))]
+    fn validate(
+        &self,
+        py: Python<'_>,
+        known_keys_only: bool,
+        profile: Option<&Bound<'_, PyAny>>,
+        placeholders: Option<&str>,
+    ) -> PyResult<conformance::ValidationReport> {
+        let data = alloy_rlp::encode(&self.inner);
+        let spec = profiles::resolve(profile)?;
+        let flag = placeholder_flags(placeholders)?;
+        profiles::validate(py, &data, known_keys_only, spec, flag)
+    }
+
+    /// Check raw RLP bytes against EIP-778 without decoding them.
+    ///
+    /// Unlike `from_bytes`, this never raises for a malformed record; framing
+    /// problems are reported as `"malformed"` violations.
+    #[staticmethod]
+    #[pyo3(signature = (data, *, known_keys_only=false, profile=None, placeholders=None))]
+    fn validate_bytes(
+        py: Python<'_>,
+        data: &[u8],
+        known_keys_only: bool,
+        profile: Option<&Bound<'_, PyAny>>,
+        placeholders: Option<&str>,
+    ) -> PyResult<conformance::ValidationReport> {
+        let spec = profiles::resolve(profile)?;
+        let flag = placeholder_flags(placeholders)?;
+        errors::guard_decode(py, || {
+            profiles::validate(py, data, known_keys_only, spec, flag)
+        })
+    }
+
+    /// Keys in the record that are not in the known-key registry, in
+    /// record order.
+    fn unknown_keys<'py>(&self, py: Python<'py>) -> Vec<Bound<'py, PyString>> {
+        self.inner
+            .iter()
+            .filter(|(k, _)| std::str::from_utf8(k).map_or(true, |k| keys::lookup(k).is_none()))
+            .map(|(k, _)| key_name(py, k))
+            .collect()
+    }
+
+    /// Encodes the record directly into a new `bytes` object, without an intermediate buffer.
     #[pyo3(name = "to_bytes")]
-    fn to_bytes_py<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
-        let mut buf = Vec::new();
-        self.inner.encode(&mut buf);
-        PyBytes::new(py, &buf)
+    fn to_bytes_py<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        PyBytes::new_with(py, rlp::encoded_len(&self.inner), |mut buf: &mut [u8]| {
+            rlp::encode_into(&self.inner, &mut buf);
+            Ok(())
+        })
+    }
+
+    /// Exports the RLP encoding through the buffer protocol, so
+    /// `memoryview(enr)`, `bytes(enr)` and `numpy.frombuffer(enr, "u1")`
+    /// read it straight from the one buffer it is encoded into.
+    ///
+    /// The view is a read-only snapshot: changing the record afterwards does
+    /// not change it.
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut pyo3::ffi::Py_buffer,
+        flags: std::ffi::c_int,
+    ) -> PyResult<()> {
+        let data = {
+            let enr = slf.try_borrow()?;
+            let mut data = Vec::with_capacity(rlp::encoded_len(&enr.inner));
+            rlp::encode_into(&enr.inner, &mut data);
+            data
+        };
+        // SAFETY: `view` and `flags` are as CPython passed them.
+        unsafe { buffer::export(view, flags, data, slf.into_any()) }
+    }
+
+    unsafe fn __releasebuffer__(&self, view: *mut pyo3::ffi::Py_buffer) {
+        // SAFETY: `view` was filled by `__getbuffer__`.
+        unsafe { buffer::release(view) }
+    }
+
+    /// The record as a dict: `seq`, `node_id`, `signature` and `content`,
+    /// the key/value pairs with values RLP-encoded as `items()` gives them.
+    ///
+    /// Keys are `str`, or `bytes` if not valid UTF-8. `from_dict` rebuilds
+    /// the record from it and verifies the signature again.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        interchange::to_dict(py, &self.inner)
+    }
+
+    /// Rebuild a record from the dict `to_dict()` returns.
+    ///
+    /// Raises `DecodeError` if a field is missing or mistyped, or if
+    /// `node_id` is given and does not match the record's public key, and
+    /// `SignatureError` if the signature does not cover the content.
+    #[staticmethod]
+    fn from_dict(py: Python<'_>, data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        interchange::from_dict(py, data)
+    }
+
+    /// `to_dict()` encoded as CBOR (RFC 8949), with byte values as CBOR
+    /// byte strings.
+    fn to_cbor<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new(py, &interchange::to_cbor(&self.inner)?))
+    }
+
+    /// Decode a record from `to_cbor()` output; raises like `from_dict`.
+    #[staticmethod]
+    fn from_cbor(py: Python<'_>, data: &[u8]) -> PyResult<Self> {
+        interchange::from_cbor(py, data)
+    }
+
+    /// `to_dict()` encoded as MessagePack, with byte values in the bin
+    /// format.
+    fn to_msgpack<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new(py, &interchange::to_msgpack(&self.inner)?))
+    }
+
+    /// Decode a record from `to_msgpack()` output; raises like `from_dict`.
+    #[staticmethod]
+    fn from_msgpack(py: Python<'_>, data: &[u8]) -> PyResult<Self> {
+        interchange::from_msgpack(py, data)
+    }
+
+    /// The record as an `Enr` protobuf message (schema in `ENR_PROTO`):
+    /// the `to_dict()` fields plus the public key, identity scheme,
+    /// addresses and ports decoded for querying.
+    fn to_protobuf_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new(py, &interchange::to_protobuf(&self.inner)?))
+    }
+
+    /// Decode a record from an `Enr` protobuf message; raises like
+    /// `from_dict`, and also if a decoded field set in the message does not
+    /// match the record's content.
+    #[staticmethod]
+    fn from_protobuf_bytes(py: Python<'_>, data: &[u8]) -> PyResult<Self> {
+        interchange::from_protobuf(py, data)
     }
 
     fn __str__(&self) -> String {
@@ -193,33 +1106,155 @@ impl Enr {
     }
 
     fn __repr__(&self) -> String {
-        format!("Enr({})", self.inner.to_base64())
+        display::repr(&self.inner)
+    }
+
+    /// Supports `f"{enr:base64}"` (the default), `f"{enr:hex}"` (the RLP
+    /// encoding as `0x` hex), `f"{enr:pretty}"` and `f"{enr:fields}"`.
+    fn __format__(&self, spec: &str) -> PyResult<String> {
+        match spec {
+            "" | "base64" => Ok(self.inner.to_base64()),
+            "hex" => Ok(format!(
+                "0x{}",
+                convert::to_hex(&alloy_rlp::encode(&self.inner))
+            )),
+            "pretty" => Ok(self.pretty()),
+            "fields" => Ok(display::fields_repr(&self.inner)),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown format {spec:?} for Enr, expected \"base64\", \"hex\", \
+                 \"pretty\" or \"fields\""
+            ))),
+        }
+    }
+
+    /// An aligned multi-line dump for people: the node id, seq and
+    /// signature, then every key in record order with known fields decoded
+    /// and other values in hex.
+    fn pretty(&self) -> String {
+        let mut rows = vec![
+            (
+                "node_id".to_string(),
+                format!("0x{}", convert::to_hex(&self.inner.node_id().raw())),
+            ),
+            ("seq".to_string(), self.inner.seq().to_string()),
+            (
+                "signature".to_string(),
+                format!("0x{}", convert::to_hex(self.inner.signature())),
+            ),
+        ];
+        for (k, v) in self.inner.iter() {
+            let key = String::from_utf8_lossy(k).into_owned();
+            let value = keys::display_value(&key, v);
+            rows.push((key, value));
+        }
+        let width = rows
+            .iter()
+            .map(|(k, _)| k.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut out = String::from("Enr");
+        for (key, value) in rows {
+            out.push_str(&format!("\n  {key:<width$}  {value}"));
+        }
+        out
+    }
+
+    /// Pickles as `Enr.from_bytes(self.to_bytes())`, so records can be sent
+    /// to other processes; the signature is verified again on the way in.
+    fn __reduce__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        let from_bytes = py.get_type::<Enr>().getattr("from_bytes")?;
+        Ok((from_bytes, (self.to_bytes_py(py)?,)))
     }
 
     // -- Iteration --
 
-    fn keys(&self) -> Vec<String> {
+    /// Returns all keys. Key names are interned, so the same name is shared
+    /// across every record instead of being allocated per call.
+    ///
+    /// Names that are not valid UTF-8 are decoded lossily; pass `raw=True`
+    /// to get every name as `bytes` instead.
+    #[pyo3(signature = (*, raw=false))]
+    fn keys<'py>(&self, py: Python<'py>, raw: bool) -> Vec<Bound<'py, PyAny>> {
         self.inner
             .iter()
-            .map(|(k, _)| String::from_utf8_lossy(k).to_string())
+            .map(|(k, _)| key_object(py, k, raw))
             .collect()
     }
 
     /// Returns all key-value pairs. Values are raw RLP-encoded bytes (same as `get_raw_rlp`).
-    fn items<'py>(&self, py: Python<'py>) -> Vec<(String, Bound<'py, PyBytes>)> {
+    /// Keys are as `keys(raw=raw)` gives them.
+    #[pyo3(signature = (*, raw=false))]
+    fn items<'py>(
+        &self,
+        py: Python<'py>,
+        raw: bool,
+    ) -> Vec<(Bound<'py, PyAny>, Bound<'py, PyBytes>)> {
         self.inner
             .iter()
-            .map(|(k, v)| {
-                (
-                    String::from_utf8_lossy(k).to_string(),
-                    PyBytes::new(py, v),
-                )
-            })
+            .map(|(k, v)| (key_object(py, k, raw), PyBytes::new(py, v)))
+            .collect()
+    }
+
+    /// Returns the `(key, value)` pairs as `to_bytes()` encodes them: keys
+    /// as `bytes` and values as RLP with their headers, in record order.
+    fn raw_entries<'py>(&self, py: Python<'py>) -> Vec<(Bound<'py, PyBytes>, Bound<'py, PyBytes>)> {
+        let data = alloy_rlp::encode(&self.inner);
+        conformance::raw_entries(&data)
+            .expect("an encoded record is well framed")
+            .into_iter()
+            .map(|(k, v)| (PyBytes::new(py, k), PyBytes::new(py, v)))
             .collect()
     }
 
+    /// Returns the `(key, value)` pairs of encoded record bytes exactly as
+    /// they appear, without decoding or verifying the record.
+    ///
+    /// Non-canonical framing is kept as is; bytes that cannot be framed as a
+    /// record raise `DecodeError`.
+    #[staticmethod]
+    fn raw_entries_bytes<'py>(
+        py: Python<'py>,
+        data: &[u8],
+    ) -> PyResult<Vec<(Bound<'py, PyBytes>, Bound<'py, PyBytes>)>> {
+        let entries =
+            conformance::raw_entries(data).map_err(|v| errors::violation_error(py, &v))?;
+        Ok(entries
+            .into_iter()
+            .map(|(k, v)| (PyBytes::new(py, k), PyBytes::new(py, v)))
+            .collect())
+    }
+
+    /// Estimated number of bytes held by this record on the Rust side: its
+    /// signature, keys and values plus the key and value handles of each
+    /// entry. The map's own bookkeeping is not counted, so treat the figure
+    /// as a lower bound for comparing records rather than an exact count.
+    fn memory_usage(&self) -> usize {
+        let entry = std::mem::size_of::<(Vec<u8>, alloy_rlp::Bytes)>();
+        let entries: usize = self
+            .inner
+            .iter()
+            .map(|(k, v)| entry + k.len() + v.len())
+            .sum();
+        std::mem::size_of::<Self>() + self.inner.signature().len() + entries
+    }
+
+    /// The encoded size split into list header, signature, seq and each
+    /// key/value pair, with the bytes remaining under the 300-byte limit.
+    fn size_breakdown(&self, py: Python<'_>) -> PyResult<size::SizeBreakdown> {
+        size::SizeBreakdown::of(py, &self.inner)
+    }
+
     // -- Comparison --
 
+    /// Compare with a later record for the same node, reporting the keys
+    /// `other` added, removed or changed and how far its seq moved.
+    fn diff(&self, py: Python<'_>, other: &Enr) -> PyResult<diff::EnrDiff> {
+        diff::EnrDiff::between(py, &self.inner, &other.inner)
+    }
+
     fn __eq__(&self, other: &Enr) -> bool {
         let mut a = Vec::new();
         let mut b = Vec::new();
@@ -229,19 +1264,116 @@ impl Enr {
     }
 
     fn __hash__(&self) -> u64 {
-        use std::hash::{Hash, Hasher};
-        let mut buf = Vec::new();
-        self.inner.encode(&mut buf);
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        buf.hash(&mut hasher);
-        hasher.finish()
+        hashing::stable_hash(&self.inner, hashing::default_mode())
+    }
+
+    /// A 64-bit hash that is the same in every process.
+    ///
+    /// `mode="content"` hashes the seq and signature (which commits to the
+    /// full content); `mode="node_seq"` hashes only the node id and seq,
+    /// suitable as a distributed dedup key. Defaults to the mode set with
+    /// `Enr.set_hash_mode`.
+    #[pyo3(signature = (mode=None))]
+    fn stable_hash(&self, mode: Option<&str>) -> PyResult<u64> {
+        let mode = match mode {
+            Some(name) => hashing::HashMode::parse(name)?,
+            None => hashing::default_mode(),
+        };
+        Ok(hashing::stable_hash(&self.inner, mode))
+    }
+
+    /// A short id for logs and metrics labels: 16 lowercase hex digits, the
+    /// first 8 bytes of keccak256(node id || seq as 8 big-endian bytes).
+    ///
+    /// The same as `f"{stable_hash('node_seq'):016x}"`, so it changes with
+    /// every update of the record but not with re-encoding.
+    fn fingerprint(&self) -> String {
+        format!(
+            "{:016x}",
+            hashing::stable_hash(&self.inner, hashing::HashMode::NodeSeq)
+        )
+    }
+
+    /// The record's content as canonical JSON: an object of its key/value
+    /// pairs, keys sorted, values as `0x`-prefixed hex of their RLP encoding,
+    /// with no whitespace.
+    ///
+    /// The seq and signature are left out, so re-signing the same content
+    /// gives the same text.
+    fn to_canonical_json(&self) -> String {
+        hashing::canonical_json(&self.inner)
+    }
+
+    /// SHA-256 of `to_canonical_json()`, a stable reference to the record's
+    /// content independent of its seq and signature.
+    fn content_hash<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &hashing::content_hash(&self.inner))
+    }
+
+    /// Select what `hash()` uses for every record: `"content"` (the
+    /// default) or `"node_seq"`.
+    ///
+    /// Both are consistent with `==`. Change it before records are put in
+    /// sets or used as dict keys, since existing containers are not rehashed.
+    #[staticmethod]
+    fn set_hash_mode(mode: &str) -> PyResult<()> {
+        hashing::set_default_mode(hashing::HashMode::parse(mode)?);
+        Ok(())
+    }
+
+    /// The mode currently used by `hash()`.
+    #[staticmethod]
+    fn get_hash_mode() -> &'static str {
+        hashing::default_mode().name()
     }
 }
 
 /// Python wrapper around `CombinedKey`.
-#[pyclass(name = "SigningKey")]
+///
+/// Immutable once created, so it can be shared between threads without any
+/// runtime borrow tracking.
+#[pyclass(name = "SigningKey", module = "pyenr", frozen)]
 struct SigningKey {
     inner: CombinedKey,
+    /// Whether pickling may copy the secret out, as opted into with `allow_pickle()`.
+    picklable: bool,
+}
+
+/// What a pickled `SigningKey` holds: its identity scheme and secret bytes.
+type KeyState = (&'static str, Vec<u8>);
+
+impl SigningKey {
+    fn new(inner: CombinedKey) -> Self {
+        SigningKey {
+            inner,
+            picklable: false,
+        }
+    }
+
+    /// The name of the key's identity scheme and its secret bytes.
+    fn secret(&self) -> KeyState {
+        match &self.inner {
+            CombinedKey::Secp256k1(key) => ("secp256k1", key.to_bytes().to_vec()),
+            CombinedKey::Ed25519(key) => ("ed25519", key.to_bytes().to_vec()),
+        }
+    }
+
+    /// A picklable key from what `secret` returns.
+    fn picklable_from(scheme: &str, secret: &[u8]) -> PyResult<Self> {
+        let key = match scheme {
+            "secp256k1" => Self::from_secp256k1(secret)?,
+            "ed25519" => Self::from_ed25519(secret)?,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown identity scheme {other:?}"
+                )))
+            }
+        };
+        Ok(SigningKey {
+            picklable: true,
+            ..key
+        })
+    }
 }
 
 #[pymethods]
@@ -249,29 +1381,36 @@ impl SigningKey {
     #[staticmethod]
     fn from_secp256k1(secret: &[u8]) -> PyResult<Self> {
         let mut bytes = secret.to_vec();
-        let key = CombinedKey::secp256k1_from_bytes(&mut bytes).map_err(to_enr_error)?;
-        Ok(SigningKey { inner: key })
+        let key = CombinedKey::secp256k1_from_bytes(&mut bytes)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(SigningKey::new(key))
     }
 
     #[staticmethod]
     fn from_ed25519(secret: &[u8]) -> PyResult<Self> {
         let mut bytes = secret.to_vec();
-        let key = CombinedKey::ed25519_from_bytes(&mut bytes).map_err(to_enr_error)?;
-        Ok(SigningKey { inner: key })
+        let key = CombinedKey::ed25519_from_bytes(&mut bytes)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(SigningKey::new(key))
     }
 
+    /// Generate a new random key of the given type.
     #[staticmethod]
-    fn generate_secp256k1() -> Self {
-        SigningKey {
-            inner: CombinedKey::generate_secp256k1(),
+    fn generate(key_type: crypto::KeyType) -> Self {
+        match key_type {
+            crypto::KeyType::Secp256k1 => Self::generate_secp256k1(),
+            crypto::KeyType::Ed25519 => Self::generate_ed25519(),
         }
     }
 
+    #[staticmethod]
+    fn generate_secp256k1() -> Self {
+        SigningKey::new(CombinedKey::generate_secp256k1())
+    }
+
     #[staticmethod]
     fn generate_ed25519() -> Self {
-        SigningKey {
-            inner: CombinedKey::generate_ed25519(),
-        }
+        SigningKey::new(CombinedKey::generate_ed25519())
     }
 
     fn public_key<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
@@ -280,14 +1419,67 @@ impl SigningKey {
         PyBytes::new(py, &pk.encode())
     }
 
+    /// The libp2p peer id of the key's public key, as `Enr.peer_id` gives it.
+    fn peer_id(&self) -> String {
+        use enr::EnrKey;
+        libp2p::peer_id(&self.inner.public())
+    }
+
+    #[getter]
+    fn key_type(&self) -> crypto::KeyType {
+        use enr::EnrKey;
+        crypto::KeyType::of(&self.inner.public())
+    }
+
     fn builder(&self) -> EnrBuilder {
         EnrBuilder {
             builder: InnerBuilder::new(),
         }
     }
+
+    /// A copy of the key that can be pickled, for handing it to worker
+    /// processes.
+    ///
+    /// Keys refuse to be pickled by default, since the pickle holds the
+    /// secret key in the clear for anyone who can read it. Unpickled copies
+    /// stay picklable.
+    fn allow_pickle(&self) -> PyResult<Self> {
+        let (scheme, secret) = self.secret();
+        Self::picklable_from(scheme, &secret)
+    }
+
+    /// Whether the key can be pickled, as `allow_pickle()` makes it.
+    #[getter]
+    fn picklable(&self) -> bool {
+        self.picklable
+    }
+
+    /// The identity scheme name and secret bytes, for keys made with
+    /// `allow_pickle()`; others raise `TypeError`.
+    fn __getstate__(&self) -> PyResult<KeyState> {
+        if !self.picklable {
+            return Err(PyTypeError::new_err(
+                "SigningKey holds secret material and is not picklable; \
+                 use key.allow_pickle() to opt in",
+            ));
+        }
+        Ok(self.secret())
+    }
+
+    fn __reduce__<'py>(&self, py: Python<'py>) -> PyResult<(Bound<'py, PyAny>, (KeyState,))> {
+        let restore = py.import("pyenr._core")?.getattr("_restore_signing_key")?;
+        Ok((restore, (self.__getstate__()?,)))
+    }
+}
+
+/// Rebuilds a picklable key from `SigningKey.__getstate__()`, for unpickling.
+#[pyfunction(name = "_restore_signing_key")]
+fn restore_signing_key(state: (String, Vec<u8>)) -> PyResult<SigningKey> {
+    SigningKey::picklable_from(&state.0, &state.1)
 }
 
 /// Internal builder state.
+#[derive(Clone)]
 struct InnerBuilder {
     ip4: Option<Ipv4Addr>,
     ip6: Option<Ipv6Addr>,
@@ -295,7 +1487,7 @@ struct InnerBuilder {
     tcp6: Option<u16>,
     udp4: Option<u16>,
     udp6: Option<u16>,
-    custom: Vec<(String, Vec<u8>)>,
+    custom: Vec<(String, rlp::RlpValue)>,
 }
 
 impl InnerBuilder {
@@ -321,17 +1513,13 @@ struct EnrBuilder {
 #[pymethods]
 impl EnrBuilder {
     fn ip4(&mut self, addr: &str) -> PyResult<()> {
-        let ip: Ipv4Addr = addr
-            .parse()
-            .map_err(|e: std::net::AddrParseError| PyValueError::new_err(e.to_string()))?;
+        let ip: Ipv4Addr = addr.parse().map_err(errors::field_error)?;
         self.builder.ip4 = Some(ip);
         Ok(())
     }
 
     fn ip6(&mut self, addr: &str) -> PyResult<()> {
-        let ip: Ipv6Addr = addr
-            .parse()
-            .map_err(|e: std::net::AddrParseError| PyValueError::new_err(e.to_string()))?;
+        let ip = net::parse_ip6(addr).map_err(errors::field_error)?;
         self.builder.ip6 = Some(ip);
         Ok(())
     }
@@ -352,8 +1540,89 @@ impl EnrBuilder {
         self.builder.udp6 = Some(port);
     }
 
-    fn add(&mut self, key: &str, value: &[u8]) {
-        self.builder.custom.push((key.to_string(), value.to_vec()));
+    #[pyo3(signature = (fork_digest, next_fork_version, next_fork_epoch=eth2::FAR_FUTURE_EPOCH))]
+    fn eth2(
+        &mut self,
+        fork_digest: &[u8],
+        next_fork_version: &[u8],
+        next_fork_epoch: u64,
+    ) -> PyResult<()> {
+        let fork_id =
+            eth2::Eth2ForkId::from_fields(fork_digest, next_fork_version, next_fork_epoch)?;
+        let value = rlp::RlpValue::String(fork_id.ssz().to_vec());
+        self.builder
+            .custom
+            .push((eth2::ETH2_ENR_KEY.to_string(), value));
+        Ok(())
+    }
+
+    fn attnets(&mut self, subnets: Vec<usize>) -> PyResult<()> {
+        let bits = eth2::subnet_bits(eth2::ATTNETS_KEY, &subnets, eth2::ATTESTATION_SUBNET_COUNT)?;
+        let value = rlp::RlpValue::String(bits);
+        self.builder
+            .custom
+            .push((eth2::ATTNETS_KEY.to_string(), value));
+        Ok(())
+    }
+
+    fn syncnets(&mut self, subnets: Vec<usize>) -> PyResult<()> {
+        let bits = eth2::subnet_bits(
+            eth2::SYNCNETS_KEY,
+            &subnets,
+            eth2::SYNC_COMMITTEE_SUBNET_COUNT,
+        )?;
+        let value = rlp::RlpValue::String(bits);
+        self.builder
+            .custom
+            .push((eth2::SYNCNETS_KEY.to_string(), value));
+        Ok(())
+    }
+
+    fn portal_client(&mut self, client: &str) {
+        let value = rlp::RlpValue::String(client.as_bytes().to_vec());
+        self.builder
+            .custom
+            .push((portal::PORTAL_CLIENT_KEY.to_string(), value));
+    }
+
+    fn waku_capabilities(&mut self, capabilities: Vec<String>) -> PyResult<()> {
+        let value = rlp::RlpValue::String(vec![waku::flags(capabilities)?]);
+        self.builder
+            .custom
+            .push((waku::WAKU2_KEY.to_string(), value));
+        Ok(())
+    }
+
+    #[pyo3(warn(
+        message = "EnrBuilder.waku_multiaddrs() is deprecated, use EnrBuilder.multiaddrs()",
+        category = PyDeprecationWarning
+    ))]
+    fn waku_multiaddrs(&mut self, multiaddrs: Vec<String>) -> PyResult<()> {
+        self.multiaddrs(multiaddrs, "length-prefixed")
+    }
+
+    #[pyo3(signature = (multiaddrs, *, encoding="length-prefixed"))]
+    fn multiaddrs(&mut self, multiaddrs: Vec<String>, encoding: &str) -> PyResult<()> {
+        let value = encode_multiaddrs(&multiaddrs, encoding)?;
+        self.builder
+            .custom
+            .push((multiaddr::MULTIADDRS_KEY.to_string(), value));
+        Ok(())
+    }
+
+    fn add(&mut self, key: &str, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let value = rlp::RlpValue::from_py(&codecs::encode(key, value)?)?;
+        keys::check_settable(key, &value)?;
+        self.builder.custom.push((key.to_string(), value));
+        Ok(())
+    }
+
+    /// An independent copy of this builder, so a common template (ports,
+    /// custom fields) can be built with many keys and changed per copy.
+    fn clone_template(&self) -> EnrBuilder {
+        EnrBuilder {
+            builder: self.builder.clone(),
+        }
     }
 
     fn build(&self, key: &SigningKey) -> PyResult<Enr> {
@@ -377,17 +1646,131 @@ impl EnrBuilder {
             builder.udp6(port);
         }
         for (k, v) in &self.builder.custom {
-            builder.add_value(k, &v.clone());
+            builder.add_value(k, v);
         }
-        let inner = builder.build(&key.inner).map_err(to_enr_error)?;
+        let inner = builder.build(&key.inner).map_err(errors::record_error)?;
         Ok(Enr { inner })
     }
 }
 
-#[pymodule]
+/// Records and builders are guarded by PyO3's per-object borrow checking, and
+/// the module's global settings and codec registry by atomics and a mutex, so
+/// it is safe to load without the GIL.
+#[pymodule(gil_used = false)]
 fn _core(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    errors::register(m)?;
+    keys::register(m)?;
+    vectors::register(m)?;
+    m.add("ENR_PROTO", interchange::ENR_PROTO)?;
     m.add_class::<Enr>()?;
     m.add_class::<SigningKey>()?;
     m.add_class::<EnrBuilder>()?;
+    m.add_class::<batch::DedupeReport>()?;
+    m.add_class::<bloom::NodeIdFilter>()?;
+    m.add_class::<conformance::ValidationReport>()?;
+    m.add_class::<conformance::PyViolation>()?;
+    m.add_class::<salvage::SalvagedEnr>()?;
+    m.add_class::<dedupe::StreamingDeduper>()?;
+    m.add_class::<dedupe::FeedResult>()?;
+    m.add_class::<cache::EnrCache>()?;
+    m.add_class::<identity::IdentityTracker>()?;
+    m.add_class::<identity::EndpointConflict>()?;
+    m.add_class::<watch::EnrWatcher>()?;
+    m.add_class::<watch::WatchEvent>()?;
+    m.add_class::<diff::EnrDiff>()?;
+    m.add_class::<history::EnrHistory>()?;
+    m.add_class::<churn::ChurnStats>()?;
+    m.add_class::<churn::NodeChurn>()?;
+    m.add_class::<churn::ChurnWindow>()?;
+    m.add_class::<liveness::LivenessResult>()?;
+    m.add_class::<progress::CancelToken>()?;
+    #[cfg(feature = "sqlite")]
+    m.add_class::<store::EnrStore>()?;
+    #[cfg(not(feature = "sqlite"))]
+    m.add_class::<features::EnrStore>()?;
+    m.add_class::<crypto::KeyType>()?;
+    m.add_class::<eth::EthForkId>()?;
+    m.add_class::<eth2::Eth2ForkId>()?;
+    m.add_class::<opstack::OpStackInfo>()?;
+    m.add_class::<fields::EnrFields>()?;
+    m.add_class::<fields::Endpoints>()?;
+    m.add_class::<files::EnrReader>()?;
+    m.add_class::<profiles::Profile>()?;
+    m.add_class::<enode::Enode>()?;
+    m.add_class::<tree::EnrTree>()?;
+    m.add_class::<tree::Root>()?;
+    m.add_class::<tree::Branch>()?;
+    m.add_class::<tree::Link>()?;
+    m.add_class::<size::SizeBreakdown>()?;
+    m.add_function(wrap_pyfunction!(convert::decode, m)?)?;
+    m.add_function(wrap_pyfunction!(convert::encode, m)?)?;
+    m.add_function(wrap_pyfunction!(convert::from_eth_enr, m)?)?;
+    m.add_function(wrap_pyfunction!(convert::to_eth_enr, m)?)?;
+    m.add_function(wrap_pyfunction!(crypto::verify_content, m)?)?;
+    m.add_function(wrap_pyfunction!(restore_signing_key, m)?)?;
+    m.add_function(wrap_pyfunction!(keys::known_keys, m)?)?;
+    m.add_function(wrap_pyfunction!(features::features, m)?)?;
+    m.add_function(wrap_pyfunction!(codecs::register_codec, m)?)?;
+    m.add_function(wrap_pyfunction!(eth::compute_fork_id, m)?)?;
+    m.add_function(wrap_pyfunction!(eth2::compute_fork_digest, m)?)?;
+    m.add_function(wrap_pyfunction!(profiles::profiles, m)?)?;
+    m.add_function(wrap_pyfunction!(profiles::set_default_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(profiles::get_default_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(codecs::unregister_codec, m)?)?;
+    m.add_function(wrap_pyfunction!(codecs::registered_codecs, m)?)?;
+    m.add_function(wrap_pyfunction!(display::set_repr_style, m)?)?;
+    m.add_function(wrap_pyfunction!(display::get_repr_style, m)?)?;
+    m.add_function(wrap_pyfunction!(placeholder::set_placeholder_policy, m)?)?;
+    m.add_function(wrap_pyfunction!(placeholder::get_placeholder_policy, m)?)?;
+    m.add_function(wrap_pyfunction!(batch::set_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(batch::get_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(batch::filter_many, m)?)?;
+    m.add_function(wrap_pyfunction!(batch::filter_routable, m)?)?;
+    m.add_function(wrap_pyfunction!(batch::group_by_fork_digest, m)?)?;
+    m.add_function(wrap_pyfunction!(batch::dedupe_report, m)?)?;
+    m.add_function(wrap_pyfunction!(batch::node_ids, m)?)?;
+    m.add_function(wrap_pyfunction!(batch::decode_many, m)?)?;
+    m.add_function(wrap_pyfunction!(batch::decode_many_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(hashing::records_digest, m)?)?;
+    m.add_function(wrap_pyfunction!(churn::churn_stats, m)?)?;
+    #[cfg(feature = "geo")]
+    m.add_function(wrap_pyfunction!(geo::enrich_geo, m)?)?;
+    #[cfg(not(feature = "geo"))]
+    m.add_function(wrap_pyfunction!(features::enrich_geo, m)?)?;
+    #[cfg(feature = "dns")]
+    m.add_function(wrap_pyfunction!(rdns::reverse_dns, m)?)?;
+    #[cfg(not(feature = "dns"))]
+    m.add_function(wrap_pyfunction!(features::reverse_dns, m)?)?;
+    m.add_function(wrap_pyfunction!(liveness::probe_liveness, m)?)?;
+    m.add_function(wrap_pyfunction!(files::read_enr_file, m)?)?;
+    m.add_function(wrap_pyfunction!(files::decode_rlp_stream, m)?)?;
+    m.add_function(wrap_pyfunction!(files::write_enr_file, m)?)?;
+    m.add_function(wrap_pyfunction!(files::process_enr_files, m)?)?;
+    m.add_function(wrap_pyfunction!(persist::load_node_enr, m)?)?;
+    m.add_function(wrap_pyfunction!(persist::save_node_enr, m)?)?;
+    m.add_function(wrap_pyfunction!(tree::parse_tree_entry, m)?)?;
+    #[cfg(feature = "dns")]
+    m.add_function(wrap_pyfunction!(dnsdisc::resolve_tree, m)?)?;
+    #[cfg(not(feature = "dns"))]
+    m.add_function(wrap_pyfunction!(features::resolve_tree, m)?)?;
+    m.add_function(wrap_pyfunction!(bootnodes::load_bootnodes, m)?)?;
+    #[cfg(feature = "http")]
+    m.add_function(wrap_pyfunction!(bootnodes::fetch_enr_list, m)?)?;
+    #[cfg(not(feature = "http"))]
+    m.add_function(wrap_pyfunction!(features::fetch_enr_list, m)?)?;
+    m.add_function(wrap_pyfunction!(bootnodes::known_bootnodes, m)?)?;
+    m.add_function(wrap_pyfunction!(aio::filter_many_async, m)?)?;
+    m.add_function(wrap_pyfunction!(aio::dedupe_report_async, m)?)?;
+    m.add_function(wrap_pyfunction!(aio::process_enr_files_async, m)?)?;
+    m.add_function(wrap_pyfunction!(aio::decode_many_async, m)?)?;
+    m.add_function(wrap_pyfunction!(aio::decode_many_bytes_async, m)?)?;
+    m.add_function(wrap_pyfunction!(aio::probe_liveness_async, m)?)?;
+    #[cfg(feature = "dns")]
+    m.add_function(wrap_pyfunction!(aio::resolve_tree_async, m)?)?;
+    #[cfg(not(feature = "dns"))]
+    m.add_function(wrap_pyfunction!(features::resolve_tree_async, m)?)?;
+    m.add_function(wrap_pyfunction!(fuzz::fuzz_decode, m)?)?;
+    m.add_function(wrap_pyfunction!(random::generate_random_enr, m)?)?;
+    m.add_function(wrap_pyfunction!(vectors::run_self_test, m)?)?;
     Ok(())
 }
@@ -0,0 +1,87 @@
+//! The libp2p view of a node record: its peer id, and the multiaddrs its
+//! addresses and ports stand for.
+
+use std::net::{IpAddr, SocketAddr};
+
+use enr::{CombinedKey, CombinedPublicKey};
+
+use crate::net;
+
+/// libp2p's protobuf `KeyType` of each identity scheme's keys.
+const KEY_TYPE_ED25519: u8 = 1;
+const KEY_TYPE_SECP256K1: u8 = 2;
+
+/// Multihash code of the identity hash, which libp2p uses for keys whose
+/// encoding is at most 42 bytes, as both kinds in records are.
+const IDENTITY_MULTIHASH: u8 = 0x00;
+
+/// The libp2p peer id of `key`, as base58btc text: the identity multihash
+/// of the key's protobuf encoding.
+pub(crate) fn peer_id(key: &CombinedPublicKey) -> String {
+    let (key_type, data) = match key {
+        CombinedPublicKey::Secp256k1(key) => (
+            KEY_TYPE_SECP256K1,
+            key.to_encoded_point(true).as_bytes().to_vec(),
+        ),
+        CombinedPublicKey::Ed25519(key) => (KEY_TYPE_ED25519, key.to_bytes().to_vec()),
+    };
+    // PublicKey { Type = 1 (varint); Data = 2 (bytes) }
+    let mut encoded = vec![0x08, key_type, 0x12, data.len() as u8];
+    encoded.extend_from_slice(&data);
+    let mut multihash = vec![IDENTITY_MULTIHASH, encoded.len() as u8];
+    multihash.extend_from_slice(&encoded);
+    bs58::encode(multihash).into_string()
+}
+
+fn quic_port(enr: &enr::Enr<CombinedKey>, key: &str) -> Option<u16> {
+    enr.get_decodable::<u16>(key).and_then(Result::ok)
+}
+
+/// The multiaddrs of the record's dialable endpoints: TCP, UDP and QUIC on
+/// the IPv4 address, then on the IPv6 address, each ending in
+/// `/p2p/<peer id>` if `p2p`.
+///
+/// With `fallback`, the IPv6 address takes the IPv4 ports the record has no
+/// IPv6 counterpart of, as `net::endpoints` does.
+pub(crate) fn multiaddrs(enr: &enr::Enr<CombinedKey>, fallback: bool, p2p: bool) -> Vec<String> {
+    let mut quic6 = quic_port(enr, "quic6");
+    if fallback {
+        quic6 = quic6.or(quic_port(enr, "quic"));
+    }
+    let quic = [
+        enr.ip4()
+            .map(IpAddr::V4)
+            .zip(quic_port(enr, "quic"))
+            .map(SocketAddr::from),
+        enr.ip6().map(IpAddr::V6).zip(quic6).map(SocketAddr::from),
+    ];
+    let mut endpoints: Vec<(&str, SocketAddr)> = net::endpoints(enr, fallback)
+        .into_iter()
+        .chain(quic.into_iter().flatten().map(|addr| ("quic", addr)))
+        .filter(|(_, addr)| net::is_dialable(addr))
+        .collect();
+    // IPv4 before IPv6, and TCP, UDP, QUIC on each.
+    let rank = |protocol: &str| match protocol {
+        "tcp" => 0,
+        "udp" => 1,
+        _ => 2,
+    };
+    endpoints.sort_by_key(|(protocol, addr)| (addr.is_ipv6(), rank(protocol)));
+
+    let suffix = if p2p {
+        format!("/p2p/{}", peer_id(&enr.public_key()))
+    } else {
+        String::new()
+    };
+    endpoints
+        .into_iter()
+        .map(|(protocol, addr)| {
+            let family = if addr.is_ipv4() { "ip4" } else { "ip6" };
+            let transport = match protocol {
+                "quic" => format!("udp/{}/quic-v1", addr.port()),
+                _ => format!("{protocol}/{}", addr.port()),
+            };
+            format!("/{family}/{}/{transport}{suffix}", addr.ip())
+        })
+        .collect()
+}
@@ -0,0 +1,196 @@
+//! Checking whether the endpoints records advertise answer.
+
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use rayon::prelude::*;
+
+#[cfg(feature = "discv5")]
+use crate::discv5;
+use crate::progress::{CancelToken, Progress};
+use crate::{batch, net, Enr};
+
+#[derive(Clone, Copy)]
+enum Method {
+    Tcp,
+    #[cfg(feature = "discv5")]
+    Discv5Ping,
+}
+
+impl Method {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "tcp" => Ok(Method::Tcp),
+            #[cfg(feature = "discv5")]
+            "discv5_ping" => Ok(Method::Discv5Ping),
+            #[cfg(not(feature = "discv5"))]
+            "discv5_ping" => Err(crate::features::missing("method=\"discv5_ping\"", "discv5")),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown probe method {name:?}, expected \"tcp\" or \"discv5_ping\""
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Method::Tcp => "tcp",
+            #[cfg(feature = "discv5")]
+            Method::Discv5Ping => "discv5_ping",
+        }
+    }
+
+    fn protocol(self) -> &'static str {
+        match self {
+            Method::Tcp => "tcp",
+            #[cfg(feature = "discv5")]
+            Method::Discv5Ping => "udp",
+        }
+    }
+}
+
+/// The outcome of probing one record.
+#[pyclass(name = "LivenessResult", frozen, get_all)]
+pub(crate) struct LivenessResult {
+    node_id: Py<PyBytes>,
+    /// `"tcp"` or `"discv5_ping"`.
+    method: &'static str,
+    /// Whether any endpoint answered.
+    reachable: bool,
+    /// The endpoint that answered, or the last one tried, as `ip:port`;
+    /// `None` if the record has no endpoint for the method.
+    endpoint: Option<String>,
+    /// Seconds until the answer, if there was one.
+    rtt: Option<f64>,
+    /// Why the last endpoint tried did not answer.
+    error: Option<String>,
+}
+
+#[pymethods]
+impl LivenessResult {
+    fn __repr__(&self, py: Python<'_>) -> String {
+        let node_id = self.node_id.bind(py).as_bytes();
+        format!(
+            "LivenessResult(node_id=0x{}\u{2026}, method={:?}, reachable={}, endpoint={:?})",
+            crate::convert::to_hex(&node_id[..4]),
+            self.method,
+            if self.reachable { "True" } else { "False" },
+            self.endpoint
+        )
+    }
+}
+
+/// A probe result before conversion to Python objects.
+struct Outcome {
+    node_id: [u8; 32],
+    endpoint: Option<SocketAddr>,
+    rtt: Option<Duration>,
+    error: Option<String>,
+}
+
+fn probe(
+    node_id: [u8; 32],
+    endpoints: &[SocketAddr],
+    method: Method,
+    timeout: Duration,
+) -> Outcome {
+    let mut outcome = Outcome {
+        node_id,
+        endpoint: None,
+        rtt: None,
+        error: None,
+    };
+    for &addr in endpoints {
+        let start = Instant::now();
+        let result = match method {
+            Method::Tcp => TcpStream::connect_timeout(&addr, timeout).map(drop),
+            #[cfg(feature = "discv5")]
+            Method::Discv5Ping => discv5::ping(&node_id, addr, timeout),
+        };
+        outcome.endpoint = Some(addr);
+        match result {
+            Ok(()) => {
+                outcome.rtt = Some(start.elapsed());
+                outcome.error = None;
+                break;
+            }
+            Err(err) => outcome.error = Some(err.to_string()),
+        }
+    }
+    if endpoints.is_empty() {
+        outcome.error = Some(format!("record has no {} endpoint", method.protocol()));
+    }
+    outcome
+}
+
+/// Check whether each record's node answers, returning a `LivenessResult`
+/// per record in input order.
+///
+/// `method="tcp"` connects to the TCP endpoints; `"discv5_ping"` sends a
+/// discv5 packet to the UDP endpoints that the node cannot decrypt, which a
+/// live node answers with a WHOAREYOU challenge, without completing a
+/// handshake. Endpoints are tried IPv4 first, as `effective_endpoints()`
+/// lists them, until one answers within `timeout` seconds. Up to
+/// `concurrency` records are probed at once, with the GIL released;
+/// `progress(done, total)`, if given, counts records probed, and `cancel` or
+/// Ctrl-C stop the call once the probes under way are done.
+#[pyfunction]
+#[pyo3(signature = (enrs, *, method="tcp", concurrency=32, timeout=2.0, progress=None, cancel=None))]
+pub(crate) fn probe_liveness(
+    py: Python<'_>,
+    enrs: Vec<PyRef<'_, Enr>>,
+    method: &str,
+    concurrency: usize,
+    timeout: f64,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<Vec<LivenessResult>> {
+    let method = Method::parse(method)?;
+    if concurrency == 0 {
+        return Err(PyValueError::new_err("concurrency must be at least 1"));
+    }
+    let timeout = Duration::try_from_secs_f64(timeout)
+        .ok()
+        .filter(|t| !t.is_zero())
+        .ok_or_else(|| PyValueError::new_err("timeout must be a positive number of seconds"))?;
+    let targets: Vec<_> = enrs
+        .iter()
+        .map(|e| {
+            let endpoints: Vec<_> = net::endpoints(&e.inner, true)
+                .into_iter()
+                .filter(|(protocol, addr)| *protocol == method.protocol() && net::is_dialable(addr))
+                .map(|(_, addr)| addr)
+                .collect();
+            (e.inner.node_id().raw(), endpoints)
+        })
+        .collect();
+    drop(enrs);
+
+    let progress = Progress::new(progress, cancel, targets.len());
+    let outcomes = progress.run(py, || {
+        batch::with_io_pool(concurrency, || {
+            targets
+                .par_iter()
+                .map(|(node_id, endpoints)| {
+                    progress.check()?;
+                    let outcome = probe(*node_id, endpoints, method, timeout);
+                    progress.advance(1).map(|()| outcome)
+                })
+                .collect()
+        })
+    })?;
+    let outcomes: Vec<Outcome> = progress.finish(py, outcomes)?;
+    Ok(outcomes
+        .into_iter()
+        .map(|outcome| LivenessResult {
+            node_id: PyBytes::new(py, &outcome.node_id).unbind(),
+            method: method.name(),
+            reachable: outcome.rtt.is_some(),
+            endpoint: outcome.endpoint.map(|addr| addr.to_string()),
+            rtt: outcome.rtt.map(|rtt| rtt.as_secs_f64()),
+            error: outcome.error,
+        })
+        .collect())
+}
@@ -0,0 +1,50 @@
+//! Diagnostics reported through the `pyenr` logger of Python's `logging` module.
+//!
+//! Batch jobs use it for data-quality events that do not fail the call, such
+//! as skipped records. The Python package installs a `NullHandler` on the
+//! logger, so nothing is printed unless the application configures logging.
+
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+
+/// Name of the logger, as passed to `logging.getLogger`.
+const LOGGER_NAME: &str = "pyenr";
+
+/// Levels used by the module, with the values of their `logging` constants.
+#[derive(Clone, Copy)]
+pub(crate) enum Level {
+    Debug = 10,
+    Warning = 30,
+}
+
+impl Level {
+    fn method(self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Warning => "warning",
+        }
+    }
+}
+
+fn logger(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    static LOGGER: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+    LOGGER
+        .get_or_try_init(py, || {
+            py.import("logging")?
+                .call_method1("getLogger", (LOGGER_NAME,))
+                .map(Bound::unbind)
+        })
+        .map(|logger| logger.bind(py))
+}
+
+/// Logs `message` at `level`, building it only if the level is enabled.
+pub(crate) fn log(py: Python<'_>, level: Level, message: impl FnOnce() -> String) -> PyResult<()> {
+    let logger = logger(py)?;
+    if logger
+        .call_method1("isEnabledFor", (level as u8,))?
+        .is_truthy()?
+    {
+        logger.call_method1(level.method(), (message(),))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,276 @@
+//! Conversion of multiaddrs between their binary and text forms, for the
+//! protocols found in node records, and of the `multiaddrs` key that lists
+//! them.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use alloy_rlp::Header;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+use crate::rlp::RlpValue;
+
+/// ENR key holding a list of multiaddrs, for addresses that `ip`/`tcp`/`udp`
+/// cannot express, such as websocket, DNS and circuit-relay addresses.
+pub(crate) const MULTIADDRS_KEY: &str = "multiaddrs";
+
+/// Protocols a multiaddr in a record may start with: those naming a host.
+const HOST_PROTOCOLS: &[&str] = &["ip4", "ip6", "dns", "dns4", "dns6", "dnsaddr"];
+
+/// How a protocol's value is encoded after its code.
+#[derive(Clone, Copy)]
+enum Value {
+    None,
+    Ip4,
+    Ip6,
+    Port,
+    /// Length-prefixed UTF-8 text, such as a DNS name.
+    Text,
+    /// Length-prefixed multihash, shown as base58btc.
+    PeerId,
+    /// Length-prefixed multihash, shown as base64url multibase.
+    CertHash,
+}
+
+const PROTOCOLS: &[(u64, &str, Value)] = &[
+    (4, "ip4", Value::Ip4),
+    (6, "tcp", Value::Port),
+    (41, "ip6", Value::Ip6),
+    (42, "ip6zone", Value::Text),
+    (53, "dns", Value::Text),
+    (54, "dns4", Value::Text),
+    (55, "dns6", Value::Text),
+    (56, "dnsaddr", Value::Text),
+    (132, "sctp", Value::Port),
+    (273, "udp", Value::Port),
+    (280, "webrtc-direct", Value::None),
+    (281, "webrtc", Value::None),
+    (290, "p2p-circuit", Value::None),
+    (421, "p2p", Value::PeerId),
+    (443, "https", Value::None),
+    (448, "tls", Value::None),
+    (449, "sni", Value::Text),
+    (454, "noise", Value::None),
+    (460, "quic", Value::None),
+    (461, "quic-v1", Value::None),
+    (465, "webtransport", Value::None),
+    (466, "certhash", Value::CertHash),
+    (477, "ws", Value::None),
+    (478, "wss", Value::None),
+    (480, "http", Value::None),
+];
+
+fn read_varint(buf: &mut &[u8]) -> Result<u64, String> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = buf.split_first().ok_or("truncated varint")?;
+        *buf = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("varint is too long".into())
+}
+
+fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+    if buf.len() < len {
+        return Err(format!("expected {len} bytes, {} left", buf.len()));
+    }
+    let (head, rest) = buf.split_at(len);
+    *buf = rest;
+    Ok(head)
+}
+
+/// Formats a binary multiaddr as text, e.g. `/ip4/1.2.3.4/tcp/30303`.
+pub(crate) fn to_text(mut buf: &[u8]) -> Result<String, String> {
+    if buf.is_empty() {
+        return Err("empty multiaddr".into());
+    }
+    let mut out = String::new();
+    while !buf.is_empty() {
+        let code = read_varint(&mut buf)?;
+        let &(_, name, value) = PROTOCOLS
+            .iter()
+            .find(|(c, _, _)| *c == code)
+            .ok_or_else(|| format!("unsupported multiaddr protocol code {code}"))?;
+        out.push('/');
+        out.push_str(name);
+        let text = match value {
+            Value::None => continue,
+            Value::Ip4 => {
+                Ipv4Addr::from(<[u8; 4]>::try_from(take(&mut buf, 4)?).unwrap()).to_string()
+            }
+            Value::Ip6 => {
+                Ipv6Addr::from(<[u8; 16]>::try_from(take(&mut buf, 16)?).unwrap()).to_string()
+            }
+            Value::Port => u16::from_be_bytes(take(&mut buf, 2)?.try_into().unwrap()).to_string(),
+            Value::Text | Value::PeerId | Value::CertHash => {
+                let len = usize::try_from(read_varint(&mut buf)?).map_err(|e| e.to_string())?;
+                let bytes = take(&mut buf, len)?;
+                match value {
+                    Value::Text => std::str::from_utf8(bytes)
+                        .map_err(|_| format!("/{name} value is not UTF-8"))?
+                        .to_string(),
+                    Value::PeerId => bs58::encode(bytes).into_string(),
+                    _ => format!("u{}", URL_SAFE_NO_PAD.encode(bytes)),
+                }
+            }
+        };
+        out.push('/');
+        out.push_str(&text);
+    }
+    Ok(out)
+}
+
+/// Parses a text multiaddr into its binary form.
+pub(crate) fn from_text(text: &str) -> Result<Vec<u8>, String> {
+    let rest = text
+        .strip_prefix('/')
+        .ok_or_else(|| format!("multiaddr {text:?} must start with '/'"))?;
+    let mut parts = rest.split('/');
+    let mut out = Vec::new();
+    while let Some(name) = parts.next() {
+        let &(code, _, value) = PROTOCOLS
+            .iter()
+            .find(|(_, n, _)| *n == name)
+            .ok_or_else(|| format!("unsupported multiaddr protocol {name:?}"))?;
+        write_varint(code, &mut out);
+        if let Value::None = value {
+            continue;
+        }
+        let arg = parts
+            .next()
+            .ok_or_else(|| format!("/{name} needs a value"))?;
+        let invalid = || format!("invalid /{name} value {arg:?}");
+        let bytes = match value {
+            Value::None => unreachable!(),
+            Value::Ip4 => {
+                out.extend(arg.parse::<Ipv4Addr>().map_err(|_| invalid())?.octets());
+                continue;
+            }
+            Value::Ip6 => {
+                out.extend(arg.parse::<Ipv6Addr>().map_err(|_| invalid())?.octets());
+                continue;
+            }
+            Value::Port => {
+                out.extend(arg.parse::<u16>().map_err(|_| invalid())?.to_be_bytes());
+                continue;
+            }
+            Value::Text if arg.is_empty() => return Err(invalid()),
+            Value::Text => arg.as_bytes().to_vec(),
+            Value::PeerId => bs58::decode(arg).into_vec().map_err(|_| invalid())?,
+            Value::CertHash => arg
+                .strip_prefix('u')
+                .and_then(|b| URL_SAFE_NO_PAD.decode(b).ok())
+                .ok_or_else(invalid)?,
+        };
+        write_varint(bytes.len() as u64, &mut out);
+        out.extend(bytes);
+    }
+    Ok(out)
+}
+
+/// How the list under the `multiaddrs` key is laid out.
+#[derive(Clone, Copy)]
+pub(crate) enum ListEncoding {
+    /// One byte string of binary multiaddrs, each prefixed by its length as
+    /// a big-endian `u16`, as Waku v2 (RFC 31) specifies.
+    LengthPrefixed,
+    /// An RLP list of binary multiaddrs.
+    Rlp,
+}
+
+impl ListEncoding {
+    pub(crate) fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "length-prefixed" => Ok(ListEncoding::LengthPrefixed),
+            "rlp" => Ok(ListEncoding::Rlp),
+            _ => Err(format!(
+                "unknown multiaddrs encoding {name:?}, expected \"length-prefixed\" or \"rlp\""
+            )),
+        }
+    }
+}
+
+/// Formats a multiaddr from a record as text, checking that it starts with
+/// a host.
+fn host_to_text(buf: &[u8]) -> Result<String, String> {
+    let text = to_text(buf)?;
+    let first = text[1..].split('/').next().unwrap_or_default();
+    if !HOST_PROTOCOLS.contains(&first) {
+        return Err(format!(
+            "multiaddr {text:?} must start with one of /{}",
+            HOST_PROTOCOLS.join(", /")
+        ));
+    }
+    Ok(text)
+}
+
+/// Decodes the length-prefixed multiaddrs in `value`.
+pub(crate) fn decode_length_prefixed(mut value: &[u8]) -> Result<Vec<String>, String> {
+    let mut out = Vec::new();
+    while !value.is_empty() {
+        let (len, rest) = value
+            .split_first_chunk::<2>()
+            .ok_or("truncated length prefix")?;
+        let len = usize::from(u16::from_be_bytes(*len));
+        if rest.len() < len {
+            return Err(format!("expected {len} bytes, {} left", rest.len()));
+        }
+        let (addr, rest) = rest.split_at(len);
+        out.push(host_to_text(addr).map_err(|e| format!("multiaddr {}: {e}", out.len()))?);
+        value = rest;
+    }
+    Ok(out)
+}
+
+/// Decodes a raw `multiaddrs` value in either layout: a byte string is
+/// length-prefixed, a list holds one multiaddr per item.
+pub(crate) fn decode_list(mut raw: &[u8]) -> Result<Vec<String>, String> {
+    let header = Header::decode(&mut raw).map_err(|e| e.to_string())?;
+    let mut payload = raw
+        .get(..header.payload_length)
+        .ok_or("truncated RLP value")?;
+    if !header.list {
+        return decode_length_prefixed(payload);
+    }
+    let mut out = Vec::new();
+    while !payload.is_empty() {
+        let addr = Header::decode_bytes(&mut payload, false)
+            .map_err(|_| format!("item {} is not a byte string", out.len()))?;
+        out.push(host_to_text(addr).map_err(|e| format!("multiaddr {}: {e}", out.len()))?);
+    }
+    Ok(out)
+}
+
+/// Encodes text multiaddrs as a `multiaddrs` value.
+pub(crate) fn encode_list(addrs: &[String], encoding: ListEncoding) -> Result<RlpValue, String> {
+    let mut binary = Vec::with_capacity(addrs.len());
+    for addr in addrs {
+        let bytes = from_text(addr)?;
+        host_to_text(&bytes)?;
+        binary.push(bytes);
+    }
+    Ok(match encoding {
+        ListEncoding::Rlp => RlpValue::List(binary.into_iter().map(RlpValue::String).collect()),
+        ListEncoding::LengthPrefixed => {
+            let mut out = Vec::new();
+            for (addr, bytes) in addrs.iter().zip(binary) {
+                let len = u16::try_from(bytes.len())
+                    .map_err(|_| format!("multiaddr {addr:?} is too long"))?;
+                out.extend(len.to_be_bytes());
+                out.extend(bytes);
+            }
+            RlpValue::String(out)
+        }
+    })
+}
@@ -0,0 +1,115 @@
+//! Classification of advertised addresses.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use enr::CombinedKey;
+
+/// Returns `true` if `ip` is publicly routable: not private (RFC 1918),
+/// loopback, link-local, shared CGNAT space (RFC 6598), documentation,
+/// benchmarking, multicast, broadcast or otherwise reserved.
+pub(crate) fn is_routable_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || a == 0
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || (a == 100 && (b & 0xc0) == 64)
+        || (a == 192 && b == 0 && c == 0)
+        || ip.is_documentation()
+        || (a == 198 && (b & 0xfe) == 18)
+        || ip.is_multicast()
+        || a >= 240)
+}
+
+/// Returns `true` if `ip` is a publicly routable IPv6 address. IPv4-mapped
+/// addresses are judged by the IPv4 address they carry.
+pub(crate) fn is_routable_v6(ip: Ipv6Addr) -> bool {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_routable_v4(v4);
+    }
+    let segments = ip.segments();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || (segments[0] & 0xfe00) == 0xfc00
+        || (segments[0] & 0xffc0) == 0xfe80
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8)
+        || (segments[0] == 0x0100 && segments[1..4] == [0, 0, 0]))
+}
+
+/// Returns `true` if the record advertises at least one routable address.
+pub(crate) fn has_routable_address(enr: &enr::Enr<CombinedKey>) -> bool {
+    enr.ip4().is_some_and(is_routable_v4) || enr.ip6().is_some_and(is_routable_v6)
+}
+
+/// The endpoints a record claims, as `(protocol, address)`: UDP then TCP on
+/// the IPv4 address, then the same on the IPv6 address.
+///
+/// With `fallback`, an IPv6 address without `udp6` or `tcp6` takes the
+/// `udp` or `tcp` port, as EIP-778 specifies; otherwise only the
+/// IPv6-specific ports apply to it.
+pub(crate) fn endpoints(
+    enr: &enr::Enr<CombinedKey>,
+    fallback: bool,
+) -> Vec<(&'static str, SocketAddr)> {
+    let ip4 = enr.ip4().map(IpAddr::V4);
+    let ip6 = enr.ip6().map(IpAddr::V6);
+    let (mut udp6, mut tcp6) = (enr.udp6(), enr.tcp6());
+    if fallback {
+        udp6 = udp6.or(enr.udp4());
+        tcp6 = tcp6.or(enr.tcp4());
+    }
+    [
+        ("udp", ip4, enr.udp4()),
+        ("tcp", ip4, enr.tcp4()),
+        ("udp", ip6, udp6),
+        ("tcp", ip6, tcp6),
+    ]
+    .into_iter()
+    .filter_map(|(protocol, ip, port)| Some((protocol, SocketAddr::new(ip?, port?))))
+    .collect()
+}
+
+/// Returns `true` unless the address is a placeholder: port 0, `0.0.0.0` or
+/// `::`, none of which can be dialed.
+pub(crate) fn is_dialable(addr: &SocketAddr) -> bool {
+    addr.port() != 0 && !addr.ip().is_unspecified()
+}
+
+/// Returns `true` if `ip` is only meaningful within one link or interface,
+/// so that a zone identifier can qualify it: unicast link-local (`fe80::/10`)
+/// or interface- or link-local multicast.
+fn is_scoped_v6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    (segments[0] & 0xffc0) == 0xfe80 || (ip.is_multicast() && matches!(segments[0] & 0x000f, 1 | 2))
+}
+
+/// Parses an IPv6 address for a record.
+///
+/// A zone identifier (`fe80::1%eth0`) names an interface on the local host
+/// and cannot be encoded, so it is stripped from scoped addresses and
+/// rejected on any other.
+pub(crate) fn parse_ip6(addr: &str) -> Result<Ipv6Addr, String> {
+    let (ip, zone) = match addr.split_once('%') {
+        Some((ip, zone)) => (ip, Some(zone)),
+        None => (addr, None),
+    };
+    let Ok(ip) = ip.parse::<Ipv6Addr>() else {
+        if ip.parse::<Ipv4Addr>().is_ok() {
+            return Err(format!(
+                "expected an IPv6 address, got IPv4 address {ip:?} \
+                 (write ::ffff:{ip} for an IPv4-mapped address)"
+            ));
+        }
+        return Err(format!("invalid IPv6 address {addr:?}"));
+    };
+    match zone {
+        None => Ok(ip),
+        Some("") => Err(format!("empty zone identifier in {addr:?}")),
+        Some(_) if is_scoped_v6(ip) => Ok(ip),
+        Some(zone) => Err(format!(
+            "zone identifier %{zone} in {addr:?} is only valid on a link-local address"
+        )),
+    }
+}
@@ -0,0 +1,76 @@
+//! The `opstack` field OP Stack consensus nodes add to their records.
+//!
+//! op-node stores the L2 chain id and a format version as two unsigned
+//! varints (LEB128) in one byte string, and ignores anything after them.
+
+use enr::CombinedKey;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::errors::FieldError;
+use crate::rlp;
+
+/// ENR key holding the OP Stack chain id and version.
+pub(crate) const OPSTACK_KEY: &str = "opstack";
+
+/// Reads one unsigned varint from the front of `data`.
+fn read_uvarint(data: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, &byte) in data.iter().enumerate().take(10) {
+        let bits = u64::from(byte & 0x7f);
+        if i == 9 && byte > 1 {
+            return None;
+        }
+        value |= bits << (7 * i);
+        if byte & 0x80 == 0 {
+            *data = &data[i + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// The decoded `opstack` field.
+#[pyclass(name = "OpStackInfo", frozen, eq, hash, get_all)]
+#[derive(PartialEq, Eq, Hash)]
+pub(crate) struct OpStackInfo {
+    /// The L2 chain id, such as 10 for OP Mainnet.
+    chain_id: u64,
+    /// Format version of the field, 0 so far.
+    version: u64,
+}
+
+impl OpStackInfo {
+    /// The record's `opstack` field, or a `FieldError` if it is malformed.
+    pub(crate) fn from_enr(enr: &enr::Enr<CombinedKey>) -> PyResult<Option<Self>> {
+        let Some(mut value) = rlp::string_value(enr, OPSTACK_KEY)? else {
+            return Ok(None);
+        };
+        let chain_id = read_uvarint(&mut value);
+        let version = read_uvarint(&mut value);
+        match (chain_id, version) {
+            (Some(chain_id), Some(version)) => Ok(Some(OpStackInfo { chain_id, version })),
+            _ => Err(FieldError::new_err(format!(
+                "key {OPSTACK_KEY:?} must hold two unsigned varints"
+            ))),
+        }
+    }
+}
+
+#[pymethods]
+impl OpStackInfo {
+    /// The fields as a dict.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let out = PyDict::new(py);
+        out.set_item("chain_id", self.chain_id)?;
+        out.set_item("version", self.version)?;
+        Ok(out)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "OpStackInfo(chain_id={}, version={})",
+            self.chain_id, self.version
+        )
+    }
+}
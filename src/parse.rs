@@ -0,0 +1,97 @@
+//! Telling which kind of node reference a piece of text is.
+
+use pyo3::prelude::*;
+
+use crate::{convert, decode_text, errors, Enr};
+
+const BASE58: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+fn is_hex(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// A libp2p peer id: base58 of an identity (secp256k1, ed25519) or
+/// sha256 multihash.
+fn is_peer_id(text: &str) -> bool {
+    let shaped = matches!(
+        (text.get(..8), text.get(..2), text.len()),
+        (Some("16Uiu2HA"), _, 53) | (Some("12D3KooW"), _, 52) | (_, Some("Qm"), 46)
+    );
+    shaped && text.chars().all(|c| BASE58.contains(c))
+}
+
+/// Names a format that is recognisably not a record, with the reason
+/// given in the error.
+fn unsupported(text: &str) -> Option<(&'static str, &'static str)> {
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    if text.starts_with("enode://") {
+        Some((
+            "enode",
+            "an enode:// URL names a node but carries no signed record; \
+             use Enode.from_url() to read it",
+        ))
+    } else if text.starts_with("enrtree://") {
+        Some((
+            "enrtree",
+            "an EIP-1459 enrtree:// link points at a DNS tree of records",
+        ))
+    } else if text.starts_with("enrtree-root:") || text.starts_with("enrtree-branch:") {
+        Some((
+            "enrtree",
+            "an EIP-1459 tree entry lists hashes, not records",
+        ))
+    } else if ["/ip4/", "/ip6/", "/dns", "/p2p/"]
+        .iter()
+        .any(|p| text.starts_with(p))
+    {
+        Some((
+            "multiaddr",
+            "a multiaddr holds an address but no signed record",
+        ))
+    } else if is_peer_id(text) {
+        Some((
+            "peer_id",
+            "a libp2p peer id is derived from a public key and holds no record",
+        ))
+    } else if text.starts_with('{') || text.starts_with('[') {
+        Some((
+            "json",
+            "this looks like JSON; pass the record text inside it",
+        ))
+    } else if is_hex(digits) && digits.len() == 64 {
+        Some((
+            "node_id",
+            "32 bytes of hex is a node id or hash, not a record",
+        ))
+    } else if is_hex(digits)
+        && (digits.len() == 66 && (digits.starts_with("02") || digits.starts_with("03"))
+            || digits.len() == 128
+            || digits.len() == 130 && digits.starts_with("04"))
+    {
+        Some(("public_key", "this is a secp256k1 public key, not a record"))
+    } else {
+        None
+    }
+}
+
+/// Implements `Enr.parse`.
+pub(crate) fn parse(py: Python<'_>, text: &str, strict: bool) -> PyResult<Enr> {
+    let text = text.trim();
+    if let Some((format, reason)) = unsupported(text) {
+        return Err(errors::unsupported_format_error(py, format, reason));
+    }
+    // Base64 of an encoded record starts with `-` or `+` (a long-list
+    // header), so bare hex of one cannot be mistaken for base64.
+    let list_header = text
+        .get(..2)
+        .is_some_and(|h| h.eq_ignore_ascii_case("f8") || h.eq_ignore_ascii_case("f9"));
+    let data = if is_hex(text) && list_header {
+        convert::from_hex(text).ok_or_else(|| errors::hex_error(py))?
+    } else {
+        decode_text(py, text)?
+    };
+    Enr::from_bytes(py, &data, strict, false)
+}
@@ -0,0 +1,180 @@
+//! The local record and key a discv5 node stores in its network directory,
+//! laid out as Lighthouse (and other users of Sigma Prime's `discv5` crate)
+//! does: `enr.dat` holds the record as base64 text, and `key` the raw 32-byte
+//! secp256k1 secret key.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use enr::{CombinedKey, EnrKey};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{decode_text, Enr, SigningKey};
+
+/// File name of the stored record.
+const ENR_FILENAME: &str = "enr.dat";
+/// File name of the stored secret key.
+const KEY_FILENAME: &str = "key";
+
+fn read_key(path: &Path) -> PyResult<Option<CombinedKey>> {
+    let mut bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    CombinedKey::secp256k1_from_bytes(&mut bytes)
+        .map(Some)
+        .map_err(|err| {
+            PyValueError::new_err(format!(
+                "{} is not a secp256k1 secret key: {err}",
+                path.display()
+            ))
+        })
+}
+
+fn read_record(py: Python<'_>, path: &Path) -> PyResult<Option<Enr>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let data = decode_text(py, &text)?;
+    Enr::from_bytes(py, &data, false, false).map(Some)
+}
+
+/// Writes `data` to `path` through a temporary file in the same directory, so
+/// that a reader sees either the old contents or the new, never a mix.
+fn write_atomic(path: &Path, data: &[u8], private: bool) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    let result = options.open(&tmp).and_then(|mut file| {
+        file.write_all(data)?;
+        file.sync_all()
+    });
+    match result.and_then(|()| fs::rename(&tmp, path)) {
+        Ok(()) => {
+            // Make the rename itself durable where directories can be synced.
+            if let Some(dir) = path.parent().and_then(|dir| File::open(dir).ok()) {
+                let _ = dir.sync_all();
+            }
+            Ok(())
+        }
+        Err(err) => {
+            let _ = fs::remove_file(&tmp);
+            Err(err)
+        }
+    }
+}
+
+/// Read the record and secret key a node stores in `network_dir` (such as
+/// Lighthouse's `beacon/network`), returning `(enr, key)`.
+///
+/// `key` is `None` if the directory has no key file. Raises `ValueError` if
+/// the key does not belong to the record, and `FileNotFoundError` if there
+/// is no `enr.dat`.
+#[pyfunction]
+pub(crate) fn load_node_enr(
+    py: Python<'_>,
+    network_dir: PathBuf,
+) -> PyResult<(Enr, Option<SigningKey>)> {
+    let enr_path = network_dir.join(ENR_FILENAME);
+    let Some(enr) = read_record(py, &enr_path)? else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no stored record at {}", enr_path.display()),
+        )
+        .into());
+    };
+    let key = read_key(&network_dir.join(KEY_FILENAME))?;
+    if let Some(key) = &key {
+        if key.public() != enr.inner.public_key() {
+            return Err(PyValueError::new_err(format!(
+                "the key in {} does not belong to the record in {}",
+                network_dir.join(KEY_FILENAME).display(),
+                enr_path.display()
+            )));
+        }
+    }
+    Ok((enr, key.map(SigningKey::new)))
+}
+
+/// Store `enr` as the local record of the node with `network_dir`, and
+/// `key` as its secret key if given.
+///
+/// Refuses, with `ValueError`, anything the node would reject or that would
+/// confuse its peers: a record not signed by the stored key, replacing the
+/// stored key with another, a non-secp256k1 `key`, or a record of the same
+/// node whose seq is not above the stored one (an identical record is left
+/// as it is). Files are replaced atomically, and the key is only readable by
+/// its owner.
+///
+/// Nodes read these files at startup, so stop the node first: a running
+/// node overwrites `enr.dat` whenever its record changes.
+#[pyfunction]
+#[pyo3(signature = (network_dir, enr, *, key=None))]
+pub(crate) fn save_node_enr(
+    py: Python<'_>,
+    network_dir: PathBuf,
+    enr: PyRef<'_, Enr>,
+    key: Option<PyRef<'_, SigningKey>>,
+) -> PyResult<()> {
+    let key_path = network_dir.join(KEY_FILENAME);
+    let enr_path = network_dir.join(ENR_FILENAME);
+    let public_key = enr.inner.public_key();
+
+    let secret = match key.as_deref().map(|key| &key.inner) {
+        Some(CombinedKey::Secp256k1(secret)) => Some(secret.to_bytes()),
+        Some(_) => {
+            return Err(PyValueError::new_err(
+                "node keys must be secp256k1 keys, got an ed25519 key",
+            ))
+        }
+        None => None,
+    };
+    if let Some(key) = &key {
+        if key.inner.public() != public_key {
+            return Err(PyValueError::new_err("the record is not signed by key"));
+        }
+    }
+    let stored_key = read_key(&key_path)?;
+    if let Some(stored) = &stored_key {
+        if stored.public() != public_key {
+            return Err(PyValueError::new_err(format!(
+                "the record does not belong to the key in {}",
+                key_path.display()
+            )));
+        }
+    }
+    let stored_enr = read_record(py, &enr_path)?;
+    if let Some(stored) = &stored_enr {
+        if stored.inner.node_id() == enr.inner.node_id()
+            && stored.inner != enr.inner
+            && enr.inner.seq() <= stored.inner.seq()
+        {
+            return Err(PyValueError::new_err(format!(
+                "seq {} is not above the stored record's seq {}",
+                enr.inner.seq(),
+                stored.inner.seq()
+            )));
+        }
+    }
+    if let (None, Some(secret)) = (&stored_key, &secret) {
+        write_atomic(&key_path, secret, true)?;
+    }
+    if stored_enr.is_none_or(|stored| stored.inner != enr.inner) {
+        write_atomic(&enr_path, enr.inner.to_base64().as_bytes(), false)?;
+    }
+    Ok(())
+}
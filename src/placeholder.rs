@@ -0,0 +1,130 @@
+//! Port 0 and the unspecified addresses `0.0.0.0` and `::`, which some
+//! records carry in place of a real endpoint.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::conformance::{self, Cursor, Violation, ViolationKind};
+
+/// What to do with placeholder values.
+#[derive(Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub(crate) enum Policy {
+    /// Treat them as ordinary values.
+    Allow,
+    /// Report them as `"placeholder"` violations when validating.
+    Flag,
+    /// Also refuse to decode records that have them.
+    Reject,
+    /// Show them as `None` from the address and port getters.
+    Unset,
+}
+
+const POLICIES: [(Policy, &str); 4] = [
+    (Policy::Allow, "allow"),
+    (Policy::Flag, "flag"),
+    (Policy::Reject, "reject"),
+    (Policy::Unset, "none"),
+];
+
+impl Policy {
+    pub(crate) fn parse(name: &str) -> PyResult<Self> {
+        POLICIES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(policy, _)| *policy)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "unknown placeholder policy {name:?}, expected \"allow\", \"flag\", \"reject\" or \"none\""
+                ))
+            })
+    }
+
+    fn name(self) -> &'static str {
+        POLICIES[self as usize].1
+    }
+
+    /// Whether validation reports placeholder values.
+    pub(crate) fn flags(self) -> bool {
+        matches!(self, Policy::Flag | Policy::Reject)
+    }
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(Policy::Allow as u8);
+
+pub(crate) fn policy() -> Policy {
+    POLICIES[usize::from(POLICY.load(Ordering::Relaxed))].0
+}
+
+/// Select how port 0, `0.0.0.0` and `::` are treated everywhere:
+/// `"allow"` (the default) as ordinary values, `"flag"` as `"placeholder"`
+/// violations in `validate()`, `"reject"` as those violations and as a
+/// `DecodeError` when decoding, or `"none"` as missing, so the address and
+/// port getters return `None`.
+#[pyfunction]
+pub(crate) fn set_placeholder_policy(policy: &str) -> PyResult<()> {
+    POLICY.store(Policy::parse(policy)? as u8, Ordering::Relaxed);
+    Ok(())
+}
+
+/// The placeholder policy currently in effect.
+#[pyfunction]
+pub(crate) fn get_placeholder_policy() -> &'static str {
+    policy().name()
+}
+
+/// `port`, or `None` if it is 0 and the policy is `"none"`.
+pub(crate) fn port(port: Option<u16>) -> Option<u16> {
+    port.filter(|p| *p != 0 || policy() != Policy::Unset)
+}
+
+/// `ip`, or `None` if it is `0.0.0.0` and the policy is `"none"`.
+pub(crate) fn ip4(ip: Option<Ipv4Addr>) -> Option<Ipv4Addr> {
+    ip.filter(|ip| !ip.is_unspecified() || policy() != Policy::Unset)
+}
+
+/// `ip`, or `None` if it is `::` and the policy is `"none"`.
+pub(crate) fn ip6(ip: Option<Ipv6Addr>) -> Option<Ipv6Addr> {
+    ip.filter(|ip| !ip.is_unspecified() || policy() != Policy::Unset)
+}
+
+/// A description of the value if `payload` is a placeholder for `key`.
+fn describe(key: &[u8], payload: &[u8]) -> Option<&'static str> {
+    let zero = payload.iter().all(|b| *b == 0);
+    match key {
+        b"ip" if payload.len() == 4 && zero => Some("0.0.0.0 is a placeholder, not an address"),
+        b"ip6" if payload.len() == 16 && zero => Some(":: is a placeholder, not an address"),
+        b"tcp" | b"udp" | b"quic" | b"tcp6" | b"udp6" | b"quic6" if zero => {
+            Some("port 0 is a placeholder, not a port")
+        }
+        _ => None,
+    }
+}
+
+/// `"placeholder"` violations for each placeholder value in an encoded record.
+pub(crate) fn check(data: &[u8]) -> Vec<Violation> {
+    let mut out = Vec::new();
+    let mut top = Cursor::new(data);
+    if let Ok(record) = top.next_item() {
+        let mut items = Cursor::inside(data, &record);
+        // Signature and seq.
+        let _ = (items.next_item(), items.next_item());
+        while let (Ok(key), Ok(value)) = (items.next_item(), items.next_item()) {
+            if value.list {
+                continue;
+            }
+            if let Some(message) = describe(key.payload, value.payload) {
+                out.push(conformance::violation(
+                    ViolationKind::Placeholder,
+                    &value,
+                    Some(&String::from_utf8_lossy(key.payload)),
+                    message.to_string(),
+                ));
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,19 @@
+//! Portal network conventions.
+//!
+//! Portal nodes advertise their client in the `c` key. uTP runs over the
+//! record's `udp` endpoint, and supported subnetworks and capabilities are
+//! negotiated in PING/PONG payloads rather than stored in the record.
+
+use enr::CombinedKey;
+
+use crate::rlp;
+
+/// ENR key holding the Portal client string, such as `"t 0.1.1"`.
+pub(crate) const PORTAL_CLIENT_KEY: &str = "c";
+
+/// The record's Portal client string, if present and valid UTF-8.
+pub(crate) fn client(enr: &enr::Enr<CombinedKey>) -> Option<&str> {
+    enr.get_raw_rlp(PORTAL_CLIENT_KEY)
+        .and_then(rlp::string_payload)
+        .and_then(|payload| std::str::from_utf8(payload).ok())
+}
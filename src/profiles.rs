@@ -0,0 +1,347 @@
+//! Network profiles: the keys a network adds to its records, bundled for
+//! typed access, validation and building.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use enr::CombinedKey;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyString};
+
+use crate::conformance::{self, Cursor, Violation, ViolationKind};
+use crate::keys::{self, ValueType};
+use crate::{eth, eth2, multiaddr, placeholder, portal, ssv, waku, Enr, EnrBuilder, SigningKey};
+
+/// Keys a network defines on top of EIP-778.
+pub(crate) struct ProfileSpec {
+    name: &'static str,
+    description: &'static str,
+    /// Keys the network defines, all in the known-key registry.
+    keys: &'static [&'static str],
+    /// Keys every record on the network carries.
+    required: &'static [&'static str],
+}
+
+const PROFILES: &[ProfileSpec] = &[
+    ProfileSpec {
+        name: "eth_consensus",
+        description: "Ethereum consensus layer",
+        keys: &[
+            eth2::ETH2_ENR_KEY,
+            "attnets",
+            "syncnets",
+            "cgc",
+            "nfd",
+            "quic",
+            "quic6",
+        ],
+        required: &[eth2::ETH2_ENR_KEY],
+    },
+    ProfileSpec {
+        name: "eth_execution",
+        description: "Ethereum execution layer (devp2p)",
+        keys: &[eth::ETH_ENR_KEY, "snap", "les"],
+        required: &[eth::ETH_ENR_KEY],
+    },
+    ProfileSpec {
+        name: "bsc",
+        description: "BNB Smart Chain execution layer",
+        keys: &[eth::ETH_ENR_KEY, "snap", eth::BSC_ENR_KEY],
+        required: &[eth::ETH_ENR_KEY, eth::BSC_ENR_KEY],
+    },
+    ProfileSpec {
+        name: "portal",
+        description: "Portal network",
+        keys: &[portal::PORTAL_CLIENT_KEY],
+        required: &[],
+    },
+    ProfileSpec {
+        name: "waku",
+        description: "Waku v2",
+        keys: &[waku::WAKU2_KEY, multiaddr::MULTIADDRS_KEY],
+        required: &[waku::WAKU2_KEY],
+    },
+    ProfileSpec {
+        name: "ssv",
+        description: "SSV network operators",
+        keys: &[
+            ssv::SUBNETS_KEY,
+            ssv::DOMAIN_TYPE_KEY,
+            ssv::NEXT_DOMAIN_TYPE_KEY,
+        ],
+        required: &[ssv::DOMAIN_TYPE_KEY],
+    },
+    ProfileSpec {
+        name: "op_stack",
+        description: "OP Stack consensus layer",
+        keys: &["opstack"],
+        required: &["opstack"],
+    },
+];
+
+/// Index into `PROFILES` of the module-wide default, or `NO_DEFAULT`.
+static DEFAULT: AtomicUsize = AtomicUsize::new(NO_DEFAULT);
+const NO_DEFAULT: usize = usize::MAX;
+
+fn lookup(name: &str) -> PyResult<&'static ProfileSpec> {
+    PROFILES.iter().find(|p| p.name == name).ok_or_else(|| {
+        let names: Vec<&str> = PROFILES.iter().map(|p| p.name).collect();
+        PyValueError::new_err(format!(
+            "unknown profile {name:?}, expected one of {names:?}"
+        ))
+    })
+}
+
+/// The profile selected by a `profile` argument: a `Profile`, a profile
+/// name, or `None` for the module-wide default (which may be unset).
+pub(crate) fn resolve(
+    profile: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Option<&'static ProfileSpec>> {
+    let Some(profile) = profile else {
+        return Ok(PROFILES.get(DEFAULT.load(Ordering::Relaxed)));
+    };
+    if let Ok(profile) = profile.cast::<Profile>() {
+        return Ok(Some(profile.get().spec));
+    }
+    if let Ok(name) = profile.cast::<PyString>() {
+        return lookup(name.to_str()?).map(Some);
+    }
+    Err(PyTypeError::new_err(format!(
+        "profile must be a Profile or a profile name, not {}",
+        profile.get_type().name()?
+    )))
+}
+
+/// Violations of `spec` in an encoded record: required keys that are
+/// missing, and fixed-length keys with the wrong length.
+pub(crate) fn check(spec: &ProfileSpec, data: &[u8]) -> Vec<Violation> {
+    let mut out = Vec::new();
+    let mut present: Vec<&[u8]> = Vec::new();
+    let mut top = Cursor::new(data);
+    if let Ok(record) = top.next_item() {
+        let mut items = Cursor::inside(data, &record);
+        // Signature and seq.
+        let _ = (items.next_item(), items.next_item());
+        while let (Ok(key), Ok(value)) = (items.next_item(), items.next_item()) {
+            present.push(key.payload);
+            let name = String::from_utf8_lossy(key.payload);
+            // The EIP-778 walk already checks the `eth2` length.
+            if !spec.keys.contains(&name.as_ref()) || name == eth2::ETH2_ENR_KEY {
+                continue;
+            }
+            if let Some(ValueType::Bytes(Some(len))) = keys::lookup(&name) {
+                if value.list || value.payload.len() != len {
+                    out.push(
+                        conformance::violation(
+                            ViolationKind::BadFieldLength,
+                            &value,
+                            Some(&name),
+                            format!("expected {len} bytes, got {}", value.payload.len()),
+                        )
+                        .lengths(len, value.payload.len()),
+                    );
+                }
+            }
+        }
+    }
+    for key in spec.required {
+        if !present.contains(&key.as_bytes()) {
+            let mut v = Violation::new(
+                ViolationKind::MissingKey,
+                0,
+                format!("{} records must have this key", spec.name),
+            );
+            v.key = Some(key.to_string());
+            out.push(v);
+        }
+    }
+    out
+}
+
+/// EIP-778 violations in an encoded record, plus those of `spec` if given.
+pub(crate) fn validate(
+    py: Python<'_>,
+    data: &[u8],
+    known_keys_only: bool,
+    spec: Option<&ProfileSpec>,
+    placeholders: bool,
+) -> PyResult<conformance::ValidationReport> {
+    let mut violations = conformance::check_with(data, known_keys_only);
+    if let Some(spec) = spec {
+        violations.extend(check(spec, data));
+    }
+    if placeholders {
+        violations.extend(placeholder::check(data));
+    }
+    violations.sort_by_key(|v| v.offset);
+    conformance::ValidationReport::new(py, violations)
+}
+
+/// A network's keys, bundled for typed access, validation and building.
+#[pyclass(name = "Profile", frozen, eq)]
+pub(crate) struct Profile {
+    spec: &'static ProfileSpec,
+}
+
+impl PartialEq for Profile {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.spec, other.spec)
+    }
+}
+
+impl Profile {
+    /// Fails unless `key` is one of the profile's keys.
+    fn check_key(&self, key: &str) -> PyResult<()> {
+        if self.spec.keys.contains(&key) {
+            Ok(())
+        } else {
+            Err(PyValueError::new_err(format!(
+                "key {key:?} is not part of the {} profile",
+                self.spec.name
+            )))
+        }
+    }
+
+    /// Encodes `str` values of text keys as UTF-8; other values are
+    /// passed on unchanged.
+    fn encode_value<'py>(
+        &self,
+        key: &str,
+        value: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.check_key(key)?;
+        match (keys::lookup(key), value.cast::<PyString>()) {
+            (Some(ValueType::Text), Ok(text)) => {
+                Ok(PyBytes::new(value.py(), text.to_str()?.as_bytes()).into_any())
+            }
+            _ => Ok(value.clone()),
+        }
+    }
+}
+
+/// The profile's keys present in `enr`, decoded to their Python values.
+pub(crate) fn fields<'py>(
+    py: Python<'py>,
+    spec: &ProfileSpec,
+    enr: &enr::Enr<CombinedKey>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let out = PyDict::new(py);
+    for key in spec.keys {
+        if let Some(raw) = enr.get_raw_rlp(key) {
+            out.set_item(key, keys::decode_value(py, key, raw)?)?;
+        }
+    }
+    Ok(out)
+}
+
+#[pymethods]
+impl Profile {
+    /// The profile called `name`, such as `"eth_consensus"`.
+    #[staticmethod]
+    fn get(name: &str) -> PyResult<Self> {
+        lookup(name).map(|spec| Profile { spec })
+    }
+
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.spec.name
+    }
+
+    #[getter]
+    fn description(&self) -> &'static str {
+        self.spec.description
+    }
+
+    /// Keys the network defines.
+    #[getter]
+    fn keys(&self) -> Vec<&'static str> {
+        self.spec.keys.to_vec()
+    }
+
+    /// Keys every record on the network must carry.
+    #[getter]
+    fn required(&self) -> Vec<&'static str> {
+        self.spec.required.to_vec()
+    }
+
+    /// The profile's keys present in `enr`, decoded by their registered type.
+    fn fields<'py>(&self, py: Python<'py>, enr: &Enr) -> PyResult<Bound<'py, PyDict>> {
+        fields(py, self.spec, &enr.inner)
+    }
+
+    /// Same as `enr.validate(profile=self)`.
+    fn validate(&self, py: Python<'_>, enr: &Enr) -> PyResult<conformance::ValidationReport> {
+        let data = alloy_rlp::encode(&enr.inner);
+        validate(
+            py,
+            &data,
+            false,
+            Some(self.spec),
+            placeholder::policy().flags(),
+        )
+    }
+
+    /// `builder.add(key, value)`, for one of the profile's keys; text keys
+    /// also accept a `str`.
+    fn add(
+        &self,
+        builder: &Bound<'_, EnrBuilder>,
+        key: &str,
+        value: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let value = self.encode_value(key, value)?;
+        builder.borrow_mut().add(key, &value)
+    }
+
+    /// `enr.set(key, value, signing_key)`, for one of the profile's keys;
+    /// text keys also accept a `str`.
+    fn set(
+        &self,
+        enr: &Bound<'_, Enr>,
+        key: &str,
+        value: &Bound<'_, PyAny>,
+        signing_key: &SigningKey,
+    ) -> PyResult<()> {
+        let value = self.encode_value(key, value)?;
+        enr.borrow_mut().set_kv(
+            enr.py(),
+            keys::KeyArg::Text(key.to_string()),
+            &value,
+            signing_key,
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Profile({:?})", self.spec.name)
+    }
+}
+
+/// Names of the built-in profiles.
+#[pyfunction]
+pub(crate) fn profiles() -> Vec<&'static str> {
+    PROFILES.iter().map(|p| p.name).collect()
+}
+
+/// Select the profile `Enr.validate()` and `Enr.profile_fields()` use when
+/// none is passed, or `None` to clear it.
+#[pyfunction]
+#[pyo3(signature = (profile))]
+pub(crate) fn set_default_profile(profile: Option<&Bound<'_, PyAny>>) -> PyResult<()> {
+    let index = match resolve(profile)? {
+        Some(spec) if profile.is_some() => PROFILES
+            .iter()
+            .position(|p| std::ptr::eq(p, spec))
+            .unwrap_or(NO_DEFAULT),
+        _ => NO_DEFAULT,
+    };
+    DEFAULT.store(index, Ordering::Relaxed);
+    Ok(())
+}
+
+/// The module-wide default profile, if one is set.
+#[pyfunction]
+pub(crate) fn get_default_profile() -> Option<Profile> {
+    PROFILES
+        .get(DEFAULT.load(Ordering::Relaxed))
+        .map(|spec| Profile { spec })
+}
@@ -0,0 +1,198 @@
+//! Progress reporting and cancellation of batch jobs.
+//!
+//! Workers count finished units with the GIL released. At most one of them
+//! at a time reattaches to call `progress(done, total)`, and only once
+//! [`REPORT_INTERVAL`] has passed since the previous call, so the callback
+//! costs next to nothing however small the units are.
+//!
+//! A job stops early when the callback raises, when its [`CancelToken`] is
+//! cancelled, or when a signal handler raises, such as the default one for
+//! Ctrl-C. Signal handlers only run on the main thread, which would
+//! otherwise sit blocked until the job is done, so [`Progress::run`] moves
+//! the job to a thread of its own and checks for signals while waiting.
+//! Workers notice at their next [`Progress::advance`], and the exception
+//! propagates from the batch call.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use pyo3::prelude::*;
+
+use crate::errors::CancelledError;
+
+/// Minimum time between two calls of the callback while a job runs.
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often the waiting thread checks for signals.
+const SIGNAL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Returned by [`Progress::advance`] once the job should stop; hand it to
+/// [`Progress::finish`] to get the reason.
+#[derive(Debug)]
+pub(crate) struct Stopped;
+
+/// A flag for stopping batch calls from another thread.
+///
+/// Pass it as `cancel=` to any number of calls; `cancel()` makes those
+/// running stop at their next checkpoint, and any started afterwards stop
+/// straight away, raising `CancelledError`.
+#[pyclass(name = "CancelToken", frozen)]
+pub(crate) struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl CancelToken {
+    #[new]
+    fn new() -> Self {
+        CancelToken {
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Request cancellation. It cannot be undone.
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether `cancel()` has been called.
+    #[getter]
+    fn cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn __repr__(&self) -> String {
+        let cancelled = if self.cancelled() { "True" } else { "False" };
+        format!("CancelToken(cancelled={cancelled})")
+    }
+}
+
+/// Counts the units of one batch job, reports them to the callback, and
+/// tells workers when to stop.
+pub(crate) struct Progress {
+    callback: Option<Py<PyAny>>,
+    cancel: Option<Arc<AtomicBool>>,
+    total: AtomicUsize,
+    done: AtomicUsize,
+    stopped: AtomicBool,
+    last_report: Mutex<Instant>,
+    error: Mutex<Option<PyErr>>,
+}
+
+impl Progress {
+    pub(crate) fn new(
+        callback: Option<Py<PyAny>>,
+        cancel: Option<Py<CancelToken>>,
+        total: usize,
+    ) -> Self {
+        Progress {
+            callback,
+            cancel: cancel.map(|token| Arc::clone(&token.get().cancelled)),
+            total: AtomicUsize::new(total),
+            done: AtomicUsize::new(0),
+            stopped: AtomicBool::new(false),
+            last_report: Mutex::new(Instant::now()),
+            error: Mutex::new(None),
+        }
+    }
+
+    /// Returns `Err` if the job should stop.
+    pub(crate) fn check(&self) -> Result<(), Stopped> {
+        if self.stopped.load(Ordering::Relaxed) {
+            return Err(Stopped);
+        }
+        match &self.cancel {
+            Some(cancelled) if cancelled.load(Ordering::Relaxed) => {
+                Err(self.stop(CancelledError::new_err("operation cancelled")))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts `n` more units as done, calling the callback if it is due.
+    ///
+    /// Must be called without the GIL held.
+    pub(crate) fn advance(&self, n: usize) -> Result<(), Stopped> {
+        self.check()?;
+        let Some(callback) = &self.callback else {
+            return Ok(());
+        };
+        self.done.fetch_add(n, Ordering::Relaxed);
+        // Whoever holds the lock is already reporting.
+        let Ok(mut last_report) = self.last_report.try_lock() else {
+            return Ok(());
+        };
+        if last_report.elapsed() < REPORT_INTERVAL {
+            return Ok(());
+        }
+        let done = self.done.load(Ordering::Relaxed);
+        let total = self.total.load(Ordering::Relaxed);
+        let result = Python::attach(|py| callback.call1(py, (done, total)).map(drop));
+        *last_report = Instant::now();
+        result.map_err(|err| self.stop(err))
+    }
+
+    /// Counts `n` more units to do, for jobs that find their work as they go.
+    #[cfg_attr(not(feature = "dns"), allow(dead_code))]
+    pub(crate) fn add_total(&self, n: usize) {
+        self.total.fetch_add(n, Ordering::Relaxed);
+    }
+
+    fn stop(&self, err: PyErr) -> Stopped {
+        self.stopped.store(true, Ordering::Relaxed);
+        self.error
+            .lock()
+            .expect("progress lock poisoned")
+            .get_or_insert(err);
+        Stopped
+    }
+
+    /// Runs `job` on a thread of its own with the GIL released, checking for
+    /// signals until it returns.
+    pub(crate) fn run<R, F>(&self, py: Python<'_>, job: F) -> R
+    where
+        R: Send,
+        F: FnOnce() -> R + Send,
+    {
+        py.detach(|| {
+            std::thread::scope(|scope| {
+                let (sender, receiver) = mpsc::channel();
+                let worker = scope.spawn(move || {
+                    // Sending only fails if the waiting side has panicked.
+                    let _ = sender.send(job());
+                });
+                loop {
+                    match receiver.recv_timeout(SIGNAL_INTERVAL) {
+                        Ok(value) => return value,
+                        Err(RecvTimeoutError::Timeout) => {
+                            if let Err(err) = Python::attach(|py| py.check_signals()) {
+                                self.stop(err);
+                            }
+                        }
+                        Err(RecvTimeoutError::Disconnected) => match worker.join() {
+                            Err(panic) => std::panic::resume_unwind(panic),
+                            Ok(()) => unreachable!("the job sends its value before exiting"),
+                        },
+                    }
+                }
+            })
+        })
+    }
+
+    /// Ends the job: raises why it stopped, if it did, or else reports the
+    /// final count and returns the job's outcome.
+    pub(crate) fn finish<T>(self, py: Python<'_>, outcome: Result<T, Stopped>) -> PyResult<T> {
+        // Also catches a token cancelled before the job reached a checkpoint.
+        let _ = self.check();
+        if let Some(err) = self.error.into_inner().expect("progress lock poisoned") {
+            return Err(err);
+        }
+        let value = outcome.expect("jobs only stop for a reason");
+        if let Some(callback) = &self.callback {
+            callback.call1(py, (self.done.into_inner(), self.total.into_inner()))?;
+        }
+        Ok(value)
+    }
+}
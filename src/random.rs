@@ -0,0 +1,193 @@
+//! Random, valid records for property-based tests.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use alloy_rlp::Bytes;
+use enr::CombinedKey;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::rlp::MAX_ENR_SIZE;
+use crate::{errors, eth2, Enr, SigningKey};
+
+/// Key of the filler field that brings a record close to the size limit.
+const PAD_KEY: &str = "pad";
+
+/// Fields `generate_random_enr` can fill, by ENR key.
+const FIELDS: &[&str] = &[
+    "ip",
+    "ip6",
+    "tcp",
+    "udp",
+    "tcp6",
+    "udp6",
+    "quic",
+    "quic6",
+    eth2::ETH2_ENR_KEY,
+    "attnets",
+    "syncnets",
+    "cgc",
+    PAD_KEY,
+];
+
+/// A port, biased towards the ends of the range.
+fn port(rng: &mut StdRng) -> u16 {
+    match rng.gen_range(0..8) {
+        0 => 1,
+        1 => u16::MAX,
+        _ => rng.gen_range(1..=u16::MAX),
+    }
+}
+
+/// A sequence number, biased towards the ends of the range.
+fn seq(rng: &mut StdRng) -> u64 {
+    match rng.gen_range(0..8) {
+        0 => 1,
+        1 => u64::MAX,
+        _ => rng.gen_range(1..1 << 32),
+    }
+}
+
+fn signing_key(rng: &mut StdRng) -> CombinedKey {
+    loop {
+        let mut secret: [u8; 32] = rng.gen();
+        let key = if rng.gen() {
+            CombinedKey::secp256k1_from_bytes(&mut secret)
+        } else {
+            CombinedKey::ed25519_from_bytes(&mut secret)
+        };
+        // Only a secp256k1 secret of zero or above the curve order fails.
+        if let Ok(key) = key {
+            return key;
+        }
+    }
+}
+
+fn random_bytes(rng: &mut StdRng, len: usize) -> Vec<u8> {
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+/// A random value for `field`, RLP-encoded.
+fn field_value(rng: &mut StdRng, field: &str) -> Bytes {
+    let value = match field {
+        "ip" => alloy_rlp::encode(Ipv4Addr::from(rng.gen::<u32>()).octets().as_slice()),
+        "ip6" => alloy_rlp::encode(Ipv6Addr::from(rng.gen::<u128>()).octets().as_slice()),
+        "tcp" | "udp" | "tcp6" | "udp6" | "quic" | "quic6" => alloy_rlp::encode(port(rng)),
+        "eth2" => alloy_rlp::encode(random_bytes(rng, 16).as_slice()),
+        "attnets" => alloy_rlp::encode(random_bytes(rng, 8).as_slice()),
+        "syncnets" => alloy_rlp::encode(random_bytes(rng, 1).as_slice()),
+        "cgc" => alloy_rlp::encode(rng.gen_range(0u64..=128)),
+        _ => unreachable!("fields are checked against FIELDS"),
+    };
+    value.into()
+}
+
+fn build(
+    seq: u64,
+    entries: &[(&str, Bytes)],
+    key: &CombinedKey,
+) -> Result<enr::Enr<CombinedKey>, enr::Error> {
+    let mut builder = enr::Enr::builder();
+    builder.seq(seq);
+    for (field, value) in entries {
+        builder.add_value_rlp(field, value.clone());
+    }
+    builder.build(key)
+}
+
+/// Builds the record with a `pad` field as long as still fits, leaving
+/// between 0 and 3 bytes of the size limit unused.
+///
+/// The builder reserves a few bytes of headroom, so the filler is inserted
+/// into a record built one seq lower, which counts the size exactly.
+fn build_padded(
+    rng: &mut StdRng,
+    seq: u64,
+    entries: &[(&str, Bytes)],
+    key: &CombinedKey,
+) -> Result<enr::Enr<CombinedKey>, enr::Error> {
+    let base = build(seq - 1, entries, key)?;
+    let limit = MAX_ENR_SIZE - rng.gen_range(0..=3);
+    let padding = random_bytes(rng, MAX_ENR_SIZE);
+    // The filler's key and header take at least 5 bytes; shrink from there
+    // until the list header has settled too.
+    let mut len = limit.saturating_sub(base.size() + 5);
+    loop {
+        let mut padded = base.clone();
+        match padded.insert(PAD_KEY, &&padding[..len], key) {
+            Ok(_) if padded.size() <= limit => return Ok(padded),
+            Ok(_) | Err(enr::Error::ExceedsMaxSize) if len > 0 => len -= 1,
+            Ok(_) => return Ok(padded),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Generate a valid, signed record with a random combination of fields.
+///
+/// `rng_seed` makes the output reproducible. `fields` lists the ENR keys to
+/// fill, from `ip`, `ip6`, `tcp`, `udp`, `tcp6`, `udp6`, `quic`, `quic6`,
+/// `eth2`, `attnets`, `syncnets`, `cgc` and `pad`; by default each is
+/// included at random. `pad` adds a filler field that brings the record to
+/// within 3 bytes of the 300-byte limit. Ports and seq favour the extremes of
+/// their range. The record is signed with `key` if given, otherwise with a
+/// random secp256k1 or ed25519 key drawn from the same generator.
+#[pyfunction]
+#[pyo3(signature = (rng_seed=None, fields=None, *, key=None))]
+pub(crate) fn generate_random_enr(
+    rng_seed: Option<u64>,
+    fields: Option<Vec<String>>,
+    key: Option<&SigningKey>,
+) -> PyResult<Enr> {
+    let mut rng = match rng_seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let fields: Vec<&str> = match &fields {
+        Some(fields) => {
+            for field in fields {
+                if !FIELDS.contains(&field.as_str()) {
+                    return Err(PyValueError::new_err(format!(
+                        "cannot generate field {field:?}, expected one of {FIELDS:?}"
+                    )));
+                }
+            }
+            fields.iter().map(String::as_str).collect()
+        }
+        None => FIELDS
+            .iter()
+            .copied()
+            .filter(|f| {
+                if *f == PAD_KEY {
+                    rng.gen_ratio(1, 4)
+                } else {
+                    rng.gen()
+                }
+            })
+            .collect(),
+    };
+
+    let generated;
+    let signing_key = match key {
+        Some(key) => &key.inner,
+        None => {
+            generated = signing_key(&mut rng);
+            &generated
+        }
+    };
+    let seq = seq(&mut rng);
+    let entries: Vec<(&str, Bytes)> = fields
+        .iter()
+        .filter(|f| **f != PAD_KEY)
+        .map(|f| (*f, field_value(&mut rng, f)))
+        .collect();
+    let inner = if fields.contains(&PAD_KEY) {
+        build_padded(&mut rng, seq, &entries, signing_key)
+    } else {
+        build(seq, &entries, signing_key)
+    }
+    .map_err(errors::record_error)?;
+    Ok(Enr { inner })
+}
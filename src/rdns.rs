@@ -0,0 +1,108 @@
+//! Reverse DNS (PTR) lookups of advertised addresses.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::progress::{CancelToken, Progress};
+use crate::{batch, Enr};
+
+/// The host name for `ip` from the system resolver, without a trailing dot.
+#[cfg(unix)]
+fn lookup(ip: IpAddr) -> Option<String> {
+    use std::ffi::CStr;
+    use std::mem;
+
+    // NI_MAXHOST on glibc and the BSDs.
+    let mut host = [0 as libc::c_char; 1025];
+    // SAFETY: an all-zero sockaddr_storage is valid, and the address written
+    // into it matches the family and length passed to getnameinfo.
+    let rc = unsafe {
+        let mut storage: libc::sockaddr_storage = mem::zeroed();
+        let len = match ip {
+            IpAddr::V4(ip) => {
+                let sin = &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in);
+                sin.sin_family = libc::AF_INET as libc::sa_family_t;
+                sin.sin_addr.s_addr = u32::from_ne_bytes(ip.octets());
+                mem::size_of::<libc::sockaddr_in>()
+            }
+            IpAddr::V6(ip) => {
+                let sin6 = &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in6);
+                sin6.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+                sin6.sin6_addr.s6_addr = ip.octets();
+                mem::size_of::<libc::sockaddr_in6>()
+            }
+        };
+        libc::getnameinfo(
+            &storage as *const _ as *const libc::sockaddr,
+            len as libc::socklen_t,
+            host.as_mut_ptr(),
+            host.len() as libc::socklen_t,
+            std::ptr::null_mut(),
+            0,
+            libc::NI_NAMEREQD,
+        )
+    };
+    if rc != 0 {
+        return None;
+    }
+    // SAFETY: on success getnameinfo wrote a NUL-terminated string.
+    let name = unsafe { CStr::from_ptr(host.as_ptr()) };
+    name.to_str()
+        .ok()
+        .map(|name| name.trim_end_matches('.').to_string())
+}
+
+#[cfg(not(unix))]
+fn lookup(_ip: IpAddr) -> Option<String> {
+    unreachable!("reverse_dns refuses to run without a Unix resolver")
+}
+
+/// Look up the PTR record of every address the records advertise.
+///
+/// Returns a dict from each distinct `ip4` and `ip6` address, as text, to its
+/// host name, or `None` if it has none or the lookup failed. Lookups go
+/// through the system resolver, `concurrency` at a time, with the GIL
+/// released; each address is looked up once however many records share it.
+/// `progress(done, total)`, if given, counts addresses looked up, and
+/// `cancel` or Ctrl-C stop the call once the lookups under way are done.
+#[pyfunction]
+#[pyo3(signature = (enrs, *, concurrency=32, progress=None, cancel=None))]
+pub(crate) fn reverse_dns(
+    py: Python<'_>,
+    enrs: Vec<PyRef<'_, Enr>>,
+    concurrency: usize,
+    progress: Option<Py<PyAny>>,
+    cancel: Option<Py<CancelToken>>,
+) -> PyResult<BTreeMap<String, Option<String>>> {
+    if cfg!(not(unix)) {
+        return Err(pyo3::exceptions::PyOSError::new_err(
+            "reverse DNS lookups are only supported on Unix systems",
+        ));
+    }
+    if concurrency == 0 {
+        return Err(PyValueError::new_err("concurrency must be at least 1"));
+    }
+    let ips: BTreeSet<IpAddr> = enrs
+        .iter()
+        .flat_map(|e| [e.inner.ip4().map(IpAddr::V4), e.inner.ip6().map(IpAddr::V6)])
+        .flatten()
+        .collect();
+    drop(enrs);
+    let progress = Progress::new(progress, cancel, ips.len());
+    let names = progress.run(py, || {
+        batch::with_io_pool(concurrency, || {
+            ips.par_iter()
+                .map(|ip| {
+                    progress.check()?;
+                    let name = lookup(*ip);
+                    progress.advance(1).map(|()| (ip.to_string(), name))
+                })
+                .collect()
+        })
+    })?;
+    progress.finish(py, names)
+}
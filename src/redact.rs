@@ -0,0 +1,127 @@
+//! Records with their network addresses taken out, for publishing datasets.
+
+use alloy_rlp::{Encodable, Header};
+use enr::CombinedKey;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+use crate::{errors, keys, SigningKey};
+
+/// Keys removed when `drop` is not given.
+pub(crate) const DEFAULT_DROP: &[&str] = &["ip", "ip6", "udp", "tcp"];
+
+/// An RLP value of the same shape as `raw` with no information in it: port
+/// 0 for integers, an empty list for lists, and zero bytes of the same
+/// length (such as `0.0.0.0`) for byte strings.
+fn zeroed(key: &str, raw: &[u8]) -> Vec<u8> {
+    if raw.first().is_some_and(|b| *b >= 0xc0) {
+        return vec![0xc0];
+    }
+    if matches!(
+        keys::lookup(key),
+        Some(keys::ValueType::Port | keys::ValueType::Uint)
+    ) {
+        return vec![0x80];
+    }
+    let len = Header::decode(&mut &raw[..]).map_or(0, |h| h.payload_length);
+    let mut out = Vec::with_capacity(len + 3);
+    vec![0u8; len].as_slice().encode(&mut out);
+    out
+}
+
+/// The record's content with `drop` removed, or zeroed if `zero`.
+fn content(enr: &enr::Enr<CombinedKey>, drop: &[String], zero: bool) -> Vec<(Vec<u8>, Vec<u8>)> {
+    enr.iter()
+        .filter_map(|(key, value)| {
+            let dropped = drop.iter().any(|d| d.as_bytes() == key.as_slice());
+            match (dropped, zero) {
+                (false, _) => Some((key.clone(), value.to_vec())),
+                (true, true) => {
+                    let value = zeroed(&String::from_utf8_lossy(key), value);
+                    Some((key.clone(), value))
+                }
+                (true, false) => None,
+            }
+        })
+        .collect()
+}
+
+/// Re-signs a copy of `enr` without the addresses in `drop`, one seq higher.
+fn resign(
+    enr: &enr::Enr<CombinedKey>,
+    drop: &[String],
+    zero: bool,
+    signing_key: &SigningKey,
+) -> PyResult<enr::Enr<CombinedKey>> {
+    for key in drop {
+        if matches!(key.as_str(), "id" | "secp256k1" | "ed25519") {
+            return Err(PyValueError::new_err(format!(
+                "cannot drop {key:?} from a signed record"
+            )));
+        }
+    }
+    let seq = enr
+        .seq()
+        .checked_add(1)
+        .ok_or_else(errors::seq_overflow_error)?;
+    let key = &signing_key.inner;
+    let mut out = enr.clone();
+    let removed = drop
+        .iter()
+        .filter(|d| !zero && enr.get_raw_rlp(d).is_some());
+    out.remove_insert(removed, std::iter::empty::<(&[u8], &[u8])>(), key)
+        .map_err(errors::record_error)?;
+    if zero {
+        for (name, value) in content(enr, drop, true) {
+            if drop.iter().any(|d| d.as_bytes() == name.as_slice()) {
+                out.insert_raw_rlp(name, value.into(), key)
+                    .map_err(errors::record_error)?;
+            }
+        }
+    }
+    // Each change above bumped the seq; publish them as one update.
+    out.set_seq(seq, key).map_err(errors::record_error)?;
+    Ok(out)
+}
+
+/// `redacted()` without a signing key: the seq, node id and the remaining
+/// key/value pairs (values RLP-encoded, as `items()` gives them).
+fn unsigned<'py>(
+    py: Python<'py>,
+    enr: &enr::Enr<CombinedKey>,
+    drop: &[String],
+    zero: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let pairs = PyDict::new(py);
+    for (key, value) in content(enr, drop, zero) {
+        pairs.set_item(
+            String::from_utf8_lossy(&key).into_owned(),
+            PyBytes::new(py, &value),
+        )?;
+    }
+    let out = PyDict::new(py);
+    out.set_item("seq", enr.seq())?;
+    out.set_item("node_id", PyBytes::new(py, &enr.node_id().raw()))?;
+    out.set_item("content", pairs)?;
+    Ok(out)
+}
+
+/// Implements `Enr.redacted()`: an `Enr` if `signing_key` is given,
+/// otherwise a dict.
+pub(crate) fn redacted(
+    py: Python<'_>,
+    enr: &enr::Enr<CombinedKey>,
+    drop: Option<Vec<String>>,
+    signing_key: Option<&SigningKey>,
+    zero: bool,
+) -> PyResult<Py<PyAny>> {
+    let drop = drop.unwrap_or_else(|| DEFAULT_DROP.iter().map(|k| k.to_string()).collect());
+    match signing_key {
+        Some(key) => {
+            let inner = resign(enr, &drop, zero, key)?;
+            Ok(Py::new(py, crate::Enr { inner })?.into_any())
+        }
+        None => Ok(unsigned(py, enr, &drop, zero)?.into_any().unbind()),
+    }
+}
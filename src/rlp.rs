@@ -0,0 +1,221 @@
+//! Helpers for working directly with the RLP encoding of a record.
+//!
+//! The `enr` crate only exposes encoding through `Encodable`, which always
+//! builds an intermediate buffer. These helpers compute lengths up front so the
+//! encoding can be written straight into a Python-owned buffer.
+
+use alloy_rlp::{BufMut, Encodable, Header};
+use enr::CombinedKey;
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyByteArray, PyBytes, PyInt, PyList, PyTuple};
+
+use crate::errors::FieldError;
+
+/// Maximum size of an encoded record, as set by EIP-778.
+pub(crate) const MAX_ENR_SIZE: usize = 300;
+
+/// Length of the list payload: signature, sequence number and all key/value pairs.
+pub(crate) fn payload_len(enr: &enr::Enr<CombinedKey>) -> usize {
+    let mut len = enr.signature().length() + enr.seq().length();
+    for (k, v) in enr.iter() {
+        len += k.as_slice().length() + v.len();
+    }
+    len
+}
+
+/// Total length of the RLP-encoded record, including the list header.
+pub(crate) fn encoded_len(enr: &enr::Enr<CombinedKey>) -> usize {
+    let payload_length = payload_len(enr);
+    Header {
+        list: true,
+        payload_length,
+    }
+    .length_with_payload()
+}
+
+/// Total length of the record after `insert`-ing a value of `value_len` encoded
+/// bytes under `key`, including the sequence number bump every mutation makes.
+pub(crate) fn encoded_len_after_insert(
+    enr: &enr::Enr<CombinedKey>,
+    key: &[u8],
+    value_len: usize,
+) -> usize {
+    let key_len = key.length();
+    let old_entry = enr.get_raw_rlp(key).map_or(0, |v| key_len + v.len());
+    let seq = enr.seq();
+    let payload_length = payload_len(enr) - seq.length() + seq.saturating_add(1).length()
+        - old_entry
+        + key_len
+        + value_len;
+    Header {
+        list: true,
+        payload_length,
+    }
+    .length_with_payload()
+}
+
+/// Writes the RLP encoding of the record into `out` without an intermediate buffer.
+pub(crate) fn encode_into(enr: &enr::Enr<CombinedKey>, out: &mut dyn BufMut) {
+    Header {
+        list: true,
+        payload_length: payload_len(enr),
+    }
+    .encode(out);
+    enr.signature().encode(out);
+    enr.seq().encode(out);
+    for (k, v) in enr.iter() {
+        k.as_slice().encode(out);
+        out.put_slice(v);
+    }
+}
+
+/// The content a record's signature covers: the RLP list of its sequence
+/// number and key/value pairs, without the signature.
+pub(crate) fn signed_content(enr: &enr::Enr<CombinedKey>) -> Vec<u8> {
+    let payload_length = payload_len(enr) - enr.signature().length();
+    let mut out = Vec::with_capacity(payload_length + 3);
+    Header {
+        list: true,
+        payload_length,
+    }
+    .encode(&mut out);
+    enr.seq().encode(&mut out);
+    for (k, v) in enr.iter() {
+        k.as_slice().encode(&mut out);
+        out.put_slice(v);
+    }
+    out
+}
+
+/// Strips the RLP string header from a raw value, returning the payload.
+pub(crate) fn string_payload(mut raw: &[u8]) -> Option<&[u8]> {
+    Header::decode_bytes(&mut raw, false).ok()
+}
+
+/// The payload of the byte string stored under `key`, or a `FieldError` if
+/// it holds a list.
+pub(crate) fn string_value<'a>(
+    enr: &'a enr::Enr<CombinedKey>,
+    key: &str,
+) -> PyResult<Option<&'a [u8]>> {
+    enr.get_raw_rlp(key)
+        .map(|raw| {
+            string_payload(raw).ok_or_else(|| {
+                FieldError::new_err(format!("key {key:?} holds a list, not a byte string"))
+            })
+        })
+        .transpose()
+}
+
+/// Decodes an RLP item into nested Python lists of `bytes`.
+pub(crate) fn to_python<'py>(py: Python<'py>, mut raw: &[u8]) -> PyResult<Bound<'py, PyAny>> {
+    let item = decode_item(py, &mut raw)?;
+    if !raw.is_empty() {
+        return Err(FieldError::new_err("trailing bytes after RLP item"));
+    }
+    Ok(item)
+}
+
+fn decode_item<'py>(py: Python<'py>, buf: &mut &[u8]) -> PyResult<Bound<'py, PyAny>> {
+    let header = Header::decode(buf).map_err(|e| FieldError::new_err(e.to_string()))?;
+    let (payload, rest) = buf.split_at(header.payload_length);
+    *buf = rest;
+    if !header.list {
+        return Ok(PyBytes::new(py, payload).into_any());
+    }
+    let list = PyList::empty(py);
+    let mut payload = payload;
+    while !payload.is_empty() {
+        list.append(decode_item(py, &mut payload)?)?;
+    }
+    Ok(list.into_any())
+}
+
+/// A value given to `set()` or `add()`: a byte string, or a list of values.
+#[derive(Clone)]
+pub(crate) enum RlpValue {
+    String(Vec<u8>),
+    List(Vec<RlpValue>),
+}
+
+impl RlpValue {
+    /// Converts `bytes`, `bytearray`, a non-negative `int` (as its minimal
+    /// big-endian encoding) or a (nested) `list`/`tuple` of these.
+    pub(crate) fn from_py(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(bytes) = value.cast::<PyBytes>() {
+            return Ok(RlpValue::String(bytes.as_bytes().to_vec()));
+        }
+        if let Ok(bytes) = value.cast::<PyByteArray>() {
+            return Ok(RlpValue::String(bytes.to_vec()));
+        }
+        if let Ok(n) = value.cast::<PyInt>() {
+            if n.lt(0)? {
+                return Err(FieldError::new_err(format!(
+                    "cannot encode negative integer {n}"
+                )));
+            }
+            let len = n
+                .call_method0("bit_length")?
+                .extract::<usize>()?
+                .div_ceil(8);
+            let bytes = n.call_method1("to_bytes", (len, "big"))?;
+            return Ok(RlpValue::String(bytes.extract()?));
+        }
+        if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+            return value
+                .try_iter()?
+                .map(|item| RlpValue::from_py(&item?))
+                .collect::<PyResult<_>>()
+                .map(RlpValue::List);
+        }
+        Err(PyTypeError::new_err(format!(
+            "value must be bytes, int or a list of them, got {}",
+            value.get_type().name()?
+        )))
+    }
+
+    /// The payload if this is a byte string.
+    pub(crate) fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            RlpValue::String(bytes) => Some(bytes),
+            RlpValue::List(_) => None,
+        }
+    }
+
+    fn payload_length(&self) -> usize {
+        match self {
+            RlpValue::String(bytes) => bytes.as_slice().length(),
+            RlpValue::List(items) => items.iter().map(Encodable::length).sum(),
+        }
+    }
+}
+
+impl Encodable for RlpValue {
+    fn encode(&self, out: &mut dyn BufMut) {
+        match self {
+            RlpValue::String(bytes) => bytes.as_slice().encode(out),
+            RlpValue::List(items) => {
+                Header {
+                    list: true,
+                    payload_length: self.payload_length(),
+                }
+                .encode(out);
+                for item in items {
+                    item.encode(out);
+                }
+            }
+        }
+    }
+
+    fn length(&self) -> usize {
+        match self {
+            RlpValue::String(_) => self.payload_length(),
+            RlpValue::List(_) => Header {
+                list: true,
+                payload_length: self.payload_length(),
+            }
+            .length_with_payload(),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Best-effort decoding of malformed records for forensic analysis.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use alloy_rlp::{Decodable, Header};
+use enr::{CombinedKey, EnrPublicKey, NodeId};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::conformance::{self, Cursor, PyViolation, Violation, ViolationKind};
+use crate::{crypto, rlp, Enr};
+
+/// Everything that could be recovered from the input bytes.
+struct Salvaged {
+    seq: Option<u64>,
+    signature: Option<Vec<u8>>,
+    /// Content entries in input order, values as raw RLP (header included).
+    entries: Vec<(String, Vec<u8>)>,
+    signature_valid: bool,
+    record: Option<enr::Enr<CombinedKey>>,
+    problems: Vec<Violation>,
+}
+
+fn salvage(data: &[u8]) -> Salvaged {
+    let mut out = Salvaged {
+        seq: None,
+        signature: None,
+        entries: Vec::new(),
+        signature_valid: false,
+        record: enr::Enr::<CombinedKey>::decode(&mut &data[..]).ok(),
+        problems: conformance::check(data),
+    };
+
+    let mut top = Cursor::new(data);
+    let Some(list) = top.next_item_truncated().filter(|item| item.list) else {
+        return out;
+    };
+    let mut items = Cursor::inside(data, &list);
+
+    let Some(signature) = items.next_item_truncated() else {
+        return out;
+    };
+    out.signature = Some(signature.payload.to_vec());
+
+    // The signed content is the list of everything after the signature.
+    let content_start = items.pos();
+    if let Some(seq) = items.next_item_truncated() {
+        if seq.payload.len() <= 8 {
+            out.seq = Some(
+                seq.payload
+                    .iter()
+                    .fold(0u64, |n, b| (n << 8) | u64::from(*b)),
+            );
+        }
+    }
+    while !items.is_empty() {
+        let Some(key) = items.next_item_truncated() else {
+            break;
+        };
+        let Some(value) = items.next_item_truncated() else {
+            break;
+        };
+        let key = String::from_utf8_lossy(key.payload).into_owned();
+        if !out.entries.iter().any(|(k, _)| *k == key) {
+            out.entries.push((key, value.raw(data).to_vec()));
+        }
+    }
+    let content = &data[content_start..items.pos()];
+
+    let scheme = out
+        .entries
+        .iter()
+        .find(|(k, _)| k == "id")
+        .and_then(|(_, v)| rlp::string_payload(v));
+    if out.record.is_some() {
+        out.signature_valid = true;
+    } else if scheme.is_some_and(|id| id != b"v4") {
+        // Signatures under an unknown identity scheme cannot be checked; the
+        // scheme itself is already reported as a problem.
+    } else {
+        let public_key = out.entries.iter().find_map(|(k, v)| {
+            rlp::string_payload(v).and_then(|pk| crypto::decode_public_key(k, pk))
+        });
+        match public_key {
+            Some(public_key) => {
+                let mut msg = Vec::with_capacity(content.len() + 3);
+                Header {
+                    list: true,
+                    payload_length: content.len(),
+                }
+                .encode(&mut msg);
+                msg.extend_from_slice(content);
+                out.signature_valid = public_key.verify_v4(&msg, signature.payload);
+                if !out.signature_valid {
+                    out.problems.push(Violation::new(
+                        ViolationKind::InvalidSignature,
+                        signature.offset,
+                        "signature does not verify against the record's public key",
+                    ));
+                }
+            }
+            None => {
+                let reported = out
+                    .problems
+                    .iter()
+                    .any(|p| p.kind == ViolationKind::BadPublicKey);
+                if !reported {
+                    out.problems.push(Violation::new(
+                        ViolationKind::BadPublicKey,
+                        content_start,
+                        "record has no usable public key",
+                    ));
+                }
+            }
+        }
+    }
+    out.problems.sort_by_key(|v| v.offset);
+    out
+}
+
+/// A record recovered from malformed bytes, with the problems found.
+///
+/// Holds whatever could be read even when the record does not decode: the
+/// content entries up to the first unreadable item, the sequence number and
+/// the signature. This is also how records under an identity scheme other
+/// than `v4` are represented, since their signatures cannot be verified.
+#[pyclass(name = "SalvagedEnr", frozen)]
+pub(crate) struct SalvagedEnr {
+    seq: Option<u64>,
+    signature: Option<Vec<u8>>,
+    entries: Vec<(String, Vec<u8>)>,
+    signature_valid: bool,
+    record: Option<Py<Enr>>,
+    problems: Vec<Py<PyViolation>>,
+}
+
+impl SalvagedEnr {
+    pub(crate) fn from_bytes(py: Python<'_>, data: &[u8]) -> PyResult<Self> {
+        let salvaged = salvage(data);
+        Ok(SalvagedEnr {
+            seq: salvaged.seq,
+            signature: salvaged.signature,
+            entries: salvaged.entries,
+            signature_valid: salvaged.signature_valid,
+            record: salvaged
+                .record
+                .map(|inner| Py::new(py, Enr { inner }))
+                .transpose()?,
+            problems: salvaged
+                .problems
+                .into_iter()
+                .map(|v| Py::new(py, PyViolation::from(v)))
+                .collect::<PyResult<_>>()?,
+        })
+    }
+
+    /// Payload of the value stored under `key`, if it is a byte string.
+    fn payload(&self, key: &str) -> Option<&[u8]> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .and_then(|(_, v)| rlp::string_payload(v))
+    }
+
+    /// Port under `key`; leading zero bytes are tolerated since they are
+    /// already reported as problems.
+    fn port(&self, key: &str) -> Option<u16> {
+        let payload = self.payload(key)?;
+        let digits = &payload[payload.iter().take_while(|b| **b == 0).count()..];
+        if digits.len() > 2 {
+            return None;
+        }
+        Some(digits.iter().fold(0u16, |n, b| (n << 8) | u16::from(*b)))
+    }
+
+    fn public_key_entry(&self) -> Option<(&str, &[u8])> {
+        ["secp256k1", "ed25519"]
+            .into_iter()
+            .find_map(|k| self.payload(k).map(|v| (k, v)))
+    }
+}
+
+#[pymethods]
+impl SalvagedEnr {
+    /// The fully decoded record, or `None` if the input did not decode.
+    #[getter]
+    fn record(&self, py: Python<'_>) -> Option<Py<Enr>> {
+        self.record.as_ref().map(|r| r.clone_ref(py))
+    }
+
+    /// Every problem found, in byte order.
+    #[getter]
+    fn problems(&self, py: Python<'_>) -> Vec<Py<PyViolation>> {
+        self.problems.iter().map(|p| p.clone_ref(py)).collect()
+    }
+
+    #[getter]
+    fn signature_valid(&self) -> bool {
+        self.signature_valid
+    }
+
+    #[getter]
+    fn seq(&self) -> Option<u64> {
+        self.seq
+    }
+
+    #[getter]
+    fn signature<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyBytes>> {
+        self.signature.as_ref().map(|s| PyBytes::new(py, s))
+    }
+
+    #[getter]
+    fn public_key<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyBytes>> {
+        self.public_key_entry().map(|(_, v)| PyBytes::new(py, v))
+    }
+
+    /// `True` only if the input decoded into a fully verified `Enr`.
+    #[getter]
+    pub(crate) fn is_verified(&self) -> bool {
+        self.record.is_some()
+    }
+
+    /// Node id derived from the public key, if it parses. Only defined for
+    /// the `v4` identity scheme.
+    #[getter]
+    fn node_id<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyBytes>> {
+        if self.payload("id") != Some(b"v4") {
+            return None;
+        }
+        let (key, value) = self.public_key_entry()?;
+        let public_key = crypto::decode_public_key(key, value)?;
+        Some(PyBytes::new(py, &NodeId::from(public_key).raw()))
+    }
+
+    #[getter]
+    fn identity_scheme(&self) -> Option<String> {
+        self.payload("id")
+            .map(|id| String::from_utf8_lossy(id).into_owned())
+    }
+
+    #[getter]
+    fn ip4(&self) -> Option<String> {
+        let octets = <[u8; 4]>::try_from(self.payload("ip")?).ok()?;
+        Some(Ipv4Addr::from(octets).to_string())
+    }
+
+    #[getter]
+    fn ip6(&self) -> Option<String> {
+        let octets = <[u8; 16]>::try_from(self.payload("ip6")?).ok()?;
+        Some(Ipv6Addr::from(octets).to_string())
+    }
+
+    #[getter]
+    fn tcp4(&self) -> Option<u16> {
+        self.port("tcp")
+    }
+
+    #[getter]
+    fn tcp6(&self) -> Option<u16> {
+        self.port("tcp6")
+    }
+
+    #[getter]
+    fn udp4(&self) -> Option<u16> {
+        self.port("udp")
+    }
+
+    #[getter]
+    fn udp6(&self) -> Option<u16> {
+        self.port("udp6")
+    }
+
+    /// Keys recovered from the record, in input order.
+    fn keys(&self) -> Vec<String> {
+        self.entries.iter().map(|(k, _)| k.clone()).collect()
+    }
+
+    /// Raw RLP-encoded value for a key, including the RLP length prefix.
+    fn get_raw_rlp<'py>(&self, py: Python<'py>, key: &str) -> Option<Bound<'py, PyBytes>> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| PyBytes::new(py, v))
+    }
+
+    fn __repr__(&self) -> String {
+        let codes: Vec<&str> = self.problems.iter().map(|p| p.get().code()).collect();
+        format!(
+            "SalvagedEnr(keys={:?}, signature_valid={}, problems={codes:?})",
+            self.keys(),
+            if self.signature_valid {
+                "True"
+            } else {
+                "False"
+            }
+        )
+    }
+}
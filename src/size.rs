@@ -0,0 +1,83 @@
+//! Where the bytes of an encoded record go.
+
+use alloy_rlp::{Encodable, Header};
+use enr::CombinedKey;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::rlp::{self, MAX_ENR_SIZE};
+
+/// The encoded size of a record split by part, as returned by
+/// `Enr.size_breakdown`.
+///
+/// `header + signature + seq + sum(fields.values())` equals `total`.
+#[pyclass(name = "SizeBreakdown", frozen, get_all)]
+pub(crate) struct SizeBreakdown {
+    /// Encoded size of the whole record.
+    total: usize,
+    /// Bytes left before the 300-byte limit; negative only for records
+    /// decoded from non-conforming input.
+    remaining: isize,
+    /// The RLP list header.
+    header: usize,
+    /// The encoded signature.
+    signature: usize,
+    /// The encoded sequence number.
+    seq: usize,
+    /// Each key's share in key order: its encoded key plus encoded value.
+    fields: Py<PyDict>,
+}
+
+impl SizeBreakdown {
+    pub(crate) fn of(py: Python<'_>, enr: &enr::Enr<CombinedKey>) -> PyResult<Self> {
+        let fields = PyDict::new(py);
+        for (key, value) in enr.iter() {
+            fields.set_item(
+                String::from_utf8_lossy(key),
+                key.as_slice().length() + value.len(),
+            )?;
+        }
+        let payload_length = rlp::payload_len(enr);
+        let header = Header {
+            list: true,
+            payload_length,
+        }
+        .length();
+        let total = header + payload_length;
+        Ok(SizeBreakdown {
+            total,
+            remaining: MAX_ENR_SIZE as isize - total as isize,
+            header,
+            signature: enr.signature().length(),
+            seq: enr.seq().length(),
+            fields: fields.unbind(),
+        })
+    }
+}
+
+#[pymethods]
+impl SizeBreakdown {
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let fields = self
+            .fields
+            .bind(py)
+            .iter()
+            .map(|(k, v)| {
+                Ok(format!(
+                    "{}={}",
+                    k.extract::<String>()?,
+                    v.extract::<usize>()?
+                ))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(format!(
+            "SizeBreakdown(total={}, remaining={}, header={}, signature={}, seq={}, fields=[{}])",
+            self.total,
+            self.remaining,
+            self.header,
+            self.signature,
+            self.seq,
+            fields.join(", ")
+        ))
+    }
+}
@@ -0,0 +1,53 @@
+//! Fields SSV network operator nodes add to their records: the `subnets`
+//! they subscribe to and the `domaintype` of the network they are on.
+
+use enr::CombinedKey;
+use pyo3::prelude::*;
+
+use crate::bitvector;
+use crate::errors::FieldError;
+use crate::rlp;
+
+/// ENR key holding the subscribed subnets as a bitvector.
+pub(crate) const SUBNETS_KEY: &str = "subnets";
+
+/// ENR key holding the network's current domain type.
+pub(crate) const DOMAIN_TYPE_KEY: &str = "domaintype";
+
+/// ENR key holding the domain type of the next network fork.
+pub(crate) const NEXT_DOMAIN_TYPE_KEY: &str = "next_domaintype";
+
+/// Number of subnets, and so bits in the `subnets` bitvector.
+pub(crate) const SUBNET_COUNT: usize = 128;
+
+/// Indices of the subnets in the record's `subnets` bitvector.
+pub(crate) fn subnets(enr: &enr::Enr<CombinedKey>) -> PyResult<Option<Vec<usize>>> {
+    let Some(bits) = rlp::string_value(enr, SUBNETS_KEY)? else {
+        return Ok(None);
+    };
+    if bits.len() != SUBNET_COUNT / 8 {
+        return Err(FieldError::new_err(format!(
+            "key {SUBNETS_KEY:?} must be {} bytes, got {}",
+            SUBNET_COUNT / 8,
+            bits.len()
+        )));
+    }
+    Ok(Some(bitvector::indices(bits)))
+}
+
+/// The 4-byte domain type under `key`, which SSV nodes store as a minimally
+/// encoded big-endian integer.
+pub(crate) fn domain_type(enr: &enr::Enr<CombinedKey>, key: &str) -> PyResult<Option<[u8; 4]>> {
+    let Some(value) = rlp::string_value(enr, key)? else {
+        return Ok(None);
+    };
+    if value.len() > 4 {
+        return Err(FieldError::new_err(format!(
+            "key {key:?} must be at most 4 bytes, got {}",
+            value.len()
+        )));
+    }
+    let mut domain = [0u8; 4];
+    domain[4 - value.len()..].copy_from_slice(value);
+    Ok(Some(domain))
+}
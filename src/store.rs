@@ -0,0 +1,279 @@
+//! Persistent storage of the latest record per node in SQLite.
+
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use alloy_rlp::Decodable;
+use enr::CombinedKey;
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+
+use crate::bloom::NodeIdArg;
+use crate::{errors, eth2, hashing, rlp, Enr};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS records (
+        node_id BLOB PRIMARY KEY NOT NULL,
+        seq BLOB NOT NULL,
+        ip4 TEXT,
+        ip6 TEXT,
+        fork_digest BLOB,
+        rlp BLOB NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS records_seq ON records (seq);
+    CREATE INDEX IF NOT EXISTS records_ip4 ON records (ip4);
+    CREATE INDEX IF NOT EXISTS records_ip6 ON records (ip6);
+    CREATE INDEX IF NOT EXISTS records_fork_digest ON records (fork_digest);
+";
+
+/// Inserts a record, or replaces the stored one only if the new seq is higher.
+const UPSERT: &str = "
+    INSERT INTO records (node_id, seq, ip4, ip6, fork_digest, rlp)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+    ON CONFLICT (node_id) DO UPDATE SET
+        seq = excluded.seq,
+        ip4 = excluded.ip4,
+        ip6 = excluded.ip6,
+        fork_digest = excluded.fork_digest,
+        rlp = excluded.rlp
+    WHERE excluded.seq > records.seq
+";
+
+/// SQLite integers are signed 64-bit, so the seq is stored as 8 big-endian
+/// bytes, which compare in the same order as the number.
+fn seq_key(seq: u64) -> [u8; 8] {
+    seq.to_be_bytes()
+}
+
+fn store_error(err: rusqlite::Error) -> PyErr {
+    PyOSError::new_err(format!("record store: {err}"))
+}
+
+fn upsert(conn: &Connection, enr: &enr::Enr<CombinedKey>) -> rusqlite::Result<bool> {
+    let mut data = Vec::with_capacity(rlp::encoded_len(enr));
+    rlp::encode_into(enr, &mut data);
+    let changed = conn.prepare_cached(UPSERT)?.execute(params![
+        enr.node_id().raw(),
+        seq_key(enr.seq()),
+        enr.ip4().map(|ip| ip.to_string()),
+        enr.ip6().map(|ip| ip.to_string()),
+        eth2::fork_digest(enr),
+        data,
+    ])?;
+    Ok(changed > 0)
+}
+
+/// Decodes stored bytes; they were written by this module, so a failure means
+/// the database was modified by something else.
+fn decode(data: &[u8]) -> PyResult<Enr> {
+    let inner = enr::Enr::<CombinedKey>::decode(&mut &data[..]).map_err(errors::decode_error)?;
+    Ok(Enr { inner })
+}
+
+/// Records kept in a SQLite database, the latest version of each node's.
+///
+/// Adding a record replaces the stored one for the same node only if its
+/// seq is higher. The node id, seq, IPv4 and IPv6 address and `eth2` fork
+/// digest are indexed for `find`.
+#[pyclass(name = "EnrStore", frozen)]
+pub(crate) struct EnrStore {
+    path: String,
+    /// `None` once closed.
+    conn: Mutex<Option<Connection>>,
+}
+
+impl EnrStore {
+    fn lock(&self) -> MutexGuard<'_, Option<Connection>> {
+        self.conn.lock().expect("store lock poisoned")
+    }
+
+    /// Runs `f` on the open connection, or raises `ValueError` if closed.
+    fn with_conn<T>(&self, f: impl FnOnce(&mut Connection) -> PyResult<T>) -> PyResult<T> {
+        match self.lock().as_mut() {
+            Some(conn) => f(conn),
+            None => Err(PyValueError::new_err("record store is closed")),
+        }
+    }
+
+    /// [`with_conn`](Self::with_conn) with the GIL released. The lock is
+    /// taken and given back while detached, so a thread waiting for it never
+    /// holds the GIL this thread needs to reattach.
+    fn with_conn_detached<T: Send>(
+        &self,
+        py: Python<'_>,
+        f: impl FnOnce(&mut Connection) -> rusqlite::Result<T> + Send,
+    ) -> PyResult<T> {
+        py.detach(|| self.with_conn(|conn| f(conn).map_err(store_error)))
+    }
+}
+
+#[pymethods]
+impl EnrStore {
+    /// Open or create the database at `path` (`":memory:"` for one that is
+    /// not saved).
+    #[new]
+    fn new(path: PathBuf) -> PyResult<Self> {
+        let conn = Connection::open(&path).map_err(store_error)?;
+        conn.execute_batch(SCHEMA).map_err(store_error)?;
+        Ok(EnrStore {
+            path: path.display().to_string(),
+            conn: Mutex::new(Some(conn)),
+        })
+    }
+
+    /// Store `enr` unless the store already holds a record for the node
+    /// with the same or a higher seq.
+    ///
+    /// Returns `True` if the record was stored.
+    fn upsert(&self, enr: &Enr) -> PyResult<bool> {
+        self.with_conn(|conn| upsert(conn, &enr.inner).map_err(store_error))
+    }
+
+    /// `upsert` each record in one transaction, returning how many were
+    /// stored.
+    fn upsert_many(&self, py: Python<'_>, enrs: Vec<PyRef<'_, Enr>>) -> PyResult<usize> {
+        let records: Vec<_> = enrs.iter().map(|e| e.inner.clone()).collect();
+        drop(enrs);
+        self.with_conn_detached(py, move |conn| {
+            let tx = conn.transaction()?;
+            let mut stored = 0;
+            for enr in &records {
+                stored += usize::from(upsert(&tx, enr)?);
+            }
+            tx.commit()?;
+            Ok(stored)
+        })
+    }
+
+    /// The stored record for a node id (or for the node of an `Enr`), or
+    /// `None`.
+    fn get(&self, node_id: NodeIdArg<'_>) -> PyResult<Option<Enr>> {
+        let node_id = node_id.node_id()?;
+        let data: Option<Vec<u8>> = self.with_conn(|conn| {
+            conn.prepare_cached("SELECT rlp FROM records WHERE node_id = ?1")
+                .and_then(|mut stmt| stmt.query_row([node_id], |row| row.get(0)).optional())
+                .map_err(store_error)
+        })?;
+        data.as_deref().map(decode).transpose()
+    }
+
+    /// Delete the record for a node id, returning `True` if there was one.
+    fn remove(&self, node_id: NodeIdArg<'_>) -> PyResult<bool> {
+        let node_id = node_id.node_id()?;
+        self.with_conn(|conn| {
+            conn.execute("DELETE FROM records WHERE node_id = ?1", [node_id])
+                .map(|n| n > 0)
+                .map_err(store_error)
+        })
+    }
+
+    /// Stored records matching every filter given, in node id order.
+    ///
+    /// `ip` matches the IPv4 or IPv6 address, by its family, `fork_digest` the
+    /// 4-byte digest of the `eth2` field, and `min_seq` records with at least
+    /// that seq. With no filters, every record is returned.
+    #[pyo3(signature = (*, ip=None, fork_digest=None, min_seq=None, limit=None))]
+    fn find(
+        &self,
+        py: Python<'_>,
+        ip: Option<&str>,
+        fork_digest: Option<&[u8]>,
+        min_seq: Option<u64>,
+        limit: Option<usize>,
+    ) -> PyResult<Vec<Enr>> {
+        let mut sql = String::from("SELECT rlp FROM records WHERE 1");
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        if let Some(ip) = ip {
+            let ip: std::net::IpAddr = ip
+                .parse()
+                .map_err(|_| PyValueError::new_err(format!("invalid IP address {ip:?}")))?;
+            sql.push_str(if ip.is_ipv4() {
+                " AND ip4 = ?"
+            } else {
+                " AND ip6 = ?"
+            });
+            values.push(ip.to_string().into());
+        }
+        if let Some(digest) = fork_digest {
+            if digest.len() != 4 {
+                return Err(PyValueError::new_err(format!(
+                    "fork digest must be 4 bytes, got {}",
+                    digest.len()
+                )));
+            }
+            sql.push_str(" AND fork_digest = ?");
+            values.push(digest.to_vec().into());
+        }
+        if let Some(seq) = min_seq {
+            sql.push_str(" AND seq >= ?");
+            values.push(seq_key(seq).to_vec().into());
+        }
+        sql.push_str(" ORDER BY node_id");
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {limit}"));
+        }
+        let rows: Vec<Vec<u8>> = self.with_conn_detached(py, move |conn| {
+            let mut stmt = conn.prepare_cached(&sql)?;
+            let rows = stmt.query_map(params_from_iter(values), |row| row.get(0))?;
+            rows.collect::<rusqlite::Result<_>>()
+        })?;
+        rows.iter().map(|data| decode(data)).collect()
+    }
+
+    /// Close the database; further use raises `ValueError`.
+    fn close(&self) -> PyResult<()> {
+        match self.lock().take() {
+            Some(conn) => conn.close().map_err(|(_, err)| store_error(err)),
+            None => Ok(()),
+        }
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+
+    /// `records_digest()` of the stored records, as an ETag for the store's
+    /// contents.
+    fn digest(&self, py: Python<'_>) -> PyResult<String> {
+        let pairs = self.with_conn_detached(py, |conn| {
+            let mut stmt = conn.prepare_cached("SELECT node_id, seq FROM records")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, [u8; 32]>(0)?, row.get::<_, [u8; 8]>(1)?))
+            })?;
+            rows.map(|row| row.map(|(node_id, seq)| (node_id, u64::from_be_bytes(seq))))
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?;
+        Ok(py.detach(|| hashing::set_digest(pairs)))
+    }
+
+    fn __contains__(&self, node_id: NodeIdArg<'_>) -> PyResult<bool> {
+        let node_id = node_id.node_id()?;
+        self.with_conn(|conn| {
+            conn.prepare_cached("SELECT 1 FROM records WHERE node_id = ?1")
+                .and_then(|mut stmt| stmt.exists([node_id]))
+                .map_err(store_error)
+        })
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        self.with_conn(|conn| {
+            conn.query_row("SELECT COUNT(*) FROM records", [], |row| row.get(0))
+                .map_err(store_error)
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("EnrStore({:?})", self.path)
+    }
+}
@@ -0,0 +1,634 @@
+//! EIP-1459 node lists: the TXT entries of a DNS tree, and building and
+//! signing a tree for publishing.
+//!
+//! A tree is a root entry at the domain itself, naming the hashes of two
+//! subtrees, one of records and one of links to other trees, and signed by
+//! the tree's secp256k1 key. Every other entry lives at `<hash>.<domain>`,
+//! where the hash is the base32 of the first 16 bytes of the keccak256 of
+//! its text, and is a branch listing child hashes, a record or a link.
+//! Trees are built as go-ethereum's `dnsdisc` builds them, so a tree of the
+//! same records and links gets the same hashes from either.
+
+use std::collections::BTreeMap;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use enr::CombinedKey;
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
+use k256::ecdsa::{Signature, VerifyingKey};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use sha3::{Digest, Keccak256};
+
+use crate::errors::DecodeError;
+use crate::{decode_text, Enr, SigningKey};
+
+const ROOT_PREFIX: &str = "enrtree-root:v1";
+const BRANCH_PREFIX: &str = "enrtree-branch:";
+const LINK_PREFIX: &str = "enrtree://";
+const RECORD_PREFIX: &str = "enr:";
+
+/// Bytes of keccak256 an entry's name keeps.
+const HASH_LEN: usize = 16;
+/// Child hashes per branch, so that a branch fits in 370 bytes of TXT data:
+/// each takes 26 base32 characters and a comma.
+const MAX_CHILDREN: usize = 370 / (26 + 1);
+/// Length of a recoverable secp256k1 signature: `r || s || v`.
+const SIGNATURE_LEN: usize = 65;
+
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// RFC 4648 base32 without padding.
+fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &byte in data {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(char::from(BASE32[(buffer >> bits) as usize & 31]));
+        }
+    }
+    if bits > 0 {
+        out.push(char::from(BASE32[(buffer << (5 - bits)) as usize & 31]));
+    }
+    out
+}
+
+/// Inverse of [`base32_encode`]; `None` for other characters, impossible
+/// lengths, or nonzero trailing bits.
+fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    if matches!(text.len() % 8, 1 | 3 | 6) {
+        return None;
+    }
+    let mut out = Vec::with_capacity(text.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in text.bytes() {
+        let value = BASE32.iter().position(|&b| b == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    (buffer & ((1 << bits) - 1) == 0).then_some(out)
+}
+
+/// Whether `text` can name an entry: base32 of 12 to 32 bytes, as
+/// go-ethereum accepts.
+fn is_valid_hash(text: &str) -> bool {
+    base32_decode(text).is_some_and(|bytes| (12..=32).contains(&bytes.len()))
+}
+
+/// The name of the entry with `text` under the tree's domain.
+pub(crate) fn hash(text: &str) -> String {
+    base32_encode(&Keccak256::digest(text.as_bytes())[..HASH_LEN])
+}
+
+/// Whether `text` is the entry `name` stands for: `name` decodes to a
+/// prefix of its keccak256.
+#[cfg_attr(not(feature = "dns"), allow(dead_code))]
+pub(crate) fn matches_hash(name: &str, text: &str) -> bool {
+    base32_decode(name)
+        .is_some_and(|prefix| Keccak256::digest(text.as_bytes()).starts_with(&prefix))
+}
+
+/// The root entry: the hashes of the record and link subtrees, the tree's
+/// seq, and the signature over them.
+#[pyclass(name = "EnrTreeRoot", frozen, eq, hash, skip_from_py_object)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Root {
+    pub(crate) enr_root: String,
+    pub(crate) link_root: String,
+    pub(crate) seq: u64,
+    signature: [u8; SIGNATURE_LEN],
+}
+
+impl Root {
+    /// The text the signature covers.
+    fn signed_text(enr_root: &str, link_root: &str, seq: u64) -> String {
+        format!("{ROOT_PREFIX} e={enr_root} l={link_root} seq={seq}")
+    }
+
+    fn parse(text: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = text.split(' ').collect();
+        let [ROOT_PREFIX, e, l, seq, sig] = fields[..] else {
+            return Err(format!(
+                "expected \"{ROOT_PREFIX} e=<hash> l=<hash> seq=<seq> sig=<signature>\""
+            ));
+        };
+        let field = |item: &'_ str, name: &str| {
+            item.strip_prefix(name)
+                .map(str::to_owned)
+                .ok_or_else(|| format!("expected {name}<...>, got {item:?}"))
+        };
+        let (enr_root, link_root) = (field(e, "e=")?, field(l, "l=")?);
+        for hash in [&enr_root, &link_root] {
+            if !is_valid_hash(hash) {
+                return Err(format!("invalid subtree hash {hash:?}"));
+            }
+        }
+        let seq = field(seq, "seq=")?
+            .parse()
+            .map_err(|_| format!("invalid seq in {seq:?}"))?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(field(sig, "sig=")?)
+            .ok()
+            .and_then(|sig| <[u8; SIGNATURE_LEN]>::try_from(sig).ok())
+            .ok_or("signature must be 65 bytes of unpadded base64url")?;
+        Ok(Root {
+            enr_root,
+            link_root,
+            seq,
+            signature,
+        })
+    }
+
+    fn sign(enr_root: String, link_root: String, seq: u64, key: &k256::ecdsa::SigningKey) -> Self {
+        let digest = Keccak256::digest(Self::signed_text(&enr_root, &link_root, seq));
+        let (signature, recovery_id) = key
+            .sign_prehash_recoverable(&digest)
+            .expect("a 32-byte prehash can always be signed");
+        let mut bytes = [0u8; SIGNATURE_LEN];
+        bytes[..64].copy_from_slice(&signature.to_bytes());
+        bytes[64] = recovery_id.to_byte();
+        Root {
+            enr_root,
+            link_root,
+            seq,
+            signature: bytes,
+        }
+    }
+
+    pub(crate) fn verify(&self, key: &VerifyingKey) -> bool {
+        let digest =
+            Keccak256::digest(Self::signed_text(&self.enr_root, &self.link_root, self.seq));
+        Signature::from_slice(&self.signature[..64])
+            .is_ok_and(|signature| key.verify_prehash(&digest, &signature).is_ok())
+    }
+
+    fn text(&self) -> String {
+        format!(
+            "{} sig={}",
+            Self::signed_text(&self.enr_root, &self.link_root, self.seq),
+            URL_SAFE_NO_PAD.encode(self.signature)
+        )
+    }
+}
+
+#[pymethods]
+impl Root {
+    /// Hash of the subtree of records.
+    #[getter]
+    fn enr_root(&self) -> &str {
+        &self.enr_root
+    }
+
+    /// Hash of the subtree of links to other trees.
+    #[getter]
+    fn link_root(&self) -> &str {
+        &self.link_root
+    }
+
+    /// Incremented by the publisher on every update.
+    #[getter]
+    fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// The 65-byte recoverable secp256k1 signature, `r || s || v`.
+    #[getter]
+    fn signature<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.signature)
+    }
+
+    /// Whether the root was signed by `public_key`, a secp256k1 key (33 or
+    /// 65 bytes) such as `EnrTreeLink.public_key`.
+    #[pyo3(name = "verify")]
+    fn py_verify(&self, public_key: &[u8]) -> PyResult<bool> {
+        let key = VerifyingKey::from_sec1_bytes(public_key).map_err(|_| {
+            PyValueError::new_err(format!(
+                "invalid public key: expected a 33- or 65-byte secp256k1 key, got {} bytes",
+                public_key.len()
+            ))
+        })?;
+        Ok(self.verify(&key))
+    }
+
+    fn __str__(&self) -> String {
+        self.text()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "EnrTreeRoot(enr_root={:?}, link_root={:?}, seq={})",
+            self.enr_root, self.link_root, self.seq
+        )
+    }
+}
+
+/// A branch entry: the hashes of its children.
+#[pyclass(name = "EnrTreeBranch", frozen, eq, hash, skip_from_py_object)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Branch {
+    pub(crate) children: Vec<String>,
+}
+
+impl Branch {
+    fn parse(text: &str) -> Result<Self, String> {
+        if text.is_empty() {
+            return Ok(Branch {
+                children: Vec::new(),
+            });
+        }
+        let children: Vec<String> = text.split(',').map(str::to_owned).collect();
+        if let Some(child) = children.iter().find(|child| !is_valid_hash(child)) {
+            return Err(format!("invalid child hash {child:?}"));
+        }
+        Ok(Branch { children })
+    }
+
+    fn text(&self) -> String {
+        format!("{BRANCH_PREFIX}{}", self.children.join(","))
+    }
+}
+
+#[pymethods]
+impl Branch {
+    #[getter]
+    fn children(&self) -> Vec<String> {
+        self.children.clone()
+    }
+
+    fn __str__(&self) -> String {
+        self.text()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("EnrTreeBranch(children={:?})", self.children)
+    }
+}
+
+/// A link to another tree: `enrtree://<public key>@<domain>`.
+#[pyclass(name = "EnrTreeLink", frozen, eq, hash, skip_from_py_object)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Link {
+    /// Compressed secp256k1 public key the linked tree is signed with.
+    public_key: [u8; 33],
+    pub(crate) domain: String,
+}
+
+impl Link {
+    pub(crate) fn parse(text: &str) -> Result<Self, String> {
+        let rest = text
+            .strip_prefix(LINK_PREFIX)
+            .ok_or_else(|| format!("expected an {LINK_PREFIX} link"))?;
+        let (key, domain) = rest
+            .split_once('@')
+            .ok_or("missing '@' between public key and domain")?;
+        let public_key = base32_decode(key)
+            .and_then(|key| <[u8; 33]>::try_from(key).ok())
+            .ok_or("public key must be 33 bytes of unpadded base32")?;
+        VerifyingKey::from_sec1_bytes(&public_key)
+            .map_err(|_| "public key is not a valid secp256k1 key")?;
+        if domain.is_empty() {
+            return Err("missing domain".into());
+        }
+        Ok(Link {
+            public_key,
+            domain: domain.to_owned(),
+        })
+    }
+
+    /// The key the linked tree's root must be signed with.
+    #[cfg_attr(not(feature = "dns"), allow(dead_code))]
+    pub(crate) fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey::from_sec1_bytes(&self.public_key).expect("checked when the link was made")
+    }
+
+    fn text(&self) -> String {
+        format!(
+            "{LINK_PREFIX}{}@{}",
+            base32_encode(&self.public_key),
+            self.domain
+        )
+    }
+}
+
+#[pymethods]
+impl Link {
+    /// A link to the tree at `domain` signed by `public_key`, a secp256k1
+    /// key (33 or 65 bytes).
+    #[new]
+    fn new(public_key: &[u8], domain: &str) -> PyResult<Self> {
+        let key = VerifyingKey::from_sec1_bytes(public_key).map_err(|_| {
+            PyValueError::new_err("public key must be a 33- or 65-byte secp256k1 key")
+        })?;
+        let public_key = <[u8; 33]>::try_from(key.to_encoded_point(true).as_bytes())
+            .expect("compressed points are 33 bytes");
+        let domain = domain.trim_end_matches('.');
+        if domain.is_empty() || domain.contains(['@', ' ']) {
+            return Err(PyValueError::new_err(format!("invalid domain {domain:?}")));
+        }
+        Ok(Link {
+            public_key,
+            domain: domain.to_owned(),
+        })
+    }
+
+    /// Parse an `enrtree://` link.
+    #[staticmethod]
+    fn from_url(url: &str) -> PyResult<Self> {
+        Link::parse(url.trim()).map_err(DecodeError::new_err)
+    }
+
+    /// The 33-byte compressed public key the linked tree is signed with.
+    #[getter]
+    fn public_key<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.public_key)
+    }
+
+    #[getter]
+    fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn __str__(&self) -> String {
+        self.text()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("EnrTreeLink({:?})", self.text())
+    }
+}
+
+/// A parsed tree entry.
+pub(crate) enum Entry {
+    Root(Root),
+    Branch(Branch),
+    Link(Link),
+    Record(Enr),
+}
+
+/// Parses a root, branch or link entry, or `None` for any other text.
+fn parse_structure(text: &str) -> Option<Result<Entry, String>> {
+    let invalid = |kind: &str, message: String| format!("invalid EIP-1459 {kind} entry: {message}");
+    if text.starts_with(ROOT_PREFIX) {
+        Some(
+            Root::parse(text)
+                .map(Entry::Root)
+                .map_err(|e| invalid("root", e)),
+        )
+    } else if let Some(rest) = text.strip_prefix(BRANCH_PREFIX) {
+        Some(
+            Branch::parse(rest)
+                .map(Entry::Branch)
+                .map_err(|e| invalid("branch", e)),
+        )
+    } else if text.starts_with(LINK_PREFIX) {
+        Some(
+            Link::parse(text)
+                .map(Entry::Link)
+                .map_err(|e| invalid("link", e)),
+        )
+    } else {
+        None
+    }
+}
+
+fn not_an_entry() -> String {
+    format!(
+        "not an EIP-1459 tree entry: expected {ROOT_PREFIX}, {BRANCH_PREFIX}, \
+         {LINK_PREFIX} or {RECORD_PREFIX}"
+    )
+}
+
+/// Parses the text of any tree entry.
+pub(crate) fn parse_entry(py: Python<'_>, text: &str) -> PyResult<Entry> {
+    if let Some(entry) = parse_structure(text) {
+        return entry.map_err(DecodeError::new_err);
+    }
+    if !text.starts_with(RECORD_PREFIX) {
+        return Err(DecodeError::new_err(not_an_entry()));
+    }
+    let data = decode_text(py, text)?;
+    Enr::from_bytes(py, &data, false, false).map(Entry::Record)
+}
+
+/// Parses the text of any tree entry without the GIL, taking records only
+/// in the `enr:` form EIP-1459 prescribes.
+#[cfg_attr(not(feature = "dns"), allow(dead_code))]
+pub(crate) fn parse_entry_detached(text: &str) -> Result<Entry, String> {
+    if let Some(entry) = parse_structure(text) {
+        return entry;
+    }
+    if !text.starts_with(RECORD_PREFIX) {
+        return Err(not_an_entry());
+    }
+    text.parse::<enr::Enr<CombinedKey>>()
+        .map(|inner| Entry::Record(Enr { inner }))
+        .map_err(|e| format!("invalid record: {e}"))
+}
+
+impl<'py> IntoPyObject<'py> for Entry {
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(match self {
+            Entry::Root(root) => Bound::new(py, root)?.into_any(),
+            Entry::Branch(branch) => Bound::new(py, branch)?.into_any(),
+            Entry::Link(link) => Bound::new(py, link)?.into_any(),
+            Entry::Record(enr) => Bound::new(py, enr)?.into_any(),
+        })
+    }
+}
+
+/// Parse one TXT entry of an EIP-1459 tree, returning an `EnrTreeRoot`,
+/// `EnrTreeBranch`, `EnrTreeLink` or, for a leaf, the `Enr`.
+///
+/// Raises `DecodeError` if the text is not a well-formed entry. A root's
+/// signature is not checked here; see `EnrTreeRoot.verify()`.
+#[pyfunction]
+pub(crate) fn parse_tree_entry(py: Python<'_>, text: &str) -> PyResult<Entry> {
+    parse_entry(py, text.trim())
+}
+
+/// A link given to `EnrTree.build`, as an `EnrTreeLink` or its URL.
+#[derive(FromPyObject)]
+enum LinkArg<'py> {
+    Link(PyRef<'py, Link>),
+    Url(String),
+}
+
+/// Accumulates the entries of a tree under construction.
+struct Builder {
+    entries: BTreeMap<String, String>,
+}
+
+impl Builder {
+    /// Adds the subtree over `texts` and returns its top entry's text, as
+    /// go-ethereum's `Tree.build` does.
+    fn build(&mut self, mut texts: Vec<String>) -> String {
+        if texts.len() == 1 {
+            return texts.pop().expect("one entry");
+        }
+        if texts.len() <= MAX_CHILDREN {
+            let children = texts
+                .into_iter()
+                .map(|text| {
+                    let name = hash(&text);
+                    self.entries.insert(name.clone(), text);
+                    name
+                })
+                .collect();
+            return Branch { children }.text();
+        }
+        let subtrees = texts
+            .chunks(MAX_CHILDREN)
+            .map(|chunk| {
+                let sub = self.build(chunk.to_vec());
+                self.entries.insert(hash(&sub), sub.clone());
+                sub
+            })
+            .collect();
+        self.build(subtrees)
+    }
+
+    /// Adds the subtree over `texts` and its top entry, returning its name.
+    fn subtree(&mut self, texts: Vec<String>) -> String {
+        let top = self.build(texts);
+        let name = hash(&top);
+        self.entries.insert(name.clone(), top);
+        name
+    }
+}
+
+/// A signed EIP-1459 tree, ready to publish as TXT records.
+#[pyclass(name = "EnrTree", frozen)]
+pub(crate) struct EnrTree {
+    root: Root,
+    /// Compressed public key the root is signed with.
+    public_key: [u8; 33],
+    records: Vec<enr::Enr<CombinedKey>>,
+    links: Vec<Link>,
+    /// Every entry but the root, by name.
+    entries: BTreeMap<String, String>,
+}
+
+#[pymethods]
+impl EnrTree {
+    /// Build the tree of `enrs` and `links` (`EnrTreeLink`s or
+    /// `enrtree://` URLs to other trees), signing its root with `key`, which
+    /// must be a secp256k1 key.
+    ///
+    /// Records are ordered by node id, keeping the highest seq of each
+    /// node, and grouped into branches of at most
+    /// 13 children, as go-ethereum does. `seq` must grow with each version of
+    /// the tree published at a domain, so that clients pick up the update.
+    #[staticmethod]
+    #[pyo3(signature = (enrs, key, *, seq, links=Vec::new()))]
+    fn build(
+        enrs: Vec<PyRef<'_, Enr>>,
+        key: PyRef<'_, SigningKey>,
+        seq: u64,
+        links: Vec<LinkArg<'_>>,
+    ) -> PyResult<Self> {
+        let CombinedKey::Secp256k1(signing_key) = &key.inner else {
+            return Err(PyValueError::new_err(
+                "trees are signed with secp256k1 keys, got an ed25519 key",
+            ));
+        };
+        let mut records: Vec<_> = enrs.iter().map(|enr| enr.inner.clone()).collect();
+        // The highest seq of each node.
+        records.sort_by_key(|enr| (enr.node_id().raw(), std::cmp::Reverse(enr.seq())));
+        records.dedup_by_key(|enr| enr.node_id().raw());
+        let links = links
+            .into_iter()
+            .map(|link| match link {
+                LinkArg::Link(link) => Ok(link.clone()),
+                LinkArg::Url(url) => Link::parse(url.trim()).map_err(DecodeError::new_err),
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let mut builder = Builder {
+            entries: BTreeMap::new(),
+        };
+        let enr_root = builder.subtree(records.iter().map(|enr| enr.to_base64()).collect());
+        let link_root = builder.subtree(links.iter().map(Link::text).collect());
+        let public_key = <[u8; 33]>::try_from(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes(),
+        )
+        .expect("compressed points are 33 bytes");
+        Ok(EnrTree {
+            root: Root::sign(enr_root, link_root, seq, signing_key),
+            public_key,
+            records,
+            links,
+            entries: builder.entries,
+        })
+    }
+
+    #[getter]
+    fn root(&self) -> Root {
+        self.root.clone()
+    }
+
+    #[getter]
+    fn seq(&self) -> u64 {
+        self.root.seq
+    }
+
+    /// The records, in node id order.
+    #[getter]
+    fn records(&self) -> Vec<Enr> {
+        self.records
+            .iter()
+            .map(|inner| Enr {
+                inner: inner.clone(),
+            })
+            .collect()
+    }
+
+    #[getter]
+    fn links(&self) -> Vec<Link> {
+        self.links.clone()
+    }
+
+    /// The link to this tree when published at `domain`.
+    fn link(&self, domain: &str) -> PyResult<Link> {
+        Link::new(&self.public_key, domain)
+    }
+
+    /// The TXT records to publish the tree at `domain`: the root at `domain`
+    /// itself, then every other entry at `<hash>.<domain>`.
+    fn to_txt<'py>(&self, py: Python<'py>, domain: &str) -> PyResult<Bound<'py, PyDict>> {
+        let domain = self.link(domain)?.domain;
+        let out = PyDict::new(py);
+        out.set_item(&domain, self.root.text())?;
+        for (name, text) in &self.entries {
+            out.set_item(format!("{name}.{domain}"), text)?;
+        }
+        Ok(out)
+    }
+
+    fn __len__(&self) -> usize {
+        self.entries.len() + 1
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "EnrTree(seq={}, records={}, links={})",
+            self.root.seq,
+            self.records.len(),
+            self.links.len()
+        )
+    }
+}
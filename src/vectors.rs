@@ -0,0 +1,244 @@
+//! Reference records and a self-test of the installed build.
+//!
+//! The valid vectors are published records; the invalid ones are derived from
+//! the EIP-778 example, each breaking exactly one rule.
+
+use std::net::Ipv4Addr;
+
+use alloy_rlp::{Encodable, Header};
+use enr::{CombinedKey, EnrKey};
+use pyo3::exceptions::PyAssertionError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyTuple};
+
+use crate::errors::{self, EnrError};
+use crate::{convert, decode_record, decode_text};
+
+/// The example record of EIP-778.
+pub(crate) const EIP778_ENR: &str = "enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8";
+const EIP778_PRIVATE_KEY: &str = "b71c71a67e1177ad4e901695e1b4b9ee17ae16c6668d313eac2f96dbcda3f291";
+const EIP778_NODE_ID: &str = "a448f24c6d18e575453db13171562b71999873db5b286df957af199ec94617f7";
+
+/// Records every decoder must accept, by name.
+const VALID: &[(&str, &str)] = &[
+    ("eip778", EIP778_ENR),
+    // From the test suite of the `enr` crate: a two-byte tcp port.
+    (
+        "tcp_port",
+        "enr:-Hy4QF_mn4BuM6hY4CuLH8xDQd7U8kVZe9fyNgRB1vjdToGWQsQhetRvsByoJCWGQ6kf2aiWC0le24lkp0IPIJkLSTUBgmlkgnY0iXNlY3AyNTZrMaECMoYV0PAXMueQz19FHpBO0jGBoLYCWhfSxGf5kQgk9KqDdGNwgnZf",
+    ),
+];
+
+/// Frames `payload` as an RLP list.
+fn list(payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 3);
+    Header {
+        list: true,
+        payload_length: payload.len(),
+    }
+    .encode(&mut out);
+    out.extend_from_slice(payload);
+    out
+}
+
+fn eip778_bytes() -> Vec<u8> {
+    let text = EIP778_ENR.strip_prefix("enr:").unwrap_or(EIP778_ENR);
+    base64::Engine::decode(&base64::engine::general_purpose::URL_SAFE_NO_PAD, text)
+        .expect("EIP-778 vector is valid base64")
+}
+
+/// Records strict decoding must reject: name, encoding, and the `code` of
+/// the resulting error (`None` for a `SignatureError`).
+fn invalid() -> Vec<(&'static str, Vec<u8>, Option<&'static str>)> {
+    let record = eip778_bytes();
+    // Header (2 bytes), signature (66), seq (1), then the key/value pairs,
+    // starting with `id` and `ip`.
+    let signature = &record[2..68];
+    let pairs = &record[69..];
+    let (id, rest) = pairs.split_at(6);
+    let (ip, rest) = rest.split_at(8);
+
+    let mut bad_signature = record.clone();
+    bad_signature[10] ^= 1;
+    let mut trailing = record.clone();
+    trailing.push(0);
+    let mut oversized = record[2..].to_vec();
+    b"zz".as_slice().encode(&mut oversized);
+    [0u8; 240].as_slice().encode(&mut oversized);
+
+    vec![
+        ("bad_signature", bad_signature, None),
+        (
+            "truncated",
+            record[..record.len() - 1].to_vec(),
+            Some("malformed"),
+        ),
+        ("trailing_bytes", trailing, Some("trailing_data")),
+        (
+            "unsorted_keys",
+            list(&[signature, &[1], ip, id, rest].concat()),
+            Some("unsorted_keys"),
+        ),
+        (
+            "non_canonical_seq",
+            list(&[signature, &[0x81, 1], pairs].concat()),
+            Some("malformed"),
+        ),
+        (
+            "missing_id",
+            list(&[signature, &[1], ip, rest].concat()),
+            Some("unknown_id_scheme"),
+        ),
+        ("oversized", list(&oversized), Some("oversize")),
+    ]
+}
+
+/// Adds the vectors to the extension module as constants.
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("EIP778_ENR", EIP778_ENR)?;
+    m.add(
+        "EIP778_PRIVATE_KEY",
+        PyBytes::new(
+            py,
+            &convert::from_hex(EIP778_PRIVATE_KEY).expect("valid hex"),
+        ),
+    )?;
+    m.add(
+        "EIP778_NODE_ID",
+        PyBytes::new(py, &convert::from_hex(EIP778_NODE_ID).expect("valid hex")),
+    )?;
+    let valid = VALID
+        .iter()
+        .map(|(name, text)| {
+            let data = decode_text(py, text)?;
+            PyTuple::new(
+                py,
+                [
+                    name.into_pyobject(py)?.into_any(),
+                    PyBytes::new(py, &data).into_any(),
+                ],
+            )
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    m.add("VALID_VECTORS", PyTuple::new(py, valid)?)?;
+    let invalid = invalid()
+        .into_iter()
+        .map(|(name, data, code)| (name, PyBytes::new(py, &data), code))
+        .collect::<Vec<_>>();
+    m.add("INVALID_VECTORS", PyTuple::new(py, invalid)?)?;
+    Ok(())
+}
+
+/// Collects the outcome of each check.
+struct Checks {
+    run: usize,
+    failures: Vec<String>,
+}
+
+impl Checks {
+    fn check(&mut self, name: &str, passed: bool) {
+        self.run += 1;
+        if !passed {
+            self.failures.push(name.to_string());
+        }
+    }
+}
+
+/// A record signed by `key` survives encoding, decoding and an update.
+fn roundtrip(py: Python<'_>, checks: &mut Checks, label: &str, key: &CombinedKey) {
+    let built = enr::Enr::builder()
+        .ip4(Ipv4Addr::new(192, 0, 2, 1))
+        .udp4(30303)
+        .build(key);
+    let Ok(mut record) = built else {
+        return checks.check(&format!("{label}: build"), false);
+    };
+    let data = alloy_rlp::encode(&record);
+    let decoded = decode_record(py, &data, true);
+    checks.check(
+        &format!("{label}: decode"),
+        decoded.as_ref().is_ok_and(|d| *d == record),
+    );
+    checks.check(&format!("{label}: verify"), record.verify());
+    let updated = record.set_tcp4(9000, key).is_ok() && record.seq() == 2;
+    checks.check(
+        &format!("{label}: update"),
+        updated && decode_record(py, &alloy_rlp::encode(&record), true).is_ok(),
+    );
+}
+
+/// Check that this build decodes, verifies and signs records correctly.
+///
+/// Runs the valid and invalid vectors and a sign/decode round trip for each
+/// key type. Returns the number of checks run, or raises `AssertionError`
+/// naming every failed check.
+#[pyfunction]
+pub(crate) fn run_self_test(py: Python<'_>) -> PyResult<usize> {
+    let mut checks = Checks {
+        run: 0,
+        failures: Vec::new(),
+    };
+
+    for (name, text) in VALID {
+        let data = decode_text(py, text)?;
+        match decode_record(py, &data, true) {
+            Ok(record) => {
+                checks.check(&format!("{name}: decode"), true);
+                checks.check(
+                    &format!("{name}: re-encode"),
+                    alloy_rlp::encode(&record) == data,
+                );
+                checks.check(&format!("{name}: base64"), record.to_base64() == *text);
+                checks.check(&format!("{name}: verify"), record.verify());
+            }
+            Err(_) => checks.check(&format!("{name}: decode"), false),
+        }
+    }
+
+    let mut secret = convert::from_hex(EIP778_PRIVATE_KEY).expect("valid hex");
+    let key = CombinedKey::secp256k1_from_bytes(&mut secret).map_err(errors::field_error)?;
+    // A failure to decode was counted with the valid vectors.
+    if let Ok(eip778) = decode_record(py, &eip778_bytes(), true) {
+        checks.check(
+            "eip778: fields",
+            convert::to_hex(&eip778.node_id().raw()) == EIP778_NODE_ID
+                && eip778.ip4() == Some(Ipv4Addr::LOCALHOST)
+                && eip778.udp4() == Some(30303)
+                && eip778.seq() == 1,
+        );
+        checks.check("eip778: public key", key.public() == eip778.public_key());
+    }
+
+    for (name, data, code) in invalid() {
+        let passed = match decode_record(py, &data, true) {
+            Ok(_) => false,
+            Err(err) => {
+                let value = err.value(py);
+                let actual: Option<String> =
+                    value.getattr("code").ok().and_then(|c| c.extract().ok());
+                err.is_instance_of::<EnrError>(py) && actual.as_deref() == code
+            }
+        };
+        checks.check(&format!("{name}: rejected"), passed);
+    }
+
+    roundtrip(
+        py,
+        &mut checks,
+        "secp256k1",
+        &CombinedKey::generate_secp256k1(),
+    );
+    roundtrip(py, &mut checks, "ed25519", &CombinedKey::generate_ed25519());
+
+    if checks.failures.is_empty() {
+        Ok(checks.run)
+    } else {
+        Err(PyAssertionError::new_err(format!(
+            "{} of {} self-test checks failed: {}",
+            checks.failures.len(),
+            checks.run,
+            checks.failures.join(", ")
+        )))
+    }
+}
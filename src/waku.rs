@@ -0,0 +1,40 @@
+//! Waku v2 fields (RFC 31/WAKU2-ENR): the `waku2` capability flags. The
+//! `multiaddrs` list Waku also uses is handled by [`crate::multiaddr`].
+
+use pyo3::prelude::*;
+
+use crate::errors::FieldError;
+
+/// ENR key holding the capability flags byte.
+pub(crate) const WAKU2_KEY: &str = "waku2";
+
+/// Capability names, by bit position from the least significant bit.
+const CAPABILITIES: [&str; 5] = ["relay", "store", "filter", "lightpush", "sync"];
+
+/// Names of the capabilities set in a `waku2` value.
+pub(crate) fn capabilities(value: &[u8]) -> PyResult<Vec<&'static str>> {
+    let [flags] = value else {
+        return Err(FieldError::new_err(format!(
+            "key {WAKU2_KEY:?} must be 1 byte, got {}",
+            value.len()
+        )));
+    };
+    Ok(CAPABILITIES
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| flags & (1 << bit) != 0)
+        .map(|(_, name)| *name)
+        .collect())
+}
+
+/// Encodes capability names as a `waku2` flags byte.
+pub(crate) fn flags(names: Vec<String>) -> PyResult<u8> {
+    names.iter().try_fold(0u8, |flags, name| {
+        let bit = CAPABILITIES.iter().position(|c| c == name).ok_or_else(|| {
+            FieldError::new_err(format!(
+                "unknown waku2 capability {name:?}, expected one of {CAPABILITIES:?}"
+            ))
+        })?;
+        Ok(flags | (1 << bit))
+    })
+}
@@ -0,0 +1,60 @@
+//! Python warnings for records that decode but look wrong.
+
+use std::ffi::CString;
+
+use enr::CombinedKey;
+use pyo3::prelude::*;
+
+use crate::conformance;
+use crate::errors::EnrWarning;
+
+/// Describes every recoverable oddity in a record decoded from `data`:
+/// EIP-778 violations the decoder tolerated, a non-canonical encoding, and
+/// values that are well-formed but cannot be meant (port 0, an unspecified
+/// or broadcast address).
+pub(crate) fn oddities(data: &[u8], enr: &enr::Enr<CombinedKey>) -> Vec<String> {
+    let violations = conformance::check(data);
+    let mut out: Vec<String> = violations
+        .iter()
+        .map(|v| format!("record violates EIP-778 ({}): {v}", v.kind.code()))
+        .collect();
+    if violations.is_empty() && !conformance::is_canonical(data) {
+        out.push("record is not canonically encoded".to_string());
+    }
+
+    let ports = [
+        ("tcp", enr.tcp4()),
+        ("udp", enr.udp4()),
+        ("tcp6", enr.tcp6()),
+        ("udp6", enr.udp6()),
+    ];
+    for (key, port) in ports {
+        if port == Some(0) {
+            out.push(format!("key {key:?}: port 0 cannot be connected to"));
+        }
+    }
+    if let Some(ip) = enr.ip4() {
+        if ip.is_unspecified() || ip.is_broadcast() {
+            out.push(format!("key \"ip\": {ip} is not a usable address"));
+        }
+    }
+    if let Some(ip) = enr.ip6() {
+        if ip.is_unspecified() {
+            out.push(format!("key \"ip6\": {ip} is not a usable address"));
+        }
+    }
+    out
+}
+
+/// Issues one `EnrWarning` per oddity found in the record.
+///
+/// Fails only if the warning filters turn a warning into an error.
+pub(crate) fn emit(py: Python<'_>, data: &[u8], enr: &enr::Enr<CombinedKey>) -> PyResult<()> {
+    let category = py.get_type::<EnrWarning>();
+    for message in oddities(data, enr) {
+        // Messages are built from formatted values and never contain NUL.
+        let message = CString::new(message).unwrap_or_default();
+        PyErr::warn(py, &category, &message, 1)?;
+    }
+    Ok(())
+}
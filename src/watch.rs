@@ -0,0 +1,248 @@
+//! Changes between successive snapshots of a set of records.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+use enr::CombinedKey;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::Enr;
+
+/// Keys holding the node's addresses.
+const IP_KEYS: &[&str] = &["ip", "ip6"];
+/// Keys holding the node's ports.
+const PORT_KEYS: &[&str] = &["tcp", "udp", "quic", "tcp6", "udp6", "quic6"];
+
+type Snapshot = BTreeMap<[u8; 32], enr::Enr<CombinedKey>>;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Added,
+    Removed,
+    IpChanged,
+    PortChanged,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Added => "added",
+            Kind::Removed => "removed",
+            Kind::IpChanged => "ip_changed",
+            Kind::PortChanged => "port_changed",
+        }
+    }
+}
+
+/// The keys in `keys` whose values differ between `old` and `new`.
+fn changed_keys(
+    old: &enr::Enr<CombinedKey>,
+    new: &enr::Enr<CombinedKey>,
+    keys: &[&'static str],
+) -> Vec<&'static str> {
+    keys.iter()
+        .copied()
+        .filter(|key| old.get_raw_rlp(key) != new.get_raw_rlp(key))
+        .collect()
+}
+
+/// One change between two snapshots.
+#[pyclass(name = "WatchEvent", frozen, get_all)]
+pub(crate) struct WatchEvent {
+    /// `"added"`, `"removed"`, `"ip_changed"` or `"port_changed"`.
+    kind: &'static str,
+    node_id: Py<PyBytes>,
+    /// The record in the previous snapshot; `None` for `"added"`.
+    old: Option<Py<Enr>>,
+    /// The record in the new snapshot; `None` for `"removed"`.
+    new: Option<Py<Enr>>,
+    /// For changes, the record keys that changed (`ip`, `ip6`, or `tcp`,
+    /// `udp`, `quic` and their `6` variants); empty otherwise.
+    keys: Vec<&'static str>,
+}
+
+#[pymethods]
+impl WatchEvent {
+    fn __repr__(&self, py: Python<'_>) -> String {
+        let node_id = self.node_id.bind(py).as_bytes();
+        let mut repr = format!(
+            "WatchEvent(kind={:?}, node_id=0x{}\u{2026}",
+            self.kind,
+            crate::convert::to_hex(&node_id[..4])
+        );
+        if !self.keys.is_empty() {
+            repr.push_str(&format!(", keys={:?}", self.keys));
+        }
+        repr.push(')');
+        repr
+    }
+}
+
+/// Compares each snapshot of a record set with the previous one, reporting
+/// nodes added and removed and changes to their IP addresses and ports.
+///
+/// Records are matched by node id; within one snapshot the highest seq of a
+/// node wins. A node whose address and ports both changed gives one event of
+/// each kind. Other changes, such as a new seq alone, are not reported.
+#[pyclass(name = "EnrWatcher", frozen)]
+pub(crate) struct EnrWatcher {
+    snapshot: Mutex<Snapshot>,
+    on_added: Option<Py<PyAny>>,
+    on_removed: Option<Py<PyAny>>,
+    on_ip_changed: Option<Py<PyAny>>,
+    on_port_changed: Option<Py<PyAny>>,
+}
+
+impl EnrWatcher {
+    fn lock(&self) -> MutexGuard<'_, Snapshot> {
+        self.snapshot.lock().expect("watcher lock poisoned")
+    }
+
+    fn callback(&self, kind: Kind) -> Option<&Py<PyAny>> {
+        match kind {
+            Kind::Added => self.on_added.as_ref(),
+            Kind::Removed => self.on_removed.as_ref(),
+            Kind::IpChanged => self.on_ip_changed.as_ref(),
+            Kind::PortChanged => self.on_port_changed.as_ref(),
+        }
+    }
+}
+
+/// An event before conversion to Python objects.
+struct Change {
+    kind: Kind,
+    node_id: [u8; 32],
+    old: Option<enr::Enr<CombinedKey>>,
+    new: Option<enr::Enr<CombinedKey>>,
+    keys: Vec<&'static str>,
+}
+
+/// The changes from `old` to `new`, in node id order.
+fn compare(old: &Snapshot, new: &Snapshot) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let mut old_iter = old.iter().peekable();
+    let mut new_iter = new.iter().peekable();
+    loop {
+        // Walk both snapshots in node id order, taking the lower id next.
+        let order = match (old_iter.peek(), new_iter.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some((old_id, _)), Some((new_id, _))) => old_id.cmp(new_id),
+        };
+        let old = (order != Ordering::Greater)
+            .then(|| old_iter.next())
+            .flatten();
+        let new = (order != Ordering::Less).then(|| new_iter.next()).flatten();
+        let change = |kind, keys| Change {
+            kind,
+            node_id: *old.or(new).expect("one side is present").0,
+            old: old.map(|(_, record)| record.clone()),
+            new: new.map(|(_, record)| record.clone()),
+            keys,
+        };
+        match (old, new) {
+            (Some((_, old)), Some((_, new))) => {
+                for (kind, keys) in [(Kind::IpChanged, IP_KEYS), (Kind::PortChanged, PORT_KEYS)] {
+                    let keys = changed_keys(old, new, keys);
+                    if !keys.is_empty() {
+                        changes.push(change(kind, keys));
+                    }
+                }
+            }
+            (Some(_), None) => changes.push(change(Kind::Removed, Vec::new())),
+            _ => changes.push(change(Kind::Added, Vec::new())),
+        }
+    }
+    changes
+}
+
+#[pymethods]
+impl EnrWatcher {
+    /// Callbacks, if given, are called with each `WatchEvent` of their kind.
+    #[new]
+    #[pyo3(signature = (*, on_added=None, on_removed=None, on_ip_changed=None, on_port_changed=None))]
+    fn new(
+        on_added: Option<Py<PyAny>>,
+        on_removed: Option<Py<PyAny>>,
+        on_ip_changed: Option<Py<PyAny>>,
+        on_port_changed: Option<Py<PyAny>>,
+    ) -> Self {
+        EnrWatcher {
+            snapshot: Mutex::new(Snapshot::new()),
+            on_added,
+            on_removed,
+            on_ip_changed,
+            on_port_changed,
+        }
+    }
+
+    /// Take `enrs` as the new snapshot and return what changed since the
+    /// last one, in node id order; every node is `"added"` the first time.
+    ///
+    /// The snapshot is stored before any callback runs, so an exception
+    /// from a callback propagates without the changes being reported again
+    /// on the next update.
+    fn update(&self, py: Python<'_>, enrs: Vec<PyRef<'_, Enr>>) -> PyResult<Vec<Py<WatchEvent>>> {
+        let records: Vec<_> = enrs.iter().map(|e| e.inner.clone()).collect();
+        drop(enrs);
+        let changes = py.detach(|| {
+            let mut new = Snapshot::new();
+            for record in records {
+                let node_id = record.node_id().raw();
+                match new.get(&node_id) {
+                    Some(held) if held.seq() >= record.seq() => {}
+                    _ => {
+                        new.insert(node_id, record);
+                    }
+                }
+            }
+            let mut snapshot = self.lock();
+            let changes = compare(&snapshot, &new);
+            *snapshot = new;
+            changes
+        });
+
+        let mut events = Vec::with_capacity(changes.len());
+        let mut callbacks = Vec::new();
+        for change in changes {
+            let to_py = |record: Option<enr::Enr<CombinedKey>>| {
+                record.map(|inner| Py::new(py, Enr { inner })).transpose()
+            };
+            let event = Py::new(
+                py,
+                WatchEvent {
+                    kind: change.kind.name(),
+                    node_id: PyBytes::new(py, &change.node_id).unbind(),
+                    old: to_py(change.old)?,
+                    new: to_py(change.new)?,
+                    keys: change.keys,
+                },
+            )?;
+            if let Some(callback) = self.callback(change.kind) {
+                callbacks.push((callback, event.clone_ref(py)));
+            }
+            events.push(event);
+        }
+        for (callback, event) in callbacks {
+            callback.call1(py, (event,))?;
+        }
+        Ok(events)
+    }
+
+    /// The current snapshot, in node id order.
+    fn records(&self) -> Vec<Enr> {
+        self.lock()
+            .values()
+            .map(|record| Enr {
+                inner: record.clone(),
+            })
+            .collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.lock().len()
+    }
+}
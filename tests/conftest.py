@@ -0,0 +1,28 @@
+"""Fixtures shared across the test suite."""
+
+import pytest
+
+
+def _make_record(key, seq=1, ip="10.0.0.1", udp=30303, tcp=None, fork_digest=None):
+    builder = key.builder()
+    if ip is not None:
+        builder.ip4(ip)
+    if udp is not None:
+        builder.udp4(udp)
+    if tcp is not None:
+        builder.tcp4(tcp)
+    if fork_digest is not None:
+        builder.add("eth2", fork_digest + bytes(12))
+    enr = builder.build(key)
+    enr.set_seq(seq, key)
+    return enr
+
+
+@pytest.fixture
+def make_record():
+    """Build a record signed by `key` at `seq`.
+
+    It has an `ip` and `udp` address unless they are passed as `None`, plus a
+    `tcp` port and an `eth2` value for `fork_digest` if given.
+    """
+    return _make_record
@@ -0,0 +1,181 @@
+"""Tests for the awaitable batch APIs."""
+
+import asyncio
+import gc
+import os
+import socket
+import subprocess
+import sys
+import textwrap
+import time
+
+import pyenr
+import pytest
+from pyenr import (
+    DecodeError,
+    SigningKey,
+    decode_many,
+    decode_many_async,
+    decode_many_bytes_async,
+    dedupe_report_async,
+    filter_many,
+    filter_many_async,
+    probe_liveness_async,
+    process_enr_files_async,
+    write_enr_file,
+)
+
+
+def _records(n=6):
+    out = []
+    for i in range(n):
+        key = SigningKey.generate_secp256k1()
+        builder = key.builder()
+        if i % 2:
+            builder.ip4("10.0.0.1")
+        builder.tcp4(9000 + i)
+        out.append(builder.build(key))
+    return out
+
+
+def test_filter_many_async_matches_sync():
+    records = _records()
+
+    async def run():
+        return await filter_many_async(records, has_ip4=True)
+
+    assert asyncio.run(run()) == filter_many(records, has_ip4=True)
+
+
+def test_calls_run_concurrently():
+    records = _records()
+
+    async def run():
+        return await asyncio.gather(
+            dedupe_report_async(records + records),
+            filter_many_async(records, tcp4=9001),
+            asyncio.sleep(0, result="loop still running"),
+        )
+
+    report, tcp, slept = asyncio.run(run())
+    assert report.records == records
+    assert report.duplicates_removed == len(records)
+    assert tcp == [records[1]]
+    assert slept == "loop still running"
+
+
+def test_process_enr_files_async(tmp_path):
+    records = _records()
+    write_enr_file(tmp_path / "a.txt", records[:4])
+    write_enr_file(tmp_path / "b.txt", records[2:])
+
+    async def run():
+        return await process_enr_files_async(
+            [tmp_path / "a.txt", tmp_path / "b.txt"], filter={"has_ip4": True}
+        )
+
+    assert asyncio.run(run()) == [r for r in records if r.ip4 is not None]
+
+
+def test_decode_many_async():
+    records = _records()
+    texts = [r.to_base64() for r in records]
+
+    async def run():
+        return await asyncio.gather(
+            decode_many_async(texts),
+            decode_many_bytes_async([r.to_bytes() for r in records]),
+            decode_many_async(texts[:2] + ["garbage"], return_errors=True),
+        )
+
+    from_text, from_bytes, (found, errors) = asyncio.run(run())
+    assert from_text == from_bytes == decode_many(texts) == records
+    assert found == records[:2]
+    assert [(index, type(err)) for index, err in errors] == [(2, DecodeError)]
+
+
+def test_probe_liveness_async():
+    with socket.socket() as listener:
+        listener.bind(("127.0.0.1", 0))
+        listener.listen()
+        key = SigningKey.generate_secp256k1()
+        builder = key.builder()
+        builder.ip4("127.0.0.1")
+        builder.tcp4(listener.getsockname()[1])
+        enr = builder.build(key)
+
+        async def run():
+            return await probe_liveness_async([enr], timeout=1.0)
+
+        [result] = asyncio.run(run())
+    assert result.reachable
+    assert result.method == "tcp"
+
+
+def test_calls_share_a_bounded_pool():
+    if not os.path.isdir("/proc/self/task"):
+        pytest.skip("counts threads through /proc")
+    records = _records()
+
+    async def round_of(n):
+        return await asyncio.gather(*(dedupe_report_async(records) for _ in range(n)))
+
+    # Finalizers of garbage left by earlier tests could otherwise run on a
+    # worker thread while it holds the GIL, and start threads of their own.
+    gc.collect()
+    asyncio.run(round_of(1))
+    threads = len(os.listdir("/proc/self/task"))
+    reports = asyncio.run(round_of(50))
+    assert all(report.records == records for report in reports)
+    # Threads that each call runs its job on, and those earlier network calls
+    # started for themselves, exit shortly after the call returns.
+    deadline = time.monotonic() + 10
+    while len(os.listdir("/proc/self/task")) > threads and time.monotonic() < deadline:
+        time.sleep(0.05)
+    assert len(os.listdir("/proc/self/task")) <= threads
+
+
+def test_exit_right_after_a_call(tmp_path):
+    """The interpreter waits for workers still attached after handing back a result."""
+    write_enr_file(tmp_path / "a.txt", _records())
+    script = textwrap.dedent(
+        """
+        import asyncio
+        import sys
+
+        from pyenr import process_enr_files_async
+
+        async def run():
+            return await process_enr_files_async([sys.argv[1]])
+
+        # Switching often makes a worker likely to lose the GIL while it
+        # hands back the result, as the main thread returns and exits.
+        sys.setswitchinterval(1e-5)
+        for _ in range(3):
+            asyncio.run(run())
+        """
+    )
+    env = dict(os.environ, PYTHONPATH=os.path.dirname(os.path.dirname(pyenr.__file__)))
+    for _ in range(5):
+        subprocess.run(
+            [sys.executable, "-c", script, str(tmp_path / "a.txt")],
+            env=env,
+            check=True,
+            timeout=60,
+        )
+
+
+def test_errors_raise_at_await(tmp_path):
+    async def run():
+        pending = process_enr_files_async([tmp_path / "missing.txt"])
+        with pytest.raises(OSError):
+            await pending
+        with pytest.raises(ValueError):
+            await filter_many_async([], fork_digest=b"\x00")
+
+    asyncio.run(run())
+
+
+def test_requires_running_loop():
+    with pytest.raises(RuntimeError):
+        filter_many_async([])
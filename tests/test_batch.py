@@ -0,0 +1,275 @@
+"""Tests for the parallel batch APIs."""
+
+import os
+import time
+
+import pytest
+from pyenr import (
+    SigningKey,
+    dedupe_report,
+    filter_many,
+    filter_routable,
+    group_by_fork_digest,
+    node_ids,
+)
+
+FORK_DIGEST = bytes.fromhex("6a95a1a9")
+
+
+def _eth2(digest):
+    return digest + bytes.fromhex("04000000") + (2**64 - 1).to_bytes(8, "little")
+
+
+def _records():
+    key = SigningKey.generate_secp256k1()
+
+    b = key.builder()
+    b.ip4("10.0.0.1")
+    b.tcp4(9000)
+    b.add("eth2", _eth2(FORK_DIGEST))
+    with_ip_and_fork = b.build(key)
+
+    b = key.builder()
+    b.ip4("10.0.0.2")
+    b.tcp4(30303)
+    b.add("attnets", b"\xff" * 8)
+    with_ip_other_port = b.build(key)
+
+    b = key.builder()
+    b.ip6("::1")
+    b.add("eth2", _eth2(b"\x00\x00\x00\x01"))
+    ip6_only = b.build(key)
+
+    return [with_ip_and_fork, with_ip_other_port, ip6_only]
+
+
+def test_filter_no_predicates_returns_all():
+    records = _records()
+    assert filter_many(records) == records
+
+
+def test_filter_has_ip4():
+    records = _records()
+    assert filter_many(records, has_ip4=True) == records[:2]
+    assert filter_many(records, has_ip4=False) == records[2:]
+
+
+def test_filter_tcp4():
+    records = _records()
+    assert filter_many(records, tcp4=9000) == [records[0]]
+
+
+def test_filter_fork_digest():
+    records = _records()
+    assert filter_many(records, fork_digest=FORK_DIGEST) == [records[0]]
+
+
+def test_filter_key_present():
+    records = _records()
+    assert filter_many(records, key_present=["attnets"]) == [records[1]]
+    assert filter_many(records, key_present=["ip", "tcp"]) == records[:2]
+
+
+def test_filter_returns_same_objects():
+    records = _records()
+    assert filter_many(records, has_ip6=True)[0] is records[2]
+
+
+def test_filter_bad_fork_digest():
+    with pytest.raises(ValueError, match="4 bytes"):
+        filter_many(_records(), fork_digest=b"\x00")
+
+
+def test_group_by_fork_digest():
+    records = _records()
+    groups = group_by_fork_digest(records)
+    assert list(groups) == [FORK_DIGEST, None, b"\x00\x00\x00\x01"]
+    assert groups[FORK_DIGEST] == [records[0]]
+    assert groups[None] == [records[1]]
+
+
+def test_group_by_fork_digest_collects_duplicates():
+    records = _records()
+    groups = group_by_fork_digest(records + records)
+    assert groups[FORK_DIGEST] == [records[0], records[0]]
+    assert group_by_fork_digest([]) == {}
+
+
+def test_dedupe_keeps_highest_seq():
+    key = SigningKey.generate_secp256k1()
+    old = key.builder().build(key)
+    new = key.builder().build(key)
+    new.set_seq(5, key)
+    other_key = SigningKey.generate_secp256k1()
+    other = other_key.builder().build(other_key)
+
+    report = dedupe_report([old, other, new, old])
+    assert report.records == [new, other]
+    assert len(report) == 2
+    assert report.duplicates_removed == 2
+    assert report.seq_conflicts == 0
+    assert report.endpoint_collisions == 0
+
+
+def test_dedupe_counts_seq_conflicts():
+    key = SigningKey.generate_secp256k1()
+    a = key.builder().build(key)
+    b = key.builder()
+    b.udp4(9000)
+    b = b.build(key)
+    assert a.seq == b.seq
+
+    report = dedupe_report([a, b, a])
+    assert report.records == [a]
+    assert report.duplicates_removed == 2
+    assert report.seq_conflicts == 1
+
+
+def test_dedupe_counts_endpoint_collisions():
+    def record(ip, port, key=None):
+        key = key or SigningKey.generate_secp256k1()
+        builder = key.builder()
+        builder.ip4(ip)
+        builder.udp4(port)
+        return builder.build(key), key
+
+    a, _ = record("10.4.0.1", 9000)
+    b, _ = record("10.4.0.1", 9000)
+    c, _ = record("10.4.0.1", 9001)
+    stale, key = record("10.4.0.1", 9001)
+    moved, _ = record("10.4.0.2", 9001, key)
+    moved.set_seq(stale.seq + 1, key)
+
+    report = dedupe_report([a, b, c, stale, moved])
+    assert report.records == [a, b, c, moved]
+    assert report.endpoint_collisions == 1
+
+
+def test_dedupe_report_repr():
+    report = dedupe_report([])
+    assert repr(report) == (
+        "DedupeReport(records=0, duplicates_removed=0, seq_conflicts=0, endpoint_collisions=0)"
+    )
+
+
+def test_set_num_threads():
+    import pyenr
+
+    default = pyenr.get_num_threads()
+    try:
+        pyenr.set_num_threads(2)
+        assert pyenr.get_num_threads() == 2
+        records = _records()
+        assert filter_many(records, has_ip4=True) == records[:2]
+    finally:
+        pyenr.set_num_threads(None)
+    assert pyenr.get_num_threads() == default
+
+
+def test_set_num_threads_rejects_zero():
+    import pyenr
+
+    with pytest.raises(ValueError):
+        pyenr.set_num_threads(0)
+
+
+def test_per_call_workers():
+    records = _records()
+    assert filter_many(records, has_ip4=True, workers=1) == records[:2]
+    assert list(group_by_fork_digest(records, workers=2)) == list(group_by_fork_digest(records))
+    assert len(dedupe_report(records, workers=3)) == 1
+    with pytest.raises(ValueError, match="workers"):
+        filter_many(records, workers=0)
+
+
+def _thread_count_settles_at(threads):
+    # Threads that run a call's job, and those of pools built for one call,
+    # exit shortly after the call returns.
+    deadline = time.monotonic() + 10
+    while len(os.listdir("/proc/self/task")) > threads and time.monotonic() < deadline:
+        time.sleep(0.05)
+    return len(os.listdir("/proc/self/task")) <= threads
+
+
+def test_per_call_workers_reuse_pools():
+    if not os.path.isdir("/proc/self/task"):
+        pytest.skip("counts threads through /proc")
+    records = _records()
+    workers = os.cpu_count() or 1
+    filter_many(records, workers=workers)
+    threads = len(os.listdir("/proc/self/task"))
+    for _ in range(20):
+        filter_many(records, workers=workers)
+    assert _thread_count_settles_at(threads)
+
+
+def test_per_call_workers_past_the_cpu_count_are_not_kept():
+    if not os.path.isdir("/proc/self/task"):
+        pytest.skip("counts threads through /proc")
+    records = _records()
+    threads = len(os.listdir("/proc/self/task"))
+    cpus = os.cpu_count() or 1
+    for extra in range(1, 11):
+        filter_many(records, workers=cpus + extra)
+    assert _thread_count_settles_at(threads)
+
+
+def _with_addresses(ip4=None, ip6=None):
+    key = SigningKey.generate_secp256k1()
+    b = key.builder()
+    if ip4:
+        b.ip4(ip4)
+    if ip6:
+        b.ip6(ip6)
+    return b.build(key)
+
+
+@pytest.mark.parametrize(
+    "ip4",
+    [
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.1.1",
+        "127.0.0.1",
+        "169.254.1.1",
+        "100.64.0.1",
+        "0.0.0.0",
+        "192.0.2.1",
+        "198.18.0.1",
+        "224.0.0.1",
+        "255.255.255.255",
+    ],
+)
+def test_non_routable_ip4(ip4):
+    assert not _with_addresses(ip4=ip4).has_routable_address()
+
+
+@pytest.mark.parametrize(
+    "ip6",
+    ["::1", "::", "fe80::1", "fd00::1", "ff02::1", "2001:db8::1", "::ffff:10.0.0.1"],
+)
+def test_non_routable_ip6(ip6):
+    assert not _with_addresses(ip6=ip6).has_routable_address()
+
+
+def test_routable_addresses():
+    assert _with_addresses(ip4="8.8.8.8").has_routable_address()
+    assert _with_addresses(ip4="100.128.0.1").has_routable_address()
+    assert _with_addresses(ip6="2a01:4f8::1").has_routable_address()
+    assert _with_addresses(ip4="10.0.0.1", ip6="2a01:4f8::1").has_routable_address()
+    assert not _with_addresses().has_routable_address()
+
+
+def test_filter_routable():
+    public = _with_addresses(ip4="1.2.3.4")
+    private = _with_addresses(ip4="192.168.0.10")
+    public6 = _with_addresses(ip6="2606:4700::1111")
+    assert filter_routable([private, public, public6], workers=2) == [public, public6]
+
+
+def test_node_ids():
+    records = _records()
+    ids = node_ids(records)
+    assert len(ids) == 32 * len(records)
+    assert [ids[i : i + 32] for i in range(0, len(ids), 32)] == [r.node_id for r in records]
+    assert node_ids([]) == b""
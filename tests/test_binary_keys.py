@@ -0,0 +1,56 @@
+"""Tests for keys whose names are not valid UTF-8."""
+
+import pytest
+from pyenr import Enr, FieldError, SigningKey
+
+_KEY = SigningKey.generate_secp256k1()
+_NAME = b"\xffk\xfe"
+
+
+def _record():
+    builder = _KEY.builder()
+    builder.udp4(30303)
+    enr = builder.build(_KEY)
+    enr.set(_NAME, b"\x01\x02", _KEY)
+    return Enr.from_bytes(enr.to_bytes())
+
+
+def test_raw_keys():
+    enr = _record()
+    assert _NAME in enr.keys(raw=True)
+    assert b"udp" in enr.keys(raw=True)
+    assert all(isinstance(k, bytes) for k in enr.keys(raw=True))
+    # The default stays text, with invalid bytes replaced.
+    assert "�k�" in enr.keys()
+    assert [k.decode() for k in enr.keys(raw=True) if k != _NAME] == [
+        k for k in enr.keys() if "�" not in k
+    ]
+
+
+def test_raw_items():
+    enr = _record()
+    items = dict(enr.items(raw=True))
+    assert items[_NAME] == b"\x82\x01\x02"
+    assert items[b"udp"] == enr.get_raw_rlp("udp")
+
+
+def test_get_bytes_key():
+    enr = _record()
+    assert enr.get(_NAME) == b"\x01\x02"
+    assert enr.get(_NAME, type=int) == 0x0102
+    assert enr.get_raw(_NAME) == enr.get_raw_rlp(_NAME) == b"\x82\x01\x02"
+    assert enr.get_decoded(_NAME) == b"\x01\x02"
+    assert enr.get(b"\xff\xff", default=7) == 7
+
+
+def test_bytes_key_that_is_utf8_matches_text():
+    enr = _record()
+    assert enr.get(b"udp", type=int) == enr.get("udp", type=int) == 30303
+    assert enr.get(b"udp4", type=int) == 30303
+    assert enr.get_raw_rlp(b"udp") == enr.get_raw_rlp("udp")
+
+
+def test_set_bytes_key_checks_reserved_names():
+    enr = _record()
+    with pytest.raises(FieldError, match="reserved"):
+        enr.set(b"id", b"v5", _KEY)
@@ -0,0 +1,57 @@
+"""Tests for the NodeIdFilter membership structure."""
+
+import os
+
+import pytest
+from pyenr import NodeIdFilter, SigningKey
+
+
+def test_add_and_contains():
+    f = NodeIdFilter(1000)
+    node_id = os.urandom(32)
+    assert not f.contains(node_id)
+    assert f.add(node_id)
+    assert f.contains(node_id)
+    assert node_id in f
+    assert not f.add(node_id)
+    assert len(f) == 1
+
+
+def test_accepts_records():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    f = NodeIdFilter(100)
+    f.add(enr)
+    assert enr.node_id in f
+    assert enr in f
+
+
+def test_bulk_operations():
+    ids = [os.urandom(32) for _ in range(5000)]
+    f = NodeIdFilter(10000, false_positive_rate=0.001)
+    assert f.add_many(ids) >= 4990
+    assert all(f.contains_many(ids))
+
+
+def test_false_positive_rate_is_bounded():
+    f = NodeIdFilter(5000, false_positive_rate=0.01)
+    f.add_many([os.urandom(32) for _ in range(5000)])
+    hits = sum(f.contains_many([os.urandom(32) for _ in range(20000)]))
+    assert hits / 20000 < 0.03
+
+
+def test_parameters():
+    f = NodeIdFilter(1000, 0.01)
+    assert f.capacity == 1000
+    assert f.false_positive_rate == 0.01
+    assert f.num_bits >= 9585
+    assert f.num_hashes == 7
+
+
+def test_invalid_arguments():
+    with pytest.raises(ValueError):
+        NodeIdFilter(0)
+    with pytest.raises(ValueError):
+        NodeIdFilter(10, false_positive_rate=1.5)
+    with pytest.raises(ValueError, match="32 bytes"):
+        NodeIdFilter(10).add(b"\x00" * 31)
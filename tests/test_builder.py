@@ -57,3 +57,25 @@ def test_builder_ed25519():
     assert enr.ip4 == "127.0.0.1"
     assert enr.udp4 == 30303
     assert enr.identity_scheme == "v4"
+
+
+def test_clone_template():
+    template = SigningKey.generate_secp256k1().builder()
+    template.tcp4(30303)
+    template.udp4(30303)
+    template.add("custom", b"\x01")
+
+    records = []
+    for i in range(3):
+        key = SigningKey.generate_ed25519() if i % 2 else SigningKey.generate_secp256k1()
+        builder = template.clone_template()
+        builder.ip4(f"10.0.0.{i + 1}")
+        records.append(builder.build(key))
+
+    assert len({r.node_id for r in records}) == 3
+    assert [r.ip4 for r in records] == ["10.0.0.1", "10.0.0.2", "10.0.0.3"]
+    assert all(r.tcp4 == 30303 and r.get("custom") == b"\x01" for r in records)
+
+    # Changes to a copy do not leak back into the template.
+    key = SigningKey.generate_secp256k1()
+    assert template.build(key).ip4 is None
@@ -0,0 +1,82 @@
+"""Tests for the LRU record cache."""
+
+import threading
+
+import pytest
+from pyenr import Enr, EnrCache, SigningKey
+
+
+def test_keeps_newest_seq(make_record):
+    key = SigningKey.generate_secp256k1()
+    cache = EnrCache(4)
+    assert cache.put(make_record(key, 3))
+    assert not cache.put(make_record(key, 3))
+    assert not cache.put(make_record(key, 2))
+    assert cache.get(make_record(key)).seq == 3
+    assert cache.put(make_record(key, 4))
+    assert cache.get(make_record(key).node_id).seq == 4
+    assert len(cache) == 1
+
+
+def test_evicts_least_recently_used(make_record):
+    keys = [SigningKey.generate_secp256k1() for _ in range(4)]
+    records = [make_record(k) for k in keys]
+    cache = EnrCache(3)
+    for enr in records[:3]:
+        cache.put(enr)
+    assert cache.get(records[0]) == records[0]
+    # A stale record still counts as use.
+    assert not cache.put(records[1])
+    cache.put(records[3])
+    assert records[2] not in cache
+    assert cache.records() == [records[0], records[1], records[3]]
+    assert cache.evictions == 1
+    assert repr(cache) == "EnrCache(max_entries=3, len=3, evictions=1)"
+
+
+def test_contains_does_not_mark_used(make_record):
+    records = [make_record(SigningKey.generate_secp256k1()) for _ in range(3)]
+    cache = EnrCache(2)
+    cache.put(records[0])
+    cache.put(records[1])
+    assert records[0] in cache
+    cache.put(records[2])
+    assert records[0] not in cache
+
+
+def test_pop_and_clear(make_record):
+    enr = make_record(SigningKey.generate_secp256k1())
+    cache = EnrCache(2)
+    cache.put(enr)
+    assert cache.pop(enr.node_id) == enr
+    assert cache.pop(enr) is None
+    cache.put(enr)
+    cache.clear()
+    assert len(cache) == 0
+    assert cache.get(enr) is None
+
+
+def test_invalid():
+    with pytest.raises(ValueError, match="positive"):
+        EnrCache(0)
+    with pytest.raises(ValueError, match="32 bytes"):
+        EnrCache(1).get(b"short")
+
+
+def test_shared_between_threads(make_record):
+    records = [make_record(SigningKey.generate_secp256k1()) for _ in range(64)]
+    cache = EnrCache(32)
+
+    def worker():
+        for enr in records:
+            cache.put(enr)
+            cache.get(enr)
+
+    threads = [threading.Thread(target=worker) for _ in range(4)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    assert len(cache) == 32
+    assert cache.max_entries == 32
+    assert all(isinstance(enr, Enr) for enr in cache.records())
@@ -0,0 +1,114 @@
+"""Tests for stopping batch calls with a CancelToken or Ctrl-C."""
+
+import asyncio
+import os
+import signal
+import socket
+import threading
+import time
+
+import pytest
+from pyenr import (
+    CancelledError,
+    CancelToken,
+    EnrError,
+    SigningKey,
+    dedupe_report_async,
+    features,
+    filter_many,
+    probe_liveness,
+    process_enr_files,
+    write_enr_file,
+)
+
+
+def _records(n=4, port=9000):
+    out = []
+    for _ in range(n):
+        key = SigningKey.generate_secp256k1()
+        builder = key.builder()
+        builder.ip4("127.0.0.1")
+        builder.udp4(port)
+        out.append(builder.build(key))
+    return out
+
+
+def test_token():
+    token = CancelToken()
+    assert not token.cancelled
+    assert repr(token) == "CancelToken(cancelled=False)"
+    token.cancel()
+    token.cancel()
+    assert token.cancelled
+    assert repr(token) == "CancelToken(cancelled=True)"
+    assert issubclass(CancelledError, EnrError)
+
+
+def test_unused_token_changes_nothing():
+    records = _records()
+    assert filter_many(records, has_ip4=True, cancel=CancelToken()) == records
+
+
+def test_cancelled_token_stops_later_calls(tmp_path):
+    token = CancelToken()
+    token.cancel()
+    with pytest.raises(CancelledError, match="cancelled"):
+        filter_many(_records(), cancel=token)
+    with pytest.raises(CancelledError):
+        filter_many([], cancel=token)
+    path = tmp_path / "nodes.txt"
+    write_enr_file(path, _records())
+    with pytest.raises(CancelledError):
+        process_enr_files([path], cancel=token)
+
+    async def run():
+        return await dedupe_report_async(_records(), cancel=token)
+
+    with pytest.raises(CancelledError):
+        asyncio.run(run())
+
+
+def _slow_probe(**kwargs):
+    if "discv5" not in features()["features"]:
+        pytest.skip("built without the discv5 feature")
+    # Bound but never read, so each discv5 ping waits for the full timeout.
+    with socket.socket(socket.AF_INET, socket.SOCK_DGRAM) as sock:
+        sock.bind(("127.0.0.1", 0))
+        records = _records(20, port=sock.getsockname()[1])
+        probe_liveness(records, method="discv5_ping", concurrency=1, timeout=0.1, **kwargs)
+
+
+def test_cancel_from_another_thread():
+    token = CancelToken()
+    timer = threading.Timer(0.25, token.cancel)
+    start = time.monotonic()
+    timer.start()
+    with pytest.raises(CancelledError):
+        _slow_probe(cancel=token)
+    assert time.monotonic() - start < 1.0
+
+
+def test_progress_stops_counting_on_cancel():
+    token = CancelToken()
+    calls = []
+
+    def progress(done, total):
+        calls.append(done)
+        token.cancel()
+
+    with pytest.raises(CancelledError):
+        _slow_probe(progress=progress, cancel=token)
+    assert calls and calls[-1] < 20
+
+
+def test_ctrl_c_interrupts():
+    if os.name != "posix":
+        pytest.skip("sends SIGINT to the test process")
+    if "discv5" not in features()["features"]:
+        pytest.skip("built without the discv5 feature")
+    timer = threading.Timer(0.25, os.kill, (os.getpid(), signal.SIGINT))
+    start = time.monotonic()
+    timer.start()
+    with pytest.raises(KeyboardInterrupt):
+        _slow_probe()
+    assert time.monotonic() - start < 1.0
@@ -0,0 +1,110 @@
+"""Tests for churn statistics over timestamped observations."""
+
+import pytest
+from pyenr import ChurnStats, SigningKey, churn_stats
+
+DAY = 86400.0
+_KEYS = [SigningKey.generate_secp256k1() for _ in range(3)]
+
+
+def test_empty():
+    stats = churn_stats([])
+    assert isinstance(stats, ChurnStats)
+    assert stats.observations == 0
+    assert stats.nodes == []
+    assert stats.field_changes == {}
+    assert stats.windows == []
+    assert stats.interval == DAY
+
+
+def test_node_update_rates(make_record):
+    key = _KEYS[0]
+    observations = [
+        (2 * DAY, make_record(key, seq=3, ip="10.0.0.2")),
+        (0.0, make_record(key, seq=1)),
+        (DAY, make_record(key, seq=1)),
+        (4 * DAY, make_record(key, seq=4, ip="10.0.0.2", udp=9000)),
+    ]
+    stats = churn_stats(observations)
+    assert stats.observations == 4
+    [node] = stats.nodes
+    assert node.node_id == observations[0][1].node_id
+    assert (node.first_seen, node.last_seen) == (0.0, 4 * DAY)
+    assert node.observations == 4
+    assert node.updates == 2
+    assert node.updates_per_day == pytest.approx(0.5)
+
+
+def test_single_instant_has_no_rate(make_record):
+    stats = churn_stats([(100.0, make_record(_KEYS[0]))])
+    assert stats.nodes[0].updates == 0
+    assert stats.nodes[0].updates_per_day is None
+
+
+def test_field_changes(make_record):
+    key = _KEYS[0]
+    stats = churn_stats(
+        [
+            (0.0, make_record(key, seq=1)),
+            (1.0, make_record(key, seq=2, ip="10.0.0.2")),
+            (2.0, make_record(key, seq=3, ip="10.0.0.3", udp=9000)),
+            (3.0, make_record(_KEYS[1], seq=1)),
+            (4.0, make_record(_KEYS[1], seq=2, udp=9000)),
+        ]
+    )
+    assert stats.field_changes == {"ip": 2, "udp": 2}
+
+
+def test_windows(make_record):
+    a, b, c = _KEYS
+    observations = [
+        (0.5 * DAY, make_record(a)),
+        (0.7 * DAY, make_record(b)),
+        (1.5 * DAY, make_record(a)),
+        (1.6 * DAY, make_record(c)),
+        (3.2 * DAY, make_record(a)),
+        (3.3 * DAY, make_record(b)),
+    ]
+    stats = churn_stats(observations)
+    summary = [(w.start, w.seen, w.joined, w.left) for w in stats.windows]
+    assert summary == [
+        (0.0, 2, 2, 0),
+        (DAY, 2, 1, 1),
+        (2 * DAY, 0, 0, 2),
+        (3 * DAY, 2, 2, 0),
+    ]
+    by_node = {n.node_id: n.observations for n in stats.nodes}
+    assert by_node == {
+        observations[0][1].node_id: 3,
+        observations[1][1].node_id: 2,
+        observations[3][1].node_id: 1,
+    }
+
+
+def test_custom_interval_and_generator(make_record):
+    key = _KEYS[0]
+    stats = churn_stats(((t * 60.0, make_record(key)) for t in range(5)), interval=120.0)
+    assert stats.observations == 5
+    assert [(w.start, w.seen) for w in stats.windows] == [(0.0, 1), (120.0, 1), (240.0, 1)]
+
+
+def test_nodes_in_node_id_order(make_record):
+    stats = churn_stats([(0.0, make_record(k)) for k in _KEYS])
+    ids = [n.node_id for n in stats.nodes]
+    assert ids == sorted(ids)
+
+
+@pytest.mark.parametrize("interval", [0.0, -1.0, float("inf")])
+def test_invalid_interval(interval):
+    with pytest.raises(ValueError, match="interval"):
+        churn_stats([], interval=interval)
+
+
+def test_invalid_timestamp(make_record):
+    with pytest.raises(ValueError, match="timestamp must be finite"):
+        churn_stats([(float("nan"), make_record(_KEYS[0]))])
+
+
+def test_bad_observation():
+    with pytest.raises(TypeError):
+        churn_stats([(0.0, "enr:-abc")])
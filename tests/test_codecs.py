@@ -0,0 +1,92 @@
+"""Tests for user-registered field codecs."""
+
+import pytest
+from pyenr import (
+    Enr,
+    SigningKey,
+    register_codec,
+    registered_codecs,
+    unregister_codec,
+)
+
+
+def _register_shard():
+    register_codec(
+        "shard",
+        encode_fn=lambda n: n.to_bytes(2, "big"),
+        decode_fn=lambda raw: int.from_bytes(raw, "big"),
+    )
+
+
+def test_set_and_get_use_codec():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    _register_shard()
+    try:
+        enr.set("shard", 7, key)
+        assert enr.get_raw("shard") == b"\x82\x00\x07"
+        assert enr.get("shard") == 7
+        assert enr.get("shard", type=bytes) == b"\x00\x07"
+    finally:
+        unregister_codec("shard")
+    assert enr.get("shard") == b"\x00\x07"
+
+
+def test_builder_add_uses_codec():
+    key = SigningKey.generate_secp256k1()
+    _register_shard()
+    try:
+        builder = key.builder()
+        builder.add("shard", 300)
+        enr = builder.build(key)
+        assert enr.get("shard") == 300
+        assert Enr.from_base64(enr.to_base64()).get("shard") == 300
+    finally:
+        unregister_codec("shard")
+
+
+def test_decode_only_codec():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    enr.set("tags", [b"a", b"b"], key)
+    register_codec("tags", decode_fn=lambda items: [i.decode() for i in items])
+    try:
+        assert enr.get("tags") == ["a", "b"]
+    finally:
+        unregister_codec("tags")
+
+
+def test_registry_listing():
+    register_codec("zz", decode_fn=bytes.hex)
+    register_codec("aa", encode_fn=bytes)
+    try:
+        assert [k for k in registered_codecs() if k in ("aa", "zz")] == ["aa", "zz"]
+    finally:
+        assert unregister_codec("aa")
+        assert unregister_codec("zz")
+    assert not unregister_codec("zz")
+    assert "aa" not in registered_codecs()
+
+
+def test_encoder_errors_propagate():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    register_codec("shard", encode_fn=lambda n: n.to_bytes(1, "big"))
+    try:
+        with pytest.raises(OverflowError):
+            enr.set("shard", 1000, key)
+        assert enr.get("shard") is None
+    finally:
+        unregister_codec("shard")
+
+
+@pytest.mark.parametrize("reserved", ["id", "secp256k1", "ed25519"])
+def test_reserved_keys_rejected(reserved):
+    with pytest.raises(ValueError):
+        register_codec(reserved, decode_fn=bytes.hex)
+
+
+def test_non_callable_rejected():
+    with pytest.raises(ValueError):
+        register_codec("shard", encode_fn=b"not callable")
+    assert "shard" not in registered_codecs()
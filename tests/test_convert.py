@@ -0,0 +1,58 @@
+"""Tests for the module-level decode() and encode()."""
+
+import pytest
+from pyenr import DecodeError, Enr, SigningKey, decode, encode
+
+
+def _record():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.udp4(30303)
+    return builder.build(key)
+
+
+def test_decode_any_form():
+    enr = _record()
+    raw = enr.to_bytes()
+    for value in (
+        enr.to_base64(),
+        enr.to_base64()[len("enr:"):],
+        "0x" + raw.hex(),
+        raw,
+        bytearray(raw),
+        f"  {enr.to_base64()}\n",
+    ):
+        assert decode(value) == enr
+    assert decode(enr) is enr
+
+
+def test_encode_formats():
+    enr = _record()
+    assert encode(enr) == enr.to_base64()
+    assert encode(enr, "bytes") == enr.to_bytes()
+    assert encode(enr, format="hex") == "0x" + enr.to_bytes().hex()
+    for fmt in ("base64", "hex", "bytes"):
+        assert decode(encode(enr, fmt)) == enr
+
+
+def test_decode_errors():
+    with pytest.raises(DecodeError):
+        decode("0xzz")
+    with pytest.raises(DecodeError):
+        decode(b"\xc0")
+    with pytest.raises(TypeError, match="int"):
+        decode(42)
+
+
+def test_decode_strict():
+    enr = _record()
+    with pytest.raises(DecodeError, match="trailing_data"):
+        decode(enr.to_bytes() + b"\x00", strict=True)
+    assert decode(enr.to_bytes() + b"\x00") == enr
+
+
+def test_encode_unknown_format():
+    with pytest.raises(ValueError, match="unknown format"):
+        encode(_record(), "json")
+    assert isinstance(decode(_record().to_base64()), Enr)
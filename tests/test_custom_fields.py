@@ -1,5 +1,6 @@
 """Tests for custom key-value pair handling."""
 
+import pytest
 from pyenr import Enr, SigningKey
 
 
@@ -116,3 +117,235 @@ def test_builder_multiple_custom_fields():
     assert enr.get("field_a") is not None
     assert enr.get("field_b") is not None
     assert enr.get("field_c") is not None
+
+
+def test_custom_value_encoded_as_byte_string():
+    """Custom values are stored as RLP byte strings, not lists of integers."""
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.add("built", b"\x01\x02\x90")
+    enr = builder.build(key)
+    enr.set("set", b"\x01\x02\x90", key)
+
+    assert enr.get_raw_rlp("built") == b"\x83\x01\x02\x90"
+    assert enr.get_raw_rlp("set") == b"\x83\x01\x02\x90"
+    assert enr.get("set") == b"\x01\x02\x90"
+
+
+def test_oversize_set_names_key_and_overflow():
+    """SizeError reports the key and how far past the limit the record would go."""
+    import pytest
+    from pyenr import SizeError
+
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    before = enr.to_bytes()
+
+    with pytest.raises(SizeError, match='"big"') as exc:
+        enr.set("big", b"\x01" * 250, key)
+    err = exc.value
+    assert err.key == "big"
+    assert err.size > 300
+    assert err.overflow == err.size - 300
+    assert enr.to_bytes() == before
+
+
+def test_set_exactly_at_limit_allowed():
+    """A value that brings the record to exactly 300 bytes is accepted."""
+    import pytest
+    from pyenr import SizeError
+
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    with pytest.raises(SizeError) as exc:
+        enr.set("pad", b"\x01" * 250, key)
+    fits = 250 - exc.value.overflow
+    enr.set("pad", b"\x01" * fits, key)
+    assert len(enr.to_bytes()) == 300
+
+
+def test_set_reserved_keys_rejected():
+    """The identity scheme and public key cannot be overwritten through set()."""
+    import pytest
+    from pyenr import FieldError
+
+    key = SigningKey.generate_secp256k1()
+    other = SigningKey.generate_ed25519()
+    enr = key.builder().build(key)
+    before = enr.to_bytes()
+
+    for reserved, value in [
+        ("id", b"v4"),
+        ("id", b"v5"),
+        ("secp256k1", key.public_key()),
+        ("ed25519", other.public_key()),
+    ]:
+        with pytest.raises(FieldError, match=f'"{reserved}" is reserved'):
+            enr.set(reserved, value, key)
+    assert enr.to_bytes() == before
+
+
+def test_set_ip_wrong_length_rejected():
+    import pytest
+    from pyenr import FieldError
+
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+
+    with pytest.raises(FieldError, match='"ip" must be 4 bytes, got 5'):
+        enr.set("ip", b"\x0a\x00\x00\x01\x00", key)
+    with pytest.raises(FieldError, match='"ip6" must be 16 bytes, got 4'):
+        enr.set("ip6", b"\x0a\x00\x00\x01", key)
+
+    enr.set("ip", b"\x0a\x00\x00\x01", key)
+    assert enr.ip4 == "10.0.0.1"
+
+
+def test_builder_add_reserved_key_rejected():
+    import pytest
+    from pyenr import FieldError
+
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    with pytest.raises(FieldError):
+        builder.add("secp256k1", key.public_key())
+    with pytest.raises(FieldError):
+        builder.add("ip", b"\x01")
+
+
+@pytest.mark.parametrize(
+    "field, value, message",
+    [
+        ("eth2", b"\x01" * 15, '"eth2" must be 16 bytes, got 15'),
+        ("tcp", b"\x00\x01\x02", '"tcp" must be a port of at most 2 bytes'),
+        ("udp6", b"\x00\x50", '"udp6" must be a minimally encoded port'),
+    ],
+)
+def test_set_well_known_shape_rejected(field, value, message):
+    from pyenr import FieldError
+
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    with pytest.raises(FieldError, match=message):
+        enr.set(field, value, key)
+
+
+def test_set_well_known_shapes_accepted():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    enr.set("eth2", b"\x01" * 16, key)
+    enr.set("tcp", b"\x76\x5f", key)
+    enr.set("udp", b"\x50", key)
+    assert enr.tcp4 == 30303
+    assert enr.udp4 == 80
+    assert enr.validate().is_valid
+
+
+def test_get_default_and_type():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.tcp4(30303)
+    builder.add("name", "nöde".encode())
+    enr = builder.build(key)
+
+    assert enr.get("missing") is None
+    assert enr.get("missing", b"") == b""
+    assert enr.get("missing", default=0, type=int) == 0
+    assert enr.get("tcp", type=int) == 30303
+    assert enr.get("tcp") == b"\x76\x5f"
+    assert enr.get("ip", type=str) == "10.0.0.1"
+    assert enr.get("name", type=str) == "nöde"
+    assert enr.get("name", type=bytes) == "nöde".encode()
+
+
+def test_get_aliases():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.udp4(9000)
+    enr = builder.build(key)
+    assert enr.get("ip4", type=str) == "10.0.0.1"
+    assert enr.get("udp4", type=int) == 9000
+    assert enr.get("tcp4", 1) == 1
+
+
+def test_get_type_errors():
+    from pyenr import FieldError
+
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    enr.set("blob", b"\xff\xfe", key)
+    with pytest.raises(FieldError, match="UTF-8"):
+        enr.get("blob", type=str)
+    with pytest.raises(TypeError, match="float"):
+        enr.get("blob", type=float)
+
+
+def test_get_raw_and_decoded():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.tcp4(30303)
+    builder.add("blob", b"\xaa" * 60)
+    enr = builder.build(key)
+
+    assert enr.get_raw("tcp") == b"\x82\x76\x5f"
+    assert enr.get_raw("tcp") == enr.get_raw_rlp("tcp")
+    assert enr.get_raw("blob") == b"\xb8\x3c" + b"\xaa" * 60
+    assert enr.get_decoded("blob") == b"\xaa" * 60
+    assert enr.get_decoded("id") == b"v4"
+    assert enr.get_raw("missing") is None
+    assert enr.get_decoded("missing") is None
+
+
+def test_set_nested_list_value():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+
+    # An execution-layer fork id: [[fork_hash, fork_next]].
+    enr.set("eth", [[b"\xfc\x64\xec\x04", 1150000]], key)
+    assert enr.get_decoded("eth") == [[b"\xfc\x64\xec\x04", b"\x11\x8c\x30"]]
+    assert enr.get_raw("eth") == b"\xca\xc9\x84\xfc\x64\xec\x04\x83\x11\x8c\x30"
+
+    enr.set("empty", [], key)
+    assert enr.get_raw("empty") == b"\xc0"
+    enr.set("mixed", (0, 1, b"", [bytearray(b"ab"), [[]]]), key)
+    assert enr.get_decoded("mixed") == [b"", b"\x01", b"", [b"ab", [[]]]]
+
+    decoded = Enr.from_bytes(enr.to_bytes())
+    assert decoded.get_decoded("eth") == [[b"\xfc\x64\xec\x04", b"\x11\x8c\x30"]]
+    assert decoded.validate().is_valid
+
+
+def test_set_int_value():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    enr.set("cgc", 4, key)
+    enr.set("tcp", 30303, key)
+    enr.set("big", 2**80, key)
+    assert enr.get_raw("cgc") == b"\x04"
+    assert enr.tcp4 == 30303
+    assert enr.get("big", type=int) == 2**80
+
+
+def test_builder_nested_list_value():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.add("snap", [])
+    builder.add("eth", [[b"\x01\x02\x03\x04", 0]])
+    enr = builder.build(key)
+    assert enr.get_decoded("snap") == []
+    assert enr.get_decoded("eth") == [[b"\x01\x02\x03\x04", b""]]
+
+
+def test_set_invalid_values():
+    from pyenr import FieldError
+
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    with pytest.raises(FieldError, match="negative"):
+        enr.set("n", -1, key)
+    with pytest.raises(TypeError, match="str"):
+        enr.set("s", ["text"], key)
+    with pytest.raises(FieldError, match="not a list"):
+        enr.set("ip", [b"\x01\x02\x03\x04"], key)
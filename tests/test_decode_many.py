@@ -0,0 +1,121 @@
+"""Tests for decoding many records at once."""
+
+import pytest
+from pyenr import (
+    CancelledError,
+    CancelToken,
+    DecodeError,
+    Enr,
+    SignatureError,
+    SigningKey,
+    decode_many,
+    decode_many_bytes,
+)
+
+
+def _records(n):
+    out = []
+    for i in range(n):
+        key = SigningKey.from_secp256k1(i.to_bytes(2, "big") + b"\x03" * 30)
+        builder = key.builder()
+        builder.ip4(f"10.2.{i // 256}.{i % 256}")
+        builder.udp4(30303)
+        out.append(builder.build(key))
+    return out
+
+
+def _tampered(enr):
+    """The record's encoding with its UDP port changed, so the signature no longer covers it."""
+    data = bytearray(enr.to_bytes())
+    data[-1] ^= 1
+    return bytes(data)
+
+
+def test_decodes_in_order():
+    records = _records(200)
+    assert decode_many([r.to_base64() for r in records]) == records
+    assert decode_many_bytes([r.to_bytes() for r in records]) == records
+    assert decode_many([]) == []
+    assert decode_many_bytes([]) == []
+
+
+def test_takes_every_text_shape():
+    enr = _records(1)[0]
+    text = enr.to_base64()
+    shapes = [text, text[len("enr:") :], f"  {text}\n", "0x" + enr.to_bytes().hex()]
+    assert decode_many(shapes) == [enr] * len(shapes)
+    assert decode_many_bytes([bytearray(enr.to_bytes())]) == [enr]
+
+
+def test_raises_first_failure():
+    texts = [r.to_base64() for r in _records(50)]
+    texts[30] = "enr:not-base64!"
+    texts[10] = "enr:-AAA"
+    with pytest.raises(DecodeError) as info:
+        decode_many(texts)
+    assert info.value.index == 10
+    with pytest.raises(DecodeError) as single:
+        Enr.from_base64("enr:-AAA")
+    assert str(info.value) == str(single.value)
+    assert info.value.offset == single.value.offset
+
+
+def test_raises_signature_error():
+    data = [r.to_bytes() for r in _records(5)]
+    data[3] = _tampered(_records(4)[3])
+    with pytest.raises(SignatureError) as info:
+        decode_many_bytes(data)
+    assert info.value.index == 3
+
+
+def test_return_errors():
+    records = _records(20)
+    texts = [r.to_base64() for r in records]
+    texts[4] = "garbage"
+    texts[12] = "0xzz"
+    found, errors = decode_many(texts, return_errors=True)
+    assert found == records[:4] + records[5:12] + records[13:]
+    assert [index for index, _ in errors] == [4, 12]
+    assert all(isinstance(err, DecodeError) for _, err in errors)
+    assert all(err.index == index for index, err in errors)
+
+    data = [r.to_bytes() for r in records]
+    data[0] = _tampered(records[0])
+    found, errors = decode_many_bytes(data, return_errors=True)
+    assert found == records[1:]
+    assert [(index, type(err)) for index, err in errors] == [(0, SignatureError)]
+
+    assert decode_many(texts[:4], return_errors=True) == (records[:4], [])
+
+
+def test_strict():
+    enr = _records(1)[0]
+    trailing = enr.to_bytes() + b"\x00"
+    assert decode_many_bytes([trailing]) == [enr]
+    with pytest.raises(DecodeError):
+        decode_many_bytes([trailing], strict=True)
+    with pytest.raises(DecodeError):
+        decode_many(["0x" + trailing.hex()], strict=True)
+
+
+def test_workers_progress_and_cancel():
+    texts = [r.to_base64() for r in _records(100)]
+    calls = []
+    found = decode_many(texts, workers=2, progress=lambda d, t: calls.append((d, t)))
+    assert len(found) == 100
+    assert calls[-1] == (100, 100)
+    token = CancelToken()
+    token.cancel()
+    with pytest.raises(CancelledError):
+        decode_many(texts, cancel=token)
+    with pytest.raises(ValueError):
+        decode_many(texts, workers=0)
+
+
+def test_rejects_wrong_item_types():
+    with pytest.raises(TypeError):
+        decode_many("enr:-AAA")
+    with pytest.raises(TypeError):
+        decode_many([b"bytes"])
+    with pytest.raises(TypeError):
+        decode_many_bytes(["text"])
@@ -0,0 +1,71 @@
+"""Tests for StreamingDeduper."""
+
+import pytest
+from pyenr import SigningKey, StreamingDeduper
+
+
+def _versions(key, seqs):
+    """Records for one key at the given sequence numbers."""
+    records = []
+    for seq in seqs:
+        enr = key.builder().build(key)
+        enr.set_seq(seq, key)
+        records.append(enr)
+    return records
+
+
+def test_keeps_highest_seq_across_batches():
+    key = SigningKey.generate_secp256k1()
+    v1, v2, v3 = _versions(key, [1, 2, 3])
+    d = StreamingDeduper()
+
+    assert d.feed([v2]).updated == [v2]
+    assert d.feed([v1]).updated == []
+    assert d.feed([v3, v1]).updated == [v3]
+    assert len(d) == 1
+    assert d.get(v1) == v3
+    assert d.get(v1.node_id) == v3
+
+
+def test_reports_each_node_once_per_batch():
+    key = SigningKey.generate_secp256k1()
+    v1, v2 = _versions(key, [1, 2])
+    result = StreamingDeduper().feed([v1, v2])
+    assert result.updated == [v2]
+    assert result.evicted == []
+
+
+def test_evicts_least_recently_seen():
+    a, b, c = (SigningKey.generate_secp256k1() for _ in range(3))
+    ra, rb, rc = (k.builder().build(k) for k in (a, b, c))
+    d = StreamingDeduper(max_entries=2)
+
+    d.feed([ra, rb])
+    d.feed([ra])  # touching a makes b the oldest
+    result = d.feed([rc])
+    assert result.updated == [rc]
+    assert result.evicted == [rb]
+    assert rb not in d
+    assert ra in d
+    assert d.records() == [ra, rc]
+    assert d.evictions == 1
+
+
+def test_clear():
+    key = SigningKey.generate_secp256k1()
+    d = StreamingDeduper(max_entries=10)
+    d.feed(_versions(key, [1]))
+    d.clear()
+    assert len(d) == 0
+    assert d.max_entries == 10
+    assert repr(d) == "StreamingDeduper(max_entries=10, len=0, evictions=0)"
+
+
+def test_rejects_zero_max_entries():
+    with pytest.raises(ValueError, match="max_entries"):
+        StreamingDeduper(max_entries=0)
+
+
+def test_get_rejects_bad_node_id():
+    with pytest.raises(ValueError, match="32 bytes"):
+        StreamingDeduper().get(b"short")
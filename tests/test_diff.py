@@ -0,0 +1,61 @@
+"""Tests for Enr.diff."""
+
+from pyenr import Enr, SigningKey
+
+
+def _record():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.tcp4(30303)
+    builder.add("custom", b"\x01")
+    return key, builder.build(key)
+
+
+def test_identical_records():
+    _, enr = _record()
+    diff = enr.diff(Enr.from_bytes(enr.to_bytes()))
+    assert not diff
+    assert diff.added == {} and diff.removed == {} and diff.changed == {}
+    assert diff.seq_delta == 0
+
+
+def test_added_removed_changed():
+    key, old = _record()
+    builder = key.builder()
+    builder.ip4("10.0.0.2")
+    builder.tcp4(30303)
+    builder.udp4(9000)
+    new = builder.build(key)
+    new.set_seq(old.seq + 3, key)
+
+    diff = old.diff(new)
+    assert diff
+    assert diff.added == {"udp": 9000}
+    assert diff.removed == {"custom": b"\x01"}
+    assert diff.changed == {"ip": ("10.0.0.1", "10.0.0.2")}
+    assert diff.seq_delta == 3
+
+    reverse = new.diff(old)
+    assert reverse.added == {"custom": b"\x01"}
+    assert reverse.removed == {"udp": 9000}
+    assert reverse.changed == {"ip": ("10.0.0.2", "10.0.0.1")}
+    assert reverse.seq_delta == -3
+
+
+def test_seq_only_change_is_empty():
+    key, old = _record()
+    new = Enr.from_bytes(old.to_bytes())
+    new.set_seq(old.seq + 1, key)
+    diff = old.diff(new)
+    assert not diff
+    assert diff.seq_delta == 1
+
+
+def test_repr_lists_keys():
+    key, old = _record()
+    new = Enr.from_bytes(old.to_bytes())
+    new.set_udp4(9000, key)
+    assert repr(old.diff(new)) == (
+        "EnrDiff(added=[\"udp\"], removed=[], changed=[], seq_delta=1)"
+    )
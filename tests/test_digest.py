@@ -0,0 +1,73 @@
+"""Tests for order-independent digests of record sets."""
+
+import random
+
+import pytest
+from pyenr import EnrStore, IdentityTracker, SigningKey, features, records_digest
+
+_KEYS = [SigningKey.generate_secp256k1() for _ in range(4)]
+
+
+def test_digest_format(make_record):
+    digest = records_digest([make_record(k) for k in _KEYS])
+    assert len(digest) == 64
+    assert int(digest, 16) >= 0
+    assert digest == digest.lower()
+
+
+def test_single_record_matches_fingerprint(make_record):
+    # Both hash the node id followed by the seq as 8 big-endian bytes.
+    enr = make_record(_KEYS[0], seq=7)
+    assert records_digest([enr])[:16] == enr.fingerprint()
+
+
+def test_order_and_repeats_do_not_matter(make_record):
+    records = [make_record(k) for k in _KEYS]
+    shuffled = records[:]
+    random.shuffle(shuffled)
+    assert records_digest(records) == records_digest(shuffled)
+    assert records_digest(records) == records_digest(records + records[:2])
+
+
+def test_seq_and_membership_change_the_digest(make_record):
+    records = [make_record(k) for k in _KEYS]
+    digest = records_digest(records)
+    assert records_digest(records[:-1]) != digest
+    assert records_digest(records[:-1] + [make_record(_KEYS[-1], seq=2)]) != digest
+
+
+def test_content_is_not_covered(make_record):
+    key = _KEYS[0]
+    builder = key.builder()
+    builder.udp4(30303)
+    other = builder.build(key)
+    assert records_digest([other]) == records_digest([make_record(key)])
+
+
+def test_empty_set(make_record):
+    assert records_digest([]) == records_digest([])
+    assert records_digest([]) != records_digest([make_record(_KEYS[0])])
+
+
+def test_store_digest(make_record):
+    if "sqlite" not in features()["features"]:
+        pytest.skip("built without the sqlite feature")
+    records = [make_record(k, seq=3) for k in _KEYS]
+    with EnrStore(":memory:") as store:
+        assert store.digest() == records_digest([])
+        store.upsert_many(records)
+        assert store.digest() == records_digest(records)
+        store.upsert(make_record(_KEYS[0], seq=1))
+        assert store.digest() == records_digest(records)
+
+
+def test_tracker_digest(make_record):
+    records = [make_record(k) for k in _KEYS]
+    fresher = make_record(_KEYS[0], seq=5)
+    a, b = IdentityTracker(), IdentityTracker()
+    a.add_many(records, "dns")
+    a.add(fresher, "discv5")
+    b.add(fresher, "static")
+    b.add_many(records[::-1], "dns")
+    assert a.digest() == b.digest()
+    assert a.digest() == records_digest(records[1:] + [fresher])
@@ -0,0 +1,57 @@
+"""Tests for effective_endpoints() and the IPv6 port fallback."""
+
+from pyenr import SigningKey
+
+_KEY = SigningKey.generate_secp256k1()
+
+
+def _record(**fields):
+    builder = _KEY.builder()
+    for name, value in fields.items():
+        getattr(builder, name)(value)
+    return builder.build(_KEY)
+
+
+def test_dual_stack_with_specific_ports():
+    enr = _record(ip4="1.2.3.4", udp4=30303, tcp4=30304, ip6="2001:db8::1", udp6=9000, tcp6=9001)
+    expected = [
+        ("udp", "1.2.3.4", 30303),
+        ("tcp", "1.2.3.4", 30304),
+        ("udp", "2001:db8::1", 9000),
+        ("tcp", "2001:db8::1", 9001),
+    ]
+    assert enr.effective_endpoints() == expected
+    assert enr.effective_endpoints(fallback=False) == expected
+
+
+def test_ip6_falls_back_to_v4_ports():
+    enr = _record(ip6="2001:db8::1", udp4=30303, tcp4=30304)
+    assert enr.effective_endpoints() == [
+        ("udp", "2001:db8::1", 30303),
+        ("tcp", "2001:db8::1", 30304),
+    ]
+    assert enr.effective_endpoints(fallback=False) == []
+
+
+def test_fallback_fills_only_missing_ports():
+    enr = _record(ip4="1.2.3.4", ip6="2001:db8::1", udp4=30303, tcp4=30304, udp6=9000)
+    assert enr.effective_endpoints() == [
+        ("udp", "1.2.3.4", 30303),
+        ("tcp", "1.2.3.4", 30304),
+        ("udp", "2001:db8::1", 9000),
+        ("tcp", "2001:db8::1", 30304),
+    ]
+
+
+def test_v6_ports_do_not_apply_to_ip4():
+    enr = _record(ip4="1.2.3.4", udp6=9000)
+    assert enr.effective_endpoints() == []
+
+
+def test_placeholders_are_skipped():
+    enr = _record(ip4="0.0.0.0", udp4=30303, ip6="2001:db8::1", tcp6=0, udp6=9000)
+    assert enr.effective_endpoints() == [("udp", "2001:db8::1", 9000)]
+
+
+def test_no_address():
+    assert _record(udp4=30303).effective_endpoints() == []
@@ -25,6 +25,46 @@ def test_decode_without_prefix():
     assert len(enr.node_id) == 32
 
 
+@pytest.mark.parametrize(
+    "shape",
+    ["padded", "standard", "standard_padded", "whitespace", "hex", "hex_upper", "no_prefix_padded"],
+)
+def test_decode_forgiving_text(shape):
+    import base64
+
+    data = _TEST_ENR.to_bytes()
+    text = {
+        "padded": "enr:" + base64.urlsafe_b64encode(data).decode(),
+        "standard": base64.b64encode(data).decode().rstrip("="),
+        "standard_padded": "enr:" + base64.b64encode(data).decode(),
+        "whitespace": f"\n\t {SAMPLE_ENR}  \r\n",
+        "hex": " 0x" + data.hex() + "\n",
+        "hex_upper": "0X" + data.hex().upper(),
+        "no_prefix_padded": base64.urlsafe_b64encode(data).decode(),
+    }[shape]
+    assert Enr.from_base64(text) == _TEST_ENR
+
+
+def test_decode_standard_alphabet():
+    import base64
+
+    from pyenr import EIP778_ENR
+
+    data = Enr.from_base64(EIP778_ENR).to_bytes()
+    text = base64.b64encode(data).decode()
+    assert "+" in text and "/" in text
+    assert Enr.from_base64(text).to_bytes() == data
+
+
+def test_decode_forgiving_text_still_rejects_garbage():
+    from pyenr import DecodeError
+
+    with pytest.raises(DecodeError, match="hex"):
+        Enr.from_base64("0x123")
+    with pytest.raises(DecodeError, match="base64"):
+        Enr.from_base64("enr:a b")
+
+
 def test_identity_scheme():
     enr = Enr.from_base64(SAMPLE_ENR)
     assert enr.identity_scheme == "v4"
@@ -99,3 +139,128 @@ def test_none_fields():
 def test_get_nonexistent_key():
     enr = Enr.from_base64(SAMPLE_ENR)
     assert enr.get("nonexistent") is None
+
+
+def test_keys_are_interned():
+    """The same key name is shared between records rather than reallocated."""
+    a = Enr.from_base64(SAMPLE_ENR)
+    b = Enr.from_base64(SAMPLE_ENR)
+    for ka, kb in zip(a.keys(), b.keys()):
+        assert ka is kb
+    assert a.items()[0][0] is b.keys()[0]
+
+
+def test_unknown_keys_are_not_interned():
+    """Names anyone can make up get strings of their own, so they are freed again."""
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.add("made-up-name", b"\x01")
+    enr = builder.build(key)
+    a = Enr.from_bytes(enr.to_bytes())
+    b = Enr.from_bytes(enr.to_bytes())
+    assert "made-up-name" in a.keys()
+    name_a = next(k for k in a.keys() if k == "made-up-name")
+    name_b = next(k for k in b.keys() if k == "made-up-name")
+    assert name_a == name_b
+    assert name_a is not name_b
+    assert next(k for k in a.keys() if k == "id") is next(k for k in b.keys() if k == "id")
+
+
+def test_memory_usage():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    before = enr.memory_usage()
+    assert before > len(enr.to_bytes())
+
+    enr.set("blob", b"\x00" * 64, key)
+    assert enr.memory_usage() >= before + 64
+
+    enr.set("more", b"\x00", key)
+    grown = enr.memory_usage() - before
+    assert 64 + len("blob") + len("more") <= grown < 200
+
+
+def test_pretty():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.tcp4(9000)
+    builder.add("zz", b"hi")
+    enr = builder.build(key)
+
+    lines = enr.pretty().splitlines()
+    assert lines[0] == "Enr"
+    rows = [line.split(maxsplit=1) for line in lines[1:]]
+    assert [k for k, _ in rows] == ["node_id", "seq", "signature"] + enr.keys()
+    values = dict(rows)
+    assert values["node_id"] == "0x" + enr.node_id.hex()
+    assert values["seq"] == "1"
+    assert values["id"] == "v4"
+    assert values["ip"] == "10.0.0.1"
+    assert values["tcp"] == "9000"
+    assert values["secp256k1"] == "0x" + key.public_key().hex()
+    assert values["zz"] == "0x6869"
+    # Values start in the same column.
+    assert len({line.index(v) for line, (_, v) in zip(lines[1:], rows)}) == 1
+
+
+def test_bump_seq():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("1.2.3.4")
+    enr = builder.build(key)
+    before = enr.items()
+    assert enr.bump_seq(key) == 2
+    assert enr.seq == 2
+    assert enr.items() == before
+    assert Enr.from_bytes(enr.to_bytes()) == enr
+
+
+def test_bump_seq_overflow():
+    from pyenr import SequenceOverflowError
+
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    enr.set_seq(2**64 - 1, key)
+    before = enr.to_bytes()
+    with pytest.raises(SequenceOverflowError):
+        enr.bump_seq(key)
+    assert enr.to_bytes() == before
+
+
+def test_repr_styles():
+    from pyenr import get_repr_style, set_repr_style
+
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("1.2.3.4")
+    builder.tcp4(9000)
+    enr = builder.build(key)
+    enr.set_seq(42, key)
+
+    assert get_repr_style() == "base64"
+    assert repr(enr) == f"Enr({enr.to_base64()})"
+    try:
+        set_repr_style("fields")
+        assert get_repr_style() == "fields"
+        prefix = enr.node_id[:4].hex()
+        assert repr(enr) == f"Enr(node_id=0x{prefix}…, seq=42, ip4=1.2.3.4, tcp4=9000)"
+        bare = key.builder().build(key)
+        assert repr(bare) == f"Enr(node_id=0x{bare.node_id[:4].hex()}…, seq=1)"
+    finally:
+        set_repr_style("base64")
+    assert str(enr) == enr.to_base64()
+
+    with pytest.raises(ValueError, match="unknown repr style"):
+        set_repr_style("rich")
+
+
+def test_format_specs():
+    enr = Enr.from_base64(SAMPLE_ENR)
+    assert f"{enr}" == SAMPLE_ENR
+    assert f"{enr:base64}" == SAMPLE_ENR
+    assert f"{enr:hex}" == "0x" + enr.to_bytes().hex()
+    assert f"{enr:pretty}" == enr.pretty()
+    assert f"{enr:fields}".startswith(f"Enr(node_id=0x{enr.node_id[:4].hex()}…, seq=")
+    with pytest.raises(ValueError, match="unknown format"):
+        f"{enr:json}"
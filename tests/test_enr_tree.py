@@ -0,0 +1,183 @@
+"""Tests for EIP-1459 tree entries and building signed trees."""
+
+import pytest
+from pyenr import (
+    DecodeError,
+    Enr,
+    EnrTree,
+    EnrTreeBranch,
+    EnrTreeLink,
+    EnrTreeRoot,
+    SigningKey,
+    parse_tree_entry,
+)
+
+# The example tree from EIP-1459.
+_RECORDS = [
+    "enr:-HW4QOFzoVLaFJnNhbgMoDXPnOvcdVuj7pDpqRvh6BRDO68aVi5ZcjB3vzQRZH2IcLBGHzo8uUN3snqmgTiE56CH3AMBgmlkgnY0iXNlY3AyNTZrMaECC2_24YYkYHEgdzxlSNKQEnHhuNAbNlMlWJxrJxbAFvA",
+    "enr:-HW4QAggRauloj2SDLtIHN1XBkvhFZ1vtf1raYQp9TBW2RD5EEawDzbtSmlXUfnaHcvwOizhVYLtr7e6vw7NAf6mTuoCgmlkgnY0iXNlY3AyNTZrMaECjrXI8TLNXU0f8cthpAMxEshUyQlK-AM0PW2wfrnacNI",
+    "enr:-HW4QLAYqmrwllBEnzWWs7I5Ev2IAs7x_dZlbYdRdMUx5EyKHDXp7AV5CkuPGUPdvbv1_Ms1CPfhcGCvSElSosZmyoqAgmlkgnY0iXNlY3AyNTZrMaECriawHKWdDRk2xeZkrOXBQ0dfMFLHY4eENZwdufn1S1o",
+]
+_BRANCH = "enrtree-branch:2XS2367YHAXJFGLZHVAWLQD4ZY,H4FHT4B454P6UXFD7JCYQ5PWDY,MHTDO6TMUBRIA2XWG5LUDACK24"
+_LINK = "enrtree://AM5FCQLWIZX2QFPNJAP7VUERCCRNGRHWZG3YYHIUV7BVDQ5FDPRT2@morenodes.example.org"
+_ROOT = (
+    "enrtree-root:v1 e=JWXYDBPXYWG6FX3GMDIBFA6CJ4 l=C7HRFPF3BLGF3YR4DY5KX3SMBE seq=1 "
+    "sig=o908WmNp7LibOfPsr4btQwatZJ5URBr2ZAuxvK4UWHlsB9sUOTJQaGAlLPVAhM__XJesCHxLISo94z5Z2a463gA"
+)
+
+
+def _key(n=1):
+    return SigningKey.from_secp256k1(bytes([n]) * 32)
+
+
+def _records(n):
+    out = []
+    for i in range(n):
+        key = SigningKey.from_secp256k1(i.to_bytes(2, "big") + b"\x01" * 30)
+        builder = key.builder()
+        builder.ip4(f"10.0.{i // 256}.{i % 256}")
+        builder.udp4(30303)
+        out.append(builder.build(key))
+    return out
+
+
+def test_parse_root():
+    root = parse_tree_entry(_ROOT)
+    assert isinstance(root, EnrTreeRoot)
+    assert root.enr_root == "JWXYDBPXYWG6FX3GMDIBFA6CJ4"
+    assert root.link_root == "C7HRFPF3BLGF3YR4DY5KX3SMBE"
+    assert root.seq == 1
+    assert len(root.signature) == 65
+    assert str(root) == _ROOT
+
+
+def test_parse_branch_link_and_record():
+    branch = parse_tree_entry(_BRANCH)
+    assert isinstance(branch, EnrTreeBranch)
+    assert branch.children == [
+        "2XS2367YHAXJFGLZHVAWLQD4ZY",
+        "H4FHT4B454P6UXFD7JCYQ5PWDY",
+        "MHTDO6TMUBRIA2XWG5LUDACK24",
+    ]
+    assert str(branch) == _BRANCH
+
+    link = parse_tree_entry(_LINK)
+    assert isinstance(link, EnrTreeLink)
+    assert link.domain == "morenodes.example.org"
+    assert len(link.public_key) == 33
+    assert str(link) == _LINK
+    assert link == EnrTreeLink.from_url(_LINK)
+    assert link == EnrTreeLink(link.public_key, "morenodes.example.org")
+
+    record = parse_tree_entry(_RECORDS[0])
+    assert isinstance(record, Enr)
+    assert record == Enr.from_base64(_RECORDS[0])
+
+
+def test_parse_strips_whitespace():
+    assert str(parse_tree_entry(f"  {_BRANCH}\n")) == _BRANCH
+
+
+@pytest.mark.parametrize(
+    "text",
+    [
+        "",
+        "enrtree-root:v2 e=JWXYDBPXYWG6FX3GMDIBFA6CJ4 l=C7HRFPF3BLGF3YR4DY5KX3SMBE seq=1 sig=AA",
+        _ROOT.replace("seq=1", "seq=x"),
+        _ROOT.replace("e=JWXY", "e=jwxy"),
+        _ROOT[: _ROOT.index(" sig=")],
+        _ROOT + "AA",
+        "enrtree-branch:2XS2367YHAXJFGLZHVAWLQD4ZY,,H4FHT4B454P6UXFD7JCYQ5PWDY",
+        "enrtree-branch:not-base32!",
+        "enrtree://AM5FCQLWIZX2QFPNJAP7VUERCCRNGRHWZG3YYHIUV7BVDQ5FDPRT2",
+        "enrtree://AM5FCQLW@morenodes.example.org",
+        "enrtree://AM5FCQLWIZX2QFPNJAP7VUERCCRNGRHWZG3YYHIUV7BVDQ5FDPRT2@",
+        "enr:not-a-record",
+        "enode://abc@127.0.0.1:30303",
+    ],
+)
+def test_parse_rejects_malformed(text):
+    with pytest.raises(DecodeError):
+        parse_tree_entry(text)
+
+
+def test_example_tree_hashes():
+    """Built from the EIP's records, the tree reuses the EIP's entry names."""
+    tree = EnrTree.build([Enr.from_base64(r) for r in _RECORDS], _key(), seq=1, links=[_LINK])
+    txt = tree.to_txt("example.org")
+    assert tree.root.enr_root == "JWXYDBPXYWG6FX3GMDIBFA6CJ4"
+    assert tree.root.link_root == "C7HRFPF3BLGF3YR4DY5KX3SMBE"
+    assert txt["JWXYDBPXYWG6FX3GMDIBFA6CJ4.example.org"] == _BRANCH
+    assert txt["C7HRFPF3BLGF3YR4DY5KX3SMBE.example.org"] == _LINK
+    assert txt["2XS2367YHAXJFGLZHVAWLQD4ZY.example.org"] == _RECORDS[0]
+    assert len(tree) == len(txt) == 6
+
+
+def test_build_signs_root():
+    key = _key()
+    tree = EnrTree.build(_records(3), key, seq=7)
+    root = parse_tree_entry(tree.to_txt("nodes.example.org")["nodes.example.org"])
+    assert root == tree.root
+    assert root.seq == tree.seq == 7
+    assert root.verify(key.public_key())
+    assert not root.verify(_key(2).public_key())
+    link = tree.link("nodes.example.org")
+    assert root.verify(link.public_key)
+    assert str(link).startswith("enrtree://")
+    assert link.domain == "nodes.example.org"
+
+
+def test_verify_rejects_bad_key():
+    root = parse_tree_entry(_ROOT)
+    with pytest.raises(ValueError):
+        root.verify(b"\x02" * 5)
+
+
+def test_build_entries_parse_back():
+    records = _records(40)
+    tree = EnrTree.build(records, _key(), seq=1)
+    txt = tree.to_txt("example.org")
+    found = []
+    for name, text in txt.items():
+        entry = parse_tree_entry(text)
+        if isinstance(entry, Enr):
+            found.append(entry)
+        if isinstance(entry, EnrTreeBranch):
+            assert len(entry.children) <= 13
+            for child in entry.children:
+                assert f"{child}.example.org" in txt
+    assert sorted(r.node_id for r in found) == sorted(r.node_id for r in records)
+    assert [r.node_id for r in tree.records] == sorted(r.node_id for r in records)
+
+
+def test_build_keeps_highest_seq():
+    key = _key(3)
+    builder = key.builder()
+    builder.udp4(30303)
+    old = builder.build(key)
+    new = Enr.from_base64(old.to_base64())
+    new.set_seq(5, key)
+    tree = EnrTree.build([new, old], _key(), seq=1)
+    assert tree.records == [new]
+
+
+def test_build_links():
+    other = EnrTree.build(_records(1), _key(2), seq=1).link("other.example.org")
+    tree = EnrTree.build([], _key(), seq=1, links=[other, _LINK])
+    assert tree.links == [other, EnrTreeLink.from_url(_LINK)]
+    assert tree.records == []
+    txt = tree.to_txt("example.org")
+    assert str(other) in txt.values()
+    with pytest.raises(DecodeError):
+        EnrTree.build([], _key(), seq=1, links=["enrtree://bad"])
+
+
+def test_build_rejects_ed25519():
+    with pytest.raises(ValueError):
+        EnrTree.build(_records(1), SigningKey.generate_ed25519(), seq=1)
+
+
+def test_repr():
+    tree = EnrTree.build(_records(2), _key(), seq=3, links=[_LINK])
+    assert repr(tree) == "EnrTree(seq=3, records=2, links=1)"
+    assert repr(parse_tree_entry(_LINK)) == f'EnrTreeLink("{_LINK}")'
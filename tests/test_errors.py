@@ -0,0 +1,122 @@
+"""Tests for the exception hierarchy."""
+
+import pytest
+from pyenr import (
+    DecodeError,
+    Enr,
+    EnrError,
+    FieldError,
+    SequenceOverflowError,
+    SignatureError,
+    SigningKey,
+    SizeError,
+)
+
+
+def _record():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    return key, builder.build(key)
+
+
+def test_hierarchy():
+    for exc in (DecodeError, SignatureError, SizeError, FieldError, SequenceOverflowError):
+        assert issubclass(exc, EnrError)
+    assert issubclass(EnrError, ValueError)
+
+
+def test_bad_base64_is_decode_error():
+    with pytest.raises(DecodeError):
+        Enr.from_base64("enr:!!!!")
+
+
+def test_truncated_bytes_is_decode_error():
+    _, enr = _record()
+    with pytest.raises(DecodeError):
+        Enr.from_bytes(enr.to_bytes()[:-3])
+
+
+def test_tampered_signature_is_signature_error():
+    _, enr = _record()
+    raw = bytearray(enr.to_bytes())
+    raw[10] ^= 0xFF  # inside the 64-byte signature
+    with pytest.raises(SignatureError):
+        Enr.from_bytes(bytes(raw))
+    with pytest.raises(EnrError):
+        Enr.from_bytes(bytes(raw))
+
+
+def test_strict_violation_is_decode_error():
+    _, enr = _record()
+    with pytest.raises(DecodeError):
+        Enr.from_bytes(enr.to_bytes() + b"\x00", strict=True)
+
+
+def test_bad_address_is_field_error():
+    key, enr = _record()
+    with pytest.raises(FieldError):
+        enr.set_ip4("not-an-ip", key)
+    with pytest.raises(FieldError):
+        key.builder().ip6("zzzz::1")
+
+
+def test_oversize_is_size_error():
+    key, enr = _record()
+    with pytest.raises(SizeError):
+        enr.set("big", b"\x00" * 400, key)
+
+
+# -- Error context --
+
+
+def test_strict_error_context():
+    _, enr = _record()
+    raw = enr.to_bytes()
+    with pytest.raises(DecodeError) as exc:
+        Enr.from_bytes(raw + b"\x00\x00", strict=True)
+    err = exc.value
+    assert err.code == "trailing_data"
+    assert err.offset == len(raw)
+    assert err.key is None
+
+
+def test_decode_error_locates_bad_ip():
+    _, enr = _record()
+    raw = enr.to_bytes()
+    # Shrink the 4-byte "ip" value to 3 bytes and fix up the list header.
+    i = raw.index(b"\x82ip\x84") + 3
+    body = raw[2:i] + b"\x83" + raw[i + 1 : i + 4] + raw[i + 5 :]
+    bad = bytes([0xF8, len(body)]) + body
+    with pytest.raises(DecodeError) as exc:
+        Enr.from_bytes(bad)
+    err = exc.value
+    assert err.code == "bad_ip_length"
+    assert err.key == "ip"
+    assert err.offset == i
+    assert (err.expected, err.actual) == (4, 3)
+
+
+def test_signature_error_has_no_location():
+    _, enr = _record()
+    raw = bytearray(enr.to_bytes())
+    raw[10] ^= 0xFF
+    with pytest.raises(SignatureError) as exc:
+        Enr.from_bytes(bytes(raw))
+    assert exc.value.offset is None
+    assert exc.value.code is None
+
+
+def test_base64_decode_error_context():
+    import base64
+
+    _, enr = _record()
+    text = base64.urlsafe_b64encode(enr.to_bytes()[:-4]).rstrip(b"=").decode()
+    with pytest.raises(DecodeError) as exc:
+        Enr.from_base64("enr:" + text)
+    assert exc.value.code == "malformed"
+    assert exc.value.offset == 0
+
+    with pytest.raises(DecodeError) as exc:
+        Enr.from_base64("enr:!!!!")
+    assert exc.value.offset is None
@@ -0,0 +1,78 @@
+"""Tests for the typed eth2 field: Enr.eth2, Enr.set_eth2 and EnrBuilder.eth2."""
+
+import struct
+
+import pytest
+from pyenr import Enr, Eth2ForkId, FieldError, SigningKey
+
+_DIGEST = bytes.fromhex("6a95a1a9")
+_VERSION = bytes.fromhex("05000000")
+
+
+def _record(**fields):
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    for name, value in fields.items():
+        builder.add(name, value)
+    return builder.build(key), key
+
+
+def test_getter_decodes_ssz():
+    enr, _ = _record(eth2=_DIGEST + _VERSION + struct.pack("<Q", 364032))
+    eth2 = enr.eth2
+    assert isinstance(eth2, Eth2ForkId)
+    assert (eth2.fork_digest, eth2.next_fork_version, eth2.next_fork_epoch) == (
+        _DIGEST,
+        _VERSION,
+        364032,
+    )
+    assert eth2 == enr.fields().eth2
+
+
+def test_getter_absent():
+    enr, _ = _record()
+    assert enr.eth2 is None
+
+
+def test_builder():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.eth2(_DIGEST, _VERSION, 364032)
+    enr = builder.build(key)
+    assert enr.get("eth2") == _DIGEST + _VERSION + struct.pack("<Q", 364032)
+    assert enr.eth2 == Eth2ForkId(_DIGEST, _VERSION, 364032)
+
+    builder = key.builder()
+    builder.eth2(_DIGEST, _VERSION)
+    assert builder.build(key).eth2.next_fork_epoch == 2**64 - 1
+
+
+def test_setter_resigns():
+    enr, key = _record()
+    seq = enr.seq
+    enr.set_eth2(_DIGEST, _VERSION, 2**64 - 1, key)
+    assert enr.seq == seq + 1
+    assert enr.eth2 == Eth2ForkId(_DIGEST, _VERSION)
+    assert Enr.from_base64(enr.to_base64()).eth2 == enr.eth2
+
+
+@pytest.mark.parametrize(
+    "digest,version", [(b"\x01" * 3, _VERSION), (_DIGEST, b"\x01" * 5)]
+)
+def test_rejects_wrong_lengths(digest, version):
+    enr, key = _record()
+    with pytest.raises(FieldError):
+        enr.set_eth2(digest, version, 0, key)
+    with pytest.raises(FieldError):
+        key.builder().eth2(digest, version)
+    with pytest.raises(FieldError):
+        Eth2ForkId(digest, version)
+
+
+def test_ssz_roundtrip():
+    fork_id = Eth2ForkId(_DIGEST, _VERSION, 7)
+    data = fork_id.to_ssz_bytes()
+    assert data == _DIGEST + _VERSION + struct.pack("<Q", 7)
+    assert Eth2ForkId.from_ssz_bytes(data) == fork_id
+    with pytest.raises(ValueError):
+        Eth2ForkId.from_ssz_bytes(data[:15])
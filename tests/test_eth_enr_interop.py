@@ -0,0 +1,86 @@
+"""Tests for conversion to and from eth-enr and ddht record objects."""
+
+import base64
+
+import pytest
+from pyenr import DecodeError, SignatureError, SigningKey, decode, from_eth_enr, to_eth_enr
+
+
+class _ForeignEnr:
+    """Stand-in with the public API eth-enr's ENR and ddht's share."""
+
+    def __init__(self, rlp):
+        self.rlp = rlp
+
+    @classmethod
+    def from_repr(cls, text):
+        assert text.startswith("enr:")
+        body = text[4:]
+        return cls(base64.urlsafe_b64decode(body + "=" * (-len(body) % 4)))
+
+    def __repr__(self):
+        return "enr:" + base64.urlsafe_b64encode(self.rlp).decode().rstrip("=")
+
+
+class _UnsignedEnr:
+    def __repr__(self):
+        return "UnsignedENR(sequence_number=1, ...)"
+
+
+def _record():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.udp4(30303)
+    return builder.build(key)
+
+
+def test_round_trip_through_foreign_class():
+    enr = _record()
+    theirs = to_eth_enr(enr, cls=_ForeignEnr)
+    assert isinstance(theirs, _ForeignEnr)
+    assert theirs.rlp == enr.to_bytes()
+    assert from_eth_enr(theirs) == enr
+    assert decode(theirs) == enr
+
+
+def test_enr_passes_through():
+    enr = _record()
+    assert from_eth_enr(enr) == enr
+
+
+def test_unsigned_and_unrelated_objects():
+    with pytest.raises(TypeError, match="UnsignedEnr"):
+        from_eth_enr(_UnsignedEnr())
+    with pytest.raises(TypeError, match="signed eth-enr"):
+        from_eth_enr(object())
+    with pytest.raises(TypeError, match="cannot decode a record from"):
+        decode(_UnsignedEnr())
+
+
+def test_signature_is_verified_again():
+    data = bytearray(_record().to_bytes())
+    data[10] ^= 0xFF
+    with pytest.raises((SignatureError, DecodeError)):
+        from_eth_enr(_ForeignEnr(bytes(data)))
+
+
+def test_missing_eth_enr():
+    try:
+        import eth_enr  # noqa: F401
+    except ImportError:
+        pass
+    else:
+        pytest.skip("eth-enr is installed")
+    with pytest.raises(ImportError, match="eth-enr"):
+        to_eth_enr(_record())
+
+
+def test_real_eth_enr():
+    eth_enr = pytest.importorskip("eth_enr")
+    enr = _record()
+    theirs = to_eth_enr(enr)
+    assert isinstance(theirs, eth_enr.ENR)
+    assert theirs.sequence_number == enr.seq
+    assert theirs.node_id == enr.node_id
+    assert from_eth_enr(theirs) == enr
@@ -0,0 +1,89 @@
+"""Tests for the execution-layer fork id (EIP-2124)."""
+
+import pytest
+from pyenr import EthForkId, FieldError, Profile, SigningKey, compute_fork_id
+
+MAINNET_GENESIS = bytes.fromhex(
+    "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+)
+# Frontier through Petersburg; Constantinople and Petersburg share a block.
+MAINNET_FORKS = [1150000, 1920000, 2463000, 2675000, 4370000, 7280000, 7280000, 9069000]
+
+
+@pytest.mark.parametrize(
+    "head,fork_hash,fork_next",
+    [
+        (0, "fc64ec04", 1150000),
+        (1149999, "fc64ec04", 1150000),
+        (1150000, "97c2c34c", 1920000),
+        (1920000, "91d1f948", 2463000),
+        (2463000, "7a64da13", 2675000),
+        (2675000, "3edd5b10", 4370000),
+        (4370000, "a00bc324", 7280000),
+        (7280000, "668db0af", 9069000),
+    ],
+)
+def test_compute_fork_id_eip2124_vectors(head, fork_hash, fork_next):
+    fork_id = compute_fork_id(MAINNET_GENESIS, MAINNET_FORKS, head_block=head)
+    assert fork_id.fork_hash == bytes.fromhex(fork_hash)
+    assert fork_id.fork_next == fork_next
+
+
+def test_compute_fork_id_time_forks():
+    all_blocks = compute_fork_id(MAINNET_GENESIS, MAINNET_FORKS)
+    assert all_blocks.fork_next == 0
+
+    before = compute_fork_id(
+        MAINNET_GENESIS, MAINNET_FORKS, [1681338455], head_time=1681338454
+    )
+    assert before.fork_next == 1681338455
+    assert before.fork_hash == all_blocks.fork_hash
+    after = compute_fork_id(MAINNET_GENESIS, MAINNET_FORKS, [1681338455])
+    assert after.fork_hash != before.fork_hash
+    assert after.fork_next == 0
+
+
+def test_compute_fork_id_rejects_bad_genesis():
+    with pytest.raises(ValueError):
+        compute_fork_id(b"\x00" * 31, [])
+
+
+def _record(*entries):
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    for k, v in entries:
+        builder.add(k, v)
+    return builder.build(key)
+
+
+def test_eth_fork_id_from_record():
+    fork_id = compute_fork_id(MAINNET_GENESIS, MAINNET_FORKS, head_block=7280000)
+    enr = _record(("eth", [[fork_id.fork_hash, fork_id.fork_next]]))
+    assert enr.eth_fork_id == fork_id
+    assert repr(enr.eth_fork_id) == "EthForkId(fork_hash=0x668db0af, fork_next=9069000)"
+    assert isinstance(enr.eth_fork_id, EthForkId)
+    assert _record().eth_fork_id is None
+
+
+def test_eth_fork_id_with_trailing_items():
+    enr = _record(("eth", [[b"\x01\x02\x03\x04", 0], b"extra"]))
+    assert enr.eth_fork_id.fork_hash == b"\x01\x02\x03\x04"
+    assert enr.eth_fork_id.fork_next == 0
+
+
+@pytest.mark.parametrize(
+    "value", [b"\x01\x02\x03\x04", [b"\x01\x02\x03\x04", 0], [[b"\x01\x02\x03", 0]], []]
+)
+def test_malformed_eth_fork_id(value):
+    enr = _record(("eth", value))
+    with pytest.raises(FieldError):
+        enr.eth_fork_id
+
+
+def test_bsc_profile():
+    bsc = Profile.get("bsc")
+    enr = _record(("eth", [[b"\x01\x02\x03\x04", 0]]), ("bsc", []))
+    assert bsc.validate(enr).is_valid
+    assert _record(("eth", [[b"\x01\x02\x03\x04", 0]])).validate(profile=bsc).codes() == [
+        "missing_key"
+    ]
@@ -0,0 +1,56 @@
+"""Tests for pyenr.features()."""
+
+import pyenr
+import pytest
+
+
+def test_features():
+    info = pyenr.features()
+    assert info["version"]
+    assert info["enr_version"].startswith("0.")
+    assert info["identity_schemes"] == ["v4"]
+    assert info["key_types"] == ["secp256k1", "ed25519"]
+    assert info["max_record_size"] == 300
+    assert set(info["features"]) <= {
+        "dns",
+        "discv5",
+        "geo",
+        "http",
+        "interchange",
+        "sqlite",
+        "toml",
+        "yaml",
+        "zstd",
+    }
+    expected = {"gzip", "zstd"} if "zstd" in info["features"] else {"gzip"}
+    assert set(info["compression"]) == expected
+
+
+def test_missing_features_raise(tmp_path):
+    compiled = pyenr.features()["features"]
+    enr = pyenr.Enr.from_base64(pyenr.EIP778_ENR)
+    zst = tmp_path / "nodes.zst"
+    zst.write_bytes(b"\x28\xb5\x2f\xfd")
+    calls = [
+        ("geo", lambda: pyenr.enrich_geo([], "x.mmdb")),
+        ("dns", lambda: pyenr.reverse_dns([])),
+        ("dns", lambda: pyenr.resolve_tree("enrtree://x@example.org")),
+        ("http", lambda: pyenr.fetch_enr_list("http://127.0.0.1:1/nodes.txt")),
+        ("interchange", enr.to_cbor),
+        ("interchange", lambda: pyenr.Enr.from_msgpack(b"")),
+        ("interchange", enr.to_protobuf_bytes),
+        ("sqlite", lambda: pyenr.EnrStore(":memory:")),
+        ("toml", lambda: pyenr.load_bootnodes("config.toml")),
+        ("yaml", lambda: pyenr.load_bootnodes("boot_enr.yaml")),
+        ("zstd", lambda: pyenr.write_enr_file(zst, [enr])),
+        ("zstd", lambda: list(pyenr.read_enr_file(zst))),
+    ]
+    for feature, call in calls:
+        if feature not in compiled:
+            with pytest.raises(NotImplementedError, match=f'"{feature}" feature'):
+                call()
+
+
+def test_features_is_a_fresh_dict():
+    pyenr.features()["version"] = "changed"
+    assert pyenr.features()["version"] != "changed"
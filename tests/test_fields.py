@@ -0,0 +1,99 @@
+"""Tests for Enr.fields()."""
+
+import datetime
+import struct
+
+import pytest
+from pyenr import Enr, EnrFields, Eth2ForkId, SigningKey
+
+
+def _eth2(digest=b"\x6a\x95\xa1\xa9", version=b"\x05\x00\x00\x00", epoch=2**64 - 1):
+    return digest + version + struct.pack("<Q", epoch)
+
+
+def test_fields_of_full_record():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.ip6("::1")
+    builder.tcp4(9000)
+    builder.udp4(9000)
+    builder.add("quic", b"\x23\x29")
+    builder.add("eth2", _eth2(epoch=364032))
+    builder.add("attnets", b"\xff" * 8)
+    builder.add("syncnets", b"\x0f")
+    builder.add("cgc", b"\x04")
+    enr = builder.build(key)
+
+    fields = enr.fields()
+    assert isinstance(fields, EnrFields)
+    assert fields.seq == enr.seq
+    assert fields.node_id == enr.node_id
+    assert fields.public_key == key.public_key()
+    assert fields.identity_scheme == "v4"
+    assert (fields.ip4, fields.ip6) == ("10.0.0.1", "::1")
+    assert (fields.tcp4, fields.udp4, fields.tcp6, fields.udp6) == (9000, 9000, None, None)
+    assert (fields.quic4, fields.quic6) == (9001, None)
+    assert fields.attnets == b"\xff" * 8
+    assert fields.syncnets == b"\x0f"
+    assert fields.cgc == 4
+
+    eth2 = fields.eth2
+    assert isinstance(eth2, Eth2ForkId)
+    assert eth2.fork_digest == b"\x6a\x95\xa1\xa9"
+    assert eth2.next_fork_version == b"\x05\x00\x00\x00"
+    assert eth2.next_fork_epoch == 364032
+    assert eth2 == Enr.from_bytes(enr.to_bytes()).fields().eth2
+    assert "next_fork_epoch=364032" in repr(eth2)
+
+
+def test_fields_of_bare_record():
+    key = SigningKey.generate_ed25519()
+    fields = key.builder().build(key).fields()
+    assert fields.public_key == key.public_key()
+    for name in ("ip4", "ip6", "tcp4", "quic4", "eth2", "attnets", "syncnets", "cgc"):
+        assert getattr(fields, name) is None
+    assert repr(fields) == "EnrFields(seq=1, identity_scheme=\"v4\")"
+
+
+def test_fields_are_read_only():
+    key = SigningKey.generate_secp256k1()
+    fields = key.builder().build(key).fields()
+    with pytest.raises(AttributeError):
+        fields.seq = 5
+
+
+def test_misshapen_values_are_none():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.add("attnets", b"\xff" * 3)
+    builder.add("cgc", b"\x01" * 9)
+    fields = builder.build(key).fields()
+    assert fields.attnets is None
+    assert fields.cgc is None
+
+
+
+def _fork(epoch):
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.add("eth2", _eth2(epoch=epoch))
+    return builder.build(key).fields().eth2
+
+
+def test_next_fork_time():
+    # Deneb on mainnet: epoch 269568, genesis at 1606824023.
+    fork = _fork(269568)
+    assert fork.next_fork_timestamp(1606824023) == 1710338135
+    assert fork.next_fork_time(1606824023) == datetime.datetime(
+        2024, 3, 13, 13, 55, 35, tzinfo=datetime.timezone.utc
+    )
+    assert fork.next_fork_timestamp(0, seconds_per_slot=6, slots_per_epoch=8) == 269568 * 48
+
+
+def test_next_fork_time_unscheduled():
+    fork = _fork(2**64 - 1)
+    assert fork.next_fork_timestamp(1606824023) is None
+    assert fork.next_fork_time(1606824023) is None
+    with pytest.raises(OverflowError):
+        _fork(2**63).next_fork_timestamp(1606824023)
@@ -0,0 +1,223 @@
+"""Tests for reading records from dump files."""
+
+import pytest
+from pyenr import (
+    DecodeError,
+    SigningKey,
+    SizeError,
+    decode_rlp_stream,
+    features,
+    process_enr_files,
+    read_enr_file,
+    write_enr_file,
+)
+
+
+def _records(n=5):
+    key = SigningKey.generate_secp256k1()
+    out = []
+    for i in range(n):
+        builder = key.builder()
+        builder.udp4(9000 + i)
+        out.append(builder.build(key))
+    return out
+
+
+def test_read_enr_file(tmp_path):
+    records = _records()
+    path = tmp_path / "nodes.txt"
+    lines = ["# crawl 1", ""] + [r.to_base64() for r in records] + ["  "]
+    path.write_text("\n".join(lines))
+    assert list(read_enr_file(path)) == records
+
+
+def test_read_enr_file_without_prefix_and_crlf(tmp_path):
+    records = _records(2)
+    path = tmp_path / "nodes.txt"
+    path.write_bytes(b"".join(r.to_base64()[4:].encode() + b"\r\n" for r in records))
+    assert list(read_enr_file(str(path))) == records
+
+
+def test_read_enr_file_reports_line_and_resumes(tmp_path):
+    records = _records(2)
+    path = tmp_path / "nodes.txt"
+    path.write_text(f"{records[0]}\nenr:garbage\n{records[1]}\n")
+    reader = read_enr_file(path)
+    assert next(reader) == records[0]
+    with pytest.raises(ValueError, match="line 2"):
+        next(reader)
+    assert next(reader) == records[1]
+    with pytest.raises(StopIteration):
+        next(reader)
+
+
+def test_empty_files(tmp_path):
+    path = tmp_path / "empty"
+    path.write_bytes(b"")
+    assert list(read_enr_file(path)) == []
+    assert list(decode_rlp_stream(path)) == []
+
+
+def test_missing_file(tmp_path):
+    with pytest.raises(FileNotFoundError):
+        read_enr_file(tmp_path / "missing.txt")
+
+
+def test_decode_rlp_stream(tmp_path):
+    records = _records()
+    path = tmp_path / "nodes.rlp"
+    path.write_bytes(b"".join(r.to_bytes() for r in records))
+    assert list(decode_rlp_stream(path)) == records
+
+
+def test_decode_rlp_stream_truncated(tmp_path):
+    records = _records(2)
+    data = b"".join(r.to_bytes() for r in records)
+    path = tmp_path / "nodes.rlp"
+    path.write_bytes(data[:-5])
+    reader = decode_rlp_stream(path)
+    assert next(reader) == records[0]
+    with pytest.raises(ValueError, match=f"offset {len(records[0].to_bytes())}"):
+        next(reader)
+    assert list(reader) == []
+
+
+@pytest.mark.parametrize("name", ["nodes.txt", "nodes.txt.gz", "nodes.txt.zst"])
+def test_write_and_read_roundtrip(tmp_path, name):
+    if name.endswith(".zst") and "zstd" not in features()["features"]:
+        pytest.skip("built without the zstd feature")
+    records = _records()
+    path = tmp_path / name
+    assert write_enr_file(path, records) == len(records)
+    assert list(read_enr_file(path)) == records
+
+
+def test_write_detects_compression_from_extension(tmp_path):
+    if "zstd" not in features()["features"]:
+        pytest.skip("built without the zstd feature")
+    records = _records(1)
+    write_enr_file(tmp_path / "a.gz", records)
+    write_enr_file(tmp_path / "a.zst", records)
+    write_enr_file(tmp_path / "a.bin", records, compression="gzip")
+    assert (tmp_path / "a.gz").read_bytes()[:2] == b"\x1f\x8b"
+    assert (tmp_path / "a.zst").read_bytes()[:4] == b"\x28\xb5\x2f\xfd"
+    assert (tmp_path / "a.bin").read_bytes()[:2] == b"\x1f\x8b"
+
+
+def test_write_unknown_compression(tmp_path):
+    with pytest.raises(ValueError, match="unknown compression"):
+        write_enr_file(tmp_path / "a.txt", _records(1), compression="lz4")
+
+
+def test_compressed_rlp_stream(tmp_path):
+    import gzip
+
+    records = _records()
+    path = tmp_path / "nodes.rlp.gz"
+    path.write_bytes(gzip.compress(b"".join(r.to_bytes() for r in records)))
+    assert list(decode_rlp_stream(path)) == records
+
+
+def test_compressed_rlp_stream_truncated(tmp_path):
+    import gzip
+
+    records = _records(2)
+    data = b"".join(r.to_bytes() for r in records)
+    path = tmp_path / "nodes.rlp.gz"
+    path.write_bytes(gzip.compress(data[:-5]))
+    reader = decode_rlp_stream(path)
+    assert next(reader) == records[0]
+    with pytest.raises(ValueError, match="truncated"):
+        next(reader)
+    assert list(reader) == []
+
+
+@pytest.mark.parametrize("name", ["nodes.rlp", "nodes.rlp.gz"])
+def test_rlp_stream_rejects_oversized_records(tmp_path, name):
+    import gzip
+
+    record = _records(1)[0]
+    # A well-formed list header announcing 400 bytes, with the payload present.
+    data = record.to_bytes() + b"\xf9\x01\x90" + bytes(400)
+    path = tmp_path / name
+    path.write_bytes(gzip.compress(data) if name.endswith(".gz") else data)
+    reader = decode_rlp_stream(path)
+    assert next(reader) == record
+    with pytest.raises(SizeError, match=f"offset {len(record.to_bytes())}: .*300 bytes"):
+        next(reader)
+    assert list(reader) == []
+    assert process_enr_files([path], format="rlp") == [record]
+
+
+@pytest.mark.parametrize("name", ["nodes.rlp", "nodes.rlp.gz"])
+def test_rlp_stream_oversized_header_past_the_end(tmp_path, name):
+    import gzip
+
+    # A header announcing 400 bytes with only 10 of them present is truncated.
+    data = b"\xf9\x01\x90" + bytes(10)
+    path = tmp_path / name
+    path.write_bytes(gzip.compress(data) if name.endswith(".gz") else data)
+    with pytest.raises(DecodeError, match="offset 0: invalid or truncated"):
+        list(decode_rlp_stream(path))
+
+
+def _distinct_records(n):
+    """One record per freshly generated key, so every node id differs."""
+    out = []
+    for i in range(n):
+        key = SigningKey.generate_secp256k1()
+        builder = key.builder()
+        builder.udp4(9000 + i)
+        out.append(builder.build(key))
+    return out
+
+
+def test_process_enr_files_dedupes_across_files(tmp_path):
+    key = SigningKey.generate_secp256k1()
+    old = key.builder().build(key)
+    new = key.builder().build(key)
+    new.set_seq(10, key)
+    others = _distinct_records(3)
+
+    write_enr_file(tmp_path / "a.txt", [old] + others[:2])
+    write_enr_file(tmp_path / "b.txt.gz", [new, others[2]])
+
+    result = process_enr_files([tmp_path / "a.txt", tmp_path / "b.txt.gz"], workers=2)
+    assert len(result) == 4
+    assert new in result
+    assert old not in result
+
+
+def test_process_enr_files_filter(tmp_path):
+    records = _distinct_records(4)
+    write_enr_file(tmp_path / "a.txt", records)
+    result = process_enr_files([tmp_path / "a.txt"], filter={"udp4": 9002})
+    assert result == [records[2]]
+
+
+def test_process_enr_files_rlp_format(tmp_path):
+    records = _distinct_records(3)
+    (tmp_path / "a.rlp").write_bytes(b"".join(r.to_bytes() for r in records))
+    assert process_enr_files([tmp_path / "a.rlp"], format="rlp") == records
+
+
+def test_process_enr_files_invalid_distinct_records(tmp_path):
+    records = _distinct_records(2)
+    path = tmp_path / "a.txt"
+    path.write_text(f"{records[0]}\nenr:broken\n{records[1]}\n")
+    assert process_enr_files([path]) == records
+    with pytest.raises(ValueError, match="a.txt: line 2"):
+        process_enr_files([path], raise_on_error=True)
+
+
+def test_process_enr_files_bad_arguments(tmp_path):
+    path = tmp_path / "a.txt"
+    write_enr_file(path, _distinct_records(1))
+    with pytest.raises(ValueError, match="unknown filter"):
+        process_enr_files([path], filter={"colour": "blue"})
+    with pytest.raises(ValueError, match="workers"):
+        process_enr_files([path], workers=0)
+    with pytest.raises(ValueError, match="unknown format"):
+        process_enr_files([path], format="csv")
+    with pytest.raises(FileNotFoundError, match="missing.txt"):
+        process_enr_files([tmp_path / "missing.txt"])
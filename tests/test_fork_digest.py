@@ -0,0 +1,39 @@
+"""Tests for consensus fork digest computation."""
+
+import pytest
+from pyenr import SigningKey, compute_fork_digest
+
+MAINNET_GVR = bytes.fromhex(
+    "4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95"
+)
+
+
+@pytest.mark.parametrize(
+    "version,digest",
+    [
+        ("00000000", "b5303f2a"),
+        ("01000000", "afcaaba0"),
+        ("02000000", "4a26c58b"),
+        ("03000000", "bba4da96"),
+        ("04000000", "6a95a1a9"),
+    ],
+)
+def test_mainnet_digests(version, digest):
+    assert compute_fork_digest(bytes.fromhex(version), MAINNET_GVR) == bytes.fromhex(digest)
+
+
+def test_matches_record_field():
+    digest = compute_fork_digest(bytes.fromhex("04000000"), MAINNET_GVR)
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.add("eth2", digest + bytes.fromhex("05000000") + b"\xff" * 8)
+    enr = builder.build(key)
+    assert enr.fields().eth2.fork_digest == digest
+
+
+@pytest.mark.parametrize(
+    "version,root", [(b"\x00" * 3, MAINNET_GVR), (b"\x00" * 4, MAINNET_GVR[:31])]
+)
+def test_invalid_lengths(version, root):
+    with pytest.raises(ValueError):
+        compute_fork_digest(version, root)
@@ -0,0 +1,88 @@
+"""Tests for panic-free decoding and the fuzzing entry point."""
+
+import gzip
+import random
+
+import pytest
+from pyenr import (
+    DecodeError,
+    Enr,
+    SigningKey,
+    decode_rlp_stream,
+    fuzz_decode,
+    set_placeholder_policy,
+)
+
+
+def _record_bytes():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.tcp4(30303)
+    builder.add("list", b"\xc3\x01\x02\x03")
+    return builder.build(key).to_bytes()
+
+
+def test_fuzz_decode_valid_record():
+    assert fuzz_decode(_record_bytes())
+
+
+def test_fuzz_decode_ignores_placeholder_policy():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.tcp4(0)
+    data = builder.build(key).to_bytes()
+    set_placeholder_policy("reject")
+    try:
+        with pytest.raises(DecodeError):
+            Enr.from_bytes(data)
+        assert fuzz_decode(data)
+    finally:
+        set_placeholder_policy("allow")
+
+
+def test_fuzz_decode_base64_text():
+    key = SigningKey.generate_secp256k1()
+    text = key.builder().build(key).to_base64().encode()
+    assert not fuzz_decode(text)
+
+
+def test_fuzz_decode_random_mutations():
+    rng = random.Random(778)
+    raw = _record_bytes()
+    for _ in range(500):
+        data = bytearray(raw)
+        for _ in range(rng.randint(1, 4)):
+            op = rng.randrange(3)
+            pos = rng.randrange(len(data))
+            if op == 0:
+                data[pos] = rng.randrange(256)
+            elif op == 1:
+                del data[pos:]
+            else:
+                data.insert(pos, rng.randrange(256))
+            if not data:
+                break
+        fuzz_decode(bytes(data))
+
+
+def test_fuzz_decode_random_bytes():
+    rng = random.Random(1459)
+    for _ in range(500):
+        fuzz_decode(bytes(rng.randrange(256) for _ in range(rng.randint(0, 64))))
+
+
+def test_huge_length_prefix_in_stream(tmp_path):
+    path = tmp_path / "bad.rlp.gz"
+    path.write_bytes(gzip.compress(b"\xff" * 9))
+    with pytest.raises(DecodeError):
+        list(decode_rlp_stream(path))
+
+
+def test_decoders_never_panic_on_garbage():
+    for data in (b"", b"\xff" * 9, b"\xf9\xff\xff", b"\xc0" * 300, b"\xbf" + b"\xff" * 8):
+        with pytest.raises(DecodeError):
+            Enr.from_bytes(data)
+        Enr.from_bytes_lenient(data)
+        Enr.validate_bytes(data)
+        assert not Enr.is_canonical(data)
@@ -0,0 +1,218 @@
+"""Tests for MaxMind GeoIP enrichment."""
+
+import ipaddress
+
+import pytest
+from pyenr import SigningKey, features, enrich_geo
+
+if "geo" not in features()["features"]:
+    pytest.skip("built without the geo feature", allow_module_level=True)
+
+_KEY = SigningKey.generate_secp256k1()
+
+
+class _DataWriter:
+    """Encodes MaxMind DB data section values, reusing strings by pointer."""
+
+    def __init__(self):
+        self.out = bytearray()
+        self.strings = {}
+
+    def _control(self, kind, size):
+        # Types above 7 are "extended": type 0 in the control byte, then
+        # the type minus 7.
+        extended = b"" if kind <= 7 else bytes([kind - 7])
+        kind = kind if kind <= 7 else 0
+        if size < 29:
+            return bytes([(kind << 5) | size]) + extended
+        assert size < 285
+        return bytes([(kind << 5) | 29]) + extended + bytes([size - 29])
+
+    def encode(self, value):
+        if isinstance(value, str):
+            if value in self.strings:
+                offset = self.strings[value]
+                assert offset < 2048
+                return bytes([(1 << 5) | (offset >> 8), offset & 0xFF])
+            raw = value.encode()
+            return self._control(2, len(raw)) + raw
+        if isinstance(value, int):
+            raw = value.to_bytes((value.bit_length() + 7) // 8, "big")
+            return self._control(6, len(raw)) + raw
+        if isinstance(value, dict):
+            out = self._control(7, len(value))
+            for k, v in value.items():
+                out += self.encode(k) + self.encode(v)
+            return out
+        if isinstance(value, list):
+            return self._control(11, len(value)) + b"".join(map(self.encode, value))
+        raise TypeError(value)
+
+    def _add_strings(self, value):
+        if isinstance(value, dict):
+            for k, v in value.items():
+                self._add_strings(k)
+                self._add_strings(v)
+        elif isinstance(value, str) and value not in self.strings:
+            offset = len(self.out)
+            self.out += self.encode(value)
+            self.strings[value] = offset
+
+    def add(self, value):
+        """Appends `value`, writing its strings first so it points to them."""
+        self._add_strings(value)
+        offset = len(self.out)
+        self.out += self.encode(value)
+        return offset
+
+
+def _mmdb(path, networks, ip_version=6, record_size=24):
+    """Writes a MaxMind DB with `networks`, a list of `(cidr, data)`."""
+    nodes = [[None, None]]
+    data = _DataWriter()
+    for cidr, value in networks:
+        network = ipaddress.ip_network(cidr)
+        bits = int(network.network_address)
+        width = network.max_prefixlen
+        if ip_version == 6 and network.version == 4:
+            width = 128
+        prefix = network.prefixlen + (width - network.max_prefixlen)
+        offset = data.add(value)
+        node = 0
+        for i in range(prefix):
+            bit = (bits >> (width - 1 - i)) & 1
+            if i == prefix - 1:
+                nodes[node][bit] = ("data", offset)
+            else:
+                if nodes[node][bit] is None:
+                    nodes.append([None, None])
+                    nodes[node][bit] = ("node", len(nodes) - 1)
+                node = nodes[node][bit][1]
+
+    count = len(nodes)
+
+    def record(entry):
+        if entry is None:
+            return count
+        kind, value = entry
+        return value if kind == "node" else count + 16 + value
+
+    tree = bytearray()
+    for left, right in nodes:
+        left, right = record(left), record(right)
+        if record_size == 24:
+            tree += left.to_bytes(3, "big") + right.to_bytes(3, "big")
+        else:
+            middle = ((left >> 24) << 4) | (right >> 24)
+            tree += left.to_bytes(4, "big")[1:] + bytes([middle]) + right.to_bytes(4, "big")[1:]
+    metadata = _DataWriter().encode(
+        {
+            "node_count": count,
+            "record_size": record_size,
+            "ip_version": ip_version,
+            "database_type": "Test",
+            "binary_format_major_version": 2,
+            "binary_format_minor_version": 0,
+            "build_epoch": 1700000000,
+            "description": {"en": "pyenr test database"},
+            "languages": ["en"],
+        }
+    )
+    path.write_bytes(
+        bytes(tree) + b"\x00" * 16 + bytes(data.out) + b"\xab\xcd\xefMaxMind.com" + metadata
+    )
+    return path
+
+
+def _record(ip4=None, ip6=None):
+    builder = _KEY.builder()
+    if ip4:
+        builder.ip4(ip4)
+    if ip6:
+        builder.ip6(ip6)
+    return builder.build(_KEY)
+
+
+def _databases(tmp_path):
+    country = _mmdb(
+        tmp_path / "country.mmdb",
+        [
+            ("1.2.3.0/24", {"country": {"iso_code": "DE", "geoname_id": 2921044}}),
+            ("5.6.0.0/16", {"country": {"iso_code": "DE"}}),
+            ("2001:db8::/32", {"registered_country": {"iso_code": "NL"}}),
+        ],
+    )
+    asn = _mmdb(
+        tmp_path / "asn.mmdb",
+        [
+            (
+                "1.2.0.0/16",
+                {
+                    "autonomous_system_number": 64500,
+                    "autonomous_system_organization": "Example Networks",
+                },
+            )
+        ],
+        ip_version=4,
+        record_size=28,
+    )
+    return country, asn
+
+
+def _rows(rows):
+    return [(r["ip"], r["country"], r["asn"], r["as_org"]) for r in rows]
+
+
+def test_country_and_asn(tmp_path):
+    country, asn = _databases(tmp_path)
+    enrs = [
+        _record(ip4="1.2.3.4"),
+        _record(ip4="5.6.7.8", ip6="2001:db8::1"),
+        _record(ip6="2001:db8::1"),
+        _record(ip4="9.9.9.9"),
+        _record(),
+    ]
+    rows = enrich_geo(enrs, [country, asn])
+    assert _rows(rows) == [
+        ("1.2.3.4", "DE", 64500, "Example Networks"),
+        ("5.6.7.8", "DE", None, None),
+        ("2001:db8::1", "NL", None, None),
+        ("9.9.9.9", None, None, None),
+        (None, None, None, None),
+    ]
+    assert [r["node_id"] for r in rows] == [e.node_id for e in enrs]
+
+
+def test_single_path(tmp_path):
+    country, _ = _databases(tmp_path)
+    rows = enrich_geo([_record(ip4="1.2.3.4")], str(country))
+    assert _rows(rows) == [("1.2.3.4", "DE", None, None)]
+
+
+def test_first_database_wins(tmp_path):
+    first = _mmdb(tmp_path / "a.mmdb", [("1.2.3.0/24", {"country": {"iso_code": "FR"}})])
+    second = _mmdb(tmp_path / "b.mmdb", [("1.2.0.0/16", {"country": {"iso_code": "DE"}})])
+    assert enrich_geo([_record(ip4="1.2.3.4")], [first, second])[0]["country"] == "FR"
+    assert enrich_geo([_record(ip4="1.2.4.4")], [first, second])[0]["country"] == "DE"
+
+
+def test_invalid_database(tmp_path):
+    path = tmp_path / "broken.mmdb"
+    path.write_bytes(b"not a database")
+    with pytest.raises(ValueError, match="invalid MaxMind database.*metadata"):
+        enrich_geo([], path)
+
+
+def test_truncated_database(tmp_path):
+    path = _mmdb(tmp_path / "country.mmdb", [("1.2.3.0/24", {"country": {"iso_code": "DE"}})])
+    data = path.read_bytes()
+    # Keep the metadata but point the tree into a data section that is gone.
+    marker = data.index(b"\xab\xcd\xefMaxMind.com")
+    path.write_bytes(data[: marker - 8] + data[marker:])
+    with pytest.raises(ValueError, match="invalid MaxMind database"):
+        enrich_geo([_record(ip4="1.2.3.4")], path)
+
+
+def test_missing_database(tmp_path):
+    with pytest.raises(OSError):
+        enrich_geo([], tmp_path / "missing.mmdb")
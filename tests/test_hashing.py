@@ -0,0 +1,117 @@
+"""Tests for hash modes and content hashes."""
+
+import hashlib
+import json
+import os
+import subprocess
+import sys
+
+import pytest
+from pyenr import EIP778_ENR, Enr, SigningKey
+
+
+def _record():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    return key, builder.build(key)
+
+
+def test_modes_differ_in_what_they_cover():
+    key, enr = _record()
+    copy = Enr.from_bytes(enr.to_bytes())
+    assert enr.stable_hash("content") == copy.stable_hash("content")
+    assert enr.stable_hash("node_seq") == copy.stable_hash("node_seq")
+
+    # Re-signing at the same seq with different content keeps (node_id, seq).
+    other = Enr.from_bytes(enr.to_bytes())
+    other.set_tcp4(30303, key)
+    other.set_seq(enr.seq, key)
+    assert other.stable_hash("node_seq") == enr.stable_hash("node_seq")
+    assert other.stable_hash("content") != enr.stable_hash("content")
+
+    enr.set_seq(enr.seq + 1, key)
+    assert enr.stable_hash("node_seq") != copy.stable_hash("node_seq")
+
+
+def test_stable_across_processes():
+    _, enr = _record()
+    code = (
+        "import sys; from pyenr import Enr; "
+        "e = Enr.from_base64(sys.argv[1]); "
+        "print(e.stable_hash('content'), e.stable_hash('node_seq'))"
+    )
+    out = subprocess.run(
+        [sys.executable, "-c", code, enr.to_base64()],
+        capture_output=True,
+        text=True,
+        check=True,
+        env={**os.environ, "PYTHONPATH": os.pathsep.join(sys.path)},
+    ).stdout.split()
+    assert [int(x) for x in out] == [enr.stable_hash("content"), enr.stable_hash("node_seq")]
+
+
+def test_class_level_mode():
+    _, enr = _record()
+    assert Enr.get_hash_mode() == "content"
+    try:
+        Enr.set_hash_mode("node_seq")
+        assert Enr.get_hash_mode() == "node_seq"
+        assert enr.stable_hash() == enr.stable_hash("node_seq")
+        assert hash(enr) == hash(Enr.from_bytes(enr.to_bytes()))
+    finally:
+        Enr.set_hash_mode("content")
+    assert enr.stable_hash() == enr.stable_hash("content")
+
+
+def test_unknown_mode_rejected():
+    _, enr = _record()
+    with pytest.raises(ValueError, match="unknown hash mode"):
+        enr.stable_hash("sha256")
+    with pytest.raises(ValueError):
+        Enr.set_hash_mode("full")
+
+
+def test_canonical_json():
+    enr = Enr.from_base64(EIP778_ENR)
+    text = enr.to_canonical_json()
+    assert text == (
+        '{"id":"0x827634","ip":"0x847f000001",'
+        '"secp256k1":"0xa103ca634cae0d49acb401d8a4c6b6fe8c55b70d115bf400769cc1400f3258cd3138",'
+        '"udp":"0x82765f"}'
+    )
+    assert list(json.loads(text)) == enr.keys()
+
+
+def test_content_hash_ignores_seq_and_signature():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.udp4(9000)
+    enr = builder.build(key)
+    before = enr.content_hash()
+    assert before == hashlib.sha256(enr.to_canonical_json().encode()).digest()
+
+    enr.set_udp4(9001, key)
+    assert enr.content_hash() != before
+    enr.set_udp4(9000, key)
+    assert enr.seq == 3
+    assert enr.content_hash() == before
+
+
+def test_fingerprint():
+    enr = Enr.from_base64(EIP778_ENR)
+    # keccak256(node id || seq as 8 big-endian bytes), first 8 bytes in hex.
+    assert enr.fingerprint() == "5574bf1ffc17eb3a"
+    assert enr.fingerprint() == f"{enr.stable_hash('node_seq'):016x}"
+
+
+def test_fingerprint_tracks_node_and_seq():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    enr = builder.build(key)
+    before = enr.fingerprint()
+    assert len(before) == 16 and int(before, 16) >= 0
+    assert Enr.from_bytes(enr.to_bytes()).fingerprint() == before
+    enr.set_tcp4(9000, key)
+    assert enr.fingerprint() != before
@@ -0,0 +1,74 @@
+"""Tests for EnrHistory."""
+
+import pytest
+from pyenr import Enr, EnrHistory, SigningKey
+
+
+def _lineage():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.tcp4(30303)
+    enr = builder.build(key)
+    versions = [Enr.from_base64(enr.to_base64())]
+    enr.set_tcp4(30304, key)
+    versions.append(Enr.from_base64(enr.to_base64()))
+    enr.set_udp4(30305, key)
+    versions.append(Enr.from_base64(enr.to_base64()))
+    return key, enr, versions
+
+
+def test_lineage():
+    _, _, versions = _lineage()
+    history = EnrHistory()
+    assert len(history) == 0
+    assert history.node_id is None and history.latest is None
+    for v in versions:
+        assert history.add(v)
+
+    assert len(history) == 3
+    assert history.seqs() == [1, 2, 3]
+    assert history.node_id == versions[0].node_id
+    assert history.latest == versions[-1]
+    assert history.get(2) == versions[1]
+    assert history.get(7) is None
+    assert history.records() == versions
+    assert "len=3, seqs=1..3" in repr(history)
+
+
+def test_changes_between():
+    _, _, versions = _lineage()
+    history = EnrHistory(versions)
+
+    diff = history.changes_between(1, 3)
+    assert diff.changed == {"tcp": (30303, 30304)}
+    assert diff.added == {"udp": 30305}
+    assert diff.seq_delta == 2
+    assert history.changes_between(3, 1).removed == {"udp": 30305}
+    assert not history.changes_between(2, 2)
+
+    with pytest.raises(KeyError):
+        history.changes_between(1, 9)
+
+    changes = history.changes()
+    assert [list(c.changed) + list(c.added) for c in changes] == [["tcp"], ["udp"]]
+
+
+def test_rejects_out_of_order_and_foreign_records():
+    key, enr, versions = _lineage()
+    history = EnrHistory(versions[1:])
+    assert not history.add(versions[-1])
+
+    with pytest.raises(ValueError):
+        history.add(versions[0])
+
+    other = SigningKey.generate_secp256k1()
+    with pytest.raises(ValueError):
+        history.add(other.builder().build(other))
+
+    # Same seq as the latest, different content: a conflicting update.
+    forked = Enr.from_base64(versions[1].to_base64())
+    forked.set_tcp6(1, key)
+    with pytest.raises(ValueError):
+        history.add(forked)
+    assert history.seqs() == [2, 3]
@@ -0,0 +1,70 @@
+"""Tests for merging records from several sources."""
+
+import pytest
+from pyenr import EndpointConflict, IdentityTracker, SigningKey
+
+
+def test_keeps_freshest_and_sources(make_record):
+    key = SigningKey.generate_secp256k1()
+    tracker = IdentityTracker()
+    assert tracker.add(make_record(key, seq=2), "dns")
+    assert not tracker.add(make_record(key, seq=1, ip="10.0.0.2"), "static")
+    assert tracker.add(make_record(key, seq=3, ip="10.0.0.3"), "discv5")
+    assert not tracker.add(make_record(key, seq=3, ip="10.0.0.3"), "dns")
+    enr = tracker.get(make_record(key))
+    assert (enr.seq, enr.ip4) == (3, "10.0.0.3")
+    assert tracker.sources(enr.node_id) == ["dns", "static", "discv5"]
+    assert tracker.sources(bytes(32)) == []
+    assert enr in tracker
+    assert len(tracker) == 1
+
+
+def test_add_many(make_record):
+    keys = [SigningKey.generate_secp256k1() for _ in range(3)]
+    tracker = IdentityTracker()
+    assert tracker.add_many([make_record(k, udp=9000 + i) for i, k in enumerate(keys)], "dns") == 3
+    assert tracker.add_many([make_record(keys[0], seq=5), make_record(keys[1])], "discv5") == 1
+    records = tracker.records()
+    assert [r.node_id for r in records] == sorted(r.node_id for r in records)
+    assert repr(tracker) == "IdentityTracker(len=3)"
+
+
+def test_endpoint_conflicts(make_record):
+    a, b, c = (SigningKey.generate_secp256k1() for _ in range(3))
+    tracker = IdentityTracker()
+    tracker.add(make_record(a, udp=30303, tcp=30303), "dns")
+    tracker.add(make_record(b, udp=30303, tcp=30304), "discv5")
+    tracker.add(make_record(c, udp=30305), "static")
+    (conflict,) = tracker.conflicts()
+    assert isinstance(conflict, EndpointConflict)
+    assert (conflict.protocol, conflict.endpoint) == ("udp", "10.0.0.1:30303")
+    assert {r.node_id for r in conflict.records} == {make_record(a).node_id, make_record(b).node_id}
+    assert repr(conflict) == 'EndpointConflict(protocol="udp", endpoint="10.0.0.1:30303", nodes=2)'
+
+    # Moving to a fresh endpoint resolves the conflict.
+    tracker.add(make_record(b, seq=2, udp=30306), "discv5")
+    assert tracker.conflicts() == []
+
+
+def test_same_node_is_not_a_conflict(make_record):
+    key = SigningKey.generate_secp256k1()
+    tracker = IdentityTracker()
+    tracker.add(make_record(key), "dns")
+    tracker.add(make_record(key), "discv5")
+    assert tracker.conflicts() == []
+
+
+def test_ipv6_endpoint():
+    tracker = IdentityTracker()
+    for key in (SigningKey.generate_secp256k1(), SigningKey.generate_ed25519()):
+        builder = key.builder()
+        builder.ip6("2001:db8::1")
+        builder.tcp6(9000)
+        tracker.add(builder.build(key), "static")
+    (conflict,) = tracker.conflicts()
+    assert (conflict.protocol, conflict.endpoint) == ("tcp", "[2001:db8::1]:9000")
+
+
+def test_invalid_node_id():
+    with pytest.raises(ValueError, match="32 bytes"):
+        IdentityTracker().get(b"short")
@@ -0,0 +1,143 @@
+"""Tests for to_dict() and its CBOR and MessagePack encodings."""
+
+import pytest
+from pyenr import (
+    EIP778_ENR,
+    EIP778_NODE_ID,
+    DecodeError,
+    Enr,
+    SignatureError,
+    SigningKey,
+    features,
+)
+
+if "interchange" not in features()["features"]:
+    pytest.skip("built without the interchange feature", allow_module_level=True)
+
+_EIP778 = Enr.from_base64(EIP778_ENR)
+
+
+def _record():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.udp4(9000)
+    builder.add("eth2", bytes(16))
+    enr = builder.build(key)
+    enr.set(b"\xff\x01", b"binary key", key)
+    return enr
+
+
+def test_to_dict_eip778():
+    data = _EIP778.to_dict()
+    assert data["seq"] == 1
+    assert data["node_id"] == EIP778_NODE_ID
+    assert data["signature"] == _EIP778.signature
+    assert data["content"] == dict(_EIP778.items())
+    assert list(data) == ["seq", "node_id", "signature", "content"]
+
+
+def test_to_dict_binary_key():
+    content = _record().to_dict()["content"]
+    assert content[b"\xff\x01"] == b"\x8abinary key"
+    assert content["udp"] == b"\x82\x23\x28"
+
+
+@pytest.mark.parametrize("fmt", ["dict", "cbor", "msgpack"])
+def test_round_trip(fmt):
+    for enr in (_EIP778, _record()):
+        encoded = getattr(enr, f"to_{fmt}")()
+        decoded = getattr(Enr, f"from_{fmt}")(encoded)
+        assert decoded == enr
+        assert decoded.to_bytes() == enr.to_bytes()
+
+
+def test_wire_format():
+    # A 4-entry map whose first key is the text "seq".
+    assert _EIP778.to_cbor().startswith(b"\xa4\x63seq\x01")
+    assert _EIP778.to_msgpack().startswith(b"\x84\xa3seq\x01")
+
+
+def test_from_dict_any_key_order_and_no_node_id():
+    data = _EIP778.to_dict()
+    del data["node_id"]
+    data["content"] = dict(reversed(list(data["content"].items())))
+    assert Enr.from_dict(data) == _EIP778
+
+
+def test_from_dict_node_id_mismatch():
+    data = _EIP778.to_dict()
+    data["node_id"] = bytes(32)
+    with pytest.raises(DecodeError, match="node_id does not match"):
+        Enr.from_dict(data)
+
+
+def test_from_dict_tampered_content():
+    data = _EIP778.to_dict()
+    data["content"]["udp"] = b"\x82\x76\x60"
+    with pytest.raises(SignatureError):
+        Enr.from_dict(data)
+
+
+@pytest.mark.parametrize(
+    "change, message",
+    [
+        ({"seq": -1}, "invalid \"seq\""),
+        ({"signature": "text"}, "invalid \"signature\""),
+        ({"content": {"id": "v4"}}, "content must map"),
+        ({"extra": 1}, "unknown field \"extra\""),
+    ],
+)
+def test_from_dict_invalid(change, message):
+    data = _EIP778.to_dict()
+    data.update(change)
+    with pytest.raises(DecodeError, match=message):
+        Enr.from_dict(data)
+
+
+def test_from_dict_missing_field():
+    data = _EIP778.to_dict()
+    del data["signature"]
+    with pytest.raises(DecodeError, match="missing \"signature\""):
+        Enr.from_dict(data)
+
+
+def test_from_dict_duplicate_key():
+    data = _EIP778.to_dict()
+    data["content"][b"ip"] = data["content"]["ip"]
+    with pytest.raises(DecodeError, match="duplicate key"):
+        Enr.from_dict(data)
+
+
+@pytest.mark.parametrize(
+    "data, message",
+    [
+        (b"", "unexpected end of input"),
+        (b"\x01", "expected a map"),
+        (b"\xa0", "missing \"seq\""),
+        (_EIP778.to_cbor() + b"\x00", "trailing bytes"),
+    ],
+)
+def test_from_cbor_invalid(data, message):
+    with pytest.raises(DecodeError, match=message):
+        Enr.from_cbor(data)
+
+
+@pytest.mark.parametrize(
+    "data, message",
+    [
+        (b"\x01", "expected a map"),
+        (b"\x80", "missing \"seq\""),
+        (_EIP778.to_msgpack() + b"\x00", "trailing bytes"),
+    ],
+)
+def test_from_msgpack_invalid(data, message):
+    with pytest.raises(DecodeError, match=message):
+        Enr.from_msgpack(data)
+
+
+def test_decode_error_attributes():
+    with pytest.raises(DecodeError) as info:
+        Enr.from_cbor(b"\xa0")
+    assert info.value.format is None
+    assert info.value.offset is None
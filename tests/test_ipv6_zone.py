@@ -0,0 +1,81 @@
+"""Tests for IPv6 zone identifiers and address normalization."""
+
+import pytest
+from pyenr import FieldError, SigningKey
+
+_KEY = SigningKey.generate_secp256k1()
+
+
+def _record():
+    builder = _KEY.builder()
+    builder.udp6(30303)
+    return builder.build(_KEY)
+
+
+@pytest.mark.parametrize(
+    "addr, expected",
+    [
+        ("fe80::1%eth0", "fe80::1"),
+        ("fe80::1%3", "fe80::1"),
+        ("FE80:0:0:0:0:0:0:1%en0", "fe80::1"),
+        ("ff02::1%eth0", "ff02::1"),
+    ],
+)
+def test_zone_is_stripped_from_scoped_addresses(addr, expected):
+    enr = _record()
+    enr.set_ip6(addr, _KEY)
+    assert enr.ip6 == expected
+
+    builder = _KEY.builder()
+    builder.ip6(addr)
+    assert builder.build(_KEY).ip6 == expected
+
+
+@pytest.mark.parametrize("addr", ["2001:db8::1%eth0", "::1%lo", "ff0e::1%eth0"])
+def test_zone_is_rejected_on_other_addresses(addr):
+    with pytest.raises(FieldError, match="only valid on a link-local address"):
+        _record().set_ip6(addr, _KEY)
+    builder = _KEY.builder()
+    with pytest.raises(FieldError, match="only valid on a link-local address"):
+        builder.ip6(addr)
+
+
+def test_empty_zone_is_rejected():
+    with pytest.raises(FieldError, match="empty zone identifier"):
+        _record().set_ip6("fe80::1%", _KEY)
+
+
+def test_ipv4_address_is_explained():
+    with pytest.raises(FieldError, match="got IPv4 address") as info:
+        _record().set_ip6("10.0.0.1", _KEY)
+    assert "::ffff:10.0.0.1" in str(info.value)
+
+
+def test_invalid_address():
+    with pytest.raises(FieldError, match="invalid IPv6 address"):
+        _record().set_ip6("fe80::g", _KEY)
+
+
+def test_failed_set_leaves_record_unchanged():
+    enr = _record()
+    enr.set_ip6("2001:db8::1", _KEY)
+    seq = enr.seq
+    with pytest.raises(FieldError):
+        enr.set_ip6("2001:db8::2%eth0", _KEY)
+    assert enr.ip6 == "2001:db8::1"
+    assert enr.seq == seq
+
+
+@pytest.mark.parametrize(
+    "addr, expected",
+    [
+        ("2001:0DB8:0000:0000:0000:0000:0000:0001", "2001:db8::1"),
+        ("2001:db8:0:0:1:0:0:1", "2001:db8::1:0:0:1"),
+        ("::FFFF:10.0.0.1", "::ffff:10.0.0.1"),
+    ],
+)
+def test_output_is_canonical(addr, expected):
+    enr = _record()
+    enr.set_ip6(addr, _KEY)
+    assert enr.ip6 == expected
+    assert enr.fields().ip6 == expected
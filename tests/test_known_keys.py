@@ -0,0 +1,43 @@
+"""Tests for the known-key registry."""
+
+from pyenr import Enr, SigningKey, known_keys
+
+
+def _record(*custom):
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.tcp4(30303)
+    builder.add("eth2", b"\x00" * 16)
+    for k, v in custom:
+        builder.add(k, v)
+    return builder.build(key)
+
+
+def test_registry_contents():
+    registry = known_keys()
+    assert registry["id"] == "text"
+    assert registry["ip"] == "ip4"
+    assert registry["udp6"] == "port"
+    assert registry["eth2"] == "bytes[16]"
+    assert registry["attnets"] == "bytes[8]"
+    assert registry["eth"] == "list"
+
+
+def test_unknown_keys():
+    enr = _record(("zzz", b"\x01"), ("aaa", b"\x02"))
+    assert enr.unknown_keys() == ["aaa", "zzz"]
+    assert _record().unknown_keys() == []
+
+
+def test_validate_known_keys_only():
+    enr = _record(("mystery", b"\x01"))
+    assert enr.validate().is_valid
+
+    report = enr.validate(known_keys_only=True)
+    assert report.codes() == ["unknown_key"]
+    assert report.violations[0].key == "mystery"
+
+    report = Enr.validate_bytes(enr.to_bytes(), known_keys_only=True)
+    assert [v.key for v in report.violations] == ["mystery"]
+    assert Enr.validate_bytes(enr.to_bytes()).is_valid
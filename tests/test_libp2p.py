@@ -0,0 +1,115 @@
+"""Tests for the libp2p view of records: multiaddrs and peer ids."""
+
+import pytest
+from pyenr import DecodeError, Enr, SigningKey
+
+_B58 = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz"
+
+
+def _b58encode(data):
+    n = int.from_bytes(data, "big")
+    out = ""
+    while n:
+        n, r = divmod(n, 58)
+        out = _B58[r] + out
+    return "1" * (len(data) - len(data.lstrip(b"\0"))) + out
+
+
+def _expected_peer_id(key):
+    """Identity multihash of the libp2p protobuf PublicKey."""
+    key_type = 2 if len(key.public_key()) == 33 else 1
+    encoded = bytes([0x08, key_type, 0x12, len(key.public_key())]) + key.public_key()
+    return _b58encode(bytes([0x00, len(encoded)]) + encoded)
+
+
+def _record(key=None, **fields):
+    key = key or SigningKey.generate_secp256k1()
+    builder = key.builder()
+    for name in ("ip4", "ip6", "tcp4", "udp4", "tcp6", "udp6"):
+        if name in fields:
+            getattr(builder, name)(fields.pop(name))
+    for name, value in fields.items():
+        builder.add(name, value)
+    return builder.build(key), key
+
+
+def test_full_set():
+    enr, key = _record(
+        ip4="1.2.3.4", tcp4=9000, udp4=9001, quic=9002, ip6="2001:db8::1", udp6=9101
+    )
+    peer = _expected_peer_id(key)
+    assert enr.to_multiaddrs() == [
+        f"/ip4/1.2.3.4/tcp/9000/p2p/{peer}",
+        f"/ip4/1.2.3.4/udp/9001/p2p/{peer}",
+        f"/ip4/1.2.3.4/udp/9002/quic-v1/p2p/{peer}",
+        f"/ip6/2001:db8::1/tcp/9000/p2p/{peer}",
+        f"/ip6/2001:db8::1/udp/9101/p2p/{peer}",
+        f"/ip6/2001:db8::1/udp/9002/quic-v1/p2p/{peer}",
+    ]
+    assert peer.startswith("16Uiu2")
+
+
+def test_without_fallback_or_p2p():
+    enr, _ = _record(ip4="1.2.3.4", tcp4=9000, ip6="2001:db8::1", udp6=9101, quic6=9102)
+    assert enr.to_multiaddrs(fallback=False, p2p=False) == [
+        "/ip4/1.2.3.4/tcp/9000",
+        "/ip6/2001:db8::1/udp/9101",
+        "/ip6/2001:db8::1/udp/9102/quic-v1",
+    ]
+
+
+def test_ed25519_peer_id():
+    key = SigningKey.generate_ed25519()
+    enr, _ = _record(key, ip4="10.0.0.1", tcp4=30303)
+    (addr,) = enr.to_multiaddrs()
+    assert addr == f"/ip4/10.0.0.1/tcp/30303/p2p/{_expected_peer_id(key)}"
+    assert addr.split("/p2p/")[1].startswith("12D3KooW")
+
+
+@pytest.mark.parametrize(
+    "fields",
+    [
+        {},
+        {"ip4": "1.2.3.4"},
+        {"tcp4": 9000},
+        {"ip4": "0.0.0.0", "tcp4": 9000},
+        {"ip4": "1.2.3.4", "tcp4": 0},
+    ],
+)
+def test_no_dialable_endpoints(fields):
+    enr, _ = _record(**fields)
+    assert enr.to_multiaddrs() == []
+
+
+def test_multiaddrs_parse_back():
+    enr, key = _record(ip4="1.2.3.4", tcp4=9000, udp4=9001, quic=9002, ip6="::1")
+    addrs = enr.to_multiaddrs()[::3]
+    assert len(addrs) == 2
+    enr.set_multiaddrs(addrs, key, encoding="rlp")
+    assert enr.multiaddrs == addrs
+
+
+@pytest.mark.parametrize(
+    "key,prefix",
+    [
+        (SigningKey.generate_secp256k1(), "16Uiu2HA"),
+        (SigningKey.generate_ed25519(), "12D3KooW"),
+    ],
+)
+def test_peer_id(key, prefix):
+    enr, _ = _record(key)
+    assert enr.peer_id == key.peer_id() == _expected_peer_id(key)
+    assert enr.peer_id.startswith(prefix)
+    assert len(enr.peer_id) == (53 if prefix == "16Uiu2HA" else 52)
+
+
+def test_peer_id_is_stable():
+    key = SigningKey.from_secp256k1(bytes(range(1, 33)))
+    assert key.peer_id() == SigningKey.from_secp256k1(bytes(range(1, 33))).peer_id()
+    enr, _ = _record(key, ip4="1.2.3.4", tcp4=9000)
+    assert enr.to_multiaddrs()[0].endswith("/p2p/" + enr.peer_id)
+
+
+def test_peer_id_is_recognised_by_parse():
+    with pytest.raises(DecodeError, match="peer id"):
+        Enr.parse(SigningKey.generate_secp256k1().peer_id())
@@ -0,0 +1,165 @@
+"""Tests for TCP and discv5 liveness probes."""
+
+import os
+import socket
+import threading
+
+import pytest
+from pyenr import LivenessResult, SigningKey, features, probe_liveness
+
+_DISCV5 = "discv5" in features()["features"]
+
+
+def _needs_discv5():
+    if not _DISCV5:
+        pytest.skip("built without the discv5 feature")
+
+
+_KEY = SigningKey.generate_secp256k1()
+
+
+def _record(ip="127.0.0.1", tcp=None, udp=None):
+    builder = _KEY.builder()
+    builder.ip4(ip)
+    if tcp is not None:
+        builder.tcp4(tcp)
+    if udp is not None:
+        builder.udp4(udp)
+    return builder.build(_KEY)
+
+
+def _closed_port(kind=socket.SOCK_STREAM):
+    with socket.socket(socket.AF_INET, kind) as sock:
+        sock.bind(("127.0.0.1", 0))
+        return sock.getsockname()[1]
+
+
+def test_tcp_reachable():
+    with socket.socket() as server:
+        server.bind(("127.0.0.1", 0))
+        server.listen()
+        port = server.getsockname()[1]
+        enr = _record(tcp=port)
+        [result] = probe_liveness([enr], timeout=2.0)
+    assert isinstance(result, LivenessResult)
+    assert result.node_id == enr.node_id
+    assert result.method == "tcp"
+    assert result.reachable
+    assert result.endpoint == f"127.0.0.1:{port}"
+    assert result.rtt is not None and result.rtt >= 0
+    assert result.error is None
+    assert "reachable=True" in repr(result)
+
+
+def test_tcp_refused():
+    port = _closed_port()
+    [result] = probe_liveness([_record(tcp=port)], timeout=1.0)
+    assert not result.reachable
+    assert result.endpoint == f"127.0.0.1:{port}"
+    assert result.rtt is None
+    assert result.error
+
+
+def test_no_endpoint():
+    [tcp] = probe_liveness([_record(udp=30303)])
+    assert not tcp.reachable
+    assert tcp.endpoint is None
+    assert tcp.error == "record has no tcp endpoint"
+
+
+def test_discv5_no_endpoint():
+    _needs_discv5()
+    [ping] = probe_liveness([_record(udp=30303)], method="discv5_ping")
+    assert ping.endpoint == "127.0.0.1:30303"
+    [result] = probe_liveness([_record(tcp=30303)], method="discv5_ping")
+    assert result.error == "record has no udp endpoint"
+
+
+def test_placeholder_endpoint_is_skipped():
+    [result] = probe_liveness([_record(ip="0.0.0.0", tcp=30303)])
+    assert result.endpoint is None
+
+
+def test_results_in_input_order():
+    enrs = [_record(tcp=_closed_port()) for _ in range(5)] + [_record(udp=1)]
+    results = probe_liveness(enrs, concurrency=2, timeout=0.5)
+    assert [r.endpoint for r in results[:5]] == [f"127.0.0.1:{e.tcp4}" for e in enrs[:5]]
+    assert results[5].endpoint is None
+
+
+def test_discv5_no_listener():
+    _needs_discv5()
+    [result] = probe_liveness(
+        [_record(udp=_closed_port(socket.SOCK_DGRAM))], method="discv5_ping", timeout=0.5
+    )
+    assert result.method == "discv5_ping"
+    assert not result.reachable
+    assert result.error
+
+
+def _aes_ctr(key, iv, data):
+    modes = pytest.importorskip("cryptography.hazmat.primitives.ciphers")
+    cipher = modes.Cipher(modes.algorithms.AES(key), modes.modes.CTR(iv))
+    encryptor = cipher.encryptor()
+    return encryptor.update(data) + encryptor.finalize()
+
+
+def _whoareyou_responder(sock, node_id, answer=True, received=None):
+    packet, addr = sock.recvfrom(1280)
+    iv, masked = packet[:16], packet[16:]
+    header = _aes_ctr(node_id[:16], iv, masked[:55])
+    if received is not None:
+        received.append(header)
+    flag, nonce, authdata = header[8], header[9:21], header[23:55]
+    if header[:8] != b"discv5\x00\x01" or flag != 0:
+        return
+    if not answer:
+        nonce = os.urandom(12)
+    reply_iv = os.urandom(16)
+    static = b"discv5\x00\x01\x01" + nonce + (24).to_bytes(2, "big")
+    reply = static + os.urandom(16) + (0).to_bytes(8, "big")
+    sock.sendto(reply_iv + _aes_ctr(authdata[:16], reply_iv, reply), addr)
+
+
+@pytest.mark.parametrize("answer", [True, False])
+def test_discv5_whoareyou(answer):
+    _needs_discv5()
+    with socket.socket(socket.AF_INET, socket.SOCK_DGRAM) as sock:
+        sock.bind(("127.0.0.1", 0))
+        enr = _record(udp=sock.getsockname()[1])
+        received = []
+        thread = threading.Thread(
+            target=_whoareyou_responder, args=(sock, enr.node_id, answer, received)
+        )
+        thread.start()
+        [result] = probe_liveness([enr], method="discv5_ping", timeout=1.0)
+        thread.join()
+    assert received[0][:9] == b"discv5\x00\x01\x00"
+    assert int.from_bytes(received[0][21:23], "big") == 32
+    assert result.reachable is answer
+    if answer:
+        assert result.rtt is not None
+    else:
+        assert result.error == "timed out"
+
+
+@pytest.mark.parametrize(
+    "kwargs, match",
+    [
+        ({"method": "ping"}, "unknown probe method"),
+        ({"timeout": 0.0}, "timeout"),
+        ({"timeout": -1.0}, "timeout"),
+        ({"timeout": float("nan")}, "timeout"),
+        ({"concurrency": 0}, "concurrency"),
+    ],
+)
+def test_invalid_arguments(kwargs, match):
+    with pytest.raises(ValueError, match=match):
+        probe_liveness([], **kwargs)
+
+
+def test_discv5_ping_without_feature():
+    if _DISCV5:
+        pytest.skip("built with the discv5 feature")
+    with pytest.raises(NotImplementedError, match='"discv5" feature'):
+        probe_liveness([_record(udp=30303)], method="discv5_ping")
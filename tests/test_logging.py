@@ -0,0 +1,82 @@
+"""Tests for diagnostics logged on the pyenr logger."""
+
+import logging
+
+from pyenr import SigningKey, dedupe_report, process_enr_files
+
+
+class _Capture(logging.Handler):
+    def __init__(self):
+        super().__init__(logging.DEBUG)
+        self.records = []
+
+    def emit(self, record):
+        self.records.append(record)
+
+
+def _capture(level):
+    logger = logging.getLogger("pyenr")
+    handler = _Capture()
+    logger.addHandler(handler)
+    old_level = logger.level
+    logger.setLevel(level)
+
+    def restore():
+        logger.removeHandler(handler)
+        logger.setLevel(old_level)
+
+    return handler, restore
+
+
+def test_skipped_records_are_logged(tmp_path):
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    path = tmp_path / "nodes.txt"
+    path.write_text(f"{enr}\nenr:garbage\nenr:alsogarbage\n")
+
+    handler, restore = _capture(logging.DEBUG)
+    try:
+        assert process_enr_files([path]) == [enr]
+    finally:
+        restore()
+    levels = [r.levelno for r in handler.records]
+    assert levels == [logging.DEBUG, logging.DEBUG, logging.WARNING]
+    assert "line 2" in handler.records[0].getMessage()
+    assert "line 3" in handler.records[1].getMessage()
+    assert "skipped 2 undecodable record(s)" in handler.records[2].getMessage()
+    assert all(r.name == "pyenr" for r in handler.records)
+
+
+def test_debug_messages_respect_level(tmp_path):
+    path = tmp_path / "nodes.txt"
+    path.write_text("enr:garbage\n")
+    handler, restore = _capture(logging.WARNING)
+    try:
+        assert process_enr_files([path]) == []
+    finally:
+        restore()
+    assert [r.levelno for r in handler.records] == [logging.WARNING]
+
+
+def test_dedupe_conflicts_are_logged():
+    key = SigningKey.generate_secp256k1()
+    a = key.builder().build(key)
+    b = key.builder()
+    b.udp4(9000)
+    b = b.build(key)
+
+    handler, restore = _capture(logging.DEBUG)
+    try:
+        dedupe_report([a, a])
+        assert handler.records == []
+        dedupe_report([a, b])
+    finally:
+        restore()
+    assert len(handler.records) == 1
+    assert handler.records[0].levelno == logging.WARNING
+    assert "1 record(s) share node id and seq" in handler.records[0].getMessage()
+
+
+def test_silent_by_default():
+    handlers = logging.getLogger("pyenr").handlers
+    assert any(isinstance(h, logging.NullHandler) for h in handlers)
@@ -0,0 +1,75 @@
+"""Tests for projecting a record onto a subset of its keys."""
+
+import pytest
+from pyenr import Enr, SequenceOverflowError, SigningKey, SizeError
+
+_KEY = SigningKey.generate_secp256k1()
+
+
+def _record():
+    builder = _KEY.builder()
+    builder.ip4("10.0.0.1")
+    builder.tcp4(9000)
+    builder.udp4(9000)
+    builder.add("eth2", bytes(16))
+    builder.add("custom", b"\x01" * 100)
+    return builder.build(_KEY)
+
+
+def test_keeps_only_requested_keys():
+    enr = _record()
+    slim = enr.minimal(["ip", "udp"], _KEY)
+    assert slim.keys() == ["id", "ip", "secp256k1", "udp"]
+    assert (slim.ip4, slim.udp4, slim.tcp4) == ("10.0.0.1", 9000, None)
+    assert slim.node_id == enr.node_id
+    assert slim.seq == enr.seq + 1
+    assert len(slim.to_bytes()) < len(enr.to_bytes())
+    assert Enr.from_bytes(slim.to_bytes()) == slim
+
+
+def test_original_unchanged():
+    enr = _record()
+    before = enr.to_bytes()
+    enr.minimal(["ip"], _KEY)
+    assert enr.to_bytes() == before
+
+
+def test_values_copied_verbatim():
+    enr = _record()
+    slim = enr.minimal(["eth2", "custom"], _KEY)
+    for key in ("eth2", "custom"):
+        assert slim.get_raw(key) == enr.get_raw(key)
+
+
+def test_absent_and_reserved_keys():
+    enr = _record()
+    slim = enr.minimal(["quic", "id", "secp256k1"], _KEY)
+    assert slim.keys() == ["id", "secp256k1"]
+
+
+def test_makes_room_for_new_field():
+    builder = _KEY.builder()
+    builder.ip4("10.0.0.1")
+    builder.add("filler", b"\x00" * 120)
+    enr = builder.build(_KEY)
+    with pytest.raises(SizeError):
+        enr.set("attnets", b"\xff" * 100, _KEY)
+    slim = enr.minimal(["ip"], _KEY)
+    slim.set("attnets", b"\xff" * 100, _KEY)
+    assert slim.get("attnets") == b"\xff" * 100
+
+
+def test_other_signing_key():
+    enr = _record()
+    other = SigningKey.generate_ed25519()
+    slim = enr.minimal(["ip"], other)
+    assert slim.keys() == ["ed25519", "id", "ip"]
+    assert slim.node_id != enr.node_id
+    assert Enr.from_bytes(slim.to_bytes()) == slim
+
+
+def test_seq_overflow():
+    enr = _record()
+    enr.set_seq(2**64 - 1, _KEY)
+    with pytest.raises(SequenceOverflowError):
+        enr.minimal(["ip"], _KEY)
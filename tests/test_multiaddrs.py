@@ -0,0 +1,105 @@
+"""Tests for the multiaddrs key in either layout."""
+
+import pytest
+from pyenr import Enr, FieldError, SigningKey
+
+WSS = "/dns4/example.com/tcp/443/wss"
+# dns4 (54) "example.com", tcp (6) 443, wss (478).
+WSS_BINARY = bytes.fromhex("360b") + b"example.com" + bytes.fromhex("0601bb" "de03")
+QUIC = "/ip6/2001:db8::1/udp/9000/quic-v1"
+CIRCUIT = (
+    "/ip4/10.0.0.1/tcp/60000/p2p/QmcgpsyWgH8Y8ajJz1Cu72KnS5uo2Aa2LpzU7kinSupNKC/p2p-circuit"
+)
+_KEY = SigningKey.generate_secp256k1()
+
+
+def _builder():
+    builder = _KEY.builder()
+    builder.ip4("10.0.0.1")
+    builder.udp4(9000)
+    return builder
+
+
+@pytest.mark.parametrize("encoding", ["length-prefixed", "rlp"])
+def test_round_trip(encoding):
+    builder = _builder()
+    builder.multiaddrs([WSS, QUIC, CIRCUIT], encoding=encoding)
+    enr = Enr.from_base64(builder.build(_KEY).to_base64())
+    assert enr.multiaddrs == [WSS, QUIC, CIRCUIT]
+    assert enr.udp4 == 9000
+
+
+def test_length_prefixed_is_the_waku_layout():
+    builder = _builder()
+    builder.multiaddrs([WSS])
+    enr = builder.build(_KEY)
+    assert enr.get("multiaddrs") == len(WSS_BINARY).to_bytes(2, "big") + WSS_BINARY
+
+
+def test_rlp_layout():
+    builder = _builder()
+    builder.multiaddrs([WSS], encoding="rlp")
+    enr = builder.build(_KEY)
+    # An RLP list holding the one binary multiaddr.
+    item = bytes([0x80 + len(WSS_BINARY)]) + WSS_BINARY
+    assert enr.get_raw("multiaddrs") == bytes([0xC0 + len(item)]) + item
+    assert enr.multiaddrs == [WSS]
+
+
+def test_decode_rlp_list_from_the_wire():
+    builder = _builder()
+    builder.add("multiaddrs", [WSS_BINARY, bytes.fromhex("047f000001" "0601bb")])
+    assert builder.build(_KEY).multiaddrs == [WSS, "/ip4/127.0.0.1/tcp/443"]
+
+
+def test_setter():
+    enr = _builder().build(_KEY)
+    assert enr.multiaddrs is None
+    enr.set_multiaddrs([QUIC], _KEY, encoding="rlp")
+    assert enr.multiaddrs == [QUIC]
+    assert enr.seq == 2
+    enr.set_multiaddrs([], _KEY)
+    assert enr.multiaddrs == []
+
+
+@pytest.mark.parametrize(
+    "addr, match",
+    [
+        ("/tcp/443/wss", "must start with"),
+        ("/p2p/QmcgpsyWgH8Y8ajJz1Cu72KnS5uo2Aa2LpzU7kinSupNKC", "must start with"),
+        ("/dns4//tcp/443", "invalid /dns4 value"),
+        ("/ip4/1.2.3.4/tcp/70000", "invalid /tcp value"),
+        ("ip4/1.2.3.4", "must start with '/'"),
+        ("/ip4/1.2.3.4/foo", "unsupported multiaddr protocol"),
+    ],
+)
+def test_invalid_multiaddrs_are_refused(addr, match):
+    enr = _builder().build(_KEY)
+    with pytest.raises(FieldError, match=match):
+        enr.set_multiaddrs([WSS, addr], _KEY)
+    with pytest.raises(FieldError, match=match):
+        _builder().multiaddrs([addr], encoding="rlp")
+    assert enr.multiaddrs is None
+    assert enr.seq == 1
+
+
+@pytest.mark.parametrize(
+    "value, match",
+    [
+        ([WSS_BINARY, bytes.fromhex("0601bb")], "multiaddr 1: .*must start with"),
+        ([WSS_BINARY, [WSS_BINARY]], "item 1 is not a byte string"),
+        ([bytes.fromhex("ff01")], "multiaddr 0: unsupported multiaddr protocol code"),
+        (bytes.fromhex("0005") + b"\x04", "expected 5 bytes"),
+    ],
+)
+def test_invalid_values_are_reported(value, match):
+    builder = _builder()
+    builder.add("multiaddrs", value)
+    enr = builder.build(_KEY)
+    with pytest.raises(FieldError, match='key "multiaddrs" is invalid: ' + match):
+        enr.multiaddrs
+
+
+def test_unknown_encoding():
+    with pytest.raises(ValueError, match="unknown multiaddrs encoding"):
+        _builder().multiaddrs([WSS], encoding="cbor")
@@ -0,0 +1,99 @@
+"""Tests for reading and writing a discv5 node's stored record and key."""
+
+import os
+import stat
+
+import pytest
+from pyenr import Enr, SigningKey, load_node_enr, save_node_enr
+
+_SECRET = bytes(range(1, 33))
+
+
+def _node(seq_bumps=0):
+    key = SigningKey.from_secp256k1(_SECRET)
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.udp4(9000)
+    enr = builder.build(key)
+    for i in range(seq_bumps):
+        enr.set("custom", bytes([i]), key)
+    return enr, key
+
+
+def test_roundtrip(tmp_path):
+    enr, key = _node()
+    save_node_enr(tmp_path, enr, key=key)
+    assert (tmp_path / "enr.dat").read_text() == enr.to_base64()
+    assert (tmp_path / "key").read_bytes() == _SECRET
+    loaded, loaded_key = load_node_enr(tmp_path)
+    assert loaded == enr
+    assert loaded_key.public_key() == key.public_key()
+    assert sorted(p.name for p in tmp_path.iterdir()) == ["enr.dat", "key"]
+
+
+def test_key_file_is_private(tmp_path):
+    if os.name != "posix":
+        pytest.skip("file modes are posix only")
+    enr, key = _node()
+    save_node_enr(str(tmp_path), enr, key=key)
+    assert stat.S_IMODE((tmp_path / "key").stat().st_mode) == 0o600
+
+
+def test_reads_files_written_by_the_node(tmp_path):
+    enr, _ = _node()
+    (tmp_path / "enr.dat").write_text(enr.to_base64() + "\n")
+    loaded, key = load_node_enr(tmp_path)
+    assert loaded == enr
+    assert key is None
+
+
+def test_missing_record(tmp_path):
+    with pytest.raises(FileNotFoundError, match="enr.dat"):
+        load_node_enr(tmp_path)
+
+
+def test_mismatched_key_file(tmp_path):
+    enr, _ = _node()
+    (tmp_path / "enr.dat").write_text(enr.to_base64())
+    (tmp_path / "key").write_bytes(bytes(range(2, 34)))
+    with pytest.raises(ValueError, match="does not belong"):
+        load_node_enr(tmp_path)
+
+
+def test_bad_key_file(tmp_path):
+    enr, _ = _node()
+    (tmp_path / "enr.dat").write_text(enr.to_base64())
+    (tmp_path / "key").write_bytes(b"short")
+    with pytest.raises(ValueError, match="not a secp256k1 secret key"):
+        load_node_enr(tmp_path)
+
+
+def test_update_needs_higher_seq(tmp_path):
+    enr, key = _node(seq_bumps=1)
+    save_node_enr(tmp_path, enr, key=key)
+    older, _ = _node()
+    with pytest.raises(ValueError, match="seq 1 is not above the stored record's seq 2"):
+        save_node_enr(tmp_path, older)
+    save_node_enr(tmp_path, enr)
+    enr.set("custom", b"\xff", key)
+    save_node_enr(tmp_path, enr)
+    assert load_node_enr(tmp_path)[0].seq == 3
+
+
+def test_refuses_other_identities(tmp_path):
+    enr, key = _node()
+    save_node_enr(tmp_path, enr, key=key)
+    other = SigningKey.generate_secp256k1()
+    other_enr = other.builder().build(other)
+    with pytest.raises(ValueError, match="does not belong to the key"):
+        save_node_enr(tmp_path, other_enr, key=other)
+    with pytest.raises(ValueError, match="not signed by key"):
+        save_node_enr(tmp_path, enr, key=other)
+    assert Enr.from_base64((tmp_path / "enr.dat").read_text()) == enr
+
+
+def test_rejects_ed25519_keys(tmp_path):
+    key = SigningKey.generate_ed25519()
+    with pytest.raises(ValueError, match="secp256k1"):
+        save_node_enr(tmp_path, key.builder().build(key), key=key)
+    assert list(tmp_path.iterdir()) == []
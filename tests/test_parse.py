@@ -0,0 +1,71 @@
+"""Tests for auto-detecting the shape of record text."""
+
+import base64
+
+import pytest
+from pyenr import EIP778_ENR, EIP778_NODE_ID, DecodeError, Enr
+
+_ENR = Enr.from_base64(EIP778_ENR)
+_DATA = _ENR.to_bytes()
+
+
+@pytest.mark.parametrize(
+    "text",
+    [
+        EIP778_ENR,
+        EIP778_ENR[4:],
+        "  " + EIP778_ENR + "\n",
+        "0x" + _DATA.hex(),
+        _DATA.hex(),
+        _DATA.hex().upper(),
+        base64.b64encode(_DATA).decode(),
+        "enr:" + base64.urlsafe_b64encode(_DATA).decode(),
+    ],
+)
+def test_parses_record_shapes(text):
+    assert Enr.parse(text) == _ENR
+
+
+@pytest.mark.parametrize(
+    "text, format",
+    [
+        ("enode://" + "ab" * 64 + "@10.0.0.1:30303", "enode"),
+        ("enrtree://AKA3AM6LPBYEUDMVNU3BSVQJ5AD45Y7YPOHJLEF6W26QOE4VTUDPE@nodes.example.org", "enrtree"),
+        ("enrtree-root:v1 e=JWXYDBPXYWG6FX3GMDIBFA6CJ4 l=C7HRFPF3BLGF3YR4DY5KX3SMBE seq=1 sig=x", "enrtree"),
+        ("enrtree-branch:2XS2367YHAXJFGLZHVAWLQD4ZY,H4FHT4B454P6UXFD7JCYQ5PWDY", "enrtree"),
+        ("/ip4/10.0.0.1/udp/9000/quic-v1", "multiaddr"),
+        ("/dns4/boot.example.org/tcp/9000", "multiaddr"),
+        ("16Uiu2HAmSH2XVgZqYHWucap5kuPzLnt2TsNQkoppVxB5eJGvaXwm", "peer_id"),
+        ("12D3KooWBmAwcd4PJNJvfV89HwE48nwkRmAgo8Vy3uQEyNNHBox2", "peer_id"),
+        ("QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N", "peer_id"),
+        ('{"enr": "' + EIP778_ENR + '"}', "json"),
+        ("0x" + EIP778_NODE_ID.hex(), "node_id"),
+        (EIP778_NODE_ID.hex(), "node_id"),
+        ("0x" + _ENR.public_key.hex(), "public_key"),
+    ],
+)
+def test_names_unsupported_formats(text, format):
+    with pytest.raises(DecodeError, match="not a record") as info:
+        Enr.parse(text)
+    assert info.value.format == format
+
+
+def test_enode_error_points_to_enode_parser():
+    with pytest.raises(DecodeError, match="Enode.from_url"):
+        Enr.parse("enode://" + "ab" * 64 + "@10.0.0.1:30303")
+
+
+def test_other_decode_errors_have_no_format():
+    with pytest.raises(DecodeError) as info:
+        Enr.parse("enr:!!!!")
+    assert info.value.format is None
+    with pytest.raises(DecodeError) as info:
+        Enr.from_base64("enr:" + EIP778_ENR[4:-8])
+    assert info.value.format is None
+
+
+def test_strict():
+    trailing = _DATA + b"\x00"
+    assert Enr.parse(trailing.hex()) == _ENR
+    with pytest.raises(DecodeError):
+        Enr.parse(trailing.hex(), strict=True)
@@ -0,0 +1,89 @@
+"""Tests for pickling records and signing keys."""
+
+import copy
+import multiprocessing
+import pickle
+from concurrent.futures import ProcessPoolExecutor
+from operator import attrgetter, methodcaller
+
+import pytest
+from pyenr import Enr, SignatureError, SigningKey
+
+
+def _record(key=None):
+    key = key or SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.3.0.1")
+    builder.tcp4(9000)
+    builder.add("eth2", bytes(16))
+    return builder.build(key), key
+
+
+@pytest.mark.parametrize("protocol", range(pickle.HIGHEST_PROTOCOL + 1))
+def test_enr_roundtrip(protocol):
+    enr, _ = _record()
+    restored = pickle.loads(pickle.dumps(enr, protocol))
+    assert restored == enr
+    assert restored.to_bytes() == enr.to_bytes()
+
+
+def test_enr_pickles_as_rlp():
+    enr, _ = _record()
+    assert enr.__reduce__() == (Enr.from_bytes, (enr.to_bytes(),))
+    assert enr.to_bytes() in pickle.dumps(enr)
+
+
+def test_enr_copies_are_independent():
+    enr, key = _record()
+    for duplicate in (copy.copy(enr), copy.deepcopy(enr)):
+        duplicate.set_seq(enr.seq + 1, key)
+        assert duplicate != enr
+        assert duplicate.seq == enr.seq + 1
+
+
+def test_enr_is_not_mutable_through_setstate():
+    enr, _ = _record()
+    assert not hasattr(enr, "__setstate__")
+
+
+def test_key_refuses_pickle_by_default():
+    key = SigningKey.generate_secp256k1()
+    assert not key.picklable
+    with pytest.raises(TypeError, match="allow_pickle"):
+        pickle.dumps(key)
+    with pytest.raises(TypeError):
+        key.__getstate__()
+    with pytest.raises(TypeError):
+        copy.deepcopy(key)
+
+
+@pytest.mark.parametrize(
+    "key", [SigningKey.generate_secp256k1(), SigningKey.generate_ed25519()]
+)
+def test_key_roundtrip_after_opt_in(key):
+    picklable = key.allow_pickle()
+    assert picklable.picklable
+    assert not key.picklable
+    restored = pickle.loads(pickle.dumps(picklable))
+    assert restored.picklable
+    assert restored.public_key() == key.public_key()
+    assert restored.key_type == key.key_type
+    enr, _ = _record(restored)
+    assert enr.public_key == key.public_key()
+
+
+def test_key_from_unknown_scheme():
+    restore, _ = SigningKey.generate_secp256k1().allow_pickle().__reduce__()
+    assert not hasattr(SigningKey, "_from_state")
+    with pytest.raises(ValueError):
+        restore(("rsa", bytes(32)))
+
+
+def test_across_processes():
+    records = [_record()[0] for _ in range(3)]
+    keys = [SigningKey.generate_secp256k1().allow_pickle() for _ in range(2)]
+    context = multiprocessing.get_context("spawn")
+    with ProcessPoolExecutor(1, mp_context=context) as pool:
+        assert list(pool.map(attrgetter("node_id"), records)) == [r.node_id for r in records]
+        assert list(pool.map(methodcaller("peer_id"), keys)) == [k.peer_id() for k in keys]
+        assert list(pool.map(copy.copy, records)) == records
@@ -0,0 +1,117 @@
+"""Tests for the placeholder policy for port 0 and unspecified addresses."""
+
+from contextlib import contextmanager
+
+import pytest
+from pyenr import (
+    DecodeError,
+    Enr,
+    SigningKey,
+    get_placeholder_policy,
+    set_placeholder_policy,
+)
+
+_KEY = SigningKey.generate_secp256k1()
+
+
+def _record(ip="0.0.0.0", udp=0, tcp=30303):
+    builder = _KEY.builder()
+    builder.ip4(ip)
+    builder.udp4(udp)
+    builder.tcp4(tcp)
+    return builder.build(_KEY)
+
+
+@contextmanager
+def _policy(name):
+    set_placeholder_policy(name)
+    try:
+        yield
+    finally:
+        set_placeholder_policy("allow")
+
+
+def test_default_policy_allows():
+    assert get_placeholder_policy() == "allow"
+    enr = _record()
+    assert enr.ip4 == "0.0.0.0"
+    assert enr.udp4 == 0
+    assert enr.validate().is_valid
+
+
+def test_validate_flags_on_request():
+    report = _record().validate(placeholders="flag")
+    assert report.codes() == ["placeholder"]
+    assert sorted(v.key for v in report.violations) == ["ip", "udp"]
+    assert all("placeholder" in v.message for v in report.violations)
+
+
+def test_validate_bytes_flags_on_request():
+    data = _record(ip="10.0.0.1").to_bytes()
+    report = Enr.validate_bytes(data, placeholders="flag")
+    assert [(v.code, v.key) for v in report.violations] == [("placeholder", "udp")]
+
+
+def test_real_values_are_not_flagged():
+    assert _record(ip="10.0.0.1", udp=30303).validate(placeholders="flag").is_valid
+
+
+def test_flag_policy_applies_to_validate():
+    with _policy("flag"):
+        assert get_placeholder_policy() == "flag"
+        assert _record().validate().codes() == ["placeholder"]
+        assert _record().validate(placeholders="allow").is_valid
+
+
+def test_reject_policy_refuses_to_decode():
+    data = _record().to_bytes()
+    with _policy("reject"):
+        with pytest.raises(DecodeError) as info:
+            Enr.from_bytes(data)
+        assert info.value.code == "placeholder"
+        assert info.value.key == "ip"
+        assert "placeholder" in str(info.value)
+
+
+def test_reject_policy_decodes_real_values():
+    data = _record(ip="10.0.0.1", udp=30303).to_bytes()
+    with _policy("reject"):
+        assert Enr.from_bytes(data).udp4 == 30303
+
+
+def test_none_policy_hides_placeholders():
+    enr = _record(tcp=0)
+    with _policy("none"):
+        assert enr.ip4 is None
+        assert enr.udp4 is None
+        assert enr.tcp4 is None
+        fields = enr.fields()
+        assert fields.ip4 is None
+        assert fields.udp4 is None
+        assert enr.validate().is_valid
+
+
+def test_none_policy_keeps_real_values():
+    enr = _record(ip="10.0.0.1", udp=30303)
+    with _policy("none"):
+        assert enr.ip4 == "10.0.0.1"
+        assert enr.udp4 == 30303
+
+
+def test_ipv6_unspecified():
+    builder = _KEY.builder()
+    builder.ip6("::")
+    builder.udp6(9000)
+    enr = builder.build(_KEY)
+    assert enr.validate(placeholders="flag").codes() == ["placeholder"]
+    with _policy("none"):
+        assert enr.ip6 is None
+        assert enr.udp6 == 9000
+
+
+def test_unknown_policy():
+    with pytest.raises(ValueError, match="unknown placeholder policy"):
+        set_placeholder_policy("ignore")
+    with pytest.raises(ValueError, match="unknown placeholder policy"):
+        _record().validate(placeholders="ignore")
+    assert get_placeholder_policy() == "allow"
@@ -0,0 +1,38 @@
+"""Tests for Portal network fields."""
+
+import pytest
+from pyenr import Enr, FieldError, Profile, SigningKey
+
+
+def test_builder_portal_client():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.udp4(9009)
+    builder.portal_client("t 0.1.1")
+    enr = builder.build(key)
+
+    assert enr.portal_client == "t 0.1.1"
+    assert enr.get("c") == b"t 0.1.1"
+    assert Enr.from_base64(enr.to_base64()).portal_client == "t 0.1.1"
+    assert Profile.get("portal").fields(enr) == {"c": "t 0.1.1"}
+
+
+def test_set_portal_client():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    assert enr.portal_client is None
+
+    enr.set_portal_client("f 0.2.0", key)
+    assert enr.portal_client == "f 0.2.0"
+    assert enr.seq == 2
+    assert enr.validate(profile="portal").is_valid
+
+
+def test_non_utf8_client_is_none():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    enr.set("c", b"\xff\xfe", key)
+    assert enr.portal_client is None
+    with pytest.raises(FieldError):
+        enr.get("c", type=str)
@@ -0,0 +1,123 @@
+"""Tests for network profiles."""
+
+import pytest
+from pyenr import (
+    Enr,
+    Profile,
+    SigningKey,
+    encode,
+    get_default_profile,
+    known_keys,
+    profiles,
+    set_default_profile,
+)
+
+
+def _consensus_record(**custom):
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.add("eth2", b"\x00" * 16)
+    for k, v in custom.items():
+        builder.add(k, v)
+    return builder.build(key), key
+
+
+def test_builtin_profiles():
+    assert profiles() == [
+        "eth_consensus",
+        "eth_execution",
+        "bsc",
+        "portal",
+        "waku",
+        "ssv",
+        "op_stack",
+    ]
+    for name in profiles():
+        profile = Profile.get(name)
+        assert profile.name == name
+        assert profile.description
+        assert set(profile.required) <= set(profile.keys)
+        assert all(k in known_keys() for k in profile.keys)
+    assert Profile.get("portal") == Profile.get("portal")
+    assert repr(Profile.get("portal")) == 'Profile("portal")'
+
+
+def test_unknown_profile():
+    with pytest.raises(ValueError):
+        Profile.get("nope")
+    enr, _ = _consensus_record()
+    with pytest.raises(ValueError):
+        enr.validate(profile="nope")
+    with pytest.raises(TypeError):
+        enr.validate(profile=3)
+
+
+def test_profile_fields():
+    enr, _ = _consensus_record(attnets=b"\xff" * 8, cgc=4, other=b"\x01")
+    fields = Profile.get("eth_consensus").fields(enr)
+    assert fields == {"eth2": b"\x00" * 16, "attnets": b"\xff" * 8, "cgc": 4}
+    assert enr.profile_fields("eth_consensus") == fields
+    assert enr.profile_fields("portal") == {}
+
+
+def test_validate_missing_and_bad_length():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.add("attnets", b"\xff" * 3)
+    enr = builder.build(key)
+    assert enr.validate().is_valid
+
+    report = enr.validate(profile="eth_consensus")
+    assert sorted(report.codes()) == ["bad_field_length", "missing_key"]
+    by_code = {v.code: v for v in report.violations}
+    assert by_code["missing_key"].key == "eth2"
+    assert by_code["bad_field_length"].key == "attnets"
+    assert by_code["bad_field_length"].expected == 8
+    assert by_code["bad_field_length"].actual == 3
+    assert Profile.get("eth_consensus").validate(enr).codes() == report.codes()
+
+    data = encode(enr, "bytes")
+    assert Enr.validate_bytes(data, profile="eth_consensus").codes() == report.codes()
+
+
+def test_valid_consensus_record():
+    enr, _ = _consensus_record(attnets=b"\x00" * 8, syncnets=b"\x00")
+    assert enr.validate(profile=Profile.get("eth_consensus")).is_valid
+    assert enr.validate(profile="eth_execution").codes() == ["missing_key"]
+
+
+def test_default_profile():
+    enr, _ = _consensus_record()
+    assert get_default_profile() is None
+    with pytest.raises(ValueError):
+        enr.profile_fields()
+    set_default_profile("eth_execution")
+    try:
+        assert get_default_profile() == Profile.get("eth_execution")
+        assert enr.validate().codes() == ["missing_key"]
+        assert enr.validate(profile="eth_consensus").is_valid
+        assert enr.profile_fields() == {}
+    finally:
+        set_default_profile(None)
+    assert get_default_profile() is None
+    assert enr.validate().is_valid
+
+
+def test_add_and_set_helpers():
+    portal = Profile.get("portal")
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    portal.add(builder, "c", "t 0.1.1")
+    enr = builder.build(key)
+    assert enr.get("c") == b"t 0.1.1"
+    assert portal.fields(enr) == {"c": "t 0.1.1"}
+
+    portal.set(enr, "c", "f 0.2.0", key)
+    assert portal.fields(enr) == {"c": "f 0.2.0"}
+    assert enr.seq == 2
+
+    with pytest.raises(ValueError):
+        portal.set(enr, "eth2", b"\x00" * 16, key)
+    with pytest.raises(ValueError):
+        portal.add(builder, "tcp", 30303)
@@ -0,0 +1,136 @@
+"""Tests for progress callbacks of the batch APIs."""
+
+import asyncio
+import socket
+import time
+
+import pytest
+from pyenr import (
+    SigningKey,
+    dedupe_report,
+    dedupe_report_async,
+    features,
+    filter_many,
+    filter_routable,
+    group_by_fork_digest,
+    probe_liveness,
+    process_enr_files,
+    write_enr_file,
+)
+
+
+def _records(n=6):
+    out = []
+    for i in range(n):
+        key = SigningKey.generate_secp256k1()
+        builder = key.builder()
+        builder.ip4("127.0.0.1")
+        builder.udp4(9000 + i)
+        out.append(builder.build(key))
+    return out
+
+
+class _Recorder:
+    def __init__(self, fail=False):
+        self.calls = []
+        self.fail = fail
+
+    def __call__(self, done, total):
+        self.calls.append((done, total))
+        if self.fail:
+            raise RuntimeError("stop")
+
+
+@pytest.mark.parametrize(
+    "run",
+    [
+        lambda enrs, progress: filter_many(enrs, has_ip4=True, progress=progress),
+        lambda enrs, progress: filter_routable(enrs, progress=progress),
+        lambda enrs, progress: group_by_fork_digest(enrs, progress=progress),
+        lambda enrs, progress: dedupe_report(enrs, workers=2, progress=progress),
+    ],
+)
+def test_record_batches_report_completion(run):
+    records = _records()
+    recorder = _Recorder()
+    run(records, recorder)
+    assert recorder.calls[-1] == (6, 6)
+
+
+def test_empty_batch():
+    recorder = _Recorder()
+    assert filter_many([], progress=recorder) == []
+    assert recorder.calls == [(0, 0)]
+
+
+def test_process_enr_files_counts_files(tmp_path):
+    paths = []
+    for i, record in enumerate(_records(3)):
+        path = tmp_path / f"nodes{i}.txt"
+        write_enr_file(path, [record])
+        paths.append(path)
+    recorder = _Recorder()
+    assert len(process_enr_files(paths, progress=recorder)) == 3
+    assert recorder.calls[-1] == (3, 3)
+
+
+def test_async_variant():
+    recorder = _Recorder()
+
+    async def run():
+        return await dedupe_report_async(_records(), progress=recorder)
+
+    assert len(asyncio.run(run())) == 6
+    assert recorder.calls[-1] == (6, 6)
+
+
+def _silent_targets(n):
+    if "discv5" not in features()["features"]:
+        pytest.skip("built without the discv5 feature")
+    # Bound but never read, so discv5 pings wait for the full timeout.
+    sock = socket.socket(socket.AF_INET, socket.SOCK_DGRAM)
+    sock.bind(("127.0.0.1", 0))
+    port = sock.getsockname()[1]
+    records = []
+    for _ in range(n):
+        key = SigningKey.generate_secp256k1()
+        builder = key.builder()
+        builder.ip4("127.0.0.1")
+        builder.udp4(port)
+        records.append(builder.build(key))
+    return sock, records
+
+
+def test_reports_during_long_jobs():
+    sock, records = _silent_targets(4)
+    recorder = _Recorder()
+    with sock:
+        probe_liveness(
+            records, method="discv5_ping", concurrency=1, timeout=0.15, progress=recorder
+        )
+    assert len(recorder.calls) >= 2
+    assert recorder.calls[-1] == (4, 4)
+    dones = [done for done, _ in recorder.calls]
+    assert dones == sorted(dones)
+    assert all(total == 4 for _, total in recorder.calls)
+
+
+def test_raising_callback_stops_the_job():
+    sock, records = _silent_targets(10)
+    recorder = _Recorder(fail=True)
+    start = time.monotonic()
+    with sock:
+        with pytest.raises(RuntimeError, match="stop"):
+            probe_liveness(
+                records, method="discv5_ping", concurrency=1, timeout=0.15, progress=recorder
+            )
+    assert len(recorder.calls) == 1
+    assert time.monotonic() - start < 1.0
+
+
+def test_raising_on_completion_propagates():
+    def fail(done, total):
+        raise ValueError("checkpoint failed")
+
+    with pytest.raises(ValueError, match="checkpoint failed"):
+        filter_many(_records(), progress=fail)
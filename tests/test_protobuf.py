@@ -0,0 +1,83 @@
+"""Tests for the protobuf encoding of records."""
+
+import pytest
+from pyenr import ENR_PROTO, EIP778_ENR, DecodeError, Enr, SignatureError, SigningKey, features
+
+if "interchange" not in features()["features"]:
+    pytest.skip("built without the interchange feature", allow_module_level=True)
+
+_EIP778 = Enr.from_base64(EIP778_ENR)
+
+
+def _varint(n):
+    out = bytearray()
+    while True:
+        byte, n = n & 0x7F, n >> 7
+        out.append(byte | (0x80 if n else 0))
+        if not n:
+            return bytes(out)
+
+
+def _field(tag, value):
+    if isinstance(value, int):
+        return _varint(tag << 3) + _varint(value)
+    return _varint(tag << 3 | 2) + _varint(len(value)) + value
+
+
+def _minimal(enr, *extra):
+    """The message with only the fields the signature covers, written by hand."""
+    out = _field(1, enr.seq) + _field(2, enr.signature)
+    for key, value in enr.items(raw=True):
+        out += _field(3, _field(1, key) + _field(2, value))
+    return out + b"".join(extra)
+
+
+def test_schema_is_exported():
+    assert "message Enr {" in ENR_PROTO
+    assert "repeated Entry content = 3;" in ENR_PROTO
+
+
+def test_round_trip():
+    key = SigningKey.generate_ed25519()
+    builder = key.builder()
+    builder.ip6("2001:db8::1")
+    builder.tcp6(30303)
+    enr = builder.build(key)
+    for record in (_EIP778, enr):
+        assert Enr.from_protobuf_bytes(record.to_protobuf_bytes()) == record
+
+
+def test_decoded_fields():
+    data = _EIP778.to_protobuf_bytes()
+    assert data.startswith(_field(1, 1) + _field(2, _EIP778.signature))
+    assert _field(4, _EIP778.node_id) in data
+    assert _field(5, _EIP778.public_key) in data
+    assert _field(6, b"v4") in data
+    assert _field(7, b"127.0.0.1") in data
+    assert _field(9, 30303) in data
+    assert _field(8, 0) not in data
+
+
+def test_from_minimal_message():
+    assert Enr.from_protobuf_bytes(_minimal(_EIP778)) == _EIP778
+
+
+def test_decoded_field_must_match():
+    assert Enr.from_protobuf_bytes(_minimal(_EIP778, _field(9, 30303))) == _EIP778
+    with pytest.raises(DecodeError, match="udp4 does not match"):
+        Enr.from_protobuf_bytes(_minimal(_EIP778, _field(9, 30304)))
+    with pytest.raises(DecodeError, match="ip4 does not match"):
+        Enr.from_protobuf_bytes(_minimal(_EIP778, _field(7, b"127.0.0.2")))
+    with pytest.raises(DecodeError, match="node_id does not match"):
+        Enr.from_protobuf_bytes(_minimal(_EIP778, _field(4, bytes(32))))
+
+
+def test_tampered_seq():
+    data = _field(1, 2) + _minimal(_EIP778)[len(_field(1, 1)) :]
+    with pytest.raises(SignatureError):
+        Enr.from_protobuf_bytes(data)
+
+
+def test_malformed():
+    with pytest.raises(DecodeError, match="invalid protobuf record"):
+        Enr.from_protobuf_bytes(b"\x0a\x05ab")
@@ -0,0 +1,49 @@
+"""Tests for the random record generator."""
+
+import pytest
+from pyenr import Enr, SigningKey, encode, generate_random_enr
+
+
+def test_seed_is_reproducible():
+    assert generate_random_enr(7) == generate_random_enr(7)
+    assert generate_random_enr(7) != generate_random_enr(8)
+
+
+@pytest.mark.parametrize("seed", range(50))
+def test_records_are_valid(seed):
+    enr = generate_random_enr(seed)
+    data = encode(enr, "bytes")
+    assert len(data) <= 300
+    assert Enr.from_bytes(data, strict=True) == enr
+    assert enr.verify_with(enr.public_key)
+
+
+def test_selected_fields():
+    enr = generate_random_enr(1, ["ip", "udp", "eth2"])
+    assert enr.ip4 is not None
+    assert enr.udp4 is not None
+    assert len(enr.get("eth2")) == 16
+    assert enr.tcp4 is None
+    assert enr.ip6 is None
+
+
+def test_no_fields():
+    enr = generate_random_enr(1, [])
+    assert set(enr.keys()) <= {"id", "secp256k1", "ed25519"}
+
+
+@pytest.mark.parametrize("seed", range(20))
+def test_padding_reaches_size_limit(seed):
+    enr = generate_random_enr(seed, ["ip", "tcp", "pad"])
+    assert 297 <= len(encode(enr, "bytes")) <= 300
+
+
+def test_signs_with_given_key():
+    key = SigningKey.generate_ed25519()
+    enr = generate_random_enr(3, ["udp"], key=key)
+    assert enr.public_key == key.public_key()
+
+
+def test_unknown_field():
+    with pytest.raises(ValueError):
+        generate_random_enr(1, ["nope"])
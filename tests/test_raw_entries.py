@@ -0,0 +1,81 @@
+"""Tests for byte-exact access to record entries."""
+
+import pytest
+from pyenr import DecodeError, Enr, SigningKey
+
+
+def _header(prefix, payload):
+    if len(payload) < 56:
+        return bytes([prefix + len(payload)])
+    length = len(payload).to_bytes((len(payload).bit_length() + 7) // 8, "big")
+    return bytes([prefix + 55 + len(length)]) + length
+
+
+def _rlp(item):
+    """Minimal RLP encoder; raw `bytearray` items are inserted as is."""
+    if isinstance(item, bytearray):
+        return bytes(item)
+    if isinstance(item, list):
+        payload = b"".join(_rlp(i) for i in item)
+        return _header(0xC0, payload) + payload
+    if len(item) == 1 and item[0] < 0x80:
+        return item
+    return _header(0x80, item) + item
+
+
+def _record():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.udp4(30303)
+    builder.add("custom", [b"\x01", [b"\x02"]])
+    return builder.build(key)
+
+
+def test_raw_entries_match_items():
+    enr = _record()
+    entries = enr.raw_entries()
+    assert entries == enr.items(raw=True)
+    assert [k for k, _ in entries] == [b"custom", b"id", b"ip", b"secp256k1", b"udp"]
+    assert dict(entries)[b"udp"] == b"\x82\x76\x5f"
+    assert dict(entries)[b"custom"] == enr.get_raw_rlp("custom")
+
+
+def test_raw_entries_bytes_of_canonical_record():
+    enr = _record()
+    assert Enr.raw_entries_bytes(enr.to_bytes()) == enr.raw_entries()
+
+
+def test_raw_entries_bytes_keeps_non_canonical_framing():
+    # "udp" with a leading zero byte and "ip" with a long-form header.
+    ip = bytearray(b"\xb8\x04\x0a\x00\x00\x01")
+    data = _rlp([b"\x00" * 64, b"\x01", b"id", b"v4", b"ip", ip, b"udp", b"\x00\x50"])
+    assert Enr.raw_entries_bytes(data) == [
+        (b"id", b"\x82v4"),
+        (b"ip", bytes(ip)),
+        (b"udp", b"\x82\x00\x50"),
+    ]
+
+
+def test_raw_entries_bytes_does_not_verify():
+    # No public key and an all-zero signature: not a decodable record.
+    data = _rlp([b"\x00" * 64, b"\x01", b"z", b"\x01"])
+    with pytest.raises(DecodeError):
+        Enr.from_bytes(data)
+    assert Enr.raw_entries_bytes(data) == [(b"z", b"\x01")]
+
+
+@pytest.mark.parametrize(
+    "data, code",
+    [
+        (b"\x80", "malformed"),
+        (_rlp([b"\x00" * 64]), "malformed"),
+        (_rlp([b"\x00" * 64, b"\x01", b"id"]), "malformed"),
+        (_rlp([b"\x00" * 64, b"\x01", [b"id"], b"v4"]), "malformed"),
+        (_rlp([b"\x00" * 64, b"\x01"]) + b"\x00", "trailing_data"),
+    ],
+)
+def test_raw_entries_bytes_rejects_bad_framing(data, code):
+    with pytest.raises(DecodeError) as info:
+        Enr.raw_entries_bytes(data)
+    assert info.value.code == code
@@ -0,0 +1,53 @@
+"""Tests for batch reverse DNS lookups."""
+
+import socket
+
+import pytest
+from pyenr import SigningKey, features, reverse_dns
+
+if "dns" not in features()["features"]:
+    pytest.skip("built without the dns feature", allow_module_level=True)
+
+_KEY = SigningKey.generate_secp256k1()
+
+
+def _record(ip4=None, ip6=None):
+    builder = _KEY.builder()
+    if ip4:
+        builder.ip4(ip4)
+    if ip6:
+        builder.ip6(ip6)
+    return builder.build(_KEY)
+
+
+def _system(ip):
+    try:
+        return socket.getnameinfo((ip, 0), socket.NI_NAMEREQD)[0].rstrip(".")
+    except OSError:
+        return None
+
+
+def test_matches_system_resolver():
+    enrs = [
+        _record(ip4="127.0.0.1"),
+        _record(ip4="127.0.0.1", ip6="::1"),
+        _record(ip4="192.0.2.1"),
+        _record(),
+    ]
+    names = reverse_dns(enrs, concurrency=2)
+    assert sorted(names) == ["127.0.0.1", "192.0.2.1", "::1"]
+    assert names == {ip: _system(ip) for ip in names}
+
+
+def test_loopback_has_a_name():
+    assert reverse_dns([_record(ip4="127.0.0.1")])["127.0.0.1"]
+
+
+def test_empty():
+    assert reverse_dns([]) == {}
+    assert reverse_dns([_record()]) == {}
+
+
+def test_invalid_concurrency():
+    with pytest.raises(ValueError, match="concurrency"):
+        reverse_dns([], concurrency=0)
@@ -0,0 +1,77 @@
+"""Tests for redacting addresses from records."""
+
+import pytest
+from pyenr import Enr, SequenceOverflowError, SigningKey
+
+_KEY = SigningKey.generate_secp256k1()
+
+
+def _record():
+    builder = _KEY.builder()
+    builder.ip4("10.0.0.1")
+    builder.ip6("2001:db8::1")
+    builder.tcp4(9000)
+    builder.udp4(9001)
+    builder.udp6(9002)
+    builder.add("eth2", bytes(16))
+    return builder.build(_KEY)
+
+
+def test_signed_drops_default_keys():
+    enr = _record()
+    redacted = enr.redacted(signing_key=_KEY)
+    assert isinstance(redacted, Enr)
+    assert redacted.keys() == ["eth2", "id", "secp256k1", "udp6"]
+    assert redacted.seq == enr.seq + 1
+    assert redacted.node_id == enr.node_id
+    assert Enr.from_bytes(redacted.to_bytes(), strict=True) == redacted
+    assert enr.ip4 == "10.0.0.1"
+
+
+def test_signed_zeroed():
+    enr = _record()
+    redacted = enr.redacted(signing_key=_KEY, zero=True)
+    assert redacted.keys() == enr.keys()
+    assert (redacted.ip4, redacted.ip6) == ("0.0.0.0", "::")
+    assert (redacted.tcp4, redacted.udp4, redacted.udp6) == (0, 0, 9002)
+    assert redacted.seq == enr.seq + 1
+    assert Enr.from_bytes(redacted.to_bytes(), strict=True) == redacted
+
+
+def test_custom_drop():
+    enr = _record()
+    redacted = enr.redacted(["udp6", "eth2", "quic"], _KEY)
+    assert "udp6" not in redacted.keys() and "eth2" not in redacted.keys()
+    assert redacted.ip4 == "10.0.0.1"
+
+
+def test_cannot_drop_identity_from_signed_record():
+    with pytest.raises(ValueError, match="secp256k1"):
+        _record().redacted(["secp256k1"], _KEY)
+
+
+def test_unsigned_dict():
+    enr = _record()
+    redacted = enr.redacted()
+    assert redacted["seq"] == enr.seq
+    assert redacted["node_id"] == enr.node_id
+    items = dict(enr.items())
+    assert redacted["content"] == {
+        k: v for k, v in items.items() if k not in ("ip", "ip6", "udp", "tcp")
+    }
+
+
+def test_unsigned_zeroed():
+    redacted = _record().redacted(["ip", "tcp", "secp256k1"], zero=True)
+    content = redacted["content"]
+    assert content["ip"] == b"\x84" + bytes(4)
+    assert content["tcp"] == b"\x80"
+    assert content["secp256k1"] == b"\xa1" + bytes(33)
+
+
+def test_seq_overflow():
+    enr = _record()
+    enr.set_seq(2**64 - 1, _KEY)
+    with pytest.raises(SequenceOverflowError):
+        enr.redacted(signing_key=_KEY)
+    assert enr.redacted()["seq"] == 2**64 - 1
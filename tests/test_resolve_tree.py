@@ -0,0 +1,278 @@
+"""Tests for resolving EIP-1459 trees over DNS, against a local nameserver."""
+
+import asyncio
+import errno
+import socket
+import struct
+import threading
+
+import pytest
+from pyenr import (
+    CancelledError,
+    CancelToken,
+    DecodeError,
+    EnrTree,
+    SignatureError,
+    SigningKey,
+    features,
+    resolve_tree,
+    resolve_tree_async,
+)
+
+if "dns" not in features()["features"]:
+    pytest.skip("built without the dns feature", allow_module_level=True)
+
+
+class _Server:
+    """Answers TXT queries from `zone` over UDP and TCP on one local port.
+
+    With `truncate`, UDP answers only set the TC flag, so that clients must
+    ask again over TCP.
+    """
+
+    def __init__(self, zone, *, truncate=False):
+        self.zone = {name.lower(): text for name, text in zone.items()}
+        self.truncate = truncate
+        self.queries = []
+        self.udp, self.tcp = self._bind()
+        self.port = self.udp.getsockname()[1]
+
+    @staticmethod
+    def _bind():
+        # The port picked for UDP may already be taken for TCP; pick again.
+        while True:
+            udp = socket.socket(socket.AF_INET, socket.SOCK_DGRAM)
+            udp.bind(("127.0.0.1", 0))
+            tcp = socket.socket(socket.AF_INET, socket.SOCK_STREAM)
+            tcp.setsockopt(socket.SOL_SOCKET, socket.SO_REUSEADDR, 1)
+            try:
+                tcp.bind(("127.0.0.1", udp.getsockname()[1]))
+            except OSError as err:
+                udp.close()
+                tcp.close()
+                if err.errno != errno.EADDRINUSE:
+                    raise
+                continue
+            tcp.listen()
+            return udp, tcp
+
+    @property
+    def address(self):
+        return f"127.0.0.1:{self.port}"
+
+    def _answer(self, query, *, truncate):
+        (qid,) = struct.unpack("!H", query[:2])
+        pos, labels = 12, []
+        while query[pos]:
+            labels.append(query[pos + 1 : pos + 1 + query[pos]].decode())
+            pos += 1 + query[pos]
+        question = query[12 : pos + 5]
+        name = ".".join(labels)
+        self.queries.append(name)
+        text = self.zone.get(name.lower())
+        flags = 0x8180 | (0x0200 if truncate else 0) | (3 if text is None else 0)
+        answers = b""
+        if text is not None and not truncate:
+            data = text.encode()
+            rdata = b"".join(
+                bytes([len(data[i : i + 255])]) + data[i : i + 255] for i in range(0, len(data), 255)
+            )
+            answers = struct.pack("!HHHIH", 0xC00C, 16, 1, 60, len(rdata)) + rdata
+        count = 1 if answers else 0
+        return struct.pack("!HHHHHH", qid, flags, 1, count, 0, 0) + question + answers
+
+    def _serve_udp(self):
+        while True:
+            try:
+                query, peer = self.udp.recvfrom(4096)
+            except OSError:
+                return
+            self.udp.sendto(self._answer(query, truncate=self.truncate), peer)
+
+    def _serve_tcp(self):
+        while True:
+            try:
+                conn, _ = self.tcp.accept()
+            except OSError:
+                return
+            with conn:
+                (n,) = struct.unpack("!H", conn.recv(2, socket.MSG_WAITALL))
+                answer = self._answer(conn.recv(n, socket.MSG_WAITALL), truncate=False)
+                conn.sendall(struct.pack("!H", len(answer)) + answer)
+
+    def __enter__(self):
+        for target in (self._serve_udp, self._serve_tcp):
+            threading.Thread(target=target, daemon=True).start()
+        return self
+
+    def __exit__(self, *exc):
+        self.udp.close()
+        self.tcp.close()
+
+
+def _records(n, offset=0):
+    out = []
+    for i in range(offset, offset + n):
+        key = SigningKey.from_secp256k1(i.to_bytes(2, "big") + b"\x02" * 30)
+        builder = key.builder()
+        builder.ip4(f"10.1.{i // 256}.{i % 256}")
+        builder.udp4(30303)
+        out.append(builder.build(key))
+    return out
+
+
+def _key(n=1):
+    return SigningKey.from_secp256k1(bytes([n]) * 32)
+
+
+def test_resolve_records():
+    records = _records(40)
+    tree = EnrTree.build(records, _key(), seq=1)
+    with _Server(tree.to_txt("nodes.example.org")) as server:
+        found = resolve_tree(str(tree.link("nodes.example.org")), nameserver=server.address)
+    assert sorted(r.node_id for r in found) == sorted(r.node_id for r in records)
+    assert len(server.queries) == len(tree)
+
+
+def test_resolve_tree_async():
+    records = _records(10)
+    tree = EnrTree.build(records, _key(), seq=1)
+
+    async def run(url):
+        return await resolve_tree_async(url, nameserver=server.address)
+
+    with _Server(tree.to_txt("nodes.example.org")) as server:
+        found = asyncio.run(run(str(tree.link("nodes.example.org"))))
+        assert sorted(r.node_id for r in found) == sorted(r.node_id for r in records)
+        with pytest.raises(DecodeError):
+            asyncio.run(run("enrtree://not-a-link"))
+
+
+def test_follows_links_once():
+    a, b = _key(1), _key(2)
+    link_a = EnrTree.build([], a, seq=1).link("a.example.org")
+    link_b = EnrTree.build([], b, seq=1).link("b.example.org")
+    tree_a = EnrTree.build(_records(2), a, seq=1, links=[link_b])
+    tree_b = EnrTree.build(_records(3, offset=2), b, seq=1, links=[link_a])
+    zone = {**tree_a.to_txt("a.example.org"), **tree_b.to_txt("b.example.org")}
+    with _Server(zone) as server:
+        found = resolve_tree(str(link_a), nameserver=server.address)
+        assert len(found) == 5
+        assert server.queries.count("a.example.org") == 1
+        assert server.queries.count("b.example.org") == 1
+        only_a = resolve_tree(str(link_a), nameserver=server.address, follow_links=False)
+        assert sorted(r.node_id for r in only_a) == sorted(r.node_id for r in tree_a.records)
+
+
+def test_keeps_highest_seq_across_trees():
+    key = _key(3)
+    builder = key.builder()
+    builder.udp4(30303)
+    old = builder.build(key)
+    new = builder.build(key)
+    new.set_seq(9, key)
+    link_b = EnrTree.build([], _key(2), seq=1).link("b.example.org")
+    tree_a = EnrTree.build([old], _key(1), seq=1, links=[link_b])
+    tree_b = EnrTree.build([new], _key(2), seq=1)
+    zone = {**tree_a.to_txt("a.example.org"), **tree_b.to_txt("b.example.org")}
+    with _Server(zone) as server:
+        found = resolve_tree(str(tree_a.link("a.example.org")), nameserver=server.address)
+    assert found == [new]
+
+
+def test_truncated_answers_retry_over_tcp():
+    tree = EnrTree.build(_records(20), _key(), seq=1)
+    with _Server(tree.to_txt("nodes.example.org"), truncate=True) as server:
+        found = resolve_tree(str(tree.link("nodes.example.org")), nameserver=server.address)
+    assert len(found) == 20
+
+
+def test_root_signed_by_other_key():
+    tree = EnrTree.build(_records(2), _key(1), seq=1)
+    imposter = EnrTree.build([], _key(2), seq=1).link("nodes.example.org")
+    with _Server(tree.to_txt("nodes.example.org")) as server:
+        with pytest.raises(SignatureError):
+            resolve_tree(str(imposter), nameserver=server.address)
+
+
+def test_tampered_entry():
+    tree = EnrTree.build(_records(3), _key(), seq=1)
+    zone = tree.to_txt("nodes.example.org")
+    other = _records(1, offset=10)[0].to_base64()
+    name = next(n for n, text in zone.items() if text.startswith("enr:"))
+    zone[name] = other
+    with _Server(zone) as server:
+        with pytest.raises(DecodeError, match="does not match its hash"):
+            resolve_tree(str(tree.link("nodes.example.org")), nameserver=server.address)
+
+
+def test_missing_entry():
+    tree = EnrTree.build(_records(3), _key(), seq=1)
+    zone = tree.to_txt("nodes.example.org")
+    del zone[next(n for n, text in zone.items() if text.startswith("enr:"))]
+    with _Server(zone) as server:
+        with pytest.raises(DecodeError, match="no such entry"):
+            resolve_tree(str(tree.link("nodes.example.org")), nameserver=server.address)
+
+
+def test_missing_root():
+    link = EnrTree.build([], _key(), seq=1).link("nodes.example.org")
+    with _Server({}) as server:
+        with pytest.raises(DecodeError, match="no EIP-1459 root"):
+            resolve_tree(str(link), nameserver=server.address)
+
+
+def test_unanswered_lookup():
+    link = EnrTree.build([], _key(), seq=1).link("nodes.example.org")
+    silent = socket.socket(socket.AF_INET, socket.SOCK_DGRAM)
+    silent.bind(("127.0.0.1", 0))
+    try:
+        with pytest.raises(ConnectionError):
+            resolve_tree(
+                str(link), nameserver=f"127.0.0.1:{silent.getsockname()[1]}", timeout=0.2
+            )
+    finally:
+        silent.close()
+
+
+def test_progress_and_cancel():
+    tree = EnrTree.build(_records(30), _key(), seq=1)
+    url = str(tree.link("nodes.example.org"))
+    calls = []
+    with _Server(tree.to_txt("nodes.example.org")) as server:
+        resolve_tree(url, nameserver=server.address, progress=lambda d, t: calls.append((d, t)))
+        token = CancelToken()
+        token.cancel()
+        with pytest.raises(CancelledError):
+            resolve_tree(url, nameserver=server.address, cancel=token)
+    assert calls[-1] == (len(tree), len(tree))
+
+
+def test_concurrent_threads():
+    tree = EnrTree.build(_records(30), _key(), seq=1)
+    url = str(tree.link("nodes.example.org"))
+    results = []
+    with _Server(tree.to_txt("nodes.example.org")) as server:
+        threads = [
+            threading.Thread(
+                target=lambda: results.append(len(resolve_tree(url, nameserver=server.address)))
+            )
+            for _ in range(4)
+        ]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+    assert results == [30] * 4
+
+
+def test_invalid_arguments():
+    with pytest.raises(DecodeError):
+        resolve_tree("enrtree://nope")
+    link = str(EnrTree.build([], _key(), seq=1).link("nodes.example.org"))
+    with pytest.raises(ValueError):
+        resolve_tree(link, nameserver="not an address")
+    with pytest.raises(ValueError):
+        resolve_tree(link, timeout=0)
+    with pytest.raises(ValueError):
+        resolve_tree(link, concurrency=0)
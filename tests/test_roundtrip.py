@@ -64,3 +64,38 @@ def test_multiple_roundtrips():
     assert decoded1 == original
     assert decoded2 == original
     assert encoded1 == encoded2
+
+
+def test_to_bytes_matches_base64():
+    """to_bytes() writes the same encoding that to_base64() wraps."""
+    import base64
+
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.add("custom", b"\x01\x02\x03")
+    enr = builder.build(key)
+
+    raw = enr.to_bytes()
+    text = enr.to_base64().removeprefix("enr:")
+    assert raw == base64.urlsafe_b64decode(text + "=" * (-len(text) % 4))
+    assert bytes(memoryview(raw)) == raw
+
+
+def test_buffer_protocol_exports_encoding():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.2")
+    builder.udp4(30303)
+    enr = builder.build(key)
+
+    view = memoryview(enr)
+    assert view.readonly
+    assert view.format == "B"
+    assert view.nbytes == len(enr.to_bytes())
+    assert bytes(view) == bytes(enr) == enr.to_bytes()
+    assert Enr.from_bytes(bytes(view)) == enr
+
+    enr.set_seq(enr.seq + 1, key)
+    assert bytes(view) != enr.to_bytes()
+    view.release()
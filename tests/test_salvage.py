@@ -0,0 +1,94 @@
+"""Tests for lenient salvage decoding."""
+
+from pyenr import Enr, SigningKey
+
+
+def _record():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.tcp4(30303)
+    builder.udp4(30303)
+    return builder.build(key)
+
+
+def test_valid_record_round_trips():
+    enr = _record()
+    salvaged = Enr.from_bytes_lenient(enr.to_bytes())
+    assert salvaged.record == enr
+    assert salvaged.signature_valid
+    assert salvaged.problems == []
+    assert salvaged.node_id == enr.node_id
+    assert salvaged.keys() == enr.keys()
+
+
+def test_bad_signature_keeps_fields():
+    enr = _record()
+    raw = bytearray(enr.to_bytes())
+    raw[10] ^= 0xFF
+    salvaged = Enr.from_bytes_lenient(bytes(raw))
+    assert salvaged.record is None
+    assert not salvaged.signature_valid
+    assert [p.code for p in salvaged.problems] == ["invalid_signature"]
+    assert salvaged.ip4 == "10.0.0.1"
+    assert salvaged.tcp4 == 30303
+    assert salvaged.seq == enr.seq
+    assert salvaged.node_id == enr.node_id
+    assert salvaged.identity_scheme == "v4"
+
+
+def test_truncated_record_recovers_prefix():
+    enr = _record()
+    raw = enr.to_bytes()
+    # Cut inside the trailing "udp" value.
+    salvaged = Enr.from_bytes_lenient(raw[:-1])
+    assert salvaged.record is None
+    assert "malformed" in [p.code for p in salvaged.problems]
+    assert salvaged.ip4 == "10.0.0.1"
+    assert salvaged.public_key == enr.public_key
+    assert "udp" in salvaged.keys()
+
+
+def test_garbage_never_raises():
+    for data in (b"", b"\xff", b"\x80", b"\xc0", bytes(range(256))):
+        salvaged = Enr.from_bytes_lenient(data)
+        assert salvaged.record is None
+        assert salvaged.problems
+
+
+def _unknown_scheme_record():
+    """A hand-built record claiming identity scheme "v5"."""
+    key = SigningKey.generate_secp256k1()
+    pk = key.public_key()
+    body = (
+        b"\xb8\x40" + b"\x00" * 64  # signature
+        + b"\x01"  # seq
+        + b"\x82id\x82v5"
+        + b"\x82ip\x84\x0a\x00\x00\x01"
+        + b"\x89secp256k1\xa1" + pk
+    )
+    return bytes([0xF8, len(body)]) + body
+
+
+def test_unknown_identity_scheme():
+    raw = _unknown_scheme_record()
+    salvaged = Enr.from_bytes_lenient(raw)
+    assert not salvaged.is_verified
+    assert salvaged.identity_scheme == "v5"
+    assert salvaged.ip4 == "10.0.0.1"
+    assert salvaged.node_id is None
+    assert not salvaged.signature_valid
+    assert [p.code for p in salvaged.problems] == ["unknown_id_scheme"]
+
+
+def test_from_base64_lenient():
+    import base64
+
+    raw = _unknown_scheme_record()
+    text = "enr:" + base64.urlsafe_b64encode(raw).rstrip(b"=").decode()
+    assert Enr.from_base64_lenient(text).identity_scheme == "v5"
+
+    enr = _record()
+    salvaged = Enr.from_base64_lenient(enr.to_base64())
+    assert salvaged.is_verified
+    assert enr.is_verified
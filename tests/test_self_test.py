@@ -0,0 +1,48 @@
+"""Tests for the bundled test vectors and run_self_test()."""
+
+import pytest
+from pyenr import (
+    EIP778_ENR,
+    EIP778_NODE_ID,
+    EIP778_PRIVATE_KEY,
+    INVALID_VECTORS,
+    VALID_VECTORS,
+    Enr,
+    EnrError,
+    SignatureError,
+    SigningKey,
+    encode,
+    run_self_test,
+)
+
+
+def test_run_self_test():
+    assert run_self_test() > len(VALID_VECTORS) + len(INVALID_VECTORS)
+
+
+def test_eip778_constants():
+    enr = Enr.from_base64(EIP778_ENR)
+    assert enr.node_id == EIP778_NODE_ID
+    key = SigningKey.from_secp256k1(EIP778_PRIVATE_KEY)
+    assert key.public_key() == enr.public_key
+    assert VALID_VECTORS[0] == ("eip778", encode(enr, "bytes"))
+
+
+@pytest.mark.parametrize("name,data", VALID_VECTORS)
+def test_valid_vectors(name, data):
+    assert encode(Enr.from_bytes(data, strict=True), "bytes") == data
+
+
+@pytest.mark.parametrize("name,data,code", INVALID_VECTORS)
+def test_invalid_vectors(name, data, code):
+    with pytest.raises(EnrError) as info:
+        Enr.from_bytes(data, strict=True)
+    if code is None:
+        assert isinstance(info.value, SignatureError)
+    else:
+        assert info.value.code == code
+
+
+def test_vector_names_are_unique():
+    names = [v[0] for v in VALID_VECTORS + INVALID_VECTORS]
+    assert len(names) == len(set(names))
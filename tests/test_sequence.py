@@ -1,6 +1,9 @@
 """Tests for sequence number behavior."""
 
-from pyenr import SigningKey
+import pytest
+from pyenr import SequenceOverflowError, SigningKey
+
+MAX_SEQ = 2**64 - 1
 
 
 def test_new_enr_starts_at_seq_1():
@@ -89,3 +92,50 @@ def test_seq_preserved_after_roundtrip():
     from pyenr import Enr
     decoded = Enr.from_base64(enr.to_base64())
     assert decoded.seq == 42
+
+
+def test_seq_remaining():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    assert enr.seq_remaining() == MAX_SEQ - 1
+
+    enr.set_seq(MAX_SEQ - 1, key)
+    assert enr.seq_remaining() == 1
+    enr.set_tcp4(30303, key)
+    assert enr.seq == MAX_SEQ
+    assert enr.seq_remaining() == 0
+
+
+@pytest.mark.parametrize(
+    "update",
+    [
+        lambda enr, key: enr.set_ip4("10.0.0.2", key),
+        lambda enr, key: enr.set_ip6("::2", key),
+        lambda enr, key: enr.set_tcp4(1, key),
+        lambda enr, key: enr.set_tcp6(1, key),
+        lambda enr, key: enr.set_udp4(1, key),
+        lambda enr, key: enr.set_udp6(1, key),
+        lambda enr, key: enr.set("custom", b"\x01", key),
+    ],
+)
+def test_update_at_max_seq_raises_and_leaves_record_unchanged(update):
+    """Seq never wraps; the record is left exactly as it was."""
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    enr.set_ip4("10.0.0.1", key)
+    enr.set_seq(MAX_SEQ, key)
+    before = enr.to_bytes()
+
+    with pytest.raises(SequenceOverflowError):
+        update(enr, key)
+    assert enr.to_bytes() == before
+    assert enr.seq == MAX_SEQ
+
+
+def test_set_seq_recovers_from_max():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    enr.set_seq(MAX_SEQ, key)
+    enr.set_seq(1, key)
+    enr.set_tcp4(30303, key)
+    assert enr.seq == 2
@@ -99,3 +99,72 @@ def test_public_key_matches_after_build():
     key = SigningKey.generate_secp256k1()
     enr = key.builder().build(key)
     assert enr.public_key == key.public_key()
+
+
+def test_verify_with_signer_key():
+    for key in (SigningKey.generate_secp256k1(), SigningKey.generate_ed25519()):
+        builder = key.builder()
+        builder.ip4("10.0.0.1")
+        enr = builder.build(key)
+        assert enr.verify_with(key.public_key())
+        enr.set_tcp4(30303, key)
+        assert enr.verify_with(key.public_key())
+
+
+def test_verify_with_other_key():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    assert not enr.verify_with(SigningKey.generate_secp256k1().public_key())
+    assert not enr.verify_with(SigningKey.generate_ed25519().public_key())
+
+
+def test_verify_with_invalid_key():
+    import pytest
+
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    with pytest.raises(ValueError, match="got 5 bytes"):
+        enr.verify_with(b"\x02" * 5)
+    with pytest.raises(ValueError):
+        enr.verify_with(b"\x05" + b"\x00" * 32)
+
+
+def test_key_type():
+    from pyenr import KeyType
+
+    for key_type, enr_key in ((KeyType.SECP256K1, "secp256k1"), (KeyType.ED25519, "ed25519")):
+        key = SigningKey.generate(key_type)
+        assert key.key_type == key_type
+        assert key_type.enr_key == enr_key
+        enr = key.builder().build(key)
+        assert enr.key_type == key_type
+        assert enr_key in enr.keys()
+    assert SigningKey.generate_secp256k1().key_type != KeyType.ED25519
+    assert len({KeyType.SECP256K1, KeyType.SECP256K1, KeyType.ED25519}) == 2
+
+
+def test_field_name_constants():
+    import pyenr
+
+    assert pyenr.KEY_ID == "id"
+    assert pyenr.KEY_SECP256K1 == "secp256k1"
+    assert pyenr.KEY_TCP6 == "tcp6"
+    assert pyenr.KEY_ETH2 == "eth2"
+    names = {getattr(pyenr, n) for n in pyenr.__all__ if n.startswith("KEY_")}
+    assert names == set(pyenr.known_keys())
+
+
+def test_verify():
+    for key in (SigningKey.generate_secp256k1(), SigningKey.generate_ed25519()):
+        enr = key.builder().build(key)
+        assert enr.verify()
+        assert Enr.from_bytes(enr.to_bytes()).verify()
+
+
+def test_verify_against():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    assert enr.verify_against(key.public_key())
+    assert not enr.verify_against(SigningKey.generate_secp256k1().public_key())
+    assert enr.verify_against(key.public_key()) == enr.verify_with(key.public_key())
+
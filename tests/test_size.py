@@ -0,0 +1,40 @@
+"""Tests for the per-field size breakdown."""
+
+from pyenr import EIP778_ENR, Enr, SizeBreakdown, SigningKey
+
+
+def test_eip778_breakdown():
+    enr = Enr.from_base64(EIP778_ENR)
+    sizes = enr.size_breakdown()
+    assert isinstance(sizes, SizeBreakdown)
+    assert sizes.total == len(enr.to_bytes()) == 134
+    assert sizes.remaining == 300 - 134
+    assert (sizes.header, sizes.signature, sizes.seq) == (2, 66, 1)
+    # key (1 + len) plus value (header + payload)
+    assert sizes.fields == {"id": 3 + 3, "ip": 3 + 5, "secp256k1": 10 + 34, "udp": 4 + 3}
+
+
+def test_parts_add_up():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.tcp4(9000)
+    builder.add("attnets", b"\xff" * 8)
+    builder.add("filler", bytes(120))
+    enr = builder.build(key)
+    sizes = enr.size_breakdown()
+    parts = sizes.header + sizes.signature + sizes.seq + sum(sizes.fields.values())
+    assert parts == sizes.total == len(enr.to_bytes())
+    assert list(sizes.fields) == enr.keys()
+    assert sizes.fields["filler"] == 7 + 2 + 120
+    assert sizes.header == 3
+
+
+def test_remaining_tracks_updates():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    before = enr.size_breakdown().remaining
+    enr.set("quic", 9001, key)
+    after = enr.size_breakdown()
+    assert after.remaining == before - after.fields["quic"]
+    assert "remaining=" in repr(after) and "quic=" in repr(after)
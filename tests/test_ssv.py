@@ -0,0 +1,45 @@
+"""Tests for SSV network fields."""
+
+import pytest
+from pyenr import FieldError, SigningKey
+
+
+def _record(*entries):
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    for k, v in entries:
+        builder.add(k, v)
+    return builder.build(key)
+
+
+def test_subnets():
+    bits = bytearray(16)
+    for i in (0, 9, 127):
+        bits[i // 8] |= 1 << (i % 8)
+    enr = _record(("subnets", bytes(bits)))
+    assert enr.ssv_subnets == [0, 9, 127]
+    assert _record(("subnets", b"\x00" * 16)).ssv_subnets == []
+    assert _record().ssv_subnets is None
+
+
+def test_subnets_wrong_length():
+    enr = _record(("subnets", b"\xff" * 8))
+    with pytest.raises(FieldError):
+        enr.ssv_subnets
+    assert sorted(enr.validate(profile="ssv").codes()) == ["bad_field_length", "missing_key"]
+
+
+def test_domain_type():
+    # Stored as a minimal big-endian integer: 0x00000302 loses its leading zeros.
+    enr = _record(("domaintype", 0x00000302), ("next_domaintype", bytes.fromhex("00000303")))
+    assert enr.get("domaintype") == b"\x03\x02"
+    assert enr.ssv_domain_type == bytes.fromhex("00000302")
+    assert enr.ssv_next_domain_type == bytes.fromhex("00000303")
+    assert enr.validate(profile="ssv").is_valid
+    assert _record().ssv_domain_type is None
+
+
+def test_domain_type_too_long():
+    enr = _record(("domaintype", b"\x01" * 5))
+    with pytest.raises(FieldError):
+        enr.ssv_domain_type
@@ -0,0 +1,154 @@
+"""Tests for the SQLite record store."""
+
+import os
+import subprocess
+import sys
+import textwrap
+
+import pyenr
+import pytest
+from pyenr import EIP778_ENR, Enr, EnrStore, SigningKey, features
+
+if "sqlite" not in features()["features"]:
+    pytest.skip("built without the sqlite feature", allow_module_level=True)
+
+_EIP778 = Enr.from_base64(EIP778_ENR)
+
+
+def test_upsert_if_newer(make_record):
+    key = SigningKey.generate_secp256k1()
+    store = EnrStore(":memory:")
+    assert store.upsert(make_record(key, seq=5))
+    assert not store.upsert(make_record(key, seq=5, ip="10.0.0.2"))
+    assert not store.upsert(make_record(key, seq=4))
+    assert store.get(make_record(key).node_id).seq == 5
+    assert store.get(make_record(key)).ip4 == "10.0.0.1"
+    newer = make_record(key, seq=6, ip="10.0.0.3")
+    assert store.upsert(newer)
+    assert store.get(newer) == newer
+    assert len(store) == 1
+
+
+def test_seq_order_beyond_signed_64_bit(make_record):
+    key = SigningKey.generate_secp256k1()
+    store = EnrStore(":memory:")
+    store.upsert(make_record(key, seq=2**63 - 1))
+    assert store.upsert(make_record(key, seq=2**63))
+    assert not store.upsert(make_record(key, seq=1))
+    assert [e.seq for e in store.find(min_seq=2**63)] == [2**63]
+
+
+def test_upsert_many_and_find(make_record):
+    digest = bytes.fromhex("b5303f2a")
+    keys = [SigningKey.generate_secp256k1() for _ in range(4)]
+    records = [
+        make_record(keys[0], seq=1, fork_digest=digest),
+        make_record(keys[1], seq=3, fork_digest=digest, ip="10.0.0.9"),
+        make_record(keys[2], seq=7),
+        make_record(keys[3], seq=2, ip="10.0.0.9"),
+        make_record(keys[0], seq=1, ip="10.0.0.5"),
+    ]
+    store = EnrStore(":memory:")
+    assert store.upsert_many(records) == 4
+    assert len(store) == 4
+
+    def node_ids(found):
+        return {e.node_id for e in found}
+
+    assert node_ids(store.find(fork_digest=digest)) == node_ids(records[:2])
+    assert node_ids(store.find(ip="10.0.0.9")) == node_ids(records[1::2][:2])
+    assert node_ids(store.find(ip="10.0.0.9", fork_digest=digest)) == {records[1].node_id}
+    assert node_ids(store.find(min_seq=3)) == node_ids(records[1:3])
+    everything = store.find()
+    assert [e.node_id for e in everything] == sorted(node_ids(records))
+    assert len(store.find(limit=2)) == 2
+
+
+def test_find_ip6():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip6("2001:db8::1")
+    enr = builder.build(key)
+    store = EnrStore(":memory:")
+    store.upsert(enr)
+    assert store.find(ip="2001:db8:0::1") == [enr]
+    assert store.find(ip="10.0.0.1") == []
+
+
+def test_find_invalid_arguments():
+    store = EnrStore(":memory:")
+    with pytest.raises(ValueError, match="invalid IP address"):
+        store.find(ip="nope")
+    with pytest.raises(ValueError, match="4 bytes"):
+        store.find(fork_digest=b"\x00")
+
+
+def test_contains_and_remove():
+    store = EnrStore(":memory:")
+    store.upsert(_EIP778)
+    assert _EIP778 in store
+    assert _EIP778.node_id in store
+    assert store.remove(_EIP778.node_id)
+    assert not store.remove(_EIP778)
+    assert _EIP778 not in store
+    assert store.get(_EIP778) is None
+    with pytest.raises(ValueError, match="32 bytes"):
+        store.get(b"short")
+
+
+def test_persists(tmp_path):
+    path = tmp_path / "nodes.db"
+    with EnrStore(path) as store:
+        store.upsert(_EIP778)
+        assert repr(store) == f'EnrStore("{path}")'
+    assert EnrStore(str(path)).get(_EIP778) == _EIP778
+
+
+def test_closed():
+    store = EnrStore(":memory:")
+    store.close()
+    store.close()
+    with pytest.raises(ValueError, match="closed"):
+        store.upsert(_EIP778)
+    with pytest.raises(ValueError, match="closed"):
+        len(store)
+
+
+def test_open_failure(tmp_path):
+    with pytest.raises(OSError, match="record store"):
+        EnrStore(tmp_path / "missing" / "nodes.db")
+
+
+def test_concurrent_readers_do_not_deadlock():
+    """Calls that release the GIL do not hold the store lock while reattaching."""
+    script = textwrap.dedent(
+        """
+        import threading
+        from pyenr import EnrStore, SigningKey
+
+        key = SigningKey.generate_secp256k1()
+        records = [key.builder().build(key) for _ in range(50)]
+        store = EnrStore(":memory:")
+        done = threading.Event()
+
+        def writer():
+            for _ in range(300):
+                store.upsert_many(records)
+                store.find(limit=10)
+                store.digest()
+            done.set()
+
+        def reader():
+            while not done.is_set():
+                len(store)
+                store.get(records[0])
+
+        threads = [threading.Thread(target=writer), threading.Thread(target=reader)]
+        for t in threads:
+            t.start()
+        for t in threads:
+            t.join()
+        """
+    )
+    env = dict(os.environ, PYTHONPATH=os.path.dirname(os.path.dirname(pyenr.__file__)))
+    subprocess.run([sys.executable, "-c", script], env=env, check=True, timeout=60)
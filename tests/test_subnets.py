@@ -0,0 +1,90 @@
+"""Tests for the attnets and syncnets subnet bitvectors."""
+
+import pytest
+from pyenr import Enr, FieldError, SigningKey
+
+
+def _record(**fields):
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    for name, value in fields.items():
+        builder.add(name, value)
+    return builder.build(key), key
+
+
+def test_getters_decode_bitvectors():
+    enr, _ = _record(attnets=b"\x05\x00\x00\x00\x00\x00\x00\x80", syncnets=b"\x0a")
+    assert enr.attnets == [0, 2, 63]
+    assert enr.attnets_raw == b"\x05\x00\x00\x00\x00\x00\x00\x80"
+    assert enr.syncnets == [1, 3]
+    assert enr.syncnets_raw == b"\x0a"
+
+
+def test_getters_absent():
+    enr, _ = _record()
+    assert enr.attnets is None and enr.attnets_raw is None
+    assert enr.syncnets is None and enr.syncnets_raw is None
+
+
+def test_all_and_no_subnets():
+    enr, _ = _record(attnets=b"\xff" * 8, syncnets=b"\x00")
+    assert enr.attnets == list(range(64))
+    assert enr.syncnets == []
+
+
+def test_attnets_wrong_length():
+    enr, _ = _record(attnets=b"\xff" * 3)
+    assert enr.attnets_raw == b"\xff" * 3
+    with pytest.raises(FieldError, match="must be 8 bytes"):
+        enr.attnets
+
+
+def test_syncnets_padding_bits():
+    enr, _ = _record(syncnets=b"\x1f")
+    assert enr.syncnets_raw == b"\x1f"
+    with pytest.raises(FieldError, match="past its 4 subnets"):
+        enr.syncnets
+
+
+def test_builder():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.attnets([63, 0, 9, 9])
+    builder.syncnets([2])
+    enr = builder.build(key)
+    assert enr.attnets_raw == b"\x01\x02\x00\x00\x00\x00\x00\x80"
+    assert enr.attnets == [0, 9, 63]
+    assert enr.syncnets_raw == b"\x04"
+    assert enr.fields().attnets == enr.attnets_raw
+
+
+def test_builder_empty_list():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.attnets([])
+    builder.syncnets([])
+    enr = builder.build(key)
+    assert enr.attnets_raw == b"\x00" * 8
+    assert enr.syncnets_raw == b"\x00"
+
+
+def test_setters_resign():
+    enr, key = _record()
+    seq = enr.seq
+    enr.set_attnets([1, 2, 3], key)
+    enr.set_syncnets([0, 3], key)
+    assert enr.seq == seq + 2
+    decoded = Enr.from_base64(enr.to_base64())
+    assert decoded.attnets == [1, 2, 3]
+    assert decoded.syncnets == [0, 3]
+
+
+@pytest.mark.parametrize("name,index", [("attnets", 64), ("syncnets", 4)])
+def test_rejects_out_of_range(name, index):
+    enr, key = _record()
+    with pytest.raises(FieldError, match=f"subnet {index}"):
+        getattr(enr, f"set_{name}")([0, index], key)
+    with pytest.raises(FieldError):
+        getattr(key.builder(), name)([index])
+    with pytest.raises(OverflowError):
+        getattr(key.builder(), name)([-1])
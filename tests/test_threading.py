@@ -0,0 +1,97 @@
+"""Tests for sharing records and keys between threads."""
+
+import asyncio
+import threading
+
+from pyenr import (
+    Enr,
+    SigningKey,
+    filter_many,
+    from_eth_enr,
+    probe_liveness_async,
+    register_codec,
+    unregister_codec,
+)
+
+
+def test_shared_records_across_threads():
+    """Many threads can read and re-encode the same records concurrently."""
+    key = SigningKey.generate_secp256k1()
+    records = []
+    for port in range(20):
+        builder = key.builder()
+        builder.ip4("10.0.0.1")
+        builder.udp4(9000 + port)
+        records.append(builder.build(key))
+
+    errors = []
+
+    def worker():
+        try:
+            for _ in range(50):
+                for enr in records:
+                    assert Enr.from_bytes(enr.to_bytes()) == enr
+                    assert enr.udp4 >= 9000
+        except Exception as e:  # pragma: no cover - surfaced below
+            errors.append(e)
+
+    threads = [threading.Thread(target=worker) for _ in range(8)]
+    for t in threads:
+        t.start()
+    for t in threads:
+        t.join()
+    assert errors == []
+
+
+def test_shared_signing_key_across_threads():
+    """A single signing key can sign independent records from several threads."""
+    key = SigningKey.generate_ed25519()
+    results = []
+    lock = threading.Lock()
+
+    def worker(port):
+        enr = key.builder().build(key)
+        enr.set_tcp4(port, key)
+        with lock:
+            results.append(enr)
+
+    threads = [threading.Thread(target=worker, args=(p,)) for p in range(30000, 30016)]
+    for t in threads:
+        t.start()
+    for t in threads:
+        t.join()
+
+    assert sorted(e.tcp4 for e in results) == list(range(30000, 30016))
+    assert all(e.public_key == key.public_key() for e in results)
+
+
+def test_batch_calls_on_a_record_being_mutated_raise():
+    """A record mutably borrowed elsewhere raises RuntimeError rather than panicking."""
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    seen = []
+
+    def encode(value):
+        # Runs inside enr.set(), while the record is mutably borrowed.
+        for call in (
+            lambda: filter_many([enr], has_ip4=True),
+            lambda: asyncio.run(_probe(enr)),
+            lambda: from_eth_enr(enr),
+            lambda: memoryview(enr),
+        ):
+            try:
+                call()
+            except RuntimeError as e:
+                seen.append(e)
+        return value
+
+    register_codec("busy", encode_fn=encode)
+    try:
+        enr.set("busy", b"\x01", key)
+    finally:
+        unregister_codec("busy")
+    assert len(seen) == 4
+
+
+async def _probe(enr):
+    return await probe_liveness_async([enr], timeout=0.1)
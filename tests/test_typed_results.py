@@ -0,0 +1,115 @@
+"""Tests for the typed result classes: to_dict(), equality and hashing."""
+
+import struct
+
+import pytest
+from pyenr import (
+    Endpoints,
+    Eth2ForkId,
+    EthForkId,
+    FieldError,
+    OpStackInfo,
+    SigningKey,
+    compute_fork_id,
+)
+
+_KEY = SigningKey.generate_secp256k1()
+
+
+def _record(*entries, ip4=None, ip6=None, tcp4=None, udp4=None):
+    builder = _KEY.builder()
+    if ip4:
+        builder.ip4(ip4)
+    if ip6:
+        builder.ip6(ip6)
+    if tcp4:
+        builder.tcp4(tcp4)
+    if udp4:
+        builder.udp4(udp4)
+    for k, v in entries:
+        builder.add(k, v)
+    return builder.build(_KEY)
+
+
+def test_eth_fork_id():
+    fork_id = compute_fork_id(b"\x00" * 32, [1150000])
+    same = _record(("eth", [[fork_id.fork_hash, fork_id.fork_next]])).eth_fork_id
+    assert isinstance(same, EthForkId)
+    assert same == fork_id
+    assert hash(same) == hash(fork_id)
+    assert len({same, fork_id}) == 1
+    assert fork_id.to_dict() == {"fork_hash": fork_id.fork_hash, "fork_next": 0}
+
+
+def test_eth2_fork_id():
+    value = b"\x6a\x95\xa1\xa9" + b"\x05\x00\x00\x00" + struct.pack("<Q", 364032)
+    a = _record(("eth2", value)).fields().eth2
+    b = _record(("eth2", value)).fields().eth2
+    assert isinstance(a, Eth2ForkId)
+    assert a == b and hash(a) == hash(b)
+    assert a.to_dict() == {
+        "fork_digest": b"\x6a\x95\xa1\xa9",
+        "next_fork_version": b"\x05\x00\x00\x00",
+        "next_fork_epoch": 364032,
+    }
+
+
+@pytest.mark.parametrize(
+    "value, chain_id, version",
+    [
+        (b"\x0a\x00", 10, 0),
+        (b"\x85\x42\x00", 8453, 0),
+        (b"\x0a\x01\xff", 10, 1),
+        (b"\xff" * 9 + b"\x01\x00", 2**64 - 1, 0),
+    ],
+)
+def test_opstack(value, chain_id, version):
+    info = _record(("opstack", value)).opstack
+    assert isinstance(info, OpStackInfo)
+    assert (info.chain_id, info.version) == (chain_id, version)
+    assert info.to_dict() == {"chain_id": chain_id, "version": version}
+    assert repr(info) == f"OpStackInfo(chain_id={chain_id}, version={version})"
+    assert info == _record(("opstack", value)).opstack
+    assert _record().opstack is None
+
+
+@pytest.mark.parametrize("value", [b"", b"\x0a", b"\x8a", b"\xff" * 10 + b"\x01", [b"\x0a"]])
+def test_malformed_opstack(value):
+    with pytest.raises(FieldError, match="opstack"):
+        _record(("opstack", value)).opstack
+
+
+def test_endpoints():
+    enr = _record(("quic", 9001), ip4="10.0.0.1", ip6="2001:db8::1", tcp4=9000, udp4=9000)
+    endpoints = enr.endpoints
+    assert isinstance(endpoints, Endpoints)
+    assert endpoints.to_dict() == {
+        "ip4": "10.0.0.1",
+        "tcp4": 9000,
+        "udp4": 9000,
+        "quic4": 9001,
+        "ip6": "2001:db8::1",
+        "tcp6": None,
+        "udp6": None,
+        "quic6": None,
+    }
+    assert repr(endpoints) == (
+        'Endpoints(ip4="10.0.0.1", tcp4=9000, udp4=9000, quic4=9001, ip6="2001:db8::1")'
+    )
+    assert endpoints
+    assert endpoints == enr.endpoints
+    assert hash(endpoints) == hash(enr.endpoints)
+    assert endpoints != _record(ip4="10.0.0.1").endpoints
+
+
+def test_empty_endpoints():
+    endpoints = _record().endpoints
+    assert not endpoints
+    assert repr(endpoints) == "Endpoints()"
+    assert set(endpoints.to_dict().values()) == {None}
+
+
+def test_endpoints_have_no_port_fallback():
+    enr = _record(ip6="2001:db8::1", udp4=30303)
+    assert enr.endpoints.udp6 is None
+    assert ("udp", "2001:db8::1", 30303) in enr.effective_endpoints()
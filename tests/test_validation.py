@@ -145,3 +145,181 @@ def test_from_bytes_empty():
 def test_from_bytes_single_byte():
     with pytest.raises(Exception):
         Enr.from_bytes(b"\xff")
+
+
+# -- Strict decoding --
+
+
+def _rlp(item):
+    """Minimal RLP encoder for hand-built records."""
+    if isinstance(item, list):
+        payload = b"".join(_rlp(i) for i in item)
+        prefix = 0xC0
+    else:
+        if len(item) == 1 and item[0] < 0x80:
+            return item
+        payload = item
+        prefix = 0x80
+    if len(payload) < 56:
+        return bytes([prefix + len(payload)]) + payload
+    length = len(payload).to_bytes((len(payload).bit_length() + 7) // 8, "big")
+    return bytes([prefix + 55 + len(length)]) + length + payload
+
+
+def _raw_record(*pairs, seq=b"\x01"):
+    key = SigningKey.generate_secp256k1()
+    content = [b"id", b"v4", b"secp256k1", key.public_key()]
+    for k, v in pairs:
+        content += [k, v]
+    flat = [b"\x00" * 64, seq] + [
+        x for k, v in sorted(zip(content[::2], content[1::2])) for x in (k, v)
+    ]
+    return _rlp(flat)
+
+
+def test_strict_accepts_valid_record():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.tcp4(30303)
+    enr = builder.build(key)
+    assert Enr.from_bytes(enr.to_bytes(), strict=True) == enr
+
+
+def test_strict_rejects_trailing_bytes():
+    key = SigningKey.generate_secp256k1()
+    raw = key.builder().build(key).to_bytes() + b"\x00"
+    Enr.from_bytes(raw)
+    with pytest.raises(ValueError, match="trailing_data"):
+        Enr.from_bytes(raw, strict=True)
+
+
+def test_strict_rejects_leading_zero_port():
+    raw = _raw_record((b"tcp", b"\x00\x50"))
+    with pytest.raises(ValueError, match=r'non_minimal_integer.*key "tcp"'):
+        Enr.from_bytes(raw, strict=True)
+
+
+def test_strict_rejects_bad_ip_length():
+    raw = _raw_record((b"ip", b"\x7f\x00\x00"))
+    with pytest.raises(ValueError, match="bad_ip_length"):
+        Enr.from_bytes(raw, strict=True)
+
+
+def test_strict_rejects_bad_eth2_length():
+    raw = _raw_record((b"eth2", b"\x01" * 12))
+    with pytest.raises(ValueError, match="bad_field_length"):
+        Enr.from_bytes(raw, strict=True)
+    violation = Enr.validate_bytes(raw).violations[0]
+    assert violation.key == "eth2"
+    assert (violation.expected, violation.actual) == (16, 12)
+
+
+def test_eth2_must_be_a_byte_string():
+    raw = _raw_record((b"eth2", [b"\x01" * 16]))
+    assert Enr.validate_bytes(raw).codes() == ["malformed"]
+
+
+def test_strict_rejects_unsorted_keys():
+    key = SigningKey.generate_secp256k1()
+    raw = _rlp([b"\x00" * 64, b"\x01", b"secp256k1", key.public_key(), b"id", b"v4"])
+    with pytest.raises(ValueError, match="unsorted_keys"):
+        Enr.from_bytes(raw, strict=True)
+
+
+def test_strict_rejects_uncompressed_public_key():
+    raw = _rlp([b"\x00" * 64, b"\x01", b"id", b"v4", b"secp256k1", b"\x04" + b"\x11" * 64])
+    with pytest.raises(ValueError, match="bad_public_key"):
+        Enr.from_bytes(raw, strict=True)
+
+
+def test_strict_rejects_oversize():
+    raw = _raw_record((b"zz", b"\x01" * 300))
+    with pytest.raises(ValueError, match="oversize"):
+        Enr.from_bytes(raw, strict=True)
+
+
+# -- Conformance reports --
+
+
+def test_validate_clean_record():
+    key = SigningKey.generate_secp256k1()
+    report = key.builder().build(key).validate()
+    assert report.is_valid
+    assert report
+    assert report.violations == []
+    assert report.codes() == []
+
+
+def test_validate_bytes_collects_all_violations():
+    raw = _raw_record((b"ip", b"\x01"), (b"udp", b"\x01\x02\x03")) + b"\x00"
+    report = Enr.validate_bytes(raw)
+    assert report.codes() == ["bad_ip_length", "integer_overflow", "trailing_data"]
+    assert len(report.violations) == 3
+    with pytest.raises(TypeError):
+        len(report)
+    assert report.violations[0].offset < report.violations[-1].offset
+
+
+def test_validate_bytes_malformed():
+    report = Enr.validate_bytes(b"\xf8")
+    assert report.codes() == ["malformed"]
+    assert report.violations[0].key is None
+
+
+def test_validate_bytes_multiple_public_keys():
+    raw = _raw_record((b"ed25519", b"\x01" * 32))
+    report = Enr.validate_bytes(raw)
+    assert report.codes() == ["key_type_mismatch"]
+    assert report.violations[0].key == "secp256k1"
+
+
+def test_validate_bytes_signature_length():
+    key = SigningKey.generate_secp256k1()
+    raw = _rlp([b"\x00" * 65, b"\x01", b"id", b"v4", b"secp256k1", key.public_key()])
+    report = Enr.validate_bytes(raw)
+    assert report.codes() == ["key_type_mismatch"]
+    assert (report.violations[0].expected, report.violations[0].actual) == (64, 65)
+
+
+def test_validate_bytes_missing_public_key():
+    raw = _rlp([b"\x00" * 64, b"\x01", b"id", b"v4"])
+    assert Enr.validate_bytes(raw).codes() == ["bad_public_key"]
+
+
+def test_validate_ed25519_record_is_clean():
+    key = SigningKey.generate_ed25519()
+    assert key.builder().build(key).validate().is_valid
+
+
+# -- Canonical encoding --
+
+
+def test_is_canonical_for_encoded_records():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.udp4(9000)
+    builder.add("list", b"\xc2\x01\x02")
+    assert Enr.is_canonical(builder.build(key).to_bytes())
+
+
+def test_is_canonical_rejects_long_form_header():
+    key = SigningKey.generate_secp256k1()
+    raw = _rlp([b"\x00" * 64, b"\x01", b"id", b"v4", b"secp256k1", key.public_key()])
+    assert Enr.is_canonical(raw)
+    # "id" written with a one-byte long-form length header.
+    loose = raw.replace(b"\x82id", b"\xb8\x02id")
+    loose = bytes([0xF8, len(loose) - 2 + 1]) + loose[2:]
+    assert not Enr.is_canonical(loose)
+
+
+def test_is_canonical_rejects_leading_zero_port_and_trailing_bytes():
+    assert not Enr.is_canonical(_raw_record((b"udp", b"\x00\x50")))
+    assert Enr.is_canonical(_raw_record((b"udp", b"\x50")))
+    assert not Enr.is_canonical(_raw_record() + b"\x00")
+
+
+def test_is_canonical_on_garbage():
+    for data in (b"", b"\x80", b"\xf8\xff", b"\xc1"):
+        assert not Enr.is_canonical(data)
@@ -0,0 +1,55 @@
+"""Tests for verify_content() and Enr.signable_content()."""
+
+import pytest
+from pyenr import Enr, SigningKey, verify_content
+
+# The example record from EIP-778.
+EIP778_ENR = (
+    "enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04j"
+    "RzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2Vj"
+    "cDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCC"
+    "dl8"
+)
+
+
+def test_eip778_example():
+    enr = Enr.from_base64(EIP778_ENR)
+    content = enr.signable_content()
+    assert content[0] >= 0xC0
+    # The record is the content list with the signature prepended.
+    assert enr.to_bytes().endswith(content[2:])
+    assert verify_content(content, enr.signature, enr.public_key)
+    assert verify_content(content, enr.signature, enr.public_key, scheme="v4")
+
+
+@pytest.mark.parametrize("make_key", [SigningKey.generate_secp256k1, SigningKey.generate_ed25519])
+def test_fresh_records(make_key):
+    key = make_key()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.udp4(30303)
+    enr = builder.build(key)
+    content = enr.signable_content()
+    assert verify_content(content, enr.signature, key.public_key())
+    enr.set_udp4(30304, key)
+    assert not verify_content(content, enr.signature, key.public_key())
+    assert verify_content(enr.signable_content(), enr.signature, key.public_key())
+
+
+def test_tampering_fails():
+    enr = Enr.from_base64(EIP778_ENR)
+    content = enr.signable_content()
+    signature = enr.signature
+    assert not verify_content(content[:-1] + b"\x00", signature, enr.public_key)
+    assert not verify_content(content, signature[:-1] + b"\x00", enr.public_key)
+    assert not verify_content(content, signature[:10], enr.public_key)
+    other = SigningKey.generate_secp256k1().public_key()
+    assert not verify_content(content, signature, other)
+
+
+def test_invalid_arguments():
+    enr = Enr.from_base64(EIP778_ENR)
+    with pytest.raises(ValueError, match="got 5 bytes"):
+        verify_content(b"", enr.signature, b"\x02" * 5)
+    with pytest.raises(ValueError, match='identity scheme "v5"'):
+        verify_content(enr.signable_content(), enr.signature, enr.public_key, scheme="v5")
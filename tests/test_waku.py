@@ -0,0 +1,88 @@
+"""Tests for Waku v2 fields."""
+
+import pytest
+from pyenr import Enr, FieldError, SigningKey
+
+WSS = "/dns4/example.com/tcp/443/wss"
+# Length prefix, then dns4 (54) "example.com", tcp (6) 443, wss (478).
+WSS_VALUE = bytes.fromhex("0012" "360b") + b"example.com" + bytes.fromhex("0601bb" "de03")
+CIRCUIT = (
+    "/ip4/10.0.0.1/tcp/60000/p2p/QmcgpsyWgH8Y8ajJz1Cu72KnS5uo2Aa2LpzU7kinSupNKC/p2p-circuit"
+)
+
+
+def _builder():
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4("10.0.0.1")
+    builder.tcp4(60000)
+    return builder, key
+
+
+def test_build_and_read():
+    builder, key = _builder()
+    builder.waku_capabilities(["relay", "store"])
+    builder.multiaddrs([WSS, CIRCUIT])
+    enr = Enr.from_base64(builder.build(key).to_base64())
+
+    assert enr.waku_capabilities == ["relay", "store"]
+    assert enr.get("waku2") == b"\x03"
+    assert enr.multiaddrs == [WSS, CIRCUIT]
+    assert enr.get("multiaddrs").startswith(WSS_VALUE)
+    assert enr.validate(profile="waku").is_valid
+
+
+def test_decode_wire_format():
+    builder, key = _builder()
+    builder.add("multiaddrs", WSS_VALUE)
+    builder.add("waku2", b"\x1d")
+    enr = builder.build(key)
+    assert enr.multiaddrs == [WSS]
+    assert enr.waku_capabilities == ["relay", "filter", "lightpush", "sync"]
+
+
+def test_setters():
+    key = SigningKey.generate_secp256k1()
+    enr = key.builder().build(key)
+    assert enr.waku_capabilities is None
+
+    enr.set_waku_capabilities(["lightpush"], key)
+    assert enr.waku_capabilities == ["lightpush"]
+    assert enr.seq == 2
+
+
+def test_multiaddrs_aliases_are_deprecated():
+    builder, key = _builder()
+    with pytest.warns(DeprecationWarning, match="multiaddrs"):
+        builder.waku_multiaddrs([WSS])
+    enr = builder.build(key)
+    assert enr.get("multiaddrs") == WSS_VALUE
+    with pytest.warns(DeprecationWarning, match="multiaddrs"):
+        assert enr.waku_multiaddrs == [WSS]
+    with pytest.warns(DeprecationWarning, match="set_multiaddrs"):
+        enr.set_waku_multiaddrs([CIRCUIT], key)
+    assert enr.multiaddrs == [CIRCUIT]
+
+
+@pytest.mark.parametrize(
+    "addr", ["ip4/1.2.3.4", "/ip4/1.2.3", "/ip4/1.2.3.4/tcp", "/foo/1", "/tcp/70000"]
+)
+def test_invalid_multiaddrs(addr):
+    builder, _ = _builder()
+    with pytest.raises(FieldError):
+        builder.multiaddrs([addr])
+
+
+def test_invalid_values():
+    builder, key = _builder()
+    with pytest.raises(FieldError):
+        builder.waku_capabilities(["gossip"])
+
+    builder.add("waku2", b"\x01\x02")
+    builder.add("multiaddrs", b"\x00\x05\x04")
+    enr = builder.build(key)
+    with pytest.raises(FieldError):
+        enr.waku_capabilities
+    with pytest.raises(FieldError):
+        enr.multiaddrs
+    assert enr.validate(profile="waku").codes() == ["bad_field_length"]
@@ -0,0 +1,62 @@
+"""Tests for warnings about records that decode but look wrong."""
+
+import warnings
+
+import pytest
+from pyenr import Enr, EnrWarning, SigningKey
+
+
+def _record(ip="10.0.0.1", tcp=30303):
+    key = SigningKey.generate_secp256k1()
+    builder = key.builder()
+    builder.ip4(ip)
+    builder.tcp4(tcp)
+    return builder.build(key)
+
+
+def _decode_messages(decode):
+    with warnings.catch_warnings(record=True) as caught:
+        warnings.simplefilter("always")
+        decode()
+    assert all(issubclass(w.category, EnrWarning) for w in caught)
+    return [str(w.message) for w in caught]
+
+
+def test_clean_record_warns_nothing():
+    enr = _record()
+    assert _decode_messages(lambda: Enr.from_bytes(enr.to_bytes(), warnings=True)) == []
+
+
+def test_off_by_default():
+    enr = _record(ip="0.0.0.0", tcp=0)
+    assert _decode_messages(lambda: Enr.from_bytes(enr.to_bytes() + b"\x00")) == []
+
+
+def test_trailing_data_warns():
+    enr = _record()
+    messages = _decode_messages(
+        lambda: Enr.from_bytes(enr.to_bytes() + b"\x00", warnings=True)
+    )
+    assert len(messages) == 1
+    assert "trailing_data" in messages[0]
+
+
+def test_suspicious_values_warn():
+    enr = _record(ip="0.0.0.0", tcp=0)
+    messages = _decode_messages(lambda: Enr.from_base64(enr.to_base64(), warnings=True))
+    assert messages == [
+        'key "tcp": port 0 cannot be connected to',
+        'key "ip": 0.0.0.0 is not a usable address',
+    ]
+
+
+def test_warning_filter_error_raises():
+    enr = _record(tcp=0)
+    with warnings.catch_warnings():
+        warnings.simplefilter("error", EnrWarning)
+        with pytest.raises(EnrWarning):
+            Enr.from_bytes(enr.to_bytes(), warnings=True)
+
+
+def test_warning_is_user_warning():
+    assert issubclass(EnrWarning, UserWarning)
@@ -0,0 +1,83 @@
+"""Tests for watching changes between record snapshots."""
+
+import pytest
+from pyenr import EnrWatcher, SigningKey, WatchEvent
+
+_KEYS = [SigningKey.generate_secp256k1() for _ in range(3)]
+
+
+def _summary(events):
+    return [(e.kind, e.node_id, e.keys) for e in events]
+
+
+def test_first_update_adds_everything(make_record):
+    records = [make_record(k) for k in _KEYS]
+    watcher = EnrWatcher()
+    events = watcher.update(records)
+    assert [e.kind for e in events] == ["added"] * 3
+    assert [e.node_id for e in events] == sorted(r.node_id for r in records)
+    assert all(e.old is None and e.new is not None for e in events)
+    assert len(watcher) == 3
+    assert watcher.update(records) == []
+
+
+def test_changes(make_record):
+    a, b, c = _KEYS
+    watcher = EnrWatcher()
+    watcher.update([make_record(a), make_record(b)])
+    events = watcher.update(
+        [make_record(a, seq=2, ip="10.0.0.2", udp=30304, tcp=30305), make_record(c)]
+    )
+    expected = sorted(
+        [
+            ("ip_changed", make_record(a).node_id, ["ip"]),
+            ("port_changed", make_record(a).node_id, ["tcp", "udp"]),
+            ("removed", make_record(b).node_id, []),
+            ("added", make_record(c).node_id, []),
+        ],
+        key=lambda event: event[1],
+    )
+    assert _summary(events) == expected
+    ip_event = next(e for e in events if e.kind == "ip_changed")
+    assert (ip_event.old.ip4, ip_event.new.ip4) == ("10.0.0.1", "10.0.0.2")
+    removed = next(e for e in events if e.kind == "removed")
+    assert removed.new is None and removed.old.node_id == make_record(b).node_id
+
+
+def test_seq_only_change_is_not_reported(make_record):
+    watcher = EnrWatcher()
+    watcher.update([make_record(_KEYS[0])])
+    assert watcher.update([make_record(_KEYS[0], seq=5)]) == []
+    assert watcher.records()[0].seq == 5
+
+
+def test_highest_seq_wins_within_snapshot(make_record):
+    key = _KEYS[0]
+    watcher = EnrWatcher()
+    watcher.update([make_record(key, seq=3, ip="10.0.0.3"), make_record(key, seq=1)])
+    assert watcher.records()[0].ip4 == "10.0.0.3"
+
+
+def test_callbacks(make_record):
+    seen = []
+    watcher = EnrWatcher(
+        on_added=lambda e: seen.append(("added", e)),
+        on_port_changed=lambda e: seen.append(("port", e)),
+    )
+    watcher.update([make_record(_KEYS[0])])
+    watcher.update([make_record(_KEYS[0], seq=2, ip="10.0.0.9", udp=1)])
+    assert [kind for kind, _ in seen] == ["added", "port"]
+    assert all(isinstance(e, WatchEvent) for _, e in seen)
+    assert seen[1][1].keys == ["udp"]
+    assert repr(seen[1][1]).startswith('WatchEvent(kind="port_changed", node_id=0x')
+
+
+def test_callback_error_keeps_snapshot(make_record):
+    def fail(event):
+        raise RuntimeError("boom")
+
+    watcher = EnrWatcher(on_added=fail)
+    with pytest.raises(RuntimeError, match="boom"):
+        watcher.update([make_record(_KEYS[0])])
+    assert len(watcher) == 1
+    assert watcher.update([make_record(_KEYS[0])]) == []